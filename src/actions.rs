@@ -0,0 +1,239 @@
+use std::io;
+use sysinfo::Pid;
+
+/// Signals offered in the kill menu, in display order
+pub const SIGNALS: &[(&str, i32)] = &[
+    ("TERM", libc::SIGTERM),
+    ("KILL", libc::SIGKILL),
+    ("INT", libc::SIGINT),
+    ("HUP", libc::SIGHUP),
+    ("QUIT", libc::SIGQUIT),
+    ("STOP", libc::SIGSTOP),
+    ("CONT", libc::SIGCONT),
+    ("USR1", libc::SIGUSR1),
+    ("USR2", libc::SIGUSR2),
+];
+
+/// Send a signal to a process
+///
+/// PID 0 is refused: `kill(0, …)` signals sysly's own process group, and
+/// macOS lists kernel_task under that PID.
+///
+/// # Arguments
+/// * `pid` - Target process ID
+/// * `signal` - Signal number, e.g. `libc::SIGTERM`
+///
+/// # Returns
+/// Ok on success, or the OS error (e.g. permission denied)
+pub fn send_signal(pid: Pid, signal: i32) -> io::Result<()> {
+    if pid.as_u32() == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "refusing to signal PID 0, which means sysly's own process group",
+        ));
+    }
+    let result = unsafe { libc::kill(pid.as_u32() as libc::pid_t, signal) };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Send a signal to every process in a process group
+///
+/// sysly's own group is refused, since signalling it would also hit sysly
+/// and the shell that started it, and so is group 0, which `killpg` takes
+/// to mean that same group.
+///
+/// # Arguments
+/// * `pgid` - Target process group ID
+/// * `signal` - Signal number, e.g. `libc::SIGTERM`
+///
+/// # Returns
+/// Ok on success, or the OS error (e.g. permission denied)
+pub fn send_group_signal(pgid: Pid, signal: i32) -> io::Result<()> {
+    let pgid = pgid.as_u32() as libc::pid_t;
+    if pgid <= 0 || pgid == unsafe { libc::getpgrp() } {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "refusing to signal sysly's own process group",
+        ));
+    }
+
+    let result = unsafe { libc::killpg(pgid, signal) };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Lowest and highest nice values accepted by `setpriority`
+const NICE_MIN: i32 = -20;
+const NICE_MAX: i32 = 19;
+
+/// Read the nice value of a process
+///
+/// # Arguments
+/// * `pid` - Target process ID
+///
+/// # Returns
+/// The current nice value, or the OS error if the process cannot be queried
+pub fn get_nice(pid: Pid) -> io::Result<i32> {
+    // getpriority can legitimately return -1, so errno must be checked
+    clear_errno();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid.as_u32() as libc::id_t) };
+
+    if nice == -1 {
+        let error = io::Error::last_os_error();
+        if error.raw_os_error().unwrap_or(0) != 0 {
+            return Err(error);
+        }
+    }
+
+    Ok(nice)
+}
+
+/// Adjust the nice value of a process by `delta`
+///
+/// A negative delta raises the priority, which normally requires root; the
+/// resulting permission error is reported with that explanation.
+///
+/// # Arguments
+/// * `pid` - Target process ID
+/// * `delta` - Change to apply to the current nice value
+///
+/// # Returns
+/// The new nice value, or the OS error
+pub fn renice(pid: Pid, delta: i32) -> io::Result<i32> {
+    let nice = (get_nice(pid)? + delta).clamp(NICE_MIN, NICE_MAX);
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid.as_u32() as libc::id_t, nice) };
+
+    if result == 0 {
+        return Ok(nice);
+    }
+
+    let error = io::Error::last_os_error();
+    if delta < 0 && error.kind() == io::ErrorKind::PermissionDenied {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "raising priority requires root",
+        ));
+    }
+
+    Err(error)
+}
+
+#[cfg(target_os = "macos")]
+fn clear_errno() {
+    unsafe { *libc::__error() = 0 };
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clear_errno() {
+    unsafe { *libc::__errno_location() = 0 };
+}
+
+/// An action that can be applied to one or more processes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    /// Send a signal, named without the `SIG` prefix
+    Signal { name: &'static str, signal: i32 },
+    /// Send a signal to whole process groups; targets are group IDs
+    SignalGroup { name: &'static str, signal: i32 },
+    /// Change the nice value by a delta
+    Renice(i32),
+}
+
+impl ProcessAction {
+    /// Short description used in confirmations and status messages
+    pub fn describe(&self) -> String {
+        match self {
+            ProcessAction::Signal { name, .. } => format!("send SIG{}", name),
+            ProcessAction::SignalGroup { name, .. } => format!("send SIG{} to", name),
+            ProcessAction::Renice(delta) if *delta < 0 => "raise priority of".to_string(),
+            ProcessAction::Renice(_) => "lower priority of".to_string(),
+        }
+    }
+
+    /// What the targets are, for confirmations and status messages
+    pub fn target_noun(&self) -> &'static str {
+        match self {
+            ProcessAction::SignalGroup { .. } => "process group(s)",
+            _ => "process(es)",
+        }
+    }
+
+    /// Whether the action can end the target, so it should always be confirmed
+    pub fn is_destructive(&self) -> bool {
+        match self {
+            ProcessAction::Signal { signal, .. } | ProcessAction::SignalGroup { signal, .. } => {
+                *signal != libc::SIGSTOP && *signal != libc::SIGCONT
+            }
+            ProcessAction::Renice(_) => false,
+        }
+    }
+
+    /// Whether the action is confirmed even for a single untagged row: it
+    /// can end the target, or stop it, which freezes whatever waits on it
+    pub fn needs_confirmation(&self) -> bool {
+        self.is_destructive()
+            || matches!(
+                self,
+                ProcessAction::Signal { signal, .. } | ProcessAction::SignalGroup { signal, .. }
+                    if *signal == libc::SIGSTOP
+            )
+    }
+
+    fn apply(&self, pid: Pid) -> io::Result<()> {
+        match self {
+            ProcessAction::Signal { signal, .. } => send_signal(pid, *signal),
+            ProcessAction::SignalGroup { signal, .. } => send_group_signal(pid, *signal),
+            ProcessAction::Renice(delta) => renice(pid, *delta).map(|_| ()),
+        }
+    }
+}
+
+/// Apply an action to every target, collecting the result for each PID
+///
+/// # Arguments
+/// * `action` - The action to apply
+/// * `targets` - PIDs to apply it to
+///
+/// # Returns
+/// One result per target, in the same order
+pub fn dispatch(action: ProcessAction, targets: &[Pid]) -> Vec<(Pid, io::Result<()>)> {
+    targets
+        .iter()
+        .map(|pid| (*pid, action.apply(*pid)))
+        .collect()
+}
+
+/// Summarize dispatch results into a one-line status message
+pub fn summarize(action: ProcessAction, results: &[(Pid, io::Result<()>)]) -> String {
+    let errors: Vec<String> = results
+        .iter()
+        .filter_map(|(pid, result)| result.as_ref().err().map(|e| format!("{}: {}", pid, e)))
+        .collect();
+
+    if errors.is_empty() {
+        format!(
+            "Done: {} {} {}",
+            action.describe(),
+            results.len(),
+            action.target_noun()
+        )
+    } else {
+        format!(
+            "Failed to {} {} of {} {}: {}",
+            action.describe(),
+            errors.len(),
+            results.len(),
+            action.target_noun(),
+            errors.join(", ")
+        )
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use sysinfo::System;
+
+use crate::export::write_snapshot;
+use crate::sort::{self, SortOrder, SortReadings};
+use sysly_core::provider;
+use sysly_core::snapshot::Snapshot;
+
+/// Port `sysly agent` listens on and `--connect` assumes when none is given
+pub const DEFAULT_PORT: u16 = 7411;
+
+/// Largest snapshot a viewer accepts, so a stray connection to another
+/// service can't make it allocate gigabytes
+const MAX_FRAME: usize = 64 * 1024 * 1024;
+
+/// How long a viewer may stall a write before it is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Run headless, sending a JSON snapshot of the system to every connected
+/// viewer each interval
+///
+/// Each snapshot is the `--json` document for all processes, in the
+/// default sort order, framed by its length as a 4-byte big-endian number;
+/// `--connect` reads it back into the dashboard.
+/// Nothing is read or rendered while no viewer is connected.
+pub fn run_agent(listen: SocketAddr, delay: Option<Duration>) -> io::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+    eprintln!(
+        "sysly agent: serving snapshots on {}",
+        listener.local_addr()?
+    );
+
+    let viewers: Arc<Mutex<Vec<TcpStream>>> = Arc::default();
+    let accepted = Arc::clone(&viewers);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_ok() {
+                let _ = stream.set_nodelay(true);
+                if let Ok(mut viewers) = accepted.lock() {
+                    viewers.push(stream);
+                }
+            }
+        }
+    });
+
+    let interval = delay
+        .unwrap_or(Duration::from_secs(1))
+        .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let mut system = System::new_all();
+    let mut snapshot = Vec::new();
+
+    loop {
+        // CPU usage is measured between two refreshes
+        thread::sleep(interval);
+        if viewers.lock().map_or(true, |viewers| viewers.is_empty()) {
+            continue;
+        }
+        system.refresh_cpu();
+        system.refresh_memory();
+        provider::refresh_processes(&mut system);
+        let taken = Snapshot::from_system(&system);
+
+        let mut processes: Vec<_> = taken
+            .processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none())
+            .collect();
+        sort::sort_processes(
+            &mut processes,
+            SortOrder::default(),
+            SortReadings::default(),
+        );
+        snapshot.clear();
+        write_snapshot(&mut snapshot, &taken, &processes)?;
+
+        if let Ok(mut viewers) = viewers.lock() {
+            viewers.retain_mut(|viewer| write_frame(viewer, &snapshot).is_ok());
+        }
+    }
+}
+
+/// Send one length-prefixed frame
+fn write_frame(out: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "snapshot too large"))?;
+    out.write_all(&length.to_be_bytes())?;
+    out.write_all(payload)?;
+    out.flush()
+}
+
+/// Receive one length-prefixed frame as text
+pub fn read_frame(input: &mut impl Read) -> io::Result<String> {
+    let mut length = [0; 4];
+    input.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} byte frame is not a sysly snapshot", length),
+        ));
+    }
+
+    let mut payload = vec![0; length];
+    input.read_exact(&mut payload)?;
+    String::from_utf8(payload).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
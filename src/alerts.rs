@@ -0,0 +1,165 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::watch::RespawnDiff;
+use sysly_core::sensors::{SensorLog, ThermalPressure};
+
+/// How alerts are raised as desktop notifications
+///
+/// The escape sequences travel over the terminal connection, so they reach
+/// the local desktop from sysly running over SSH too. Native notifications
+/// appear on the machine sysly runs on, whatever the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotificationStyle {
+    #[default]
+    Off,
+    /// `OSC 9`, understood by iTerm2, WezTerm, Windows Terminal and Ghostty
+    Osc9,
+    /// `OSC 777;notify`, understood by rxvt-unicode, foot, Konsole and Ghostty
+    Osc777,
+    /// Notification Center through `osascript` on macOS, `notify-send`
+    /// elsewhere
+    Native,
+}
+
+impl NotificationStyle {
+    /// Parse a config value such as `off`, `osc9`, `osc777` or `native`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" | "false" => Some(NotificationStyle::Off),
+            "osc9" | "on" | "true" => Some(NotificationStyle::Osc9),
+            "osc777" => Some(NotificationStyle::Osc777),
+            "native" | "macos" => Some(NotificationStyle::Native),
+            _ => None,
+        }
+    }
+}
+
+/// Watches for conditions worth interrupting the user about and sends
+/// them as desktop notifications
+///
+/// Alerts fire once on the transition into a condition: when a watched
+/// process respawns, a thermal-throttle episode starts, or thermal
+/// pressure rises to heavy or critical.
+#[derive(Debug, Default)]
+pub struct Alerts {
+    style: NotificationStyle,
+    /// Inside tmux, sequences are wrapped so tmux passes them through
+    tmux: bool,
+    throttled: bool,
+    pressure_high: bool,
+    pending: Vec<String>,
+}
+
+impl Alerts {
+    pub fn new(style: NotificationStyle) -> Self {
+        Alerts {
+            style,
+            tmux: std::env::var_os("TMUX").is_some(),
+            ..Alerts::default()
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.style != NotificationStyle::Off
+    }
+
+    /// Queue an alert for every watched process that respawned
+    pub fn respawned(&mut self, diffs: &[RespawnDiff]) {
+        for diff in diffs {
+            self.pending.push(format!(
+                "{} respawned: PID {} -> {}",
+                diff.name, diff.old_pid, diff.new_pid
+            ));
+        }
+    }
+
+    /// Queue alerts for throttling and thermal pressure that began since
+    /// the last check
+    pub fn check_thermal(&mut self, sensors: &SensorLog, pressure: Option<ThermalPressure>) {
+        let throttled = sensors
+            .events
+            .front()
+            .is_some_and(|event| event.duration.is_none());
+        if throttled && !self.throttled {
+            self.pending.push("CPU is thermally throttled".to_string());
+        }
+        self.throttled = throttled;
+
+        let pressure_high = matches!(
+            pressure,
+            Some(ThermalPressure::Heavy | ThermalPressure::Critical)
+        );
+        if let Some(level) = pressure.filter(|_| pressure_high && !self.pressure_high) {
+            self.pending
+                .push(format!("Thermal pressure is {}", level.label()));
+        }
+        self.pressure_high = pressure_high;
+    }
+
+    /// Write queued alerts to the terminal, or post them natively
+    pub fn flush(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        if !self.enabled() || pending.is_empty() {
+            return Ok(());
+        }
+        for message in pending {
+            let message: String = message.chars().filter(|c| !c.is_control()).collect();
+            let sequence = match self.style {
+                NotificationStyle::Off => continue,
+                NotificationStyle::Osc9 => format!("\x1b]9;sysly: {}\x07", message),
+                NotificationStyle::Osc777 => format!("\x1b]777;notify;sysly;{}\x07", message),
+                NotificationStyle::Native => {
+                    post_native(&message);
+                    continue;
+                }
+            };
+            if self.tmux {
+                // DCS passthrough; tmux needs `allow-passthrough on`
+                write!(
+                    out,
+                    "\x1bPtmux;{}\x1b\\",
+                    sequence.replace('\x1b', "\x1b\x1b")
+                )?;
+            } else {
+                write!(out, "{}", sequence)?;
+            }
+        }
+        out.flush()
+    }
+}
+
+/// Post a notification through the desktop's own tool without waiting
+/// for it
+///
+/// A missing tool only loses the notification; the alert still fired.
+fn post_native(message: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        // The message is passed as an argument, so it needs no AppleScript
+        // quoting
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 1 of argv) with title \"sysly\"",
+            "-e",
+            "end run",
+            message,
+        ]);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["sysly", message]);
+        command
+    };
+    let spawned = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = spawned {
+        // Reaped off the main loop so it doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
+    }
+}
@@ -0,0 +1,229 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::Pid;
+
+use crate::export::{json_string, write_process_list, write_snapshot, ProcessRow};
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// How long a client gets for its whole exchange, request and response
+const DEADLINE: Duration = Duration::from_secs(5);
+
+/// Longest request line accepted, in bytes
+const MAX_REQUEST_LINE: u64 = 8 * 1024;
+
+/// Most header bytes accepted after the request line
+const MAX_HEADERS: u64 = 16 * 1024;
+
+/// Connections answered at once; more are closed unanswered
+const MAX_CONNECTIONS: usize = 16;
+
+/// The data as of the last refresh, rendered only when asked for
+#[derive(Debug)]
+struct Published {
+    system: Snapshot,
+    /// The processes the display or report shows, in its order
+    listed: Vec<Pid>,
+}
+
+/// A read-only HTTP server answering with the JSON export's documents
+///
+/// The main loop hands over each refresh and connections render from it on
+/// their own threads, so a client polling fast costs no extra collection,
+/// an idle server costs no serialization, and a slow client holds up
+/// nobody else.
+pub struct ApiServer {
+    published: Arc<Mutex<Option<Arc<Published>>>>,
+}
+
+impl ApiServer {
+    /// Bind to `addr` and answer requests on background threads
+    pub fn start(addr: SocketAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let published = Arc::new(Mutex::new(None));
+
+        let shared = Arc::clone(&published);
+        thread::spawn(move || {
+            let open = Arc::new(AtomicUsize::new(0));
+            for stream in listener.incoming().flatten() {
+                if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    open.fetch_sub(1, Ordering::SeqCst);
+                    continue;
+                }
+                let shared = Arc::clone(&shared);
+                let open = Arc::clone(&open);
+                thread::spawn(move || {
+                    // A client that hangs up early only loses its own answer
+                    let _ = respond(stream, &shared);
+                    open.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        Ok(ApiServer { published })
+    }
+
+    /// Make a fresh refresh available, with `listed` as the processes the
+    /// display or report shows
+    pub fn publish(&self, sys: &Snapshot, listed: &[&ProcessSnapshot]) {
+        let published = Published {
+            system: sys.clone(),
+            listed: listed.iter().map(|process| process.pid()).collect(),
+        };
+        if let Ok(mut current) = self.published.lock() {
+            *current = Some(Arc::new(published));
+        }
+    }
+}
+
+/// Read one request and answer it, then close the connection
+fn respond(stream: TcpStream, published: &Mutex<Option<Arc<Published>>>) -> io::Result<()> {
+    let mut stream = Deadline {
+        stream,
+        until: Instant::now() + DEADLINE,
+    };
+    let (status, body) = match read_request(&mut stream)? {
+        Ok((method, _)) if method != "GET" => (
+            "405 Method Not Allowed",
+            error_body("only GET is supported"),
+        ),
+        Ok((_, path)) => {
+            // Render outside the lock so the next refresh isn't held up
+            let current = published.lock().map_err(|_| io::ErrorKind::Other)?.clone();
+            match current {
+                Some(published) => route(&published, &path),
+                None => ("503 Service Unavailable", error_body("no data yet")),
+            }
+        }
+        Err(status) => (status, error_body("request too large")),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// The method and path of the request, or the status refusing it when its
+/// request line or headers run past their limits
+fn read_request(stream: &mut Deadline) -> io::Result<Result<(String, String), &'static str>> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    (&mut reader)
+        .take(MAX_REQUEST_LINE)
+        .read_line(&mut request_line)?;
+    if !request_line.ends_with('\n') && request_line.len() as u64 >= MAX_REQUEST_LINE {
+        return Ok(Err("414 URI Too Long"));
+    }
+    // Headers are read and ignored, so the client isn't cut off mid-request
+    let mut headers = (&mut reader).take(MAX_HEADERS);
+    let mut header = String::new();
+    while headers.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+    if headers.limit() == 0 {
+        return Ok(Err("431 Request Header Fields Too Large"));
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+    let path = path
+        .strip_suffix('/')
+        .filter(|p| !p.is_empty())
+        .unwrap_or(path);
+    Ok(Ok((method, path.to_string())))
+}
+
+/// The status and body answering `path`
+fn route(published: &Published, path: &str) -> (&'static str, String) {
+    let system = &published.system;
+    let listed: Vec<&ProcessSnapshot> = published
+        .listed
+        .iter()
+        .filter_map(|pid| system.process(*pid))
+        .collect();
+    // Writing to memory can't fail
+    let mut body = Vec::new();
+    match path {
+        "/snapshot" => {
+            let _ = write_snapshot(&mut body, system, &listed);
+        }
+        "/processes" => {
+            let _ = write_process_list(&mut body, &listed);
+        }
+        path => match path.strip_prefix("/process/") {
+            Some(pid) => {
+                let process = pid
+                    .parse()
+                    .ok()
+                    .and_then(|pid: usize| system.process(Pid::from(pid)))
+                    .filter(|process| process.thread_kind().is_none());
+                match process {
+                    Some(process) => {
+                        let _ =
+                            serde_json::to_writer(&mut body, &ProcessRow::from_process(process));
+                        body.push(b'\n');
+                    }
+                    None => return ("404 Not Found", error_body(&format!("no process {}", pid))),
+                }
+            }
+            None => {
+                return (
+                    "404 Not Found",
+                    error_body(&format!("no endpoint {}", path)),
+                )
+            }
+        },
+    }
+    ("200 OK", String::from_utf8_lossy(&body).into_owned())
+}
+
+/// A JSON object carrying an error message
+fn error_body(message: &str) -> String {
+    format!("{{\"error\": {}}}\n", json_string(message))
+}
+
+/// A connection whose reads and writes all share one deadline, so a client
+/// trickling bytes can't keep its thread past it
+struct Deadline {
+    stream: TcpStream,
+    until: Instant,
+}
+
+impl Deadline {
+    /// Time left, or a timeout error once it has run out
+    fn remaining(&self) -> io::Result<Duration> {
+        self.until
+            .checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "client too slow"))
+    }
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.set_read_timeout(Some(self.remaining()?))?;
+        self.stream.read(buf)
+    }
+}
+
+impl Write for Deadline {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.set_write_timeout(Some(self.remaining()?))?;
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use sysinfo::ProcessStatus;
+
+use crate::cli::Options;
+use crate::sort::{read_sched, sort_processes, SortReadings};
+use sysly_core::helpers::{format_bytes, format_runtime, format_uptime, TimeFormat};
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Processes listed in each report, unless `--top` gives a count
+const PROCESS_LINES: usize = 20;
+
+/// Write one `top -b` style report: a summary of the system, then a table of
+/// the top processes
+///
+/// Plain text without escape codes, so it reads the same in a file, a pipe
+/// or an ssh session without a TTY. Processes follow the filters and sort
+/// key given on the command line.
+pub fn write_report(
+    out: &mut impl Write,
+    sys: &Snapshot,
+    users: &HashMap<u32, String>,
+    options: &Options,
+) -> io::Result<()> {
+    let load = sys.load_average();
+    writeln!(
+        out,
+        "sysly - {} up {}, load average: {:.2}, {:.2}, {:.2}",
+        chrono::Local::now().format("%H:%M:%S"),
+        format_uptime(sys.uptime(), TimeFormat::Clock),
+        load.one,
+        load.five,
+        load.fifteen
+    )?;
+
+    let processes: Vec<&ProcessSnapshot> = sys
+        .processes()
+        .values()
+        .filter(|process| process.thread_kind().is_none())
+        .collect();
+    let count = |status: ProcessStatus| {
+        processes
+            .iter()
+            .filter(|process| process.status() == status)
+            .count()
+    };
+    writeln!(
+        out,
+        "Tasks: {} total, {} running, {} sleeping, {} stopped, {} zombie",
+        processes.len(),
+        count(ProcessStatus::Run),
+        count(ProcessStatus::Sleep),
+        count(ProcessStatus::Stop),
+        count(ProcessStatus::Zombie)
+    )?;
+    writeln!(
+        out,
+        "CPU: {:.1}% used, cores: {}",
+        sys.global_cpu_info().cpu_usage(),
+        sys.cpus().len()
+    )?;
+    writeln!(
+        out,
+        "Mem: {} total, {} used, {} available",
+        format_bytes(sys.total_memory()),
+        format_bytes(sys.used_memory()),
+        format_bytes(sys.available_memory())
+    )?;
+    writeln!(
+        out,
+        "Swap: {} total, {} used",
+        format_bytes(sys.total_swap()),
+        format_bytes(sys.used_swap())
+    )?;
+    writeln!(out)?;
+
+    let mut listed: Vec<&ProcessSnapshot> = processes
+        .into_iter()
+        .filter(|process| options.matches(sys, process))
+        .collect();
+    let order = options.sort.unwrap_or_default();
+    let sched = read_sched(&listed, order);
+    let readings = SortReadings {
+        sched: Some(&sched),
+        ..SortReadings::default()
+    };
+    sort_processes(&mut listed, order, readings);
+
+    writeln!(
+        out,
+        "{:>7} {:<12} {:1} {:>5} {:>5} {:>8} {:>8} {:>9}  Command",
+        "PID", "USER", "S", "CPU%", "MEM%", "RES", "VIRT", "TIME+"
+    )?;
+    for process in listed.iter().take(options.limit.unwrap_or(PROCESS_LINES)) {
+        let user = process
+            .user_id()
+            .and_then(|uid| users.get(uid))
+            .map_or("?", String::as_str);
+        let command = if process.cmd().is_empty() {
+            process.name().to_string()
+        } else {
+            // Arguments may hold newlines, which would break the table
+            process.cmd().join(" ").replace(char::is_control, " ")
+        };
+        writeln!(
+            out,
+            "{:>7} {:<12.12} {:1} {:>5.1} {:>5.1} {:>8} {:>8} {:>9}  {}",
+            process.pid().as_u32(),
+            user,
+            status_char(process.status()),
+            process.cpu_usage(),
+            process.memory() as f64 / sys.total_memory().max(1) as f64 * 100.0,
+            format_bytes(process.memory()),
+            format_bytes(process.virtual_memory()),
+            format_runtime(process.run_time(), TimeFormat::Clock),
+            command
+        )?;
+    }
+
+    // A blank line separates one report from the next
+    writeln!(out)?;
+    out.flush()
+}
+
+fn status_char(status: ProcessStatus) -> char {
+    match status {
+        ProcessStatus::Run => 'R',
+        ProcessStatus::Sleep => 'S',
+        ProcessStatus::Stop => 'T',
+        ProcessStatus::Zombie => 'Z',
+        ProcessStatus::Idle => 'I',
+        ProcessStatus::UninterruptibleDiskSleep => 'D',
+        _ => '?',
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use sysinfo::ProcessStatus;
+
+use crate::cli::Options;
+use sysly_core::snapshot::Snapshot;
+
+/// Column names of the capture, written once at the top of a new file
+const HEADER: &str = "timestamp,pid,ppid,user,name,state,cpu_percent,memory_percent,\
+resident_bytes,virtual_bytes,run_time_seconds,command";
+
+/// A CSV capture of every process at each refresh, for `--csv`
+///
+/// Rows are appended, so several runs can add to the same file and a long
+/// capture can be loaded into a spreadsheet or pandas as it grows.
+pub struct CsvCapture {
+    out: BufWriter<File>,
+}
+
+impl CsvCapture {
+    /// Open the file for appending, writing the header if it is new or empty
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let empty = file.metadata()?.len() == 0;
+        let mut out = BufWriter::new(file);
+        if empty {
+            writeln!(out, "{}", HEADER)?;
+            out.flush()?;
+        }
+        Ok(CsvCapture { out })
+    }
+
+    /// Append a row per process from the latest refresh, all stamped with
+    /// the same time
+    ///
+    /// Threads are left out, and the `--user`/`--pid` filters apply.
+    pub fn record(
+        &mut self,
+        sys: &Snapshot,
+        users: &HashMap<u32, String>,
+        options: &Options,
+    ) -> io::Result<()> {
+        let timestamp = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+        let total_memory = sys.total_memory().max(1) as f64;
+
+        let processes = sys
+            .processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none() && options.matches(sys, process));
+        for process in processes {
+            let user = process
+                .user_id()
+                .and_then(|uid| users.get(uid))
+                .map_or("", String::as_str);
+            writeln!(
+                self.out,
+                "{},{},{},{},{},{},{:.1},{:.2},{},{},{},{}",
+                timestamp,
+                process.pid(),
+                process
+                    .parent()
+                    .map_or(String::new(), |ppid| ppid.to_string()),
+                csv_field(user),
+                csv_field(process.name()),
+                state_name(process.status()),
+                process.cpu_usage(),
+                process.memory() as f64 / total_memory * 100.0,
+                process.memory(),
+                process.virtual_memory(),
+                process.run_time(),
+                csv_field(&process.cmd().join(" "))
+            )?;
+        }
+        self.out.flush()
+    }
+}
+
+/// Quote a field when it holds a comma, quote or line break, doubling any
+/// quotes inside, as RFC 4180 has it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A process status as `--csv`, `--record` and the JSON export name it
+pub fn state_name(status: ProcessStatus) -> &'static str {
+    match status {
+        ProcessStatus::Run => "running",
+        ProcessStatus::Sleep => "sleeping",
+        ProcessStatus::Idle => "idle",
+        ProcessStatus::Stop => "stopped",
+        ProcessStatus::Zombie => "zombie",
+        ProcessStatus::UninterruptibleDiskSleep => "uninterruptible",
+        _ => "other",
+    }
+}
+
+/// The status a `state_name` stands for
+pub fn parse_state(name: &str) -> ProcessStatus {
+    match name {
+        "running" => ProcessStatus::Run,
+        "sleeping" => ProcessStatus::Sleep,
+        "idle" => ProcessStatus::Idle,
+        "stopped" => ProcessStatus::Stop,
+        "zombie" => ProcessStatus::Zombie,
+        "uninterruptible" => ProcessStatus::UninterruptibleDiskSleep,
+        _ => ProcessStatus::Unknown(0),
+    }
+}
@@ -0,0 +1,653 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+use sysinfo::Pid;
+
+use crate::agent::DEFAULT_PORT;
+use crate::export::TreeFormat;
+use crate::pidlog::default_path;
+use crate::sort::SortOrder;
+use crate::stress::StressOptions;
+use crate::tree::descends_from;
+use sysly_core::helpers::parse_size;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Usage text printed for `--help` and argument errors
+pub const USAGE: &str = "\
+Usage: sysly [options]
+       sysly tree [--dot] [-o FILE]
+       sysly stress [--cpu N] [--mem SIZE] [--duration SECONDS]
+       sysly log-pid PID [--out FILE] [-d DELAY]
+       sysly agent [--listen ADDR] [-d DELAY]
+       sysly replay FILE [-C]
+
+Print the process tree with CPU and memory annotations, as indented text
+or as a Graphviz DOT graph (--dot, or a FILE ending in .dot or .gv).
+
+Load N CPUs (default: all) and hold SIZE of memory (e.g. 512M, 2G) for
+SECONDS (default: 60) while the monitor runs, then print a before/after
+report of CPU, memory, and temperatures.
+
+Record PID's CPU and memory to a CSV file (default: sysly-PID.csv) every
+DELAY tenths of a second (default: 10) until it exits.
+
+Run headless and stream a snapshot every DELAY to each viewer connected to
+ADDR (HOST:PORT, or a PORT on 127.0.0.1; default: 127.0.0.1:7411), for
+--connect to show. The stream is unauthenticated, so other machines can
+only connect when ADDR names a wider HOST, e.g. 0.0.0.0:7411.
+
+Play back a database written by --record: Space plays and pauses, Left
+and Right step one snapshot, + and - change the speed.
+
+Options:
+  -d, --delay=DELAY       Delay between updates, in tenths of seconds
+  -u, --user[=USERNAME]   Show only processes of a given user (default: you)
+  -p, --pid=PID[,PID...]  Show only the given PIDs and their children
+  -n, --top=COUNT         Show only the first COUNT processes in sort order
+  -s, --sort=COLUMN[,COLUMN2]
+                          Sort by COLUMN (PERCENT_CPU, PERCENT_MEM, M_VIRT,
+                          PID, TIME, STARTTIME, COMM, QOS), breaking ties
+                          by COLUMN2;
+                          --sort-key is accepted too
+  -t, --tree              Start in tree view
+  -C, --no-color          Use a monochrome scheme of bold and reverse
+                          video; also when NO_COLOR is set or TERM=dumb
+      --title             Set the terminal title to a live summary
+                          (\"sysly: cpu 34% mem 62% HOST\")
+      --control=SOURCE    Read commands (sort:COLUMN, filter:TEXT,
+                          export:PATH, tree:PATH, quit) from SOURCE, one
+                          per line; - reads them from stdin
+      --linear            Print the dashboard as plain sentences every few
+                          seconds instead of drawing it, for screen readers
+                          and braille displays
+  -b, --batch             Print the system summary and top processes as
+                          plain text every DELAY instead of drawing them,
+                          like `top -b`, for files, pipes and ssh without
+                          a TTY
+      --json              Print a JSON snapshot of the system (CPUs,
+                          memory, load, processes) and exit
+      --csv=FILE          Append a row per process (time, PID, user, CPU,
+                          memory, ...) to FILE at every refresh, alongside
+                          the display, --batch or --linear
+      --record=FILE       Save a snapshot of the system and every process
+                          to the SQLite database FILE every 10 seconds,
+                          alongside the display, --batch or --linear
+      --event-log=FILE    Append a line to FILE for every process that
+                          starts or exits (time, event, PID, user, exit
+                          reason, command), as the Events view lists them
+      --listen=ADDR       Serve /snapshot, /processes and /process/PID as
+                          JSON over HTTP on ADDR (HOST:PORT, or a PORT on
+                          127.0.0.1) while the display, --batch or
+                          --linear runs
+      --connect=HOST[:PORT]
+                          Show the snapshots a `sysly agent` on HOST
+                          streams (default port: 7411; an IPv6 HOST goes
+                          in brackets before a PORT) instead of this
+                          machine
+  -h, --help              Print this help and exit
+  -V, --version           Print version information and exit";
+
+/// Startup options, accepting htop's common flags
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Refresh interval; None keeps the default
+    pub delay: Option<Duration>,
+    /// Only show processes owned by this UID
+    pub user: Option<u32>,
+    /// Only show these PIDs and their descendants
+    pub pids: Option<HashSet<Pid>>,
+    /// Show at most this many processes, from the top of the sort order
+    pub limit: Option<usize>,
+    pub sort: Option<SortOrder>,
+    pub tree: bool,
+    pub no_color: bool,
+    /// Keep the terminal title set to a CPU/memory summary
+    pub title: bool,
+    /// Where to read control commands from, `-` for stdin
+    pub control: Option<String>,
+    /// Print plain-text reports instead of the full-screen display
+    pub linear: bool,
+    /// Print `top -b` style tables instead of the full-screen display
+    pub batch: bool,
+    /// Print one JSON snapshot of the system and exit
+    pub json: bool,
+    /// Append every process to this CSV file at each refresh
+    pub csv: Option<PathBuf>,
+    /// Record snapshots to this SQLite database
+    pub record: Option<PathBuf>,
+    /// Append process start and exit events to this file
+    pub event_log: Option<PathBuf>,
+    /// Serve the JSON API on this address
+    pub listen: Option<SocketAddr>,
+    /// Show the snapshots of the agent at this address
+    pub connect: Option<String>,
+}
+
+impl Options {
+    /// Whether a process passes the `--user` and `--pid` filters
+    pub fn matches(&self, sys: &Snapshot, process: &ProcessSnapshot) -> bool {
+        let user_matches = self
+            .user
+            .is_none_or(|uid| process.user_id().is_some_and(|id| **id == uid));
+        let pid_matches = self
+            .pids
+            .as_ref()
+            .is_none_or(|pids| descends_from(sys, process, pids));
+        user_matches && pid_matches
+    }
+}
+
+/// What the command line asked sysly to do
+#[derive(Debug)]
+pub enum Invocation {
+    Interactive(Options),
+    /// `sysly tree`: print the process tree to stdout or a file
+    Tree {
+        format: TreeFormat,
+        output: Option<PathBuf>,
+    },
+    /// `sysly stress`: generate load while the monitor records it
+    Stress(StressOptions),
+    /// `sysly agent`: stream snapshots to remote viewers
+    Agent {
+        listen: SocketAddr,
+        /// Refresh interval; None keeps the default
+        delay: Option<Duration>,
+    },
+    /// `sysly replay`: play back a `--record` database
+    Replay {
+        path: PathBuf,
+        no_color: bool,
+    },
+    /// `sysly log-pid`: record one process to CSV until it exits
+    LogPid {
+        pid: Pid,
+        output: PathBuf,
+        /// Refresh interval; None keeps the default
+        delay: Option<Duration>,
+    },
+    Help,
+    Version,
+}
+
+/// Parse command-line arguments, excluding the program name
+///
+/// Short options take their value attached (`-d10`) or as the next argument
+/// (`-d 10`); long options accept `--delay=10` or `--delay 10`. Short
+/// options without a value can share a dash, as in `-tC` or `-td10`. As in
+/// htop, `-u` without a following user name means the current user.
+///
+/// # Returns
+/// The requested invocation, or a message describing the bad argument
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Invocation, String> {
+    let mut options = Options {
+        no_color: color_disabled_by_env(),
+        ..Options::default()
+    };
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "tree").is_some() {
+        return parse_tree(args);
+    }
+    if args.next_if(|arg| arg == "stress").is_some() {
+        return parse_stress(args);
+    }
+    if args.next_if(|arg| arg == "log-pid").is_some() {
+        return parse_log_pid(args);
+    }
+    if args.next_if(|arg| arg == "agent").is_some() {
+        return parse_agent(args);
+    }
+    if args.next_if(|arg| arg == "replay").is_some() {
+        return parse_replay(args);
+    }
+
+    // The flags left of a cluster such as `-tC` once its first is taken
+    let mut clustered: Option<String> = None;
+    while let Some(arg) = clustered.take().or_else(|| args.next()) {
+        let (flag, mut inline) = split_flag(&arg)?;
+        if SWITCHES.contains(&flag.as_str()) {
+            clustered = inline.take().map(|rest| format!("-{}", rest));
+        }
+        let mut value = |name: &str| -> Result<String, String> {
+            inline
+                .take()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("option '{}' requires an argument", name))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Invocation::Help),
+            "-V" | "--version" => return Ok(Invocation::Version),
+            "-t" | "--tree" => options.tree = true,
+            "-C" | "--no-color" | "--no-colour" => options.no_color = true,
+            "--title" => options.title = true,
+            "--linear" => options.linear = true,
+            "-b" | "--batch" => options.batch = true,
+            "--json" => options.json = true,
+            "--csv" => options.csv = Some(PathBuf::from(value(&flag)?)),
+            "--record" => options.record = Some(PathBuf::from(value(&flag)?)),
+            "--event-log" => options.event_log = Some(PathBuf::from(value(&flag)?)),
+            "-d" | "--delay" => options.delay = Some(parse_delay(&value(&flag)?)?),
+            "--control" => options.control = Some(value(&flag)?),
+            "--listen" => options.listen = Some(parse_listen(&value(&flag)?)?),
+            "--connect" => options.connect = Some(parse_connect(&value(&flag)?)?),
+            "-p" | "--pid" => {
+                let raw = value(&flag)?;
+                let pids = raw
+                    .split(',')
+                    .map(|pid| {
+                        pid.trim()
+                            .parse::<usize>()
+                            .map(Pid::from)
+                            .map_err(|_| format!("invalid PID '{}'", pid))
+                    })
+                    .collect::<Result<HashSet<Pid>, String>>()?;
+                options.pids = Some(pids);
+            }
+            "-n" | "--top" => {
+                let raw = value(&flag)?;
+                let count = raw
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| format!("invalid process count '{}'", raw))?;
+                options.limit = Some(count);
+            }
+            "-s" | "--sort-key" | "--sort" => {
+                let raw = value(&flag)?;
+                let order =
+                    SortOrder::parse(&raw).ok_or_else(|| format!("invalid column '{}'", raw))?;
+                options.sort = Some(order);
+            }
+            "-u" | "--user" => {
+                // The user name is optional, so only take the next argument
+                // when it isn't another flag
+                let name = inline
+                    .take()
+                    .or_else(|| args.next_if(|next| !next.starts_with('-')));
+                let uid = match name {
+                    Some(name) => users::get_user_by_name(&name)
+                        .map(|user| user.uid())
+                        .ok_or_else(|| format!("invalid user '{}'", name))?,
+                    None => users::get_current_uid(),
+                };
+                options.user = Some(uid);
+            }
+            _ => return Err(format!("unrecognized option '{}'", arg)),
+        }
+
+        if inline.is_some() {
+            return Err(format!("option '{}' doesn't allow an argument", flag));
+        }
+    }
+
+    Ok(Invocation::Interactive(options))
+}
+
+/// Parse the arguments of `sysly tree`
+fn parse_tree(args: impl Iterator<Item = String>) -> Result<Invocation, String> {
+    let mut dot = false;
+    let mut output: Option<PathBuf> = None;
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = split_flag(&arg)?;
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Invocation::Help),
+            "--dot" if inline.is_none() => dot = true,
+            "-o" | "--output" => {
+                let path = inline
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("option '{}' requires an argument", flag))?;
+                output = Some(PathBuf::from(path));
+            }
+            _ => return Err(format!("unrecognized option '{}'", arg)),
+        }
+    }
+
+    let format = match &output {
+        _ if dot => TreeFormat::Dot,
+        Some(path) => TreeFormat::from_path(path),
+        None => TreeFormat::Text,
+    };
+    Ok(Invocation::Tree { format, output })
+}
+
+/// Parse the arguments of `sysly stress`
+fn parse_stress(args: impl Iterator<Item = String>) -> Result<Invocation, String> {
+    let mut options = StressOptions {
+        cpus: std::thread::available_parallelism().map_or(1, |cpus| cpus.get()),
+        memory: 0,
+        duration: Duration::from_secs(60),
+    };
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = split_flag(&arg)?;
+        if matches!(flag.as_str(), "-h" | "--help") {
+            return Ok(Invocation::Help);
+        }
+        let raw = inline
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("option '{}' requires an argument", flag))?;
+
+        match flag.as_str() {
+            "--cpu" => {
+                options.cpus = raw
+                    .parse()
+                    .map_err(|_| format!("invalid CPU count '{}'", raw))?
+            }
+            "--mem" => {
+                options.memory =
+                    parse_size(&raw).ok_or_else(|| format!("invalid size '{}'", raw))?
+            }
+            "--duration" => {
+                let seconds: u64 = raw
+                    .parse()
+                    .map_err(|_| format!("invalid duration '{}'", raw))?;
+                options.duration = Duration::from_secs(seconds.max(1));
+            }
+            _ => return Err(format!("unrecognized option '{}'", arg)),
+        }
+    }
+
+    Ok(Invocation::Stress(options))
+}
+
+/// Parse the arguments of `sysly log-pid`
+fn parse_log_pid(args: impl Iterator<Item = String>) -> Result<Invocation, String> {
+    let mut pid: Option<Pid> = None;
+    let mut output: Option<PathBuf> = None;
+    let mut delay: Option<Duration> = None;
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') && pid.is_none() {
+            let raw = arg
+                .parse::<usize>()
+                .map_err(|_| format!("invalid PID '{}'", arg))?;
+            pid = Some(Pid::from(raw));
+            continue;
+        }
+        let (flag, inline) = split_flag(&arg)?;
+        if matches!(flag.as_str(), "-h" | "--help") {
+            return Ok(Invocation::Help);
+        }
+        let raw = inline
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("option '{}' requires an argument", flag))?;
+
+        match flag.as_str() {
+            "-o" | "--out" => output = Some(PathBuf::from(raw)),
+            "-d" | "--delay" => delay = Some(parse_delay(&raw)?),
+            _ => return Err(format!("unrecognized option '{}'", arg)),
+        }
+    }
+
+    let pid = pid.ok_or_else(|| "log-pid needs a PID".to_string())?;
+    Ok(Invocation::LogPid {
+        pid,
+        output: output.unwrap_or_else(|| default_path(pid)),
+        delay,
+    })
+}
+
+/// Parse the arguments of `sysly agent`
+fn parse_agent(args: impl Iterator<Item = String>) -> Result<Invocation, String> {
+    // Only this machine unless a HOST is given: the stream carries every
+    // command line, unauthenticated
+    let mut listen = SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_PORT));
+    let mut delay: Option<Duration> = None;
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = split_flag(&arg)?;
+        if matches!(flag.as_str(), "-h" | "--help") {
+            return Ok(Invocation::Help);
+        }
+        let raw = inline
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("option '{}' requires an argument", flag))?;
+
+        match flag.as_str() {
+            "--listen" => listen = parse_listen(&raw)?,
+            "-d" | "--delay" => delay = Some(parse_delay(&raw)?),
+            _ => return Err(format!("unrecognized option '{}'", arg)),
+        }
+    }
+
+    Ok(Invocation::Agent { listen, delay })
+}
+
+/// Parse the arguments of `sysly replay`
+fn parse_replay(args: impl Iterator<Item = String>) -> Result<Invocation, String> {
+    let mut path: Option<PathBuf> = None;
+    let mut no_color = color_disabled_by_env();
+
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Invocation::Help),
+            "-C" | "--no-color" => no_color = true,
+            _ if !arg.starts_with('-') && path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unrecognized option '{}'", arg)),
+        }
+    }
+
+    let path = path.ok_or_else(|| "replay needs a recording FILE".to_string())?;
+    Ok(Invocation::Replay { path, no_color })
+}
+
+/// Whether the environment asks for monochrome output: `NO_COLOR` set to
+/// anything but an empty string (see no-color.org), or `TERM=dumb`
+fn color_disabled_by_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+        || std::env::var_os("TERM").is_some_and(|term| term == "dumb")
+}
+
+/// Parse a HOST:PORT address to listen on; a bare PORT means 127.0.0.1
+fn parse_listen(raw: &str) -> Result<SocketAddr, String> {
+    match raw.parse::<u16>() {
+        Ok(port) => Ok(SocketAddr::from((Ipv4Addr::LOCALHOST, port))),
+        Err(_) => raw
+            .parse()
+            .map_err(|_| format!("invalid listen address '{}'", raw)),
+    }
+}
+
+/// Parse a HOST[:PORT] agent address, giving one without a port the
+/// agent's; an IPv6 address may come bare or in brackets
+fn parse_connect(raw: &str) -> Result<String, String> {
+    if raw.parse::<SocketAddr>().is_ok() {
+        return Ok(raw.to_string());
+    }
+    let unbracketed = raw
+        .strip_prefix('[')
+        .and_then(|raw| raw.strip_suffix(']'))
+        .unwrap_or(raw);
+    if let Ok(ip) = unbracketed.parse::<IpAddr>() {
+        return Ok(SocketAddr::from((ip, DEFAULT_PORT)).to_string());
+    }
+    match raw.rsplit_once(':') {
+        None if !raw.is_empty() && !raw.starts_with('[') => Ok(format!("{}:{}", raw, DEFAULT_PORT)),
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+            Ok(raw.to_string())
+        }
+        _ => Err(format!("invalid agent address '{}'", raw)),
+    }
+}
+
+/// Parse a delay in tenths of a second; zero means the shortest, 0.1s
+fn parse_delay(raw: &str) -> Result<Duration, String> {
+    raw.parse::<u64>()
+        .ok()
+        .and_then(|tenths| tenths.max(1).checked_mul(100))
+        .map(Duration::from_millis)
+        .ok_or_else(|| format!("invalid delay value '{}'", raw))
+}
+
+/// Short options that take no value, which can be clustered behind one dash
+const SWITCHES: [&str; 5] = ["-h", "-V", "-t", "-C", "-b"];
+
+/// Split an argument into its flag and any value attached to it
+fn split_flag(arg: &str) -> Result<(String, Option<String>), String> {
+    if let Some(long) = arg.strip_prefix("--") {
+        return Ok(match long.split_once('=') {
+            Some((name, value)) => (format!("--{}", name), Some(value.to_string())),
+            None => (arg.to_string(), None),
+        });
+    }
+
+    let mut chars = arg.chars();
+    match (chars.next(), chars.next()) {
+        (Some('-'), Some(short)) => {
+            let rest: String = chars.collect();
+            Ok((format!("-{}", short), (!rest.is_empty()).then_some(rest)))
+        }
+        _ => Err(format!("unexpected argument '{}'", arg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn interactive(line: &str) -> Options {
+        match parse(args(line)) {
+            Ok(Invocation::Interactive(options)) => options,
+            other => panic!("{:?} parsed as {:?}", line, other),
+        }
+    }
+
+    #[test]
+    fn short_switches_cluster_behind_one_dash() {
+        let options = interactive("-tC");
+        assert!(options.tree && options.no_color);
+
+        let options = interactive("-td10");
+        assert!(options.tree);
+        assert_eq!(options.delay, Some(Duration::from_secs(1)));
+
+        let options = interactive("-bt -d 5");
+        assert!(options.batch && options.tree);
+        assert_eq!(options.delay, Some(Duration::from_millis(500)));
+
+        assert!(matches!(parse(args("-th")), Ok(Invocation::Help)));
+        assert_eq!(parse(args("-tx")).unwrap_err(), "unrecognized option '-x'");
+    }
+
+    #[test]
+    fn values_attach_or_follow() {
+        assert_eq!(interactive("-d10").delay, Some(Duration::from_secs(1)));
+        assert_eq!(
+            interactive("--delay=20").delay,
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(interactive("-n 5").limit, Some(5));
+        assert_eq!(
+            interactive("--connect far-away").connect,
+            Some(format!("far-away:{}", DEFAULT_PORT))
+        );
+        assert_eq!(
+            parse(args("--json=yes")).unwrap_err(),
+            "option '--json' doesn't allow an argument"
+        );
+        assert_eq!(
+            parse(args("-d")).unwrap_err(),
+            "option '-d' requires an argument"
+        );
+    }
+
+    #[test]
+    fn delays_too_long_to_count_are_rejected() {
+        assert_eq!(parse_delay("0"), Ok(Duration::from_millis(100)));
+        assert_eq!(
+            parse_delay("184467440737095516"),
+            Ok(Duration::from_millis(18446744073709551600))
+        );
+        assert_eq!(
+            parse(args("-d 184467440737095517")).unwrap_err(),
+            "invalid delay value '184467440737095517'"
+        );
+        for line in [
+            "agent -d 999999999999999999",
+            "log-pid 1 -d 999999999999999999",
+        ] {
+            assert_eq!(
+                parse(args(line)).unwrap_err(),
+                "invalid delay value '999999999999999999'"
+            );
+        }
+    }
+
+    #[test]
+    fn listen_addresses_default_to_this_machine() {
+        assert_eq!(
+            parse_listen("9000"),
+            Ok(SocketAddr::from((Ipv4Addr::LOCALHOST, 9000)))
+        );
+        assert_eq!(
+            parse_listen("0.0.0.0:7000"),
+            Ok(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 7000)))
+        );
+        assert!(parse_listen("far-away").is_err());
+    }
+
+    #[test]
+    fn agent_addresses_get_the_default_port_when_they_have_none() {
+        let port = DEFAULT_PORT;
+        assert_eq!(parse_connect("far-away"), Ok(format!("far-away:{}", port)));
+        assert_eq!(parse_connect("far-away:9000"), Ok("far-away:9000".into()));
+        assert_eq!(parse_connect("10.0.0.2"), Ok(format!("10.0.0.2:{}", port)));
+        assert_eq!(parse_connect("10.0.0.2:9000"), Ok("10.0.0.2:9000".into()));
+        assert_eq!(parse_connect("::1"), Ok(format!("[::1]:{}", port)));
+        assert_eq!(parse_connect("[::1]"), Ok(format!("[::1]:{}", port)));
+        assert_eq!(parse_connect("[::1]:9000"), Ok("[::1]:9000".into()));
+        assert!(parse_connect("far-away:port").is_err());
+        assert!(parse_connect("[far-away]").is_err());
+    }
+
+    #[test]
+    fn agents_listen_locally_unless_told_otherwise() {
+        match parse(args("agent")) {
+            Ok(Invocation::Agent { listen, delay }) => {
+                assert_eq!(
+                    listen,
+                    SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_PORT))
+                );
+                assert_eq!(delay, None);
+            }
+            other => panic!("parsed as {:?}", other),
+        }
+        match parse(args("agent --listen 0.0.0.0:7000 -d 20")) {
+            Ok(Invocation::Agent { listen, delay }) => {
+                assert_eq!(listen, SocketAddr::from((Ipv4Addr::UNSPECIFIED, 7000)));
+                assert_eq!(delay, Some(Duration::from_secs(2)));
+            }
+            other => panic!("parsed as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn replay_takes_one_recording() {
+        match parse(args("replay history.db -C")) {
+            Ok(Invocation::Replay { path, no_color }) => {
+                assert_eq!(path, PathBuf::from("history.db"));
+                assert!(no_color);
+            }
+            other => panic!("parsed as {:?}", other),
+        }
+        assert_eq!(
+            parse(args("replay")).unwrap_err(),
+            "replay needs a recording FILE"
+        );
+        assert_eq!(
+            parse(args("replay one.db two.db")).unwrap_err(),
+            "unrecognized option 'two.db'"
+        );
+    }
+}
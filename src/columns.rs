@@ -0,0 +1,383 @@
+use crate::sort::SortSemantics;
+
+/// A column of the process table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Pid,
+    Ppid,
+    Pgid,
+    Sid,
+    User,
+    Priority,
+    Nice,
+    Virtual,
+    Resident,
+    State,
+    Cpu,
+    Memory,
+    Time,
+    Started,
+    IdleWakeups,
+    Origin,
+    Energy,
+    Qos,
+    Nap,
+    DiskRead,
+    DiskWrite,
+    Container,
+    Command,
+}
+
+impl Column {
+    /// Every column, in the position each takes when it is switched on
+    pub const ALL: [Column; 23] = [
+        Column::Pid,
+        Column::Ppid,
+        Column::Pgid,
+        Column::Sid,
+        Column::User,
+        Column::Priority,
+        Column::Nice,
+        Column::Virtual,
+        Column::Resident,
+        Column::State,
+        Column::Cpu,
+        Column::Memory,
+        Column::Time,
+        Column::Started,
+        Column::IdleWakeups,
+        Column::Origin,
+        Column::Energy,
+        Column::Qos,
+        Column::Nap,
+        Column::DiskRead,
+        Column::DiskWrite,
+        Column::Container,
+        Column::Command,
+    ];
+
+    /// The htop-like columns shown without a configuration
+    pub const DEFAULT: [Column; 11] = [
+        Column::Pid,
+        Column::User,
+        Column::Priority,
+        Column::Nice,
+        Column::Virtual,
+        Column::Resident,
+        Column::State,
+        Column::Cpu,
+        Column::Memory,
+        Column::Time,
+        Column::Command,
+    ];
+
+    /// Columns read from this machine by PID rather than from the snapshot,
+    /// which mean nothing for processes from elsewhere
+    pub fn is_local(self) -> bool {
+        matches!(
+            self,
+            Column::Pgid | Column::Origin | Column::Qos | Column::Nap | Column::Container
+        )
+    }
+
+    /// Title in the table header
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Ppid => "PPID",
+            Column::Pgid => "PGID",
+            Column::Sid => "SID",
+            Column::User => "USER",
+            Column::Priority => "PRI",
+            Column::Nice => "NI",
+            Column::Virtual => "VIRT",
+            Column::Resident => "RES",
+            Column::State => "S",
+            Column::Cpu => "CPU% ",
+            Column::Memory => "MEM% ",
+            Column::Time => "TIME+",
+            Column::Started => "STARTED",
+            Column::IdleWakeups => "IDLEW",
+            Column::Origin => "ORIG",
+            Column::Energy => "ENERGY",
+            Column::Qos => "QOS",
+            Column::Nap => "NAP",
+            Column::DiskRead => "DISK R/s",
+            Column::DiskWrite => "DISK W/s",
+            Column::Container => "CONTAINER",
+            Column::Command => "Command",
+        }
+    }
+
+    /// Whether the column holds numbers, which are right-aligned so their
+    /// digits line up
+    pub fn is_numeric(self) -> bool {
+        !matches!(
+            self,
+            Column::User
+                | Column::State
+                | Column::Origin
+                | Column::Qos
+                | Column::Nap
+                | Column::Container
+                | Column::Command
+        )
+    }
+
+    /// How the column's values compare when the list is sorted by it, or
+    /// `None` for columns no `SortKey` sorts by
+    pub fn sort_semantics(self) -> Option<SortSemantics> {
+        match self {
+            Column::Pid
+            | Column::Virtual
+            | Column::Cpu
+            | Column::Memory
+            | Column::Started
+            | Column::Qos => Some(SortSemantics::Numeric),
+            Column::Time => Some(SortSemantics::Duration),
+            Column::Command => Some(SortSemantics::Text),
+            _ => None,
+        }
+    }
+
+    /// Whether sorting by the column lists the largest values first, as
+    /// usage and time columns do; start times list the newest first, and
+    /// PIDs and names go in ascending order
+    pub fn sorts_descending(self) -> bool {
+        !matches!(self, Column::Pid | Column::Command)
+    }
+
+    /// Width in cells; the command takes at least this and any space left
+    pub fn width(self) -> u16 {
+        match self {
+            Column::Pid | Column::Ppid | Column::Pgid | Column::Sid => 7,
+            Column::User => 12,
+            Column::Priority => 5,
+            Column::Nice => 4,
+            Column::Virtual | Column::Resident | Column::Time | Column::Started => 8,
+            Column::State => 2,
+            Column::Cpu | Column::Memory | Column::IdleWakeups => 6,
+            Column::Origin | Column::Nap => 5,
+            Column::Energy => 7,
+            Column::Qos => 8,
+            Column::DiskRead | Column::DiskWrite => 9,
+            Column::Container => 16,
+            Column::Command => 10,
+        }
+    }
+
+    /// What the column shows, for the setup screen
+    pub fn description(self) -> &'static str {
+        match self {
+            Column::Pid => "Process ID",
+            Column::Ppid => "Parent process ID",
+            Column::Pgid => "Process group ID",
+            Column::Sid => "Session ID",
+            Column::User => "Owner",
+            Column::Priority => "Scheduling priority",
+            Column::Nice => "Nice value",
+            Column::Virtual => "Virtual memory size",
+            Column::Resident => "Resident memory size",
+            Column::State => "Process state",
+            Column::Cpu => "CPU usage",
+            Column::Memory => "Memory usage",
+            Column::Time => "CPU time",
+            Column::Started => "When the process started",
+            Column::IdleWakeups => "Idle wake-ups per second",
+            Column::Origin => "Where the executable came from",
+            Column::Energy => "Energy impact (powermetrics)",
+            Column::Qos => "QoS or scheduling class",
+            Column::Nap => "App Nap and background throttling",
+            Column::DiskRead => "Disk reads per second",
+            Column::DiskWrite => "Disk writes per second",
+            Column::Container => "Container the process runs in",
+            Column::Command => "Command line",
+        }
+    }
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::Ppid => "ppid",
+            Column::Pgid => "pgid",
+            Column::Sid => "sid",
+            Column::User => "user",
+            Column::Priority => "pri",
+            Column::Nice => "ni",
+            Column::Virtual => "virt",
+            Column::Resident => "res",
+            Column::State => "state",
+            Column::Cpu => "cpu",
+            Column::Memory => "mem",
+            Column::Time => "time",
+            Column::Started => "started",
+            Column::IdleWakeups => "idlew",
+            Column::Origin => "orig",
+            Column::Energy => "energy",
+            Column::Qos => "qos",
+            Column::Nap => "nap",
+            Column::DiskRead => "disk_read",
+            Column::DiskWrite => "disk_write",
+            Column::Container => "container",
+            Column::Command => "command",
+        }
+    }
+
+    /// Parse a config file name, ignoring case
+    pub fn parse(name: &str) -> Option<Self> {
+        Column::ALL
+            .into_iter()
+            .find(|column| column.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// A meter beside the CPU grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Meter {
+    /// Memory, split into app, wired, compressed and cached where known
+    Memory,
+    /// Swap and compressor occupancy, with swap activity
+    Swap,
+    /// Usage against the limits of sysly's cgroup
+    Cgroup,
+    /// GPU utilization and memory
+    Gpu,
+    /// Disk throughput
+    Disk,
+    Battery,
+}
+
+impl Meter {
+    /// Every meter, in the order they are drawn without a configuration
+    pub const ALL: [Meter; 6] = [
+        Meter::Memory,
+        Meter::Swap,
+        Meter::Cgroup,
+        Meter::Gpu,
+        Meter::Disk,
+        Meter::Battery,
+    ];
+
+    /// Title on the setup screen
+    pub fn title(self) -> &'static str {
+        match self {
+            Meter::Memory => "Memory",
+            Meter::Swap => "Swap",
+            Meter::Cgroup => "Cgroup",
+            Meter::Gpu => "GPU",
+            Meter::Disk => "Disk",
+            Meter::Battery => "Battery",
+        }
+    }
+
+    /// What the meter shows, for the setup screen
+    pub fn description(self) -> &'static str {
+        match self {
+            Meter::Memory => "Memory use and breakdown",
+            Meter::Swap => "Swap, compressor and paging",
+            Meter::Cgroup => "cgroup CPU and memory limits",
+            Meter::Gpu => "GPU utilization and memory",
+            Meter::Disk => "Disk throughput",
+            Meter::Battery => "Charge and time remaining",
+        }
+    }
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Meter::Memory => "memory",
+            Meter::Swap => "swap",
+            Meter::Cgroup => "cgroup",
+            Meter::Gpu => "gpu",
+            Meter::Disk => "disk",
+            Meter::Battery => "battery",
+        }
+    }
+
+    /// Parse a config file name, ignoring case
+    pub fn parse(name: &str) -> Option<Self> {
+        Meter::ALL
+            .into_iter()
+            .find(|meter| meter.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// How the CPU meters, and with braille the Mem meter, show usage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeterMode {
+    /// Current usage as bars
+    #[default]
+    Bars,
+    /// Each core's recent usage as a block graph, one reading per cell
+    Graphs,
+    /// Recent usage as braille dots, two readings per cell at four heights,
+    /// memory included
+    Braille,
+}
+
+impl MeterMode {
+    /// The mode after this one, wrapping around to bars
+    pub fn next(self) -> MeterMode {
+        match self {
+            MeterMode::Bars => MeterMode::Graphs,
+            MeterMode::Graphs => MeterMode::Braille,
+            MeterMode::Braille => MeterMode::Bars,
+        }
+    }
+
+    /// Parse a config value such as `bars` or `braille`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "bars" => Some(MeterMode::Bars),
+            "graphs" => Some(MeterMode::Graphs),
+            "braille" => Some(MeterMode::Braille),
+            _ => None,
+        }
+    }
+}
+
+/// Switch a set of columns on or off together
+///
+/// When any of them is missing they are all switched on, each where
+/// `insert_shown` puts it, so columns switched on by key land where they
+/// always have; otherwise they are all removed.
+pub fn toggle_columns(columns: &mut Vec<Column>, toggled: &[Column]) {
+    if toggled.iter().all(|column| columns.contains(column)) {
+        columns.retain(|column| !toggled.contains(column));
+        return;
+    }
+    for &column in toggled {
+        if !columns.contains(&column) {
+            insert_shown(columns, column, &Column::ALL);
+        }
+    }
+}
+
+/// Insert an item before the first shown one that follows it in `all`
+pub fn insert_shown<T: PartialEq>(shown: &mut Vec<T>, item: T, all: &[T]) {
+    let rank = |item: &T| all.iter().position(|other| other == item);
+    let position = shown
+        .iter()
+        .position(|other| rank(other) > rank(&item))
+        .unwrap_or(shown.len());
+    shown.insert(position, item);
+}
+
+/// Shown items in their order, then the hidden ones in the order of `all`,
+/// as the setup screen lists them
+pub fn listing<T: Copy + PartialEq>(shown: &[T], all: &[T]) -> Vec<T> {
+    let hidden = all.iter().filter(|item| !shown.contains(item));
+    shown.iter().chain(hidden).copied().collect()
+}
+
+/// Parse a comma-separated list of names, skipping unknown and repeated ones
+pub fn parse_list<T: PartialEq>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Vec<T> {
+    let mut items = Vec::new();
+    for item in value.split(',').filter_map(parse) {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items
+}
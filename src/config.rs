@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::alerts::NotificationStyle;
+use crate::columns::{self, Column, Meter, MeterMode};
+use crate::theme::{self, BarStyle};
+use sysly_core::helpers::TimeFormat;
+
+/// What sysly paints behind its panes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Background {
+    /// The color theme's solid background, whatever the terminal's own
+    #[default]
+    Black,
+    /// The terminal's own background, including transparency
+    Terminal,
+}
+
+impl Background {
+    /// Parse a config value such as `black` or `terminal`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "black" => Some(Background::Black),
+            "terminal" | "transparent" => Some(Background::Terminal),
+            _ => None,
+        }
+    }
+}
+
+/// User configuration loaded from `~/.config/sysly/config`
+///
+/// The file holds one `key = value` setting per line; blank lines and lines
+/// starting with `#` are ignored, as are unknown keys.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub time_format: TimeFormat,
+    /// Separator between groups of three digits in PID and count columns;
+    /// None leaves them ungrouped
+    pub thousands_separator: Option<String>,
+    /// CPU usage (percent of one core) above which sysly flags its own overhead
+    pub self_cpu_budget: f32,
+    /// Resident memory in bytes above which sysly flags its own overhead
+    pub self_memory_budget: u64,
+    /// Process table columns at startup, in order
+    pub columns: Vec<Column>,
+    /// Meters beside the CPU grid, in order
+    pub meters: Vec<Meter>,
+    /// Keep the terminal title set to a live CPU/memory summary
+    pub terminal_title: bool,
+    /// Weight of the newest reading in the moving average of process CPU
+    /// usage, in (0, 1]; 1 shows raw readings
+    pub cpu_smoothing: f32,
+    /// Sort the CPU% column by raw readings even when smoothing is on
+    pub sort_raw_cpu: bool,
+    /// Terminal notifications sent when alerts fire
+    pub notifications: NotificationStyle,
+    pub background: Background,
+    /// Name of the color theme
+    pub theme: &'static str,
+    /// What the CPU, memory and other meter bars are filled with
+    pub bar_style: BarStyle,
+    /// How the CPU meters start out: bars, or graphs of recent usage
+    pub meter_mode: MeterMode,
+    /// Filesystem types left out of the filesystem panel
+    pub hidden_filesystems: Vec<String>,
+    /// Time between runs of the slower collectors (ps, nettop,
+    /// powermetrics); None runs them with every refresh
+    pub collector_interval: Option<Duration>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            time_format: TimeFormat::default(),
+            thousands_separator: None,
+            self_cpu_budget: 5.0,
+            self_memory_budget: 100 * 1024 * 1024,
+            columns: Column::DEFAULT.to_vec(),
+            meters: Meter::ALL.to_vec(),
+            terminal_title: false,
+            cpu_smoothing: 1.0,
+            sort_raw_cpu: false,
+            notifications: NotificationStyle::default(),
+            background: Background::default(),
+            theme: theme::DEFAULT.name,
+            bar_style: BarStyle::default(),
+            meter_mode: MeterMode::default(),
+            hidden_filesystems: [
+                "autofs", "devfs", "devtmpfs", "nullfs", "overlay", "proc", "squashfs", "sysfs",
+                "tmpfs",
+            ]
+            .map(String::from)
+            .to_vec(),
+            collector_interval: None,
+        }
+    }
+}
+
+impl Config {
+    /// Load the configuration file, falling back to defaults when it is
+    /// missing or a value cannot be parsed
+    pub fn load() -> Self {
+        let settings = config_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| parse_settings(&contents))
+            .unwrap_or_default();
+
+        let mut config = Config::default();
+
+        if let Some(format) = settings
+            .get("time_format")
+            .and_then(|v| TimeFormat::parse(v))
+        {
+            config.time_format = format;
+        }
+
+        if let Some(value) = settings.get("thousands_separator") {
+            config.thousands_separator = match value.to_ascii_lowercase().as_str() {
+                "" | "none" | "off" => None,
+                "space" => Some(" ".to_string()),
+                "locale" => locale_thousands_separator(),
+                _ => Some(value.clone()),
+            };
+        }
+
+        if let Some(budget) = settings
+            .get("self_cpu_budget")
+            .and_then(|v| v.parse::<f32>().ok())
+        {
+            config.self_cpu_budget = budget;
+        }
+
+        if let Some(megabytes) = settings
+            .get("self_memory_budget_mb")
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            config.self_memory_budget = megabytes * 1024 * 1024;
+        }
+
+        // A comma-separated list in display order; without one, the older
+        // per-column switches add to the default columns
+        match settings.get("columns") {
+            Some(value) => {
+                let columns = columns::parse_list(value, Column::parse);
+                if !columns.is_empty() {
+                    config.columns = columns;
+                }
+            }
+            None => {
+                let switches = [
+                    ("group_columns", &[Column::Pgid, Column::Sid][..]),
+                    ("wakeups_column", &[Column::IdleWakeups]),
+                    ("origin_column", &[Column::Origin]),
+                ];
+                for (key, added) in switches {
+                    if settings.get(key).and_then(|v| v.parse::<bool>().ok()) == Some(true) {
+                        columns::toggle_columns(&mut config.columns, added);
+                    }
+                }
+            }
+        }
+
+        // Also comma-separated; an empty value hides every meter
+        if let Some(value) = settings.get("meters") {
+            config.meters = columns::parse_list(value, Meter::parse);
+        }
+
+        if let Some(enabled) = settings
+            .get("terminal_title")
+            .and_then(|v| v.parse::<bool>().ok())
+        {
+            config.terminal_title = enabled;
+        }
+
+        if let Some(alpha) = settings
+            .get("cpu_smoothing")
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|alpha| *alpha > 0.0 && *alpha <= 1.0)
+        {
+            config.cpu_smoothing = alpha;
+        }
+
+        if let Some(enabled) = settings
+            .get("sort_raw_cpu")
+            .and_then(|v| v.parse::<bool>().ok())
+        {
+            config.sort_raw_cpu = enabled;
+        }
+
+        if let Some(style) = settings
+            .get("notifications")
+            .and_then(|v| NotificationStyle::parse(v))
+        {
+            config.notifications = style;
+        }
+
+        if let Some(background) = settings
+            .get("background")
+            .and_then(|v| Background::parse(v))
+        {
+            config.background = background;
+        }
+
+        if let Some(index) = settings.get("theme").and_then(|v| theme::find(v)) {
+            config.theme = theme::PRESETS[index].name;
+        }
+
+        if let Some(style) = settings.get("bar_style").and_then(|v| BarStyle::parse(v)) {
+            config.bar_style = style;
+        }
+
+        if let Some(mode) = settings.get("meter_mode").and_then(|v| MeterMode::parse(v)) {
+            config.meter_mode = mode;
+        }
+
+        // A comma-separated list; an empty value shows every filesystem
+        if let Some(filesystems) = settings.get("hidden_filesystems") {
+            config.hidden_filesystems = filesystems
+                .split(',')
+                .map(str::trim)
+                .filter(|fs| !fs.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        if let Some(seconds) = settings
+            .get("collector_interval")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|seconds| *seconds > 0.0 && seconds.is_finite())
+        {
+            config.collector_interval = Some(Duration::from_secs_f64(seconds));
+        }
+
+        config
+    }
+}
+
+/// Location of the configuration file, honoring `XDG_CONFIG_HOME`
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(base.join("sysly").join("config"))
+}
+
+/// Set one `key = value` line in the configuration file
+///
+/// Lines already holding the key are replaced and everything else,
+/// comments included, is kept; a new key is appended. The file is created
+/// when there is none.
+///
+/// # Returns
+/// The path of the file written
+pub fn save_setting(key: &str, value: &str) -> io::Result<PathBuf> {
+    let path = config_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+
+    let setting = format!("{} = {}", key, value);
+    let mut replaced = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            let is_key = !trimmed.starts_with('#')
+                && trimmed
+                    .split_once('=')
+                    .is_some_and(|(name, _)| name.trim() == key);
+            if is_key {
+                replaced = true;
+                setting.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(setting);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, lines.join("\n") + "\n")?;
+    Ok(path)
+}
+
+/// Parse `key = value` lines into a map
+/// The thousands separator of the locale set in the environment (LC_ALL,
+/// LC_NUMERIC or LANG), or None when it doesn't group digits
+fn locale_thousands_separator() -> Option<String> {
+    // SAFETY: localeconv's result is read before the locale changes again,
+    // and sysly sets the locale nowhere else
+    unsafe {
+        libc::setlocale(libc::LC_NUMERIC, c"".as_ptr());
+        let conventions = libc::localeconv();
+        let separator = (!conventions.is_null() && !(*conventions).thousands_sep.is_null())
+            .then(|| {
+                std::ffi::CStr::from_ptr((*conventions).thousands_sep)
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .filter(|separator| !separator.is_empty());
+        libc::setlocale(libc::LC_NUMERIC, c"C".as_ptr());
+        separator
+    }
+}
+
+fn parse_settings(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
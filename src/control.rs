@@ -0,0 +1,76 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::sort::SortKey;
+
+/// A command read from the control channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// `sort:COLUMN`, taking the same column names as `--sort`
+    Sort(SortKey),
+    /// `filter:TEXT` to list only matching processes; `filter:` clears it
+    Filter(Option<String>),
+    /// `export:PATH` to write the listed processes as JSON
+    Export(PathBuf),
+    /// `tree:PATH` to write the full process tree, as DOT for `.dot` files
+    Tree(PathBuf),
+    /// `quit`
+    Quit,
+}
+
+impl ControlCommand {
+    /// Parse one line of the control channel
+    ///
+    /// # Returns
+    /// The command, or a message describing why the line was rejected
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (name, argument) = line.split_once(':').unwrap_or((line, ""));
+
+        match name {
+            "sort" => SortKey::from_htop_name(argument)
+                .map(ControlCommand::Sort)
+                .ok_or_else(|| format!("invalid column '{}'", argument)),
+            "filter" => Ok(ControlCommand::Filter(
+                (!argument.is_empty()).then(|| argument.to_string()),
+            )),
+            "export" if !argument.is_empty() => Ok(ControlCommand::Export(argument.into())),
+            "tree" if !argument.is_empty() => Ok(ControlCommand::Tree(argument.into())),
+            "export" | "tree" => Err(format!("{} needs a path", name)),
+            "quit" => Ok(ControlCommand::Quit),
+            _ => Err(format!("unknown command '{}'", line)),
+        }
+    }
+}
+
+/// Read commands from stdin (`-`) or a file such as a named pipe
+///
+/// Lines are read on a background thread so a quiet channel never stalls
+/// the UI; blank lines and `#` comments are skipped. The channel closes at
+/// end of input.
+pub fn spawn_reader(source: &str) -> io::Result<Receiver<Result<ControlCommand, String>>> {
+    let input: Box<dyn Read + Send> = match source {
+        "-" => Box::new(io::stdin()),
+        path => Box::new(File::open(path)?),
+    };
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in BufReader::new(input).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if sender.send(ControlCommand::parse(&line)).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(receiver)
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// How long the Docker daemon gets to answer all of one sample's requests
+const DEADLINE: Duration = Duration::from_secs(2);
+
+/// Usage of one running container, as `docker stats` shows it
+#[derive(Debug, Clone)]
+pub struct ContainerStats {
+    /// Short ID, the first 12 hex digits
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Percent of one core; None until a second sample gives a delta
+    pub cpu: Option<f64>,
+    /// Bytes in use, without the page cache
+    pub memory: u64,
+    pub memory_limit: u64,
+    /// Bytes received and sent by all interfaces since the container started
+    pub received: u64,
+    pub sent: u64,
+    pub pids: Option<u64>,
+}
+
+/// Containers read from the Docker Engine API over its Unix socket
+///
+/// Works with Docker Desktop on macOS (`~/.docker/run/docker.sock`) and the
+/// Linux daemon (`/var/run/docker.sock`), or the socket `DOCKER_HOST` names.
+#[derive(Debug, Default)]
+pub struct DockerTracker {
+    /// Container names by short ID, for the process table
+    names: HashMap<String, String>,
+    /// CPU time of each container and of the whole system at the last
+    /// sample, in nanoseconds
+    previous: HashMap<String, (f64, f64)>,
+    /// Each container's last reading, by full ID, for containers the
+    /// daemon didn't get to before the deadline
+    last: HashMap<String, ContainerStats>,
+}
+
+impl DockerTracker {
+    /// Names of the containers seen at the last listing, by short ID
+    pub fn names(&self) -> &HashMap<String, String> {
+        &self.names
+    }
+
+    /// List the running containers to keep their names current
+    pub fn refresh_names(&mut self) -> io::Result<()> {
+        let socket = socket()?;
+        self.list(&socket, Instant::now() + DEADLINE).map(|_| ())
+    }
+
+    /// Read the usage of every running container
+    ///
+    /// The containers are listed once, then each is asked for one stats
+    /// reading without the daemon's own one-second wait; CPU usage comes
+    /// from the delta to the last call. All requests share one deadline:
+    /// containers not reached by then keep their last reading, so a slow
+    /// daemon costs at most that long per sample.
+    pub fn sample(&mut self) -> io::Result<Vec<ContainerStats>> {
+        let deadline = Instant::now() + DEADLINE;
+        let socket = socket()?;
+        let containers = self.list(&socket, deadline)?;
+        let mut previous = HashMap::with_capacity(containers.len());
+        let mut last = HashMap::with_capacity(containers.len());
+        let mut sampled = Vec::with_capacity(containers.len());
+
+        for (id, name, image) in containers {
+            let stats = match get(
+                &socket,
+                &format!("/containers/{}/stats?stream=false&one-shot=true", id),
+                deadline,
+            ) {
+                Ok(stats) => stats,
+                Err(err) if err.kind() == io::ErrorKind::TimedOut => {
+                    if let Some(reading) = self.last.remove(&id) {
+                        if let Some(cpu_times) = self.previous.remove(&id) {
+                            previous.insert(id.clone(), cpu_times);
+                        }
+                        sampled.push(reading.clone());
+                        last.insert(id, reading);
+                    }
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let number = |path| stats.pointer(path).and_then(Value::as_f64);
+
+            let container_cpu = number("/cpu_stats/cpu_usage/total_usage").unwrap_or(0.0);
+            let system_cpu = number("/cpu_stats/system_cpu_usage").unwrap_or(0.0);
+            let online = number("/cpu_stats/online_cpus").unwrap_or(1.0);
+            let cpu = self
+                .previous
+                .get(&id)
+                .filter(|(_, last_system)| system_cpu > *last_system)
+                .map(|(last_container, last_system)| {
+                    (container_cpu - last_container).max(0.0) / (system_cpu - last_system)
+                        * online
+                        * 100.0
+                });
+            previous.insert(id.clone(), (container_cpu, system_cpu));
+
+            // The page cache is reclaimable, so `docker stats` leaves it out:
+            // inactive_file under cgroup v2, cache under v1
+            let cache = number("/memory_stats/stats/inactive_file")
+                .or_else(|| number("/memory_stats/stats/cache"))
+                .unwrap_or(0.0);
+            let usage = number("/memory_stats/usage").unwrap_or(0.0);
+            let (received, sent) = stats
+                .get("networks")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .fold((0.0, 0.0), |(received, sent), (_, interface)| {
+                    let bytes = |key| interface.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+                    (received + bytes("rx_bytes"), sent + bytes("tx_bytes"))
+                });
+
+            let reading = ContainerStats {
+                id: id[..id.len().min(12)].to_string(),
+                name,
+                image,
+                cpu,
+                memory: (usage - cache).max(0.0) as u64,
+                memory_limit: number("/memory_stats/limit").unwrap_or(0.0) as u64,
+                received: received as u64,
+                sent: sent as u64,
+                pids: number("/pids_stats/current").map(|pids| pids as u64),
+            };
+            sampled.push(reading.clone());
+            last.insert(id, reading);
+        }
+
+        self.previous = previous;
+        self.last = last;
+        Ok(sampled)
+    }
+
+    /// The ID, name and image of every running container, updating the names
+    fn list(
+        &mut self,
+        socket: &Path,
+        deadline: Instant,
+    ) -> io::Result<Vec<(String, String, String)>> {
+        let containers = listed(&get(socket, "/containers/json", deadline)?);
+        self.names = containers
+            .iter()
+            .map(|(id, name, _)| (id[..id.len().min(12)].to_string(), name.clone()))
+            .collect();
+        Ok(containers)
+    }
+}
+
+/// The ID, name and image of each container in a `/containers/json` answer
+fn listed(listing: &Value) -> Vec<(String, String, String)> {
+    listing
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|container| {
+            let id = container.get("Id")?.as_str()?.to_string();
+            // Names carry a leading slash, as in "/web-1"
+            let name = container
+                .pointer("/Names/0")
+                .and_then(Value::as_str)
+                .map_or_else(
+                    || id.clone(),
+                    |name| name.trim_start_matches('/').to_string(),
+                );
+            let image = container
+                .get("Image")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            Some((id, name, image))
+        })
+        .collect()
+}
+
+/// The Docker socket to talk to
+fn socket() -> io::Result<PathBuf> {
+    if let Some(host) = std::env::var_os("DOCKER_HOST") {
+        return match host.to_string_lossy().strip_prefix("unix://") {
+            Some(path) => Ok(PathBuf::from(path)),
+            None => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "DOCKER_HOST is not a unix:// socket",
+            )),
+        };
+    }
+
+    let desktop =
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".docker/run/docker.sock"));
+    desktop
+        .into_iter()
+        .chain([PathBuf::from("/var/run/docker.sock")])
+        .find(|path| path.exists())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Docker is not running"))
+}
+
+/// Send a GET request to the Docker API and parse the JSON answer
+///
+/// Fails with `TimedOut` once `deadline` passes, however far the answer got.
+fn get(socket: &Path, endpoint: &str, deadline: Instant) -> io::Result<Value> {
+    let remaining = || {
+        deadline
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "Docker took too long"))
+    };
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_write_timeout(Some(remaining()?))?;
+    // HTTP/1.0 gets a plain body that ends with the connection, not chunks
+    let request = format!("GET {} HTTP/1.0\r\nHost: docker\r\n\r\n", endpoint);
+    stream.write_all(request.as_bytes())?;
+    let mut response = Vec::new();
+    let mut chunk = [0; 8192];
+    loop {
+        stream.set_read_timeout(Some(remaining()?))?;
+        match stream.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => response.extend_from_slice(&chunk[..read]),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Docker took too long",
+                ))
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| invalid("truncated answer from Docker"))?;
+    let status = String::from_utf8_lossy(&response[..split]);
+    let status = status.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(io::Error::other(format!("Docker answered {}", status)));
+    }
+    serde_json::from_slice(&response[split + 4..]).map_err(io::Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_reads_escaped_names() {
+        let listing = serde_json::from_str(
+            r#"[{"Id": "0123456789abcdef", "Names": ["/web-\ud83d\ude80"], "Image": "nginx"},
+                {"Id": "fedcba9876543210", "Names": [], "Image": "redis"},
+                {"Names": ["/no-id"]}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            listed(&listing),
+            [
+                (
+                    "0123456789abcdef".into(),
+                    "web-\u{1f680}".into(),
+                    "nginx".into()
+                ),
+                (
+                    "fedcba9876543210".into(),
+                    "fedcba9876543210".into(),
+                    "redis".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn deeply_nested_answers_are_rejected() {
+        let nested = "[".repeat(100_000);
+        assert!(serde_json::from_str::<Value>(&nested).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use sysinfo::Pid;
+
+use sysly_core::origin::app_bundle;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Ancestors searched for the app of a process whose executable is unknown
+const MAX_APP_DEPTH: usize = 8;
+
+/// Energy use of one application, summed over its processes
+#[derive(Debug, Clone, Default)]
+pub struct AppEnergy {
+    /// App bundle name, or the process name outside a bundle
+    pub name: String,
+    /// Processes running now
+    pub processes: usize,
+    /// CPU usage now, in percent of one core
+    pub cpu: f32,
+    /// Energy impact now; None while powermetrics isn't running
+    pub impact: Option<f64>,
+    /// Energy impact integrated over the seconds it was measured
+    impact_seconds: f64,
+    /// Idle wake-ups per second now
+    pub wakeups: f64,
+    /// Idle wake-ups since sysly started
+    pub total_wakeups: f64,
+}
+
+/// Energy impact and wake-ups per application, accumulated over the
+/// session like Activity Monitor's Energy pane
+///
+/// Apps stay listed after their last process exits, so short-lived work
+/// still shows up in the session totals.
+#[derive(Debug, Default)]
+pub struct EnergyLedger {
+    apps: HashMap<String, AppEnergy>,
+    /// Time during which energy impact was being measured, to average over
+    measured: Duration,
+}
+
+impl EnergyLedger {
+    /// Fold one refresh into the per-app totals
+    ///
+    /// `energy` is the latest powermetrics sample, if it is running;
+    /// `wakeups` the idle wake-up rates over the same `interval`.
+    pub fn sample(
+        &mut self,
+        sys: &Snapshot,
+        cpu_usage: impl Fn(&ProcessSnapshot) -> f32,
+        energy: Option<&HashMap<u32, f64>>,
+        wakeups: &HashMap<Pid, f64>,
+        interval: Duration,
+    ) {
+        let seconds = interval.as_secs_f64();
+        for app in self.apps.values_mut() {
+            app.processes = 0;
+            app.cpu = 0.0;
+            app.impact = None;
+            app.wakeups = 0.0;
+        }
+
+        for (pid, process) in sys.processes() {
+            let name = app_name(sys, process);
+            let app = self.apps.entry(name.clone()).or_insert_with(|| AppEnergy {
+                name,
+                ..AppEnergy::default()
+            });
+            app.processes += 1;
+            app.cpu += cpu_usage(process);
+            if let Some(rate) = wakeups.get(pid) {
+                app.wakeups += rate;
+                app.total_wakeups += rate * seconds;
+            }
+            if let Some(energy) = energy {
+                let impact = energy.get(&pid.as_u32()).copied().unwrap_or(0.0);
+                app.impact = Some(app.impact.unwrap_or(0.0) + impact);
+                app.impact_seconds += impact * seconds;
+            }
+        }
+        if energy.is_some() {
+            self.measured += interval;
+        }
+    }
+
+    /// Average energy impact over the time it was measured
+    pub fn average_impact(&self, app: &AppEnergy) -> Option<f64> {
+        let seconds = self.measured.as_secs_f64();
+        (seconds > 0.0).then(|| app.impact_seconds / seconds)
+    }
+
+    /// Apps with the highest average energy impact first, then by wake-ups
+    pub fn apps(&self) -> Vec<&AppEnergy> {
+        let mut apps: Vec<&AppEnergy> = self.apps.values().collect();
+        apps.sort_by(|a, b| {
+            b.impact_seconds
+                .total_cmp(&a.impact_seconds)
+                .then(b.total_wakeups.total_cmp(&a.total_wakeups))
+                .then(b.cpu.total_cmp(&a.cpu))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        apps
+    }
+}
+
+/// Group a process under its app bundle, so an app's helpers count
+/// towards the app
+///
+/// A process whose executable can't be read, such as another user's
+/// helper, goes under the app of the nearest ancestor that is in a bundle.
+pub fn app_name(sys: &Snapshot, process: &ProcessSnapshot) -> String {
+    let bundle_name = |process: &ProcessSnapshot| {
+        process
+            .exe()
+            .and_then(app_bundle)
+            .and_then(|bundle| bundle.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+    };
+    if let Some(name) = bundle_name(process) {
+        return name;
+    }
+
+    let mut current = process;
+    for _ in 0..MAX_APP_DEPTH {
+        if current.exe().is_some() {
+            break;
+        }
+        let Some(parent) = current.parent().and_then(|pid| sys.process(pid)) else {
+            break;
+        };
+        if let Some(name) = bundle_name(parent) {
+            return name;
+        }
+        current = parent;
+    }
+    process.name().to_string()
+}
@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::capture::state_name;
+use crate::sort::{SortKey, SortOrder, SortReadings};
+use crate::tree::build_tree;
+use crate::ui::{display_order, AppState};
+use serde::{Deserialize, Serialize};
+use sysly_core::helpers::format_bytes;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// How a process tree export is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    /// Indented text with branch lines, like `pstree`
+    Text,
+    /// A Graphviz DOT digraph of parent -> child edges
+    Dot,
+}
+
+impl TreeFormat {
+    /// DOT for `.dot` and `.gv` files, text otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("dot" | "gv") => TreeFormat::Dot,
+            _ => TreeFormat::Text,
+        }
+    }
+}
+
+/// Write the full process tree, every process annotated with its CPU and
+/// resident memory
+///
+/// Filters and collapsed subtrees don't apply; siblings are ordered by PID
+/// and threads are left out, as in `pstree`.
+///
+/// # Returns
+/// The number of processes written
+pub fn write_tree(out: &mut impl Write, sys: &Snapshot, format: TreeFormat) -> io::Result<usize> {
+    let nodes = build_tree(
+        sys,
+        &HashSet::new(),
+        SortOrder::by(SortKey::Pid),
+        SortReadings::default(),
+        None,
+    );
+
+    if format == TreeFormat::Dot {
+        writeln!(out, "digraph processes {{")?;
+        writeln!(out, "  node [shape=box, fontname=monospace];")?;
+    }
+    let mut written = 0;
+    for node in &nodes {
+        let Some(process) = sys
+            .process(node.pid)
+            .filter(|process| process.thread_kind().is_none())
+        else {
+            continue;
+        };
+        let annotation = format!(
+            "cpu {:.1}% mem {}",
+            process.cpu_usage(),
+            format_bytes(process.memory())
+        );
+
+        match format {
+            TreeFormat::Text => writeln!(
+                out,
+                "{}{} {}  ({})",
+                node.prefix,
+                node.pid,
+                process.name(),
+                annotation
+            )?,
+            TreeFormat::Dot => {
+                let label = format!("{} {}\n{}", node.pid, process.name(), annotation);
+                writeln!(out, "  p{} [label={}];", node.pid, json_string(&label))?;
+                if let Some(parent) = process.parent().filter(|ppid| sys.process(*ppid).is_some()) {
+                    writeln!(out, "  p{} -> p{};", parent, node.pid)?;
+                }
+            }
+        }
+        written += 1;
+    }
+    if format == TreeFormat::Dot {
+        writeln!(out, "}}")?;
+    }
+    out.flush()?;
+
+    Ok(written)
+}
+
+/// Write the full process tree to a file, as DOT when its extension is
+/// `.dot` or `.gv`
+pub fn export_tree(path: &Path, sys: &Snapshot) -> io::Result<usize> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_tree(&mut out, sys, TreeFormat::from_path(path))
+}
+
+/// Write the processes currently listed as a JSON array
+///
+/// Rows follow the on-screen order, so the file matches what the table
+/// shows under the active sort and filters.
+pub fn export_json(path: &Path, sys: &Snapshot, app_state: &AppState) -> io::Result<usize> {
+    let processes = listed_processes(sys, app_state);
+    let mut out = BufWriter::new(File::create(path)?);
+    write_process_list(&mut out, &processes)?;
+    Ok(processes.len())
+}
+
+/// Write processes as a JSON array, one object per line
+pub fn write_process_list(out: &mut impl Write, processes: &[&ProcessSnapshot]) -> io::Result<()> {
+    let rows: Vec<ProcessRow> = processes
+        .iter()
+        .map(|process| ProcessRow::from_process(process))
+        .collect();
+    writeln!(out, "[")?;
+    for (index, row) in rows.iter().enumerate() {
+        write!(out, "  ")?;
+        serde_json::to_writer(&mut *out, row)?;
+        writeln!(out, "{}", if index + 1 < rows.len() { "," } else { "" })?;
+    }
+    writeln!(out, "]")?;
+    out.flush()
+}
+
+/// Write a snapshot of the whole system as one JSON object: host, uptime,
+/// load, per-CPU usage, memory and swap, and the given process rows
+///
+/// Memory figures are in bytes and CPU usage in percent (of one core for
+/// processes), so other tools can read them without knowing sysly's units.
+pub fn write_snapshot(
+    out: &mut impl Write,
+    sys: &Snapshot,
+    processes: &[&ProcessSnapshot],
+) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, &SnapshotDocument::new(sys, processes))?;
+    writeln!(out)?;
+    out.flush()
+}
+
+/// Write the system snapshot with the processes currently listed to a file
+///
+/// # Returns
+/// The number of processes written
+pub fn export_snapshot(path: &Path, sys: &Snapshot, app_state: &AppState) -> io::Result<usize> {
+    let processes = listed_processes(sys, app_state);
+    let mut out = BufWriter::new(File::create(path)?);
+    write_snapshot(&mut out, sys, &processes)?;
+    Ok(processes.len())
+}
+
+/// The processes the table lists, in its order
+pub fn listed_processes<'a>(sys: &'a Snapshot, app_state: &AppState) -> Vec<&'a ProcessSnapshot> {
+    display_order(sys, app_state)
+        .iter()
+        .filter_map(|node| sys.process(node.pid))
+        .collect()
+}
+
+/// The `--json` snapshot document, which is also what `sysly agent` streams
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDocument {
+    /// When the document was written, RFC 3339 in local time
+    pub timestamp: String,
+    pub host: Option<String>,
+    /// Seconds since boot
+    pub uptime: u64,
+    pub load: LoadDocument,
+    pub cpu: CpuDocument,
+    pub memory: MemoryDocument,
+    pub swap: SwapDocument,
+    pub processes: Vec<ProcessRow>,
+}
+
+/// Load averages over one, five and fifteen minutes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadDocument {
+    pub one: Option<f64>,
+    pub five: Option<f64>,
+    pub fifteen: Option<f64>,
+}
+
+/// Overall and per-core CPU usage in percent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuDocument {
+    /// The chip, e.g. `Apple M2 Pro`
+    #[serde(default)]
+    pub brand: String,
+    pub usage: Option<f32>,
+    pub cores: Vec<Option<f32>>,
+}
+
+/// Memory figures in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryDocument {
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+}
+
+/// Swap figures in bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapDocument {
+    pub total: u64,
+    pub used: u64,
+}
+
+/// One process as exported: the `--json` rows and the API's process objects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessRow {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub uid: Option<u32>,
+    pub user: Option<String>,
+    pub name: String,
+    /// Percent of one core
+    pub cpu: Option<f32>,
+    /// Resident memory in bytes
+    pub memory: u64,
+    /// Virtual memory in bytes
+    #[serde(rename = "virtual")]
+    pub virtual_memory: u64,
+    /// `running`, `sleeping` and so on, as in `--csv`
+    #[serde(default)]
+    pub state: String,
+    /// Seconds since the Unix epoch
+    #[serde(default)]
+    pub started: u64,
+    /// Seconds the process has been running
+    #[serde(default)]
+    pub run_time: u64,
+    pub command: String,
+}
+
+impl SnapshotDocument {
+    /// The system as of `sys`, listing `processes` in the given order
+    pub fn new(sys: &Snapshot, processes: &[&ProcessSnapshot]) -> Self {
+        let load = sys.load_average();
+        SnapshotDocument {
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            host: sys.host_name(),
+            uptime: sys.uptime(),
+            load: LoadDocument {
+                one: rounded(load.one, 100.0),
+                five: rounded(load.five, 100.0),
+                fifteen: rounded(load.fifteen, 100.0),
+            },
+            cpu: CpuDocument {
+                brand: sys
+                    .cpus()
+                    .first()
+                    .map(|cpu| cpu.brand().trim().to_string())
+                    .unwrap_or_default(),
+                usage: percent(sys.global_cpu_info().cpu_usage()),
+                cores: sys
+                    .cpus()
+                    .iter()
+                    .map(|cpu| percent(cpu.cpu_usage()))
+                    .collect(),
+            },
+            memory: MemoryDocument {
+                total: sys.total_memory(),
+                used: sys.used_memory(),
+                available: sys.available_memory(),
+            },
+            swap: SwapDocument {
+                total: sys.total_swap(),
+                used: sys.used_swap(),
+            },
+            processes: processes
+                .iter()
+                .map(|process| ProcessRow::from_process(process))
+                .collect(),
+        }
+    }
+}
+
+impl ProcessRow {
+    pub fn from_process(process: &ProcessSnapshot) -> Self {
+        let user = process
+            .user_id()
+            .and_then(|uid| users::get_user_by_uid(**uid))
+            .map(|user| user.name().to_string_lossy().to_string());
+
+        ProcessRow {
+            pid: process.pid().as_u32(),
+            ppid: process.parent().map(|ppid| ppid.as_u32()),
+            uid: process.user_id().map(|uid| **uid),
+            user,
+            name: process.name().to_string(),
+            cpu: percent(process.cpu_usage()),
+            memory: process.memory(),
+            virtual_memory: process.virtual_memory(),
+            state: state_name(process.status()).to_string(),
+            started: process.start_time(),
+            run_time: process.run_time(),
+            command: process.cmd().join(" "),
+        }
+    }
+}
+
+/// A percentage to one decimal, or `None` when it isn't a number JSON can
+/// carry (a NaN from a zero-length interval, say)
+fn percent(value: f32) -> Option<f32> {
+    rounded(value as f64, 10.0).map(|value| value as f32)
+}
+
+/// `value` rounded to `1 / scale`, or `None` when it isn't finite
+fn rounded(value: f64, scale: f64) -> Option<f64> {
+    value.is_finite().then(|| (value * scale).round() / scale)
+}
+
+/// Quote a string as a JSON string literal
+pub fn json_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysly_core::snapshot::CpuSnapshot;
+
+    #[test]
+    fn unreadable_cpu_usage_is_written_as_null() {
+        let sys = Snapshot {
+            cpus: vec![CpuSnapshot {
+                usage: f32::NAN,
+                ..CpuSnapshot::default()
+            }],
+            global_cpu: CpuSnapshot {
+                usage: f32::INFINITY,
+                ..CpuSnapshot::default()
+            },
+            ..Snapshot::default()
+        };
+        let process = ProcessSnapshot {
+            name: "quoted \"name\"".to_string(),
+            cpu_usage: f32::NAN,
+            ..ProcessSnapshot::default()
+        };
+
+        let mut out = Vec::new();
+        write_snapshot(&mut out, &sys, &[&process]).unwrap();
+        let written: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert!(written["cpu"]["usage"].is_null());
+        assert!(written["cpu"]["cores"][0].is_null());
+        assert!(written["processes"][0]["cpu"].is_null());
+        assert_eq!(written["processes"][0]["name"], "quoted \"name\"");
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use sysinfo::Pid;
+
+use crate::energy::app_name;
+use crate::tree::TreeNode;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// What the process table sections its rows by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping {
+    #[default]
+    Off,
+    User,
+    /// App bundle, or the process name outside a bundle
+    App,
+    /// Container runtime cgroup; everything else is the host
+    Container,
+}
+
+impl Grouping {
+    /// The grouping after this one, wrapping around to off
+    pub fn next(self) -> Grouping {
+        match self {
+            Grouping::Off => Grouping::User,
+            Grouping::User => Grouping::App,
+            Grouping::App => Grouping::Container,
+            Grouping::Container => Grouping::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Grouping::Off => "off",
+            Grouping::User => "user",
+            Grouping::App => "app",
+            Grouping::Container => "container",
+        }
+    }
+}
+
+/// A section header in the grouped process table, with subtotals of the
+/// processes under it
+#[derive(Debug, Clone)]
+pub struct GroupHeader {
+    pub name: String,
+    pub processes: usize,
+    /// CPU usage in percent of one core
+    pub cpu: f32,
+    /// Resident memory in bytes
+    pub memory: u64,
+    /// The group's process rows are hidden
+    pub collapsed: bool,
+}
+
+/// A row of the process table: a process, or a group header above them
+#[derive(Debug, Clone)]
+pub enum DisplayRow {
+    Process(TreeNode),
+    Group(GroupHeader),
+}
+
+impl DisplayRow {
+    pub fn pid(&self) -> Option<Pid> {
+        match self {
+            DisplayRow::Process(node) => Some(node.pid),
+            DisplayRow::Group(_) => None,
+        }
+    }
+}
+
+/// What the users view ranks users by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserOrder {
+    #[default]
+    Cpu,
+    Memory,
+    Processes,
+    Name,
+}
+
+impl UserOrder {
+    /// The order after this one, wrapping around to CPU
+    pub fn next(self) -> UserOrder {
+        match self {
+            UserOrder::Cpu => UserOrder::Memory,
+            UserOrder::Memory => UserOrder::Processes,
+            UserOrder::Processes => UserOrder::Name,
+            UserOrder::Name => UserOrder::Cpu,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UserOrder::Cpu => "CPU%",
+            UserOrder::Memory => "RES",
+            UserOrder::Processes => "Procs",
+            UserOrder::Name => "User",
+        }
+    }
+}
+
+/// Usage summed over every process of one user
+#[derive(Debug, Clone)]
+pub struct UserTotals {
+    pub name: String,
+    pub processes: usize,
+    /// CPU usage in percent of one core
+    pub cpu: f32,
+    /// Resident memory in bytes
+    pub memory: u64,
+}
+
+/// Sum the usage of every process by user, ranked by `order`
+///
+/// Unlike the grouped process table this counts every process, not only
+/// the ones the filters show. Threads are left out.
+pub fn user_totals(
+    sys: &Snapshot,
+    users: &HashMap<u32, String>,
+    cpu_usage: impl Fn(&ProcessSnapshot) -> f32,
+    order: UserOrder,
+) -> Vec<UserTotals> {
+    // Processes whose owner can't be read are summed under "?"
+    let mut totals: HashMap<Option<u32>, UserTotals> = HashMap::new();
+    for process in sys.processes().values() {
+        if process.thread_kind().is_some() {
+            continue;
+        }
+        let uid = process.user_id().map(|uid| **uid);
+        let entry = totals.entry(uid).or_insert_with(|| UserTotals {
+            name: match uid {
+                Some(uid) => users.get(&uid).cloned().unwrap_or_else(|| uid.to_string()),
+                None => "?".to_string(),
+            },
+            processes: 0,
+            cpu: 0.0,
+            memory: 0,
+        });
+        entry.processes += 1;
+        entry.cpu += cpu_usage(process);
+        entry.memory += process.memory();
+    }
+
+    let mut totals: Vec<_> = totals.into_values().collect();
+    // Busiest first, ties and the name order alphabetical
+    totals.sort_by(|a, b| {
+        let ranked = match order {
+            UserOrder::Cpu => b.cpu.total_cmp(&a.cpu),
+            UserOrder::Memory => b.memory.cmp(&a.memory),
+            UserOrder::Processes => b.processes.cmp(&a.processes),
+            UserOrder::Name => std::cmp::Ordering::Equal,
+        };
+        ranked.then_with(|| a.name.cmp(&b.name))
+    });
+    totals
+}
+
+/// Section sorted process rows under a header per group
+///
+/// Groups appear in the order of their first process, so the group holding
+/// the top row under the active sort comes first, and rows keep their order
+/// within a group. Collapsed groups keep only their header.
+///
+/// # Arguments
+/// * `nodes` - Process rows in sorted order
+/// * `key` - Name of the group a process belongs to
+/// * `cpu_usage` - CPU usage to subtotal, e.g. smoothed readings
+/// * `collapsed` - Names of the groups whose rows are hidden
+pub fn group_rows(
+    sys: &Snapshot,
+    nodes: Vec<TreeNode>,
+    key: impl Fn(&ProcessSnapshot) -> String,
+    cpu_usage: impl Fn(&ProcessSnapshot) -> f32,
+    collapsed: &HashSet<String>,
+) -> Vec<DisplayRow> {
+    let mut groups: Vec<(GroupHeader, Vec<TreeNode>)> = Vec::new();
+    let mut index_by_name: HashMap<String, usize> = HashMap::new();
+
+    for node in nodes {
+        let Some(process) = sys.process(node.pid) else {
+            continue;
+        };
+        let name = key(process);
+        let index = *index_by_name.entry(name.clone()).or_insert_with(|| {
+            groups.push((
+                GroupHeader {
+                    collapsed: collapsed.contains(&name),
+                    name,
+                    processes: 0,
+                    cpu: 0.0,
+                    memory: 0,
+                },
+                Vec::new(),
+            ));
+            groups.len() - 1
+        });
+
+        let (header, members) = &mut groups[index];
+        header.processes += 1;
+        header.cpu += cpu_usage(process);
+        header.memory += process.memory();
+        if !header.collapsed {
+            members.push(node);
+        }
+    }
+
+    groups
+        .into_iter()
+        .flat_map(|(header, members)| {
+            std::iter::once(DisplayRow::Group(header))
+                .chain(members.into_iter().map(DisplayRow::Process))
+        })
+        .collect()
+}
+
+/// Name of the group a process falls in under this grouping
+///
+/// Containers are named from `containers`, names by short ID, when Docker
+/// reported them.
+pub fn group_name(
+    grouping: Grouping,
+    sys: &Snapshot,
+    process: &ProcessSnapshot,
+    users: &HashMap<u32, String>,
+    containers: &HashMap<String, String>,
+) -> String {
+    match grouping {
+        Grouping::Off => String::new(),
+        Grouping::User => match process.user_id() {
+            Some(uid) => users.get(uid).cloned().unwrap_or_else(|| uid.to_string()),
+            None => "?".to_string(),
+        },
+        Grouping::App => app_name(sys, process),
+        Grouping::Container => {
+            container_label(process.pid(), containers).unwrap_or_else(|| "host".to_string())
+        }
+    }
+}
+
+/// The container a process runs in: its name when known, or the runtime
+/// and short ID
+pub fn container_label(pid: Pid, names: &HashMap<String, String>) -> Option<String> {
+    let (runtime, id) = container_of(pid)?;
+    Some(match names.get(id.as_str()) {
+        Some(name) => name.clone(),
+        None => format!("{} {}", runtime, id),
+    })
+}
+
+/// Container a process runs in, from container runtime names in its cgroup
+/// path, e.g. `/system.slice/docker-<id>.scope` or `/kubepods/.../<id>`
+///
+/// # Returns
+/// The runtime and a short container ID, or None on the host (always on
+/// macOS, where containers run inside a VM)
+fn container_of(pid: Pid) -> Option<(&'static str, String)> {
+    let contents = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let path = contents.lines().find_map(|line| line.strip_prefix("0::"))?;
+
+    const RUNTIMES: [(&str, &str); 5] = [
+        ("docker", "docker"),
+        ("libpod", "podman"),
+        ("cri-containerd", "containerd"),
+        ("crio", "cri-o"),
+        ("kubepods", "k8s"),
+    ];
+    let segments: Vec<&str> = path.trim().split('/').collect();
+    for (marker, runtime) in RUNTIMES {
+        let Some(position) = segments.iter().position(|s| s.starts_with(marker)) else {
+            continue;
+        };
+        // The ID is either part of the segment (`docker-<id>.scope`) or
+        // the last segment below it (`/docker/<id>`)
+        let segment = segments[position].trim_end_matches(".scope");
+        let id = match segment.rsplit_once(['-', ':']) {
+            Some((_, id)) if is_container_id(id) => Some(id),
+            _ => segments[position + 1..]
+                .iter()
+                .rev()
+                .map(|s| s.trim_end_matches(".scope"))
+                .map(|s| s.rsplit_once(['-', ':']).map_or(s, |(_, id)| id))
+                .find(|s| is_container_id(s)),
+        };
+        // Without an ID it is the runtime's own daemon, e.g. docker.service
+        if let Some(id) = id {
+            return Some((runtime, id[..12].to_string()));
+        }
+    }
+    None
+}
+
+/// Container IDs are 64 hex digits
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -0,0 +1,488 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use sysinfo::Pid;
+
+use sysly_core::files::{fetch_open_files, OpenFile};
+use sysly_core::identity::{fetch_identity, Identity};
+use sysly_core::origin::{fetch_code_signature, CodeSignature};
+use sysly_core::process::fetch_environ;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+use sysly_core::threads::{fetch_threads, ThreadInfo};
+
+/// Number of per-refresh CPU samples kept for the history graph
+pub const CPU_HISTORY_LEN: usize = 120;
+
+/// Substrings that mark an environment variable as holding a secret
+const SENSITIVE_KEY_PARTS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "KEY",
+    "CREDENTIAL",
+    "AUTH",
+    "COOKIE",
+    "SESSION",
+];
+
+/// Placeholder shown instead of a masked value
+const MASK: &str = "********";
+
+/// Tabs of the inspector view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectorTab {
+    Overview,
+    Identity,
+    Environment,
+    Files,
+    Threads,
+}
+
+impl InspectorTab {
+    pub const ALL: [InspectorTab; 5] = [
+        InspectorTab::Overview,
+        InspectorTab::Identity,
+        InspectorTab::Environment,
+        InspectorTab::Files,
+        InspectorTab::Threads,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            InspectorTab::Overview => "Overview",
+            InspectorTab::Identity => "Identity",
+            InspectorTab::Environment => "Environment",
+            InspectorTab::Files => "Open files",
+            InspectorTab::Threads => "Threads",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+
+    /// The tab after this one, wrapping around
+    pub fn next(self) -> InspectorTab {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    /// The tab before this one, wrapping around
+    pub fn previous(self) -> InspectorTab {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Counters from the kernel's task info for one process
+#[derive(Debug, Clone, Copy)]
+pub struct TaskInfo {
+    pub faults: u64,
+    pub pageins: u64,
+    pub cow_faults: u64,
+    pub context_switches: u64,
+}
+
+/// Resident memory split by what backs it
+#[derive(Debug, Clone, Copy)]
+pub struct ResidentSplit {
+    /// Heap, stacks, and shared memory segments, only freed by the process
+    pub anonymous: u64,
+    /// Mapped files and libraries, which the kernel can drop and re-read
+    pub file_backed: u64,
+}
+
+/// Memory usage of the inspected process
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryBreakdown {
+    pub resident: u64,
+    pub virtual_size: u64,
+    /// Extra counters, where the platform provides them
+    pub task: Option<TaskInfo>,
+    /// Anonymous versus file-backed resident memory, where readable
+    pub split: Option<ResidentSplit>,
+}
+
+/// Details of a single process, gathered when the inspector opens
+#[derive(Debug, Clone)]
+pub struct ProcessDetails {
+    pub name: String,
+    pub cmd: Vec<String>,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    /// Parent PID and name
+    pub parent: Option<(Pid, String)>,
+    pub start_time: u64,
+    pub run_time: u64,
+    pub user: String,
+    pub group: String,
+    pub memory: MemoryBreakdown,
+    /// Code signature of the executable, where the platform has one
+    pub signature: Option<CodeSignature>,
+}
+
+/// Detail view for one process
+///
+/// The expensive per-process queries run once when the inspector opens;
+/// afterwards only CPU and memory are sampled on each data tick.
+#[derive(Debug, Clone)]
+pub struct Inspector {
+    pub pid: Pid,
+    pub details: Option<ProcessDetails>,
+    pub cpu_history: VecDeque<f32>,
+    /// Set once the process disappears; the last details stay visible
+    pub exited: bool,
+    pub tab: InspectorTab,
+    /// Hash, ancestry and owning service, loaded the first time the
+    /// Identity tab is shown
+    pub identity: Option<Identity>,
+    /// Environment, loaded the first time the Environment tab is shown
+    pub environ: Option<Vec<String>>,
+    /// Open files, loaded the first time the Files tab is shown
+    pub open_files: Option<Vec<OpenFile>>,
+    /// Threads, loaded when the Threads tab is shown and resampled on each
+    /// data tick while it stays open
+    pub threads: Option<Vec<ThreadInfo>>,
+    /// Filter applied to the environment, open file, and thread lists
+    pub search: String,
+    /// Whether keys are currently typed into the search
+    pub searching: bool,
+    /// Hide the values of variables that look like secrets
+    pub mask_secrets: bool,
+    /// First list row shown on the current tab
+    pub scroll: usize,
+}
+
+impl Inspector {
+    /// Open the inspector for a process, refreshing everything sysinfo
+    /// knows about it
+    ///
+    /// # Arguments
+    /// * `pid` - Process to inspect
+    /// * `system` - System snapshot, whose entry for this PID is replaced
+    ///   with a full reading
+    pub fn open(pid: Pid, system: &mut Snapshot) -> Self {
+        system.refresh_process(pid);
+
+        let details = system
+            .process(pid)
+            .map(|process| gather_details(process, system));
+        let cpu_history = system
+            .process(pid)
+            .map(|process| VecDeque::from([process.cpu_usage()]))
+            .unwrap_or_default();
+
+        Inspector {
+            pid,
+            exited: details.is_none(),
+            details,
+            cpu_history,
+            tab: InspectorTab::Overview,
+            identity: None,
+            environ: None,
+            open_files: None,
+            threads: None,
+            search: String::new(),
+            searching: false,
+            mask_secrets: true,
+            scroll: 0,
+        }
+    }
+
+    /// Switch tabs, loading the data the new tab needs on first use
+    pub fn show_tab(&mut self, tab: InspectorTab, sys: &Snapshot) {
+        self.tab = tab;
+        self.scroll = 0;
+
+        match tab {
+            InspectorTab::Identity if self.identity.is_none() => {
+                self.identity = sys
+                    .process(self.pid)
+                    .map(|process| fetch_identity(process, sys));
+            }
+            InspectorTab::Environment if self.environ.is_none() => {
+                self.environ = Some(load_environ(self.pid, sys));
+            }
+            InspectorTab::Files if self.open_files.is_none() => {
+                self.open_files = Some(fetch_open_files(self.pid.as_u32()));
+            }
+            InspectorTab::Threads => {
+                self.threads = Some(fetch_threads(self.pid, sys));
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the current tab is a list that can be searched and scrolled
+    pub fn has_list(&self) -> bool {
+        !matches!(self.tab, InspectorTab::Overview | InspectorTab::Identity)
+    }
+
+    /// Number of rows in the current tab's filtered list
+    pub fn row_count(&self) -> usize {
+        match self.tab {
+            InspectorTab::Overview | InspectorTab::Identity => 0,
+            InspectorTab::Environment => self.visible_environ().len(),
+            InspectorTab::Files => self.visible_files().len(),
+            InspectorTab::Threads => self.visible_threads().len(),
+        }
+    }
+
+    /// Reload the open files list, which changes as the process runs
+    pub fn reload_files(&mut self) {
+        self.open_files = Some(fetch_open_files(self.pid.as_u32()));
+    }
+
+    /// Threads whose ID or name match the search
+    pub fn visible_threads(&self) -> Vec<&ThreadInfo> {
+        let search = self.search.to_lowercase();
+
+        self.threads
+            .iter()
+            .flatten()
+            .filter(|thread| {
+                search.is_empty()
+                    || thread.tid.to_string().contains(&search)
+                    || thread.name.to_lowercase().contains(&search)
+            })
+            .collect()
+    }
+
+    /// Open files whose descriptor, kind, or name match the search
+    pub fn visible_files(&self) -> Vec<&OpenFile> {
+        let search = self.search.to_lowercase();
+
+        self.open_files
+            .iter()
+            .flatten()
+            .filter(|file| {
+                search.is_empty()
+                    || [&file.fd, &file.kind, &file.name]
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(&search))
+            })
+            .collect()
+    }
+
+    /// Environment entries matching the search, as key/value pairs with
+    /// secrets masked when masking is on
+    pub fn visible_environ(&self) -> Vec<(String, String)> {
+        let search = self.search.to_lowercase();
+
+        self.environ
+            .iter()
+            .flatten()
+            .filter(|entry| search.is_empty() || entry.to_lowercase().contains(&search))
+            .map(|entry| {
+                let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+                let value = if self.mask_secrets && is_sensitive(key) {
+                    MASK.to_string()
+                } else {
+                    value.to_string()
+                };
+                (key.to_string(), value)
+            })
+            .collect()
+    }
+
+    /// Record a CPU sample and refresh memory figures after a data tick
+    pub fn sample(&mut self, sys: &Snapshot) {
+        let Some(process) = sys.process(self.pid) else {
+            self.exited = true;
+            return;
+        };
+
+        if self.cpu_history.len() == CPU_HISTORY_LEN {
+            self.cpu_history.pop_front();
+        }
+        self.cpu_history.push_back(process.cpu_usage());
+
+        if let Some(details) = &mut self.details {
+            details.memory = memory_breakdown(process);
+            details.run_time = process.run_time();
+        }
+
+        if self.tab == InspectorTab::Threads {
+            self.threads = Some(fetch_threads(self.pid, sys));
+        }
+    }
+}
+
+/// Read the environment from sysinfo, falling back to `ps` when it is empty
+fn load_environ(pid: Pid, sys: &Snapshot) -> Vec<String> {
+    let Some(process) = sys.process(pid) else {
+        return Vec::new();
+    };
+
+    if process.environ().is_empty() {
+        fetch_environ(pid.as_u32(), process.cmd())
+    } else {
+        process.environ().to_vec()
+    }
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+fn gather_details(process: &ProcessSnapshot, sys: &Snapshot) -> ProcessDetails {
+    let parent = process.parent().map(|ppid| match sys.process(ppid) {
+        Some(parent) => (ppid, parent.name().to_string()),
+        None => (ppid, "?".to_string()),
+    });
+
+    let user = match process.user_id() {
+        Some(uid) => {
+            let name = users::get_user_by_uid(**uid)
+                .map(|u| u.name().to_string_lossy().to_string())
+                .unwrap_or_else(|| "?".to_string());
+            format!("{} ({})", name, **uid)
+        }
+        None => "?".to_string(),
+    };
+
+    let group = match process.group_id() {
+        Some(gid) => {
+            let name = users::get_group_by_gid(*gid)
+                .map(|g| g.name().to_string_lossy().to_string())
+                .unwrap_or_else(|| "?".to_string());
+            format!("{} ({})", name, *gid)
+        }
+        None => "?".to_string(),
+    };
+
+    ProcessDetails {
+        name: process.name().to_string(),
+        cmd: process.cmd().to_vec(),
+        exe: process.exe().map(PathBuf::from),
+        cwd: process.cwd().map(PathBuf::from),
+        parent,
+        start_time: process.start_time(),
+        run_time: process.run_time(),
+        user,
+        group,
+        memory: memory_breakdown(process),
+        signature: process.exe().and_then(fetch_code_signature),
+    }
+}
+
+fn memory_breakdown(process: &ProcessSnapshot) -> MemoryBreakdown {
+    MemoryBreakdown {
+        resident: process.memory(),
+        virtual_size: process.virtual_memory(),
+        task: fetch_task_info(process.pid()),
+        split: fetch_resident_split(process.pid()),
+    }
+}
+
+/// Read fault, page-in, and context switch counters via `proc_pidinfo`
+#[cfg(target_os = "macos")]
+fn fetch_task_info(pid: Pid) -> Option<TaskInfo> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid.as_u32() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+
+    if written != size {
+        return None;
+    }
+
+    Some(TaskInfo {
+        faults: info.pti_faults as u64,
+        pageins: info.pti_pageins as u64,
+        cow_faults: info.pti_cow_faults as u64,
+        context_switches: info.pti_csw as u64,
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn fetch_task_info(_pid: Pid) -> Option<TaskInfo> {
+    None
+}
+
+/// `task_vm_info` from `<mach/task_info.h>`, up to the fields read here
+#[cfg(target_os = "macos")]
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Default)]
+struct TaskVmInfo {
+    virtual_size: u64,
+    region_count: i32,
+    page_size: i32,
+    resident_size: u64,
+    resident_size_peak: u64,
+    device: u64,
+    device_peak: u64,
+    internal: u64,
+    internal_peak: u64,
+    external: u64,
+    external_peak: u64,
+}
+
+#[cfg(target_os = "macos")]
+const TASK_VM_INFO: libc::task_flavor_t = 22;
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    static mach_task_self_: libc::mach_port_t;
+    fn mach_port_deallocate(task: libc::mach_port_t, name: libc::mach_port_t) -> libc::c_int;
+}
+
+/// Read internal (anonymous) and external (file-backed) memory from the
+/// task's `TASK_VM_INFO`
+///
+/// Getting another process's task port needs root, so this comes up empty
+/// for other processes when sysly runs as a normal user.
+#[cfg(target_os = "macos")]
+fn fetch_resident_split(pid: Pid) -> Option<ResidentSplit> {
+    let mut task: libc::mach_port_t = 0;
+    let own_task = unsafe { mach_task_self_ };
+    if unsafe { libc::task_for_pid(own_task, pid.as_u32() as libc::pid_t, &mut task) } != 0 {
+        return None;
+    }
+
+    let mut info = TaskVmInfo::default();
+    let mut count = (std::mem::size_of::<TaskVmInfo>() / std::mem::size_of::<libc::natural_t>())
+        as libc::mach_msg_type_number_t;
+    let result = unsafe {
+        libc::task_info(
+            task,
+            TASK_VM_INFO,
+            &mut info as *mut _ as libc::task_info_t,
+            &mut count,
+        )
+    };
+    unsafe {
+        mach_port_deallocate(own_task, task);
+    }
+
+    (result == 0).then_some(ResidentSplit {
+        anonymous: info.internal,
+        file_backed: info.external,
+    })
+}
+
+/// Read `RssAnon`, `RssFile`, and `RssShmem` from `/proc/<pid>/status`
+#[cfg(not(target_os = "macos"))]
+fn fetch_resident_split(pid: Pid) -> Option<ResidentSplit> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    // "RssAnon:	    1234 kB"
+    let kilobytes = |name: &str| -> Option<u64> {
+        status.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            value.split_whitespace().next()?.parse::<u64>().ok()
+        })
+    };
+
+    Some(ResidentSplit {
+        anonymous: (kilobytes("RssAnon")? + kilobytes("RssShmem").unwrap_or(0)) * 1024,
+        file_backed: kilobytes("RssFile")? * 1024,
+    })
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use sysinfo::Pid;
+use sysly_core::snapshot::Snapshot;
+
+/// Events kept for the events view; older ones are dropped
+const MAX_EVENTS: usize = 1000;
+
+/// Whether a process appeared or went away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Started,
+    Exited,
+}
+
+impl EventKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Started => "start",
+            EventKind::Exited => "exit",
+        }
+    }
+}
+
+/// A process that started or exited between two refreshes
+#[derive(Debug, Clone)]
+pub struct LifecycleEvent {
+    /// When the refresh that noticed it ran
+    pub time: DateTime<Local>,
+    pub kind: EventKind,
+    pub pid: u32,
+    pub user: Option<String>,
+    pub command: String,
+    /// Why the process exited, when sysly knows: only signals it sent itself
+    pub reason: Option<String>,
+}
+
+/// What was last seen of a running process
+#[derive(Debug)]
+struct Seen {
+    /// Tells a reused PID from the process seen before
+    start_time: u64,
+    user: Option<String>,
+    command: String,
+}
+
+/// Processes that started and exited, found by comparing the PIDs of each
+/// refresh with the last one
+///
+/// The processes running at the first refresh are taken as the baseline
+/// rather than reported as started. Threads are left out.
+#[derive(Debug, Default)]
+pub struct Lifecycle {
+    seen: HashMap<Pid, Seen>,
+    /// Signals sent from sysly, reported as the reason when the process exits
+    signalled: HashMap<Pid, String>,
+    /// Newest last
+    events: VecDeque<LifecycleEvent>,
+}
+
+impl Lifecycle {
+    /// Recorded events, oldest first
+    pub fn events(&self) -> &VecDeque<LifecycleEvent> {
+        &self.events
+    }
+
+    /// Remember that sysly sent `signal` (named without `SIG`) to `pid`
+    pub fn signalled(&mut self, pid: Pid, signal: &str) {
+        self.signalled
+            .insert(pid, format!("SIG{} from sysly", signal));
+    }
+
+    /// Compare a fresh refresh with the last one
+    ///
+    /// # Returns
+    /// The events found, also added to the ones kept
+    pub fn update(&mut self, sys: &Snapshot, users: &HashMap<u32, String>) -> Vec<LifecycleEvent> {
+        let processes: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none())
+            .collect();
+        // The process list is still empty before the first sample
+        if processes.is_empty() {
+            return Vec::new();
+        }
+        let baseline = self.seen.is_empty();
+        let time = Local::now();
+        let mut found = Vec::new();
+
+        let mut current = HashMap::with_capacity(processes.len());
+        for process in processes {
+            let command = if process.cmd().is_empty() {
+                process.name().to_string()
+            } else {
+                process.cmd().join(" ")
+            };
+            let seen = Seen {
+                start_time: process.start_time(),
+                user: process.user_id().and_then(|uid| users.get(uid)).cloned(),
+                command,
+            };
+            let known = self
+                .seen
+                .get(&process.pid())
+                .is_some_and(|old| old.start_time == seen.start_time);
+            if !baseline && !known {
+                found.push(LifecycleEvent {
+                    time,
+                    kind: EventKind::Started,
+                    pid: process.pid().as_u32(),
+                    user: seen.user.clone(),
+                    command: seen.command.clone(),
+                    reason: None,
+                });
+            }
+            current.insert(process.pid(), seen);
+        }
+
+        for (pid, old) in &self.seen {
+            let reused = current
+                .get(pid)
+                .is_some_and(|new| new.start_time != old.start_time);
+            if reused || !current.contains_key(pid) {
+                found.push(LifecycleEvent {
+                    time,
+                    kind: EventKind::Exited,
+                    pid: pid.as_u32(),
+                    user: old.user.clone(),
+                    command: old.command.clone(),
+                    reason: self.signalled.remove(pid),
+                });
+            }
+        }
+        // Exits first, so a restart reads as exit then start
+        found.sort_by_key(|event| event.kind == EventKind::Started);
+        self.signalled.retain(|pid, _| current.contains_key(pid));
+        self.seen = current;
+
+        for event in &found {
+            if self.events.len() == MAX_EVENTS {
+                self.events.pop_front();
+            }
+            self.events.push_back(event.clone());
+        }
+        found
+    }
+}
+
+/// Lifecycle events appended to a text file, for `--event-log`
+///
+/// One tab-separated line per event: time, `start` or `exit`, PID, user,
+/// exit reason and command, with `-` for what isn't known.
+pub struct EventLog {
+    out: BufWriter<File>,
+}
+
+impl EventLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(EventLog {
+            out: BufWriter::new(file),
+        })
+    }
+
+    pub fn write(&mut self, events: &[LifecycleEvent]) -> io::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        for event in events {
+            writeln!(
+                self.out,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                event
+                    .time
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+                event.kind.label(),
+                event.pid,
+                event.user.as_deref().unwrap_or("-"),
+                event.reason.as_deref().unwrap_or("-"),
+                event.command.replace(['\t', '\n'], " ")
+            )?;
+        }
+        self.out.flush()
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use sysinfo::ProcessStatus;
+
+use crate::cli::Options;
+use crate::sort::{read_sched, sort_processes, SortKey, SortReadings};
+use sysly_core::battery::{fetch_battery, BatteryState};
+use sysly_core::helpers::format_bytes;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Processes listed in each report, unless `--top` gives a count
+const PROCESS_LINES: usize = 10;
+
+/// Busiest cores named in each report
+const CORE_LINES: usize = 3;
+
+/// Write one report of the dashboard as plain sentences
+///
+/// Every fact is its own line with its label spelled out, with no box
+/// drawing, bars or column alignment, so screen readers and braille
+/// displays read it in order. Processes follow the filters and sort key
+/// given on the command line.
+pub fn write_report(
+    out: &mut impl Write,
+    sys: &Snapshot,
+    users: &HashMap<u32, String>,
+    options: &Options,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "sysly report at {}",
+        chrono::Local::now().format("%H:%M:%S")
+    )?;
+
+    let cpus = sys.cpus();
+    writeln!(
+        out,
+        "CPU: {:.0} percent in use across {}.",
+        sys.global_cpu_info().cpu_usage(),
+        plural(cpus.len() as u64, "core")
+    )?;
+    let mut cores: Vec<(usize, f32)> = cpus
+        .iter()
+        .enumerate()
+        .map(|(index, cpu)| (index + 1, cpu.cpu_usage()))
+        .collect();
+    cores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let busiest: Vec<String> = cores
+        .iter()
+        .take(CORE_LINES)
+        .map(|(core, usage)| format!("core {} at {:.0} percent", core, usage))
+        .collect();
+    writeln!(out, "Busiest cores: {}.", busiest.join(", "))?;
+
+    writeln!(
+        out,
+        "Memory: {} of {} in use, {:.0} percent.",
+        format_bytes(sys.used_memory()),
+        format_bytes(sys.total_memory()),
+        percent(sys.used_memory(), sys.total_memory())
+    )?;
+    writeln!(
+        out,
+        "Swap: {} of {} in use.",
+        format_bytes(sys.used_swap()),
+        format_bytes(sys.total_swap())
+    )?;
+
+    let load = sys.load_average();
+    writeln!(
+        out,
+        "Load average: {:.2} over 1 minute, {:.2} over 5, {:.2} over 15.",
+        load.one, load.five, load.fifteen
+    )?;
+
+    let processes: Vec<&ProcessSnapshot> = sys
+        .processes()
+        .values()
+        .filter(|process| process.thread_kind().is_none())
+        .collect();
+    let running = processes
+        .iter()
+        .filter(|process| process.status() == ProcessStatus::Run)
+        .count();
+    writeln!(
+        out,
+        "Tasks: {} processes, {} running.",
+        processes.len(),
+        running
+    )?;
+    writeln!(out, "Uptime: {}.", spoken_duration(sys.uptime()))?;
+
+    if let Some(battery) = fetch_battery() {
+        let state = match battery.state {
+            BatteryState::Charging => "charging",
+            BatteryState::Discharging => "on battery",
+            BatteryState::NotCharging => "on power, not charging",
+        };
+        let remaining = battery
+            .minutes_remaining
+            .map(|minutes| format!(", {} left", spoken_duration(minutes * 60)))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "Battery: {:.0} percent, {}{}.",
+            battery.percent, state, remaining
+        )?;
+    }
+
+    let mut listed: Vec<&ProcessSnapshot> = processes
+        .into_iter()
+        .filter(|process| options.matches(sys, process))
+        .collect();
+    let order = options.sort.unwrap_or_default();
+    let sched = read_sched(&listed, order);
+    let readings = SortReadings {
+        sched: Some(&sched),
+        ..SortReadings::default()
+    };
+    sort_processes(&mut listed, order, readings);
+    let sort_names = match order.secondary {
+        Some(secondary) => format!("{} then {}", sort_name(order.primary), sort_name(secondary)),
+        None => sort_name(order.primary).to_string(),
+    };
+
+    let shown = options.limit.unwrap_or(PROCESS_LINES);
+    writeln!(
+        out,
+        "Top {} processes by {}:",
+        listed.len().min(shown),
+        sort_names
+    )?;
+    for (rank, process) in listed.iter().take(shown).enumerate() {
+        let user = process
+            .user_id()
+            .and_then(|uid| users.get(uid))
+            .map_or("unknown", String::as_str);
+        writeln!(
+            out,
+            "{}. {}, PID {}, user {}, CPU {:.1} percent, memory {:.1} percent, {} resident.",
+            rank + 1,
+            process.name(),
+            process.pid(),
+            user,
+            process.cpu_usage(),
+            percent(process.memory(), sys.total_memory()),
+            format_bytes(process.memory())
+        )?;
+    }
+
+    // A blank line separates one report from the next
+    writeln!(out)?;
+    out.flush()
+}
+
+/// The sort column in words
+fn sort_name(key: SortKey) -> &'static str {
+    match key {
+        SortKey::Cpu => "CPU",
+        SortKey::Memory => "memory",
+        SortKey::Virtual => "virtual memory",
+        SortKey::Pid => "PID",
+        SortKey::Time => "CPU time",
+        SortKey::Started => "start time",
+        SortKey::Command => "command",
+        SortKey::Qos => "QoS class",
+    }
+}
+
+/// A duration in words, to the minute: "2 days 3 hours", "1 hour 5 minutes"
+fn spoken_duration(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+
+    match (days, hours) {
+        (0, 0) => plural(minutes, "minute"),
+        (0, _) => format!("{} {}", plural(hours, "hour"), plural(minutes, "minute")),
+        _ => format!("{} {}", plural(days, "day"), plural(hours, "hour")),
+    }
+}
+
+/// A count with its noun: "1 core", "8 cores"
+fn plural(count: u64, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    part as f64 / total as f64 * 100.0
+}
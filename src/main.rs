@@ -1,4 +1,4 @@
-use std::io;
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
 use crossterm::{
@@ -7,34 +7,313 @@ use crossterm::{
         MouseEventKind,
     },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    backend::CrosstermBackend,
-    layout::Rect,
-    style::{Color, Style},
-    Terminal,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
 };
-use sysinfo::System;
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use sysinfo::{Pid, System};
 
+mod actions;
+mod agent;
+mod alerts;
+mod api;
+mod batch;
 mod build_info;
-mod helpers;
-mod process;
+mod capture;
+mod cli;
+mod columns;
+mod config;
+mod control;
+mod docker;
+mod energy;
+mod export;
+mod grouping;
+mod inspector;
+mod lifecycle;
+mod linear;
+mod pidlog;
+mod record;
+mod remote;
+mod replay;
+mod sampler;
+mod smoothing;
+mod sort;
+mod states;
+mod stress;
+mod theme;
+mod throttle;
+mod tree;
 mod ui;
+mod watch;
 
-use ui::{draw_dashboard, draw_help_window, AppState};
+use sysly_core::{
+    battery, brew, cache, collector, disk, extensions, fans, gpu, helpers, host, memstat, origin,
+    process, rosetta, sensors, startup, topology,
+};
+
+use actions::{dispatch, get_nice, summarize, ProcessAction, SIGNALS};
+use alerts::Alerts;
+use api::ApiServer;
+use battery::BatteryTrend;
+use cache::Inventory;
+use capture::CsvCapture;
+use cli::{Invocation, Options};
+use collector::Collectors;
+use columns::{insert_shown, listing, toggle_columns, Column, Meter};
+use config::Background;
+use control::ControlCommand;
+use disk::DiskMeter;
+use energy::EnergyLedger;
+use export::TreeFormat;
+use grouping::{user_totals, Grouping, UserOrder};
+use helpers::format_bytes;
+use host::HostInfo;
+use inspector::{Inspector, InspectorTab};
+use lifecycle::{EventLog, Lifecycle};
+use memstat::SwapMeter;
+use origin::OriginCache;
+use pidlog::PidLog;
+use process::ProcessMaps;
+use record::HistoryRecorder;
+use remote::RemoteProvider;
+use replay::RecordingProvider;
+use rosetta::RosettaCache;
+use sampler::{Recorder, Sampler};
+use sensors::SensorLog;
+use smoothing::CpuSmoother;
+use sort::{SortKey, SortReadings};
+use states::StateTracker;
+use stress::{StressOptions, StressRun};
+use sysly_core::provider::{self, Demand, LiveProvider, Playback, SystemProvider};
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+use throttle::RenderThrottle;
+use ui::{
+    action_confirmation, action_targets, display_rows, draw_confirm_window, draw_dashboard,
+    draw_diagnostics_window, draw_help_window, draw_inspector_window, draw_respawn_window,
+    draw_setup_window, draw_signal_menu, draw_sort_menu, filesystem_panel_height, group_targets,
+    info_bar_height, inherit_background, outer_block, selected_group, selected_pid, strip_colors,
+    AppState, Confirmation, CpuHistory, DisplayCache, Modal, NetworkOrder, Pane, PendingAction,
+    RowCache, SetupMenu, SetupSection, View,
+};
+use watch::{check_respawns, WatchedProcess};
 
 /// Application configuration constants
 const REFRESH_INTERVAL_MS: u64 = 1000;
 const EVENT_POLL_TIMEOUT_MS: u64 = 100;
+const PAGE_SIZE: usize = 10;
+/// Time between reports in `--linear` mode
+const LINEAR_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Main application entry point
 ///
 /// Initializes the terminal, runs the main application loop,
 /// and ensures proper cleanup on exit
 fn main() -> Result<(), io::Error> {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(Invocation::Interactive(options)) => options,
+        Ok(Invocation::Tree { format, output }) => return print_tree(format, output),
+        Ok(Invocation::Stress(stress)) => return run_stress(stress),
+        Ok(Invocation::LogPid { pid, output, delay }) => return log_pid(pid, &output, delay),
+        Ok(Invocation::Agent { listen, delay }) => return agent::run_agent(listen, delay),
+        Ok(Invocation::Replay { path, no_color }) => {
+            let recording = match RecordingProvider::open(&path) {
+                Ok(recording) => recording,
+                Err(err) => {
+                    eprintln!("sysly: cannot replay {}: {}", path.display(), err);
+                    std::process::exit(1);
+                }
+            };
+            let options = Options {
+                no_color,
+                ..Options::default()
+            };
+            let remote = Remote {
+                provider: Box::new(recording),
+                playback: true,
+            };
+            return run_tui(&options, None, Outputs::default(), Some(remote));
+        }
+        Ok(Invocation::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(Invocation::Version) => {
+            println!("{} {}", build_info::PROJECT_NAME, build_info::VERSION);
+            return Ok(());
+        }
+        Err(message) => {
+            eprintln!("sysly: {}\n\n{}", message, cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    let control = match options.control.as_deref().map(control::spawn_reader) {
+        Some(Ok(commands)) => Some(commands),
+        Some(Err(err)) => {
+            eprintln!("sysly: cannot read control commands: {}", err);
+            std::process::exit(1);
+        }
+        None => None,
+    };
+
+    if options.json {
+        return print_snapshot(&options);
+    }
+    if let Some(address) = &options.connect {
+        let remote = Remote {
+            provider: Box::new(RemoteProvider::connect(address)),
+            playback: false,
+        };
+        return run_tui(&options, control, Outputs::default(), Some(remote));
+    }
+
+    let capture = match &options.csv {
+        Some(path) => match CsvCapture::open(path) {
+            Ok(capture) => Some(capture),
+            Err(err) => {
+                eprintln!("sysly: cannot write {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let history = match &options.record {
+        Some(path) => match HistoryRecorder::open(path) {
+            Ok(history) => Some(history),
+            Err(err) => {
+                eprintln!("sysly: cannot record to {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let api = match options.listen {
+        Some(addr) => match ApiServer::start(addr) {
+            Ok(api) => Some(api),
+            Err(err) => {
+                eprintln!("sysly: cannot listen on {}: {}", addr, err);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let events = match &options.event_log {
+        Some(path) => match EventLog::open(path) {
+            Ok(events) => Some(events),
+            Err(err) => {
+                eprintln!("sysly: cannot write {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let outputs = Outputs {
+        capture,
+        history,
+        api,
+        events,
+    };
+
+    if options.linear {
+        let interval = options.delay.unwrap_or(LINEAR_INTERVAL);
+        return run_reports(&options, interval, linear::write_report, outputs);
+    }
+    if options.batch {
+        let interval = options
+            .delay
+            .unwrap_or(Duration::from_millis(REFRESH_INTERVAL_MS));
+        return run_reports(&options, interval, batch::write_report, outputs);
+    }
+
     print_build_info();
+    run_tui(&options, control, outputs, None)
+}
+
+/// Where each refresh goes besides the display or report
+#[derive(Default)]
+struct Outputs {
+    /// `--csv`
+    capture: Option<CsvCapture>,
+    /// `--record`
+    history: Option<HistoryRecorder>,
+    /// `--listen`
+    api: Option<ApiServer>,
+    /// `--event-log`
+    events: Option<EventLog>,
+}
+
+/// Data shown in place of this machine's, such as an agent's stream
+struct Remote {
+    provider: Box<dyn SystemProvider>,
+    /// Space, the arrows, `,` `.` `+` and `-` move through the data, as
+    /// for a recording
+    playback: bool,
+}
+
+/// Print a plain-text report every interval until interrupted, for
+/// `--linear` and `--batch`
+///
+/// Without `--delay`, linear reports are further apart than screen
+/// refreshes, so a screen reader can finish one before the next arrives.
+fn run_reports(
+    options: &Options,
+    interval: Duration,
+    write_report: fn(
+        &mut io::StdoutLock<'static>,
+        &Snapshot,
+        &HashMap<u32, String>,
+        &Options,
+    ) -> io::Result<()>,
+    mut outputs: Outputs,
+) -> io::Result<()> {
+    let interval = interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let users = cache::fetch_users();
+    let mut system = System::new_all();
+    let mut lifecycle = Lifecycle::default();
+
+    loop {
+        // CPU usage is measured between two refreshes
+        std::thread::sleep(interval);
+        system.refresh_cpu();
+        system.refresh_memory();
+        provider::refresh_processes(&mut system);
+        let system = Snapshot::from_system(&system);
+        if let Some(events) = &mut outputs.events {
+            events.write(&lifecycle.update(&system, &users))?;
+        }
+        if let Some(capture) = &mut outputs.capture {
+            capture.record(&system, &users, options)?;
+        }
+        if let Some(history) = &mut outputs.history {
+            history.record(&system, &users, options)?;
+        }
+        if let Some(api) = &outputs.api {
+            api.publish(&system, &matching_processes(&system, options));
+        }
 
+        match write_report(&mut io::stdout().lock(), &system, &users, options) {
+            // The reader, e.g. `head`, has gone away
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+    }
+}
+
+/// Take over the terminal and run the monitor until it quits
+fn run_tui(
+    options: &Options,
+    control: Option<Receiver<Result<ControlCommand, String>>>,
+    outputs: Outputs,
+    remote: Option<Remote>,
+) -> io::Result<()> {
     // Initialize terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -44,7 +323,7 @@ fn main() -> Result<(), io::Error> {
     let mut terminal = Terminal::new(backend)?;
 
     // Run the main application
-    let result = run_application(&mut terminal);
+    let result = run_application(&mut terminal, options, control, outputs, remote);
 
     // Cleanup terminal
     disable_raw_mode()?;
@@ -58,6 +337,139 @@ fn main() -> Result<(), io::Error> {
     result
 }
 
+/// Run `sysly stress`: load the machine while the monitor shows it, then
+/// print a before/after report
+///
+/// The monitor quits by itself when the duration is up; quitting earlier
+/// ends the load early.
+fn run_stress(options: StressOptions) -> io::Result<()> {
+    let mut system = System::new();
+    system.refresh_memory();
+    if options.memory >= system.total_memory() {
+        eprintln!(
+            "sysly: {} is more than the machine's {} of memory",
+            format_bytes(options.memory),
+            format_bytes(system.total_memory())
+        );
+        std::process::exit(1);
+    }
+
+    println!("Measuring an idle baseline...");
+    let baseline = stress::measure(stress::SETTLE_TIME);
+    let duration = options.duration;
+    let run = StressRun::start(options, baseline);
+
+    let (sender, control) = mpsc::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = sender.send(Ok(ControlCommand::Quit));
+    });
+    let result = run_tui(&Options::default(), Some(control), Outputs::default(), None);
+
+    println!("Load stopped, measuring recovery...");
+    let report = run.finish();
+    result?;
+    print!("{}", report);
+    Ok(())
+}
+
+/// Run `sysly log-pid`: record a process to CSV until it exits
+///
+/// Interrupting stops the recording; the rows written so far are kept.
+fn log_pid(pid: Pid, output: &Path, delay: Option<Duration>) -> io::Result<()> {
+    let interval = delay
+        .unwrap_or(Duration::from_millis(REFRESH_INTERVAL_MS))
+        .max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        eprintln!("sysly: no process with PID {}", pid);
+        std::process::exit(1);
+    }
+    let name = system.process(pid).map_or("", |process| process.name());
+    println!(
+        "Logging PID {} ({}) to {} until it exits",
+        pid,
+        name,
+        output.display()
+    );
+
+    let mut log = PidLog::create(pid, output)?;
+    loop {
+        // CPU usage is measured between two refreshes
+        std::thread::sleep(interval);
+        // A single-process refresh leaves an exited process in the list
+        if !system.refresh_process(pid) || !log.record(&Snapshot::from_system(&system))? {
+            break;
+        }
+    }
+    println!(
+        "PID {} exited; wrote {} rows to {}",
+        pid,
+        log.rows,
+        output.display()
+    );
+    Ok(())
+}
+
+/// Print a JSON snapshot of the system for `--json`
+///
+/// Processes follow the `--user`/`--pid` filters, the `--sort` order and
+/// the `--top` count.
+fn print_snapshot(options: &Options) -> io::Result<()> {
+    let mut system = System::new_all();
+    // CPU usage is measured between two refreshes
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_cpu();
+    system.refresh_processes();
+
+    let system = Snapshot::from_system(&system);
+    let processes = matching_processes(&system, options);
+    export::write_snapshot(&mut io::stdout().lock(), &system, &processes)
+}
+
+/// Processes passing the `--user`/`--pid` filters, in `--sort` order and
+/// cut to the `--top` count
+fn matching_processes<'a>(system: &'a Snapshot, options: &Options) -> Vec<&'a ProcessSnapshot> {
+    let mut processes: Vec<_> = system
+        .processes()
+        .values()
+        .filter(|process| process.thread_kind().is_none() && options.matches(system, process))
+        .collect();
+    let order = options.sort.unwrap_or_default();
+    let sched = sort::read_sched(&processes, order);
+    let readings = SortReadings {
+        sched: Some(&sched),
+        ..SortReadings::default()
+    };
+    sort::sort_processes(&mut processes, order, readings);
+    if let Some(limit) = options.limit {
+        processes.truncate(limit);
+    }
+    processes
+}
+
+/// Print the process tree for `sysly tree`
+///
+/// CPU usage needs two samples, so the process list is read twice with
+/// sysinfo's minimum interval in between.
+fn print_tree(format: TreeFormat, output: Option<PathBuf>) -> Result<(), io::Error> {
+    let mut system = System::new_all();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes();
+    let system = Snapshot::from_system(&system);
+
+    match output {
+        Some(path) => {
+            let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+            export::write_tree(&mut out, &system, format)?;
+        }
+        None => {
+            export::write_tree(&mut io::stdout().lock(), &system, format)?;
+        }
+    }
+    Ok(())
+}
+
 /// Print build information to stdout
 fn print_build_info() {
     println!("Project: {}", build_info::PROJECT_NAME);
@@ -72,94 +484,1525 @@ fn print_build_info() {
 /// Main application loop
 ///
 /// Handles terminal rendering, event processing, and system updates
-fn run_application(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> io::Result<()> {
-    let mut system = System::new_all();
-    let mut last_update = Instant::now();
+fn run_application(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    options: &Options,
+    control: Option<Receiver<Result<ControlCommand, String>>>,
+    mut outputs: Outputs,
+    remote: Option<Remote>,
+) -> io::Result<()> {
+    let refresh_interval = options
+        .delay
+        .unwrap_or(Duration::from_millis(REFRESH_INTERVAL_MS));
+    // Data from elsewhere starts out empty, since this machine's meters,
+    // inventory and collectors would only be wrong for it
+    let local = remote.is_none();
+    let playback = remote.as_ref().is_some_and(|remote| remote.playback);
+    // Processes are read on the sampling thread, so the first frame shows
+    // the meters, and the inventory from the last run when there is one,
+    // until its first sample arrives
+    let inventory = Inventory::load().filter(|_| local);
+    let mut warm_start = inventory.is_some();
+    let mut system = if local {
+        let mut meters = System::new();
+        meters.refresh_cpu();
+        meters.refresh_memory();
+        Snapshot::from_system(&meters)
+    } else {
+        Snapshot::default()
+    };
+    let mut last_process_refresh = Instant::now();
+    let mut inventory_saved = !local;
+    let mut collectors = Collectors::default();
+    let config = config::Config::load();
+    let source = remote.as_ref().and_then(|remote| remote.provider.source());
+    let provider: Box<dyn SystemProvider> = match (remote, &inventory) {
+        (Some(remote), _) => remote.provider,
+        (None, Some(inventory)) => {
+            Box::new(LiveProvider::new().with_known_sensors(&inventory.sensors))
+        }
+        (None, None) => Box::new(LiveProvider::new()),
+    };
+    let sampler = Sampler::spawn(
+        provider,
+        refresh_interval,
+        config.collector_interval.unwrap_or(refresh_interval),
+        recorders(&mut outputs, options),
+    );
+    theme::set_theme(config.theme);
+    theme::set_bar_style(config.bar_style);
+    let mut title = (options.title || config.terminal_title).then(|| TerminalTitle {
+        current: String::new(),
+    });
+    let mut alerts = Alerts::new(config.notifications);
+    if title.is_some() {
+        // Save the terminal's own title to restore on exit
+        write!(terminal.backend_mut(), "\x1b[22;0t")?;
+    }
     let mut app_state = AppState {
-        show_help: false,
+        modal: None,
         selected_row_index: 0,
+        tree_view: options.tree,
+        grouping: Grouping::default(),
+        collapsed_groups: HashSet::new(),
+        sort: options.sort.unwrap_or_default(),
+        user_filter: options.user,
+        pid_filter: options.pids.clone(),
+        row_limit: options.limit,
+        name_filter: None,
+        collapsed: HashSet::new(),
+        table_offset: 0,
+        watched: Vec::new(),
+        respawns: VecDeque::new(),
+        compressor: local
+            .then(|| collectors.run_optional("vm_stat", process::fetch_compressor_stats))
+            .flatten(),
+        memory_stats: local
+            .then(|| collectors.run_optional("memory breakdown", memstat::fetch_memory_stats))
+            .flatten(),
+        swap: SwapMeter::default(),
+        disk: {
+            let mut meter = DiskMeter::default();
+            if local {
+                meter.sample(collectors.run_optional("disk counters", disk::fetch_disk_counters));
+            }
+            meter
+        },
+        gpu: local
+            .then(|| collectors.run_optional("gpu", gpu::fetch_gpu_usage))
+            .flatten(),
+        battery: local
+            .then(|| collectors.run_optional("battery", battery::fetch_battery))
+            .flatten(),
+        battery_trend: BatteryTrend::default(),
+        meter_mode: config.meter_mode,
+        cpu_history: CpuHistory::default(),
+        reduced_rendering: false,
+        cgroup: None,
+        cgroups: Vec::new(),
+        containers: Vec::new(),
+        docker_error: None,
+        container_names: HashMap::new(),
+        host: if local {
+            host::fetch_host_info(&system)
+        } else {
+            HostInfo::default()
+        },
+        core_clusters: match &inventory {
+            _ if !local => Vec::new(),
+            Some(inventory) if fits_cpus(&inventory.core_clusters, system.cpus().len()) => {
+                inventory.core_clusters.clone()
+            }
+            _ => topology::fetch_core_clusters(system.cpus().len()),
+        },
+        show_filesystems: false,
+        volumes: inventory
+            .as_ref()
+            .map(|inventory| inventory.volumes.clone())
+            .unwrap_or_default(),
+        fans: local
+            .then(|| collectors.run_optional("fans", fans::fetch_fans))
+            .flatten()
+            .unwrap_or_default(),
+        sensors: match &inventory {
+            Some(inventory) => SensorLog::with_known(&inventory.sensors),
+            None => SensorLog::default(),
+        },
+        users: match inventory {
+            Some(inventory) => inventory.users,
+            None if local => collectors
+                .run("users", cache::fetch_users)
+                .unwrap_or_default(),
+            // The provider names the other machine's users
+            None => HashMap::new(),
+        },
+        extensions: Vec::new(),
+        startup_items: Vec::new(),
+        brew_services: Some(Vec::new()),
+        brew_restart: None,
+        tagged: HashSet::new(),
+        lifecycle: Lifecycle::default(),
+        status_message: None,
+        // Filled in below, so the collectors behind the columns start
+        columns: Vec::new(),
+        meters: config.meters.clone(),
+        wakeups: HashMap::new(),
+        origins: OriginCache::default(),
+        rosetta: RosettaCache::default(),
+        disk_io: HashMap::new(),
+        sched: HashMap::new(),
+        nap: HashMap::new(),
+        cpu_smoother: CpuSmoother::new(config.cpu_smoothing),
+        process_states: StateTracker::default(),
+        pid_logs: Vec::new(),
+        energy: false,
+        energy_impact: HashMap::new(),
+        energy_ledger: EnergyLedger::default(),
+        view: View::default(),
+        process_interval: Duration::ZERO,
+        network: HashMap::new(),
+        network_order: NetworkOrder::default(),
+        user_order: UserOrder::default(),
+        config,
+        focus: Pane::Processes,
+        paused: HashSet::new(),
+        process_maps: ProcessMaps::default(),
+        row_cache: RowCache::default(),
+        display_cache: DisplayCache::default(),
+        thread_totals: Default::default(),
+        thermal_pressure: local
+            .then(|| collectors.run_optional("thermal pressure", sensors::fetch_thermal_pressure))
+            .flatten(),
+        source,
+        collectors,
     };
+    let columns = app_state.config.columns.clone();
+    set_columns(&mut app_state, &system, columns);
+    let mut throttle = RenderThrottle::default();
+    let mut input = true;
 
     loop {
-        // Render the current state
-        terminal.draw(|frame| {
-            let size = frame.size();
-            let outer_block = ratatui::widgets::Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .style(Style::default().bg(Color::Black));
-
-            frame.render_widget(outer_block, size);
-
-            let inner_area = Rect {
-                x: size.x + 1,
-                y: size.y + 1,
-                width: size.width - 2,
-                height: size.height - 2,
-            };
-
-            if app_state.show_help {
-                draw_help_window(frame, inner_area);
-            } else {
-                draw_dashboard(frame, &system, inner_area, app_state.selected_row_index);
+        // Render the current state, less often when the terminal is slow
+        if throttle.should_draw(input) {
+            let started = Instant::now();
+            draw_frame(terminal, &system, &mut app_state, options)?;
+            if let Some(slow) = throttle.record(started.elapsed()) {
+                app_state.reduced_rendering = slow;
+                app_state.status_message = Some(
+                    if slow {
+                        "Slow terminal: redrawing less often, graphs off"
+                    } else {
+                        "Terminal caught up: full redraws"
+                    }
+                    .to_string(),
+                );
             }
-        })?;
+            input = false;
+        }
 
         // Handle user input
         if event::poll(Duration::from_millis(EVENT_POLL_TIMEOUT_MS))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    handle_key_event(&mut app_state, key.code);
-                    if key.code == KeyCode::Char('q') {
-                        break;
+            input = true;
+            let quit = match event::read()? {
+                Event::Key(key) => match playback_step(key.code) {
+                    Some(step) if playback && app_state.modal.is_none() => {
+                        sampler.play(step);
+                        false
                     }
-                }
+                    _ => handle_key_event(&mut app_state, key.code, &mut system),
+                },
                 Event::Mouse(me) => {
-                    handle_mouse_event(&mut app_state, me);
+                    handle_mouse_event(&mut app_state, me, &system);
+                    false
                 }
-                _ => {}
+                _ => false,
+            };
+            if quit {
+                break;
+            }
+        }
+        // Scripted commands apply in order, after any keys pressed
+        if let Some(commands) = &control {
+            if commands
+                .try_iter()
+                .any(|command| apply_control_command(&mut app_state, command, &system))
+            {
+                break;
             }
         }
 
-        // Update system information periodically
-        if !app_state.show_help
-            && last_update.elapsed() > Duration::from_millis(REFRESH_INTERVAL_MS)
-        {
-            system.refresh_all();
-            last_update = Instant::now();
+        // Take in the sampling thread's latest readings
+        sampler.set_demand(demand(&app_state));
+        let sample = if matches!(app_state.modal, Some(Modal::Help)) {
+            None
+        } else {
+            sampler.try_take()
+        };
+        if let Some(sample) = sample {
+            app_state.collectors.absorb(&sample.health);
+            app_state.source = sample.source;
+            if let Some(message) = sample.stopped.into_iter().last() {
+                app_state.status_message = Some(message);
+            }
+            // Paused panes keep showing the data from their last refresh
+            if app_state.paused.contains(&Pane::Processes) {
+                if !app_state.paused.contains(&Pane::Meters) {
+                    system.update_meters(sample.system);
+                }
+            } else {
+                system = sample.system;
+            }
+            if !local {
+                app_state.host = HostInfo {
+                    name: system.host_name().unwrap_or_default(),
+                    chip: system
+                        .cpus()
+                        .first()
+                        .map(|cpu| cpu.brand().to_string())
+                        .unwrap_or_default(),
+                    ..HostInfo::default()
+                };
+            }
+            if !app_state.paused.contains(&Pane::Meters) {
+                app_state.cpu_history.record(system.cpus());
+                app_state
+                    .cpu_history
+                    .record_memory(system.used_memory(), system.total_memory());
+                app_state.compressor = sample.readings.compressor;
+                app_state.memory_stats = sample.readings.memory_stats;
+                app_state.swap.sample(app_state.memory_stats.as_ref());
+                app_state.disk.sample(sample.readings.disk_counters);
+                app_state.gpu = sample.readings.gpu;
+                app_state.battery = sample.readings.battery;
+                app_state.battery_trend.record(app_state.battery.as_ref());
+                if let Some(volumes) = sample.readings.volumes {
+                    app_state.volumes = volumes;
+                }
+                app_state.cgroup = sample.readings.cgroup;
+                app_state.sensors = sample.readings.sensors;
+                app_state.fans = sample.readings.fans;
+                app_state.thermal_pressure = sample.readings.thermal_pressure;
+                alerts.check_thermal(&app_state.sensors, app_state.thermal_pressure);
+            }
+            if !app_state.paused.contains(&Pane::Processes) {
+                app_state.row_cache.clear();
+                app_state.display_cache.clear();
+                app_state.cpu_smoother.update(&system);
+                app_state.process_states.update(&system);
+                app_state.process_interval = last_process_refresh.elapsed();
+                last_process_refresh = Instant::now();
+                app_state.wakeups = sample.readings.wakeups;
+                app_state.disk_io = sample.readings.disk_io;
+                app_state.sched = sample.readings.sched;
+                app_state.nap = sample.readings.nap;
+                if app_state.shows(Column::Origin) {
+                    classify_origins(&mut app_state, &system);
+                }
+                if local {
+                    let rosetta = &mut app_state.rosetta;
+                    app_state
+                        .collectors
+                        .run("rosetta", || rosetta.update(&system));
+                }
+                if let Some(users) = sample.readings.users {
+                    app_state.users = users;
+                }
+                if let Some(impact) = sample.readings.energy_impact {
+                    app_state.energy_impact = impact;
+                }
+                // The column stays up showing `-`, with the reason on the
+                // status line, e.g. when sudo wanted a password
+                if let Some(message) = sample.readings.energy_error {
+                    app_state.status_message =
+                        Some(format!("Energy impact unavailable: {}", message));
+                    app_state.energy = false;
+                }
+                if let Some(groups) = sample.readings.cgroups {
+                    app_state.cgroups = groups;
+                }
+                update_energy_ledger(&mut app_state, &system);
+                if let Some(slow) = sample.slow {
+                    if let Some(totals) = slow.thread_totals {
+                        app_state.thread_totals = totals;
+                    }
+                    app_state.process_maps = slow.process_maps;
+                    if let Some(network) = slow.network {
+                        app_state.network = network;
+                    }
+                }
+                if let Some(names) = sample.container_names {
+                    app_state.container_names = names;
+                }
+                if let Some(Modal::Inspector(inspector)) = &mut app_state.modal {
+                    inspector.sample(&system);
+                }
+                record_pid_logs(&mut app_state, &system);
+                let events = app_state.lifecycle.update(&system, &app_state.users);
+                if let Some(log) = &mut outputs.events {
+                    if let Err(err) = log.write(&events) {
+                        app_state.status_message = Some(format!("Event log stopped: {}", err));
+                        outputs.events = None;
+                    }
+                }
+                match sample.containers {
+                    Some(Ok(containers)) => {
+                        app_state.containers = containers;
+                        app_state.docker_error = None;
+                    }
+                    Some(Err(err)) => {
+                        app_state.containers.clear();
+                        app_state.docker_error = Some(err);
+                    }
+                    None => {}
+                }
+                finish_brew_restart(&mut app_state);
+                if let Some(api) = &outputs.api {
+                    api.publish(&system, &export::listed_processes(&system, &app_state));
+                }
+            }
+            app_state
+                .collapsed
+                .retain(|pid| system.process(*pid).is_some());
+            app_state
+                .tagged
+                .retain(|pid| system.process(*pid).is_some());
+            let respawns = check_respawns(&mut app_state.watched, &system);
+            alerts.respawned(&respawns);
+            alerts.flush(terminal.backend_mut())?;
+            app_state.respawns.extend(respawns);
+            // Don't pull an open dialog out from under the user; the next
+            // diff shows once it's closed
+            if app_state.modal.is_none() {
+                app_state.modal = app_state.respawns.pop_front().map(Modal::Respawn);
+            }
+            if let Some(title) = &mut title {
+                title.update(terminal.backend_mut(), &system)?;
+            }
+            if warm_start {
+                // Accounts may have changed since the inventory was saved
+                if let Some(users) = app_state.collectors.run("users", cache::fetch_users) {
+                    app_state.users = users;
+                    app_state.display_cache.clear();
+                }
+                warm_start = false;
+            }
+            if !inventory_saved {
+                save_inventory(&mut app_state);
+                inventory_saved = true;
+            }
         }
     }
 
+    if title.is_some() {
+        write!(terminal.backend_mut(), "\x1b[23;0t")?;
+    }
+    Ok(())
+}
+
+/// Draw one frame: the dashboard or help, with any modal over it
+fn draw_frame(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    system: &Snapshot,
+    app_state: &mut AppState,
+    options: &Options,
+) -> io::Result<()> {
+    terminal.draw(|frame| {
+        let size = frame.size();
+        frame.render_widget(outer_block(app_state), size);
+
+        let inner_area = Rect {
+            x: size.x + 1,
+            y: size.y + 1,
+            width: size.width - 2,
+            height: size.height - 2,
+        };
+
+        // Help replaces the dashboard; every other modal overlays it
+        if let Some(Modal::Help) = app_state.modal {
+            draw_help_window(frame, inner_area);
+        } else {
+            draw_dashboard(frame, system, inner_area, app_state);
+        }
+
+        match &app_state.modal {
+            Some(Modal::Respawn(diff)) => draw_respawn_window(frame, inner_area, diff),
+            Some(Modal::Diagnostics) => {
+                draw_diagnostics_window(frame, inner_area, &app_state.collectors)
+            }
+            Some(Modal::Confirm(confirmation)) => {
+                draw_confirm_window(frame, inner_area, confirmation)
+            }
+            Some(Modal::SortMenu(selected)) => {
+                draw_sort_menu(frame, inner_area, *selected, app_state.sort)
+            }
+            Some(Modal::Setup(setup)) => draw_setup_window(frame, inner_area, setup, app_state),
+            Some(Modal::SignalMenu { selected, group }) => {
+                let target_count = if *group {
+                    group_targets(system, app_state).len()
+                } else {
+                    action_targets(system, app_state).len()
+                };
+                draw_signal_menu(frame, inner_area, *selected, target_count, *group);
+            }
+            Some(Modal::Inspector(inspector)) => draw_inspector_window(
+                frame,
+                inner_area,
+                inspector,
+                app_state.network.get(&inspector.pid),
+                app_state.process_states.span(inspector.pid),
+                app_state.config.time_format,
+            ),
+            Some(Modal::Help) | None => {}
+        }
+
+        if app_state.config.background == Background::Terminal {
+            inherit_background(frame.buffer_mut());
+        }
+        if options.no_color {
+            strip_colors(frame.buffer_mut());
+        }
+    })?;
     Ok(())
 }
 
+/// The terminal title kept up to date with `--title`, so CPU and memory
+/// show in tab bars and tmux status lines while the pane is in the background
+struct TerminalTitle {
+    /// Title last written, to skip rewriting an unchanged one
+    current: String,
+}
+
+impl TerminalTitle {
+    fn update(&mut self, out: &mut impl io::Write, system: &Snapshot) -> io::Result<()> {
+        let memory = if system.total_memory() > 0 {
+            system.used_memory() as f64 / system.total_memory() as f64 * 100.0
+        } else {
+            0.0
+        };
+        let title = format!(
+            "sysly: cpu {:.0}% mem {:.0}% {}",
+            system.global_cpu_info().cpu_usage(),
+            memory,
+            system.host_name().unwrap_or_default()
+        );
+        if title != self.current {
+            execute!(out, SetTitle(&title))?;
+            self.current = title;
+        }
+        Ok(())
+    }
+}
+
+/// Apply a command read from the control channel
+///
+/// Rejected lines and export results are reported in the status line.
+///
+/// # Returns
+/// True when the command asked to quit
+fn apply_control_command(
+    app_state: &mut AppState,
+    command: Result<ControlCommand, String>,
+    system: &Snapshot,
+) -> bool {
+    match command {
+        Ok(ControlCommand::Sort(key)) => {
+            app_state.sort.set_primary(key);
+            app_state.selected_row_index = 0;
+        }
+        Ok(ControlCommand::Filter(filter)) => {
+            app_state.name_filter = filter;
+            app_state.selected_row_index = 0;
+        }
+        Ok(ControlCommand::Export(path)) => {
+            app_state.status_message = Some(match export::export_json(&path, system, app_state) {
+                Ok(count) => format!("Exported {} processes to {}", count, path.display()),
+                Err(err) => format!("Export to {} failed: {}", path.display(), err),
+            });
+        }
+        Ok(ControlCommand::Tree(path)) => write_tree_file(app_state, system, &path),
+        Ok(ControlCommand::Quit) => return true,
+        Err(message) => app_state.status_message = Some(format!("control: {}", message)),
+    }
+    false
+}
+
+/// Start recording a process to `sysly-<pid>.csv`, or stop if it already is
+fn toggle_pid_log(app_state: &mut AppState, pid: Pid) {
+    if let Some(index) = app_state.pid_logs.iter().position(|log| log.pid == pid) {
+        let log = app_state.pid_logs.remove(index);
+        app_state.status_message = Some(format!(
+            "Stopped logging PID {}; wrote {} rows to {}",
+            pid,
+            log.rows,
+            log.path.display()
+        ));
+        return;
+    }
+
+    let path = pidlog::default_path(pid);
+    app_state.status_message = Some(match PidLog::create(pid, &path) {
+        Ok(log) => {
+            app_state.pid_logs.push(log);
+            format!("Logging PID {} to {} until it exits", pid, path.display())
+        }
+        Err(err) => format!("Logging to {} failed: {}", path.display(), err),
+    });
+}
+
+/// Append the latest refresh to each process log, closing the logs of
+/// processes that exited
+fn record_pid_logs(app_state: &mut AppState, system: &Snapshot) {
+    let mut finished = None;
+    app_state
+        .pid_logs
+        .retain_mut(|log| match log.record(system) {
+            Ok(true) => true,
+            Ok(false) => {
+                finished = Some(format!(
+                    "PID {} exited; wrote {} rows to {}",
+                    log.pid,
+                    log.rows,
+                    log.path.display()
+                ));
+                false
+            }
+            Err(err) => {
+                finished = Some(format!(
+                    "Logging PID {} to {} failed: {}",
+                    log.pid,
+                    log.path.display(),
+                    err
+                ));
+                false
+            }
+        });
+    if finished.is_some() {
+        app_state.status_message = finished;
+    }
+}
+
+/// Write the process tree to a file and report the result in the status line
+fn write_tree_file(app_state: &mut AppState, system: &Snapshot, path: &std::path::Path) {
+    app_state.status_message = Some(match export::export_tree(path, system) {
+        Ok(count) => format!(
+            "Wrote the tree of {} processes to {}",
+            count,
+            path.display()
+        ),
+        Err(err) => format!("Writing the tree to {} failed: {}", path.display(), err),
+    });
+}
+
 /// Handle keyboard events and update application state
 ///
 /// * `app_state` - Current application state to modify
 /// * `key_code` - The key code that was pressed
-fn handle_key_event(app_state: &mut AppState, key_code: KeyCode) {
+/// * `system` - Current system snapshot, used to resolve the selected row
+///
+/// # Returns
+/// True when the user asked to quit
+fn handle_key_event(app_state: &mut AppState, key_code: KeyCode, system: &mut Snapshot) -> bool {
+    if key_code == KeyCode::Char('q')
+        && !app_state
+            .modal
+            .as_ref()
+            .is_some_and(|modal| modal.captures_text())
+    {
+        return true;
+    }
+
+    if let Some(modal) = app_state.modal.take() {
+        app_state.modal = handle_modal_key(app_state, modal, key_code, system);
+        return false;
+    }
+
+    app_state.status_message = None;
+    if app_state.source.is_some() && acts_locally(key_code) {
+        app_state.status_message =
+            Some("Processes from elsewhere can't be acted on or inspected".to_string());
+        return false;
+    }
+
+    let row_count = match app_state.view {
+        View::Extensions => app_state.extensions.len(),
+        View::Startup => app_state.startup_items.len(),
+        View::Users => user_totals(system, &app_state.users, |_| 0.0, app_state.user_order).len(),
+        View::Services => app_state.brew_services.as_ref().map_or(0, Vec::len),
+        View::Cgroups => app_state.cgroups.len(),
+        View::Containers => app_state.containers.len(),
+        View::Events => app_state.lifecycle.events().len(),
+        View::Energy => app_state.energy_ledger.apps().len(),
+        _ => display_rows(system, app_state).len(),
+    };
+    let last_row = row_count.saturating_sub(1);
+
     match key_code {
-        KeyCode::Char('q') => {
-            // Exit handled in main loop
-        }
         KeyCode::F(1) => {
-            app_state.show_help = true;
+            app_state.modal = Some(Modal::Help);
+        }
+        KeyCode::F(9) | KeyCode::Char('k') if !action_targets(system, app_state).is_empty() => {
+            app_state.modal = Some(Modal::SignalMenu {
+                selected: 0,
+                group: false,
+            });
+        }
+        KeyCode::Char('K') if !group_targets(system, app_state).is_empty() => {
+            app_state.modal = Some(Modal::SignalMenu {
+                selected: 0,
+                group: true,
+            });
+        }
+        KeyCode::Char(']') => switch_view(app_state, step_view(app_state, View::next)),
+        KeyCode::Char('[') => switch_view(app_state, step_view(app_state, View::previous)),
+        KeyCode::Char('s') if app_state.view == View::Users => {
+            app_state.user_order = app_state.user_order.next();
+            app_state.selected_row_index = 0;
+        }
+        KeyCode::Char('s') if app_state.view == View::Network => {
+            app_state.network_order = app_state.network_order.toggle();
+            app_state.selected_row_index = 0;
+        }
+        KeyCode::Char('G') => {
+            app_state.meter_mode = app_state.meter_mode.next();
         }
-        _ => {
-            // Any other key closes help window if it's open
-            if app_state.show_help {
-                app_state.show_help = false;
+        KeyCode::Char('f') => {
+            app_state.show_filesystems = !app_state.show_filesystems;
+        }
+        KeyCode::Char('W') => toggle(app_state, system, &[Column::IdleWakeups]),
+        KeyCode::Char('O') => toggle(app_state, system, &[Column::Origin]),
+        KeyCode::Char('E') => toggle(app_state, system, &[Column::Energy]),
+        KeyCode::Char('Q') => toggle(app_state, system, &[Column::Qos]),
+        KeyCode::Char('A') => toggle(app_state, system, &[Column::Nap]),
+        KeyCode::Char('T') => {
+            let theme = theme::cycle_theme();
+            app_state.status_message = Some(format!("Theme: {}", theme.name));
+        }
+        KeyCode::Char('I') => toggle(app_state, system, &[Column::DiskRead, Column::DiskWrite]),
+        KeyCode::Char('X') => {
+            let name = format!(
+                "sysly-tree-{}.txt",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            );
+            write_tree_file(app_state, system, &PathBuf::from(name));
+        }
+        KeyCode::Char('J') => {
+            let path = PathBuf::from(format!(
+                "sysly-snapshot-{}.json",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ));
+            app_state.status_message =
+                Some(match export::export_snapshot(&path, system, app_state) {
+                    Ok(count) => format!(
+                        "Wrote a snapshot with {} processes to {}",
+                        count,
+                        path.display()
+                    ),
+                    Err(err) => format!("Writing {} failed: {}", path.display(), err),
+                });
+        }
+        KeyCode::Char('g') => toggle(app_state, system, &[Column::Pgid, Column::Sid]),
+        KeyCode::Char('P') => toggle(app_state, system, &[Column::Ppid]),
+        KeyCode::Backspace if app_state.view.lists_processes() => {
+            let Some(pid) = selected_pid(system, app_state) else {
+                return false;
+            };
+            match system.process(pid).and_then(|process| process.parent()) {
+                Some(parent) => {
+                    if !show_process(app_state, system, parent) {
+                        app_state.status_message =
+                            Some(format!("Parent PID {} is hidden by the filter", parent));
+                    }
+                }
+                None => app_state.status_message = Some(format!("PID {} has no parent", pid)),
+            }
+        }
+        KeyCode::Char('L') => {
+            if let Some(pid) = selected_pid(system, app_state) {
+                toggle_pid_log(app_state, pid);
             }
         }
+        KeyCode::Char('z') => {
+            let action = ProcessAction::Signal {
+                name: "STOP",
+                signal: libc::SIGSTOP,
+            };
+            app_state.modal = request_action(app_state, system, action);
+        }
+        KeyCode::Char('c') => {
+            let action = ProcessAction::Signal {
+                name: "CONT",
+                signal: libc::SIGCONT,
+            };
+            app_state.modal = request_action(app_state, system, action);
+        }
+        KeyCode::F(7) => {
+            app_state.modal = request_action(app_state, system, ProcessAction::Renice(-1));
+        }
+        KeyCode::F(8) => {
+            app_state.modal = request_action(app_state, system, ProcessAction::Renice(1));
+        }
+        KeyCode::Enter if app_state.view == View::Services => {
+            let selected = app_state
+                .brew_services
+                .as_ref()
+                .and_then(|services| services.get(app_state.selected_row_index));
+            let Some(service) = selected else {
+                return false;
+            };
+            match service.running_process(system) {
+                Some(pid) => {
+                    if !show_process(app_state, system, pid) {
+                        app_state.status_message =
+                            Some(format!("PID {} is hidden by the filter", pid));
+                    }
+                }
+                None => app_state.status_message = Some(format!("{} is not running", service.name)),
+            }
+        }
+        KeyCode::Char('r') if app_state.view == View::Services => {
+            let selected = app_state
+                .brew_services
+                .as_ref()
+                .and_then(|services| services.get(app_state.selected_row_index));
+            let Some(service) = selected else {
+                return false;
+            };
+            if app_state.brew_restart.is_some() {
+                app_state.status_message = Some("A restart is still running".to_string());
+                return false;
+            }
+            let name = service.name.clone();
+            match brew::restart_service(&name) {
+                Ok(child) => {
+                    app_state.status_message = Some(format!("Restarting {}...", name));
+                    app_state.brew_restart = Some((name, child));
+                }
+                Err(err) => {
+                    app_state.status_message =
+                        Some(format!("Cannot run brew services restart: {}", err))
+                }
+            }
+        }
+        KeyCode::Enter => {
+            if let Some(pid) = selected_pid(system, app_state) {
+                app_state.modal = Some(Modal::Inspector(Box::new(Inspector::open(pid, system))));
+            }
+        }
+        KeyCode::Char('i') => {
+            if let Some(pid) = selected_pid(system, app_state) {
+                let mut inspector = Inspector::open(pid, system);
+                inspector.show_tab(InspectorTab::Identity, system);
+                app_state.modal = Some(Modal::Inspector(Box::new(inspector)));
+            }
+        }
+        KeyCode::F(2) => {
+            app_state.modal = Some(Modal::Setup(SetupMenu {
+                section: SetupSection::Columns,
+                selected: 0,
+            }));
+        }
+        KeyCode::F(6) => {
+            let current = SortKey::ALL
+                .iter()
+                .position(|key| *key == app_state.sort.primary)
+                .unwrap_or(0);
+            app_state.modal = Some(Modal::SortMenu(current));
+        }
+        KeyCode::Char('D') => {
+            app_state.modal = Some(Modal::Diagnostics);
+        }
+        KeyCode::Tab => {
+            app_state.focus = app_state.focus.next();
+        }
+        KeyCode::Char('p') => {
+            let pane = app_state.focus;
+            if !app_state.paused.remove(&pane) {
+                app_state.paused.insert(pane);
+            }
+        }
+        KeyCode::Char('Z') => {
+            // Freeze every pane at once, or thaw them all
+            if app_state.paused.len() == Pane::ALL.len() {
+                app_state.paused.clear();
+                app_state.status_message = Some("Updates resumed".to_string());
+            } else {
+                app_state.paused.extend(Pane::ALL);
+                app_state.status_message = Some("Updates frozen, Z to resume".to_string());
+            }
+        }
+        KeyCode::Char('t') => {
+            app_state.tree_view = !app_state.tree_view;
+        }
+        KeyCode::Char('b') => {
+            // Grouping replaces the tree
+            app_state.tree_view = false;
+            app_state.grouping = app_state.grouping.next();
+            app_state.selected_row_index = 0;
+            app_state.status_message = Some(format!("Group by: {}", app_state.grouping.label()));
+        }
+        KeyCode::Char('-') | KeyCode::Char('+') if selected_group(system, app_state).is_some() => {
+            if let Some(group) = selected_group(system, app_state) {
+                if key_code == KeyCode::Char('-') {
+                    app_state.collapsed_groups.insert(group.name);
+                } else {
+                    app_state.collapsed_groups.remove(&group.name);
+                }
+            }
+        }
+        KeyCode::Char('-') if app_state.tree_view => {
+            if let Some(pid) = selected_pid(system, app_state) {
+                app_state.collapsed.insert(pid);
+            }
+        }
+        KeyCode::Char('+') if app_state.tree_view => {
+            if let Some(pid) = selected_pid(system, app_state) {
+                app_state.collapsed.remove(&pid);
+            }
+        }
+        KeyCode::Char(' ') => {
+            if let Some(pid) = selected_pid(system, app_state) {
+                // PID 0 can't be acted on, so it isn't tagged either
+                if !app_state.tagged.remove(&pid) && pid.as_u32() != 0 {
+                    app_state.tagged.insert(pid);
+                }
+                app_state.selected_row_index = (app_state.selected_row_index + 1).min(last_row);
+            }
+        }
+        KeyCode::Char('U') => {
+            app_state.tagged.clear();
+        }
+        KeyCode::Char('w') => {
+            if let Some(process) =
+                selected_pid(system, app_state).and_then(|pid| system.process(pid))
+            {
+                let pid = process.pid();
+                if app_state.watched.iter().any(|w| w.pid == pid) {
+                    app_state.watched.retain(|w| w.pid != pid);
+                } else {
+                    app_state
+                        .watched
+                        .push(WatchedProcess::from_process(process));
+                }
+            }
+        }
+        KeyCode::Up => {
+            app_state.selected_row_index = app_state.selected_row_index.saturating_sub(1);
+        }
+        KeyCode::Down => {
+            app_state.selected_row_index = (app_state.selected_row_index + 1).min(last_row);
+        }
+        KeyCode::PageUp => {
+            app_state.selected_row_index = app_state.selected_row_index.saturating_sub(PAGE_SIZE);
+        }
+        KeyCode::PageDown => {
+            app_state.selected_row_index = (app_state.selected_row_index + PAGE_SIZE).min(last_row);
+        }
+        KeyCode::Home => {
+            app_state.selected_row_index = 0;
+        }
+        KeyCode::End => {
+            app_state.selected_row_index = last_row;
+        }
+        _ => {}
+    }
+
+    false
+}
+
+/// The step through played-back data a key stands for
+fn playback_step(key_code: KeyCode) -> Option<Playback> {
+    match key_code {
+        KeyCode::Char(' ') => Some(Playback::Toggle),
+        KeyCode::Left | KeyCode::Char(',') => Some(Playback::Back),
+        KeyCode::Right | KeyCode::Char('.') => Some(Playback::Forward),
+        KeyCode::Char('+') | KeyCode::Char('=') => Some(Playback::Faster),
+        KeyCode::Char('-') => Some(Playback::Slower),
+        _ => None,
+    }
+}
+
+/// Whether a key signals, renices, inspects or logs the selected process, or
+/// restarts a service, all of which need the process to be this machine's
+fn acts_locally(key_code: KeyCode) -> bool {
+    matches!(
+        key_code,
+        KeyCode::F(7..=9) | KeyCode::Enter | KeyCode::Char('k' | 'K' | 'z' | 'c' | 'i' | 'L' | 'r')
+    )
+}
+
+/// The view `step` leads to, passing over the views of this machine's own
+/// setup when the data comes from elsewhere
+fn step_view(app_state: &AppState, step: fn(View) -> View) -> View {
+    let mut view = step(app_state.view);
+    while app_state.source.is_some() && view.is_local() {
+        view = step(view);
+    }
+    view
+}
+
+/// Switch process table columns on or off by key
+fn toggle(app_state: &mut AppState, system: &Snapshot, toggled: &[Column]) {
+    let mut columns = app_state.columns.clone();
+    toggle_columns(&mut columns, toggled);
+    set_columns(app_state, system, columns);
+}
+
+/// Show a new set of process table columns, starting the collectors behind
+/// columns that appear and dropping the data of those that go
+fn set_columns(app_state: &mut AppState, system: &Snapshot, mut columns: Vec<Column>) {
+    if app_state.source.is_some() {
+        columns.retain(|column| !column.is_local());
+    }
+    let added = |column| columns.contains(&column) && !app_state.shows(column);
+    let origin_added = added(Column::Origin);
+    let energy_added = added(Column::Energy);
+    app_state.columns = columns;
+
+    if origin_added {
+        classify_origins(app_state, system);
+    } else if !app_state.shows(Column::Origin) {
+        app_state.origins = OriginCache::default();
+    }
+
+    if energy_added {
+        app_state.energy = true;
+    } else if !app_state.shows(Column::Energy) {
+        app_state.energy = false;
+        app_state.energy_impact.clear();
+    }
+
+    if !shows_disk_io(&app_state.columns) {
+        app_state.disk_io.clear();
+    }
+}
+
+/// Whether either per-process disk rate column is shown
+fn shows_disk_io(columns: &[Column]) -> bool {
+    columns.contains(&Column::DiskRead) || columns.contains(&Column::DiskWrite)
+}
+
+/// What the dashboard shows that the provider has to read for it
+fn demand(app_state: &AppState) -> Demand {
+    // Sample energy as often as the process list refreshes, or the slower
+    // collectors run when they have their own interval
+    let energy_interval = match (
+        app_state.config.collector_interval,
+        app_state.process_interval,
+    ) {
+        (Some(interval), _) => interval,
+        (None, Duration::ZERO) => Duration::from_millis(REFRESH_INTERVAL_MS),
+        (None, interval) => interval,
+    };
+    Demand {
+        filesystems: app_state.show_filesystems,
+        hidden_filesystems: app_state.config.hidden_filesystems.clone(),
+        network: app_state.view == View::Network
+            || matches!(app_state.modal, Some(Modal::Inspector(_))),
+        cgroups: app_state.view == View::Cgroups,
+        disk_io: shows_disk_io(&app_state.columns),
+        energy: app_state.energy.then_some(energy_interval),
+        containers: app_state.view == View::Containers,
+        container_names: app_state.grouping == Grouping::Container
+            || app_state.shows(Column::Container),
+        sched: app_state.shows(Column::Qos) || app_state.sort.uses(SortKey::Qos),
+        nap: app_state.shows(Column::Nap),
+    }
+}
+
+/// Turn `--csv` and `--record` into recorders for the sampling thread
+fn recorders(outputs: &mut Outputs, options: &Options) -> Vec<Recorder> {
+    let mut recorders: Vec<Recorder> = Vec::new();
+    if let Some(mut capture) = outputs.capture.take() {
+        let options = options.clone();
+        let mut users = None;
+        recorders.push(Box::new(move |system| {
+            let users = users.get_or_insert_with(cache::fetch_users);
+            capture
+                .record(system, users, &options)
+                .map_err(|err| format!("CSV capture stopped: {}", err))
+        }));
+    }
+    if let Some(mut history) = outputs.history.take() {
+        let options = options.clone();
+        let mut users = None;
+        recorders.push(Box::new(move |system| {
+            let users = users.get_or_insert_with(cache::fetch_users);
+            history
+                .record(system, users, &options)
+                .map_err(|err| format!("Recording stopped: {}", err))
+        }));
+    }
+    recorders
+}
+
+/// Add the latest refresh to the per-app totals of the Energy view
+fn update_energy_ledger(app_state: &mut AppState, system: &Snapshot) {
+    let energy = app_state.energy.then_some(&app_state.energy_impact);
+    let smoother = &app_state.cpu_smoother;
+    app_state.energy_ledger.sample(
+        system,
+        |process| smoother.usage(process),
+        energy,
+        &app_state.wakeups,
+        app_state.process_interval,
+    );
+}
+
+/// Classify new processes for the origin column
+fn classify_origins(app_state: &mut AppState, system: &Snapshot) {
+    let origins = &mut app_state.origins;
+    app_state
+        .collectors
+        .run("process origins", || origins.update(system));
+}
+
+/// Show another view, loading data that is only read on demand
+fn switch_view(app_state: &mut AppState, view: View) {
+    app_state.view = view;
+    app_state.selected_row_index = 0;
+    match view {
+        View::Services => list_brew_services(app_state),
+        View::Extensions => {
+            if let Some(extensions) = app_state
+                .collectors
+                .run_optional("extensions", extensions::fetch_extensions)
+            {
+                app_state.extensions = extensions;
+            }
+        }
+        View::Startup => {
+            if let Some(items) = app_state
+                .collectors
+                .run("startup items", startup::fetch_startup_items)
+            {
+                app_state.startup_items = items;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Read `brew services list` for the services view
+fn list_brew_services(app_state: &mut AppState) {
+    app_state.brew_services = app_state
+        .collectors
+        .run_optional("brew services", brew::fetch_brew_services);
+}
+
+/// Check whether a `brew services restart` started from the services view
+/// has finished, and list the services again once it has
+fn finish_brew_restart(app_state: &mut AppState) {
+    let Some((name, child)) = &mut app_state.brew_restart else {
+        return;
+    };
+    let message = match child.try_wait() {
+        Ok(None) => return,
+        Ok(Some(status)) if status.success() => format!("Restarted {}", name),
+        Ok(Some(status)) => format!("brew services restart {} failed ({})", name, status),
+        Err(err) => format!("brew services restart {} failed: {}", name, err),
+    };
+    app_state.status_message = Some(message);
+    app_state.brew_restart = None;
+    if app_state.view == View::Services {
+        list_brew_services(app_state);
+    }
+}
+
+/// Select a process, switching to the process table unless a view listing
+/// processes is open
+///
+/// # Returns
+/// Whether the process is listed; a filter or row limit can hide it
+fn show_process(app_state: &mut AppState, system: &Snapshot, pid: Pid) -> bool {
+    if !app_state.view.lists_processes() {
+        switch_view(app_state, View::Processes);
+    }
+    match display_rows(system, app_state)
+        .iter()
+        .position(|row| row.pid() == Some(pid))
+    {
+        Some(index) => {
+            app_state.selected_row_index = index;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Save the inventory for the next start once everything has been
+/// collected for real
+fn save_inventory(app_state: &mut AppState) {
+    let inventory = Inventory {
+        users: app_state.users.clone(),
+        volumes: app_state.volumes.clone(),
+        sensors: app_state.sensors.known(),
+        core_clusters: app_state.core_clusters.clone(),
+    };
+    app_state
+        .collectors
+        .run_optional("inventory cache", || inventory.save().ok());
+}
+
+/// Whether cached core clusters still describe this machine's CPUs
+fn fits_cpus(clusters: &[topology::CoreCluster], cpu_count: usize) -> bool {
+    clusters
+        .iter()
+        .flat_map(|cluster| &cluster.cpus)
+        .all(|&cpu| cpu < cpu_count)
+}
+
+/// Start an action on the selected or tagged processes
+///
+/// A non-destructive action on a single selected process runs immediately;
+/// tagged batches and anything that can end a process go through a
+/// confirmation listing every affected process.
+///
+/// # Returns
+/// The confirmation modal to open, if one is needed
+fn request_action(
+    app_state: &mut AppState,
+    system: &mut Snapshot,
+    action: ProcessAction,
+) -> Option<Modal> {
+    let targets = match action {
+        ProcessAction::SignalGroup { .. } => group_targets(system, app_state),
+        _ => action_targets(system, app_state),
+    };
+    if targets.is_empty() {
+        return None;
+    }
+
+    if app_state.tagged.is_empty() && !action.needs_confirmation() {
+        run_action(app_state, system, action, &targets);
+        return None;
+    }
+
+    let pending = PendingAction { action, targets };
+    Some(Modal::Confirm(action_confirmation(system, pending)))
+}
+
+/// Dispatch an action to its targets and report the outcome
+///
+/// Targets are refreshed immediately afterwards so state changes such as
+/// SIGSTOP's "T" show up without waiting for the next data tick, and cached
+/// PRI/NI data and rows are updated so a renice shows in the NI column right
+/// away.
+fn run_action(
+    app_state: &mut AppState,
+    system: &mut Snapshot,
+    action: ProcessAction,
+    targets: &[Pid],
+) {
+    let results = dispatch(action, targets);
+
+    for (pid, result) in &results {
+        system.refresh_process(*pid);
+        if let (ProcessAction::Signal { name, .. }, Ok(())) = (action, result) {
+            app_state.lifecycle.signalled(*pid, name);
+        }
+
+        if let (ProcessAction::Renice(_), Ok(())) = (action, result) {
+            if let (Ok(nice), Some(entry)) = (
+                get_nice(*pid),
+                app_state.process_maps.priority.get_mut(&pid.as_u32()),
+            ) {
+                entry.nice = nice.to_string();
+            }
+        }
+    }
+    app_state.row_cache.clear();
+    app_state.display_cache.clear();
+
+    app_state.status_message = Some(summarize(action, &results));
+}
+
+/// Feed a key to the open modal
+///
+/// # Returns
+/// The modal to show next: the same one, a follow-up, or None once closed
+fn handle_modal_key(
+    app_state: &mut AppState,
+    modal: Modal,
+    key_code: KeyCode,
+    system: &mut Snapshot,
+) -> Option<Modal> {
+    match modal {
+        // Any key closes an informational window
+        Modal::Help | Modal::Diagnostics | Modal::Respawn(_) => None,
+        Modal::Inspector(inspector) => handle_inspector_key(key_code, inspector, system),
+        Modal::SortMenu(selected) => handle_sort_menu_key(app_state, key_code, selected),
+        Modal::Setup(setup) => handle_setup_key(app_state, key_code, setup, system),
+        Modal::SignalMenu { selected, group } => {
+            handle_signal_menu_key(app_state, key_code, selected, group, system)
+        }
+        Modal::Confirm(confirmation) => {
+            handle_confirm_key(app_state, key_code, confirmation, system)
+        }
+    }
+}
+
+/// Handle keys while the process inspector is open
+///
+/// Tab/Left/Right switch tabs and Esc closes. On the list tabs, `/` starts a
+/// search that takes every key until Enter (keep) or Esc (clear), and
+/// Up/Down/PgUp/PgDn scroll. `m` toggles secret masking in the environment
+/// and `r` reloads the open files.
+fn handle_inspector_key(
+    key_code: KeyCode,
+    mut inspector: Box<Inspector>,
+    system: &Snapshot,
+) -> Option<Modal> {
+    if inspector.searching {
+        match key_code {
+            KeyCode::Enter => inspector.searching = false,
+            KeyCode::Esc => {
+                inspector.searching = false;
+                inspector.search.clear();
+            }
+            KeyCode::Backspace => {
+                inspector.search.pop();
+            }
+            KeyCode::Char(c) => inspector.search.push(c),
+            _ => {}
+        }
+        inspector.scroll = 0;
+        return Some(Modal::Inspector(inspector));
+    }
+
+    let last_row = inspector.row_count().saturating_sub(1);
+
+    match key_code {
+        KeyCode::Esc | KeyCode::Enter => return None,
+        KeyCode::Tab | KeyCode::Right => inspector.show_tab(inspector.tab.next(), system),
+        KeyCode::BackTab | KeyCode::Left => inspector.show_tab(inspector.tab.previous(), system),
+        KeyCode::Char('/') if inspector.has_list() => {
+            inspector.searching = true;
+        }
+        KeyCode::Char('m') if inspector.tab == InspectorTab::Environment => {
+            inspector.mask_secrets = !inspector.mask_secrets;
+        }
+        KeyCode::Char('r') if inspector.tab == InspectorTab::Files => {
+            inspector.reload_files();
+            inspector.scroll = inspector.scroll.min(last_row);
+        }
+        KeyCode::Up => inspector.scroll = inspector.scroll.saturating_sub(1),
+        KeyCode::Down => inspector.scroll = (inspector.scroll + 1).min(last_row),
+        KeyCode::PageUp => inspector.scroll = inspector.scroll.saturating_sub(PAGE_SIZE),
+        KeyCode::PageDown => inspector.scroll = (inspector.scroll + PAGE_SIZE).min(last_row),
+        _ => {}
+    }
+
+    Some(Modal::Inspector(inspector))
+}
+
+/// Handle keys while a confirmation dialog is open
+///
+/// y answers Yes and n/Esc answers No directly; Left/Right/Tab move between
+/// the buttons and Enter activates the focused one
+fn handle_confirm_key(
+    app_state: &mut AppState,
+    key_code: KeyCode,
+    mut confirmation: Confirmation,
+    system: &mut Snapshot,
+) -> Option<Modal> {
+    let confirmed = match key_code {
+        KeyCode::Char('y') => true,
+        KeyCode::Enter => confirmation.yes_focused,
+        KeyCode::Char('n') | KeyCode::Esc => false,
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+            confirmation.yes_focused = !confirmation.yes_focused;
+            return Some(Modal::Confirm(confirmation));
+        }
+        _ => return Some(Modal::Confirm(confirmation)),
+    };
+
+    if confirmed {
+        let pending = confirmation.on_confirm;
+        run_action(app_state, system, pending.action, &pending.targets);
+    }
+    None
+}
+
+/// Handle keys while the sort menu is open
+///
+/// Up/Down move through the columns, Enter sorts by the highlighted one,
+/// Space makes it the secondary column breaking ties (or clears it when it
+/// already is), and Esc/F6 close the menu unchanged
+fn handle_sort_menu_key(
+    app_state: &mut AppState,
+    key_code: KeyCode,
+    selected: usize,
+) -> Option<Modal> {
+    match key_code {
+        KeyCode::Up => Some(Modal::SortMenu(selected.saturating_sub(1))),
+        KeyCode::Down => Some(Modal::SortMenu((selected + 1).min(SortKey::ALL.len() - 1))),
+        KeyCode::Enter => {
+            app_state.sort.set_primary(SortKey::ALL[selected]);
+            None
+        }
+        KeyCode::Char(' ') => {
+            let key = SortKey::ALL[selected];
+            if app_state.sort.secondary == Some(key) {
+                app_state.sort.secondary = None;
+            } else if key != app_state.sort.primary {
+                app_state.sort.secondary = Some(key);
+            }
+            None
+        }
+        KeyCode::Esc | KeyCode::F(6) => None,
+        _ => Some(Modal::SortMenu(selected)),
+    }
+}
+
+/// Handle keys while the setup screen is open
+///
+/// Up/Down move through the list and Tab/Left/Right switch between the
+/// columns and the meters. Space/Enter show or hide the highlighted entry and
+/// F7/F8 move a shown one up or down, all applied at once; `s` writes both
+/// lists to the config file, and Esc/F2 close the screen.
+fn handle_setup_key(
+    app_state: &mut AppState,
+    key_code: KeyCode,
+    mut setup: SetupMenu,
+    system: &Snapshot,
+) -> Option<Modal> {
+    let mut columns = app_state.columns.clone();
+    let selected = setup.selected;
+
+    // Edits return the entry to keep highlighted, which moves with it
+    let highlighted = match (setup.section, key_code) {
+        (_, KeyCode::Esc | KeyCode::F(2)) => return None,
+        (_, KeyCode::Char('s')) => {
+            save_setup(app_state);
+            None
+        }
+        (section, KeyCode::Tab | KeyCode::BackTab | KeyCode::Left | KeyCode::Right) => {
+            setup.section = match section {
+                SetupSection::Columns => SetupSection::Meters,
+                SetupSection::Meters => SetupSection::Columns,
+            };
+            setup.selected = 0;
+            None
+        }
+        (_, KeyCode::Up) => {
+            setup.selected = selected.saturating_sub(1);
+            None
+        }
+        (SetupSection::Columns, KeyCode::Down) => {
+            setup.selected = (selected + 1).min(Column::ALL.len() - 1);
+            None
+        }
+        (SetupSection::Meters, KeyCode::Down) => {
+            setup.selected = (selected + 1).min(Meter::ALL.len() - 1);
+            None
+        }
+        (SetupSection::Columns, KeyCode::Char(' ') | KeyCode::Enter) => {
+            let column = listing(&columns, &Column::ALL)[selected];
+            if !columns.contains(&column) {
+                insert_shown(&mut columns, column, &Column::ALL);
+            } else if columns.len() > 1 {
+                // The table keeps at least one column
+                columns.retain(|shown| *shown != column);
+            }
+            Some(SetupEntry::Column(column))
+        }
+        (SetupSection::Meters, KeyCode::Char(' ') | KeyCode::Enter) => {
+            let meter = listing(&app_state.meters, &Meter::ALL)[selected];
+            if app_state.meters.contains(&meter) {
+                app_state.meters.retain(|shown| *shown != meter);
+            } else {
+                insert_shown(&mut app_state.meters, meter, &Meter::ALL);
+            }
+            Some(SetupEntry::Meter(meter))
+        }
+        (SetupSection::Columns, KeyCode::F(7) | KeyCode::F(8)) if selected < columns.len() => {
+            let column = columns[selected];
+            move_entry(&mut columns, selected, key_code == KeyCode::F(8));
+            Some(SetupEntry::Column(column))
+        }
+        (SetupSection::Meters, KeyCode::F(7) | KeyCode::F(8))
+            if selected < app_state.meters.len() =>
+        {
+            let meter = app_state.meters[selected];
+            move_entry(&mut app_state.meters, selected, key_code == KeyCode::F(8));
+            Some(SetupEntry::Meter(meter))
+        }
+        _ => None,
+    };
+
+    if columns != app_state.columns {
+        set_columns(app_state, system, columns);
+    }
+    match highlighted {
+        Some(SetupEntry::Column(column)) => {
+            setup.selected = listing(&app_state.columns, &Column::ALL)
+                .iter()
+                .position(|listed| *listed == column)
+                .unwrap_or(selected);
+        }
+        Some(SetupEntry::Meter(meter)) => {
+            setup.selected = listing(&app_state.meters, &Meter::ALL)
+                .iter()
+                .position(|listed| *listed == meter)
+                .unwrap_or(selected);
+        }
+        None => {}
+    }
+    Some(Modal::Setup(setup))
+}
+
+/// An entry of the setup screen, followed across edits
+enum SetupEntry {
+    Column(Column),
+    Meter(Meter),
+}
+
+/// Swap a shown entry with its neighbour, staying within the list
+fn move_entry<T>(items: &mut [T], index: usize, down: bool) {
+    if down && index + 1 < items.len() {
+        items.swap(index, index + 1);
+    } else if !down && index > 0 {
+        items.swap(index, index - 1);
+    }
+}
+
+/// Write the setup screen's columns and meters to the config file
+fn save_setup(app_state: &mut AppState) {
+    let columns: Vec<&str> = app_state.columns.iter().map(|c| c.name()).collect();
+    let meters: Vec<&str> = app_state.meters.iter().map(|m| m.name()).collect();
+    let saved = config::save_setting("columns", &columns.join(", "))
+        .and_then(|_| config::save_setting("meters", &meters.join(", ")));
+
+    app_state.status_message = Some(match saved {
+        Ok(path) => {
+            app_state.config.columns = app_state.columns.clone();
+            app_state.config.meters = app_state.meters.clone();
+            format!("Saved columns and meters to {}", path.display())
+        }
+        Err(err) => format!("Saving the setup failed: {}", err),
+    });
+}
+
+/// Handle keys while the signal menu is open
+///
+/// Up/Down move through the signal list, Enter picks the highlighted signal
+/// for every target (or every target's process group), and Esc closes the
+/// menu without sending anything
+fn handle_signal_menu_key(
+    app_state: &mut AppState,
+    key_code: KeyCode,
+    selected: usize,
+    group: bool,
+    system: &mut Snapshot,
+) -> Option<Modal> {
+    let menu = |selected| Some(Modal::SignalMenu { selected, group });
+
+    match key_code {
+        KeyCode::Up => menu(selected.saturating_sub(1)),
+        KeyCode::Down => menu((selected + 1).min(SIGNALS.len() - 1)),
+        KeyCode::Enter => {
+            let (name, signal) = SIGNALS[selected];
+            let action = if group {
+                ProcessAction::SignalGroup { name, signal }
+            } else {
+                ProcessAction::Signal { name, signal }
+            };
+            request_action(app_state, system, action)
+        }
+        KeyCode::Esc | KeyCode::F(9) => None,
+        _ => menu(selected),
     }
 }
 
-fn handle_mouse_event(app_state: &mut AppState, me: MouseEvent) {
+fn handle_mouse_event(app_state: &mut AppState, me: MouseEvent, system: &Snapshot) {
     if let MouseEventKind::Down(MouseButton::Left) = me.kind {
-        // Assume process table starts at y = 8 (after info bar and header), adjust as needed
-        let process_table_start_y = 8;
+        // Process rows start below the border, info bar, filesystem panel, view tabs, and header
+        let process_table_start_y = 3
+            + info_bar_height(system.cpus().len(), app_state) as usize
+            + filesystem_panel_height(app_state) as usize;
         let row = me.row as usize;
         if row >= process_table_start_y {
-            app_state.selected_row_index = row - process_table_start_y;
+            app_state.selected_row_index = app_state.table_offset + row - process_table_start_y;
         }
     }
 }
@@ -0,0 +1,73 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use sysinfo::{Pid, ProcessStatus};
+use sysly_core::snapshot::Snapshot;
+
+/// Records one process's CPU and memory to a CSV file, a row per refresh,
+/// like psrecord
+///
+/// Rows are flushed as they are written, so the file stays complete if
+/// sysly is killed while recording.
+pub struct PidLog {
+    pub pid: Pid,
+    pub path: PathBuf,
+    out: BufWriter<File>,
+    started: Instant,
+    /// Rows written so far
+    pub rows: usize,
+}
+
+impl PidLog {
+    /// Create (or truncate) the CSV file and write its header
+    pub fn create(pid: Pid, path: &Path) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(
+            out,
+            "timestamp,elapsed_seconds,cpu_percent,resident_bytes,virtual_bytes"
+        )?;
+        out.flush()?;
+        Ok(PidLog {
+            pid,
+            path: path.to_path_buf(),
+            out,
+            started: Instant::now(),
+            rows: 0,
+        })
+    }
+
+    /// Append a row from the latest refresh
+    ///
+    /// # Returns
+    /// False once the process has exited, when there is nothing to record
+    pub fn record(&mut self, sys: &Snapshot) -> io::Result<bool> {
+        // A zombie has exited and only waits for its parent to reap it
+        let Some(process) = sys.process(self.pid).filter(|process| {
+            !matches!(
+                process.status(),
+                ProcessStatus::Zombie | ProcessStatus::Dead
+            )
+        }) else {
+            return Ok(false);
+        };
+        writeln!(
+            self.out,
+            "{},{:.1},{:.1},{},{}",
+            chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            self.started.elapsed().as_secs_f64(),
+            process.cpu_usage(),
+            process.memory(),
+            process.virtual_memory()
+        )?;
+        self.out.flush()?;
+        self.rows += 1;
+        Ok(true)
+    }
+}
+
+/// Default file for a process's log: `sysly-<pid>.csv` in the current
+/// directory
+pub fn default_path(pid: Pid) -> PathBuf {
+    PathBuf::from(format!("sysly-{}.csv", pid))
+}
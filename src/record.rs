@@ -0,0 +1,148 @@
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::capture::state_name;
+use crate::cli::Options;
+use sysly_core::snapshot::Snapshot;
+
+/// Time between recorded snapshots, so a night of recording stays small
+const RECORD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tables of the history database, created when missing
+///
+/// Times are seconds since the Unix epoch, sizes are bytes and CPU usage is
+/// percent (of one core for processes). The README shows example queries.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    host TEXT,
+    cpu_percent REAL NOT NULL,
+    load_one REAL NOT NULL,
+    load_five REAL NOT NULL,
+    load_fifteen REAL NOT NULL,
+    memory_total INTEGER NOT NULL,
+    memory_used INTEGER NOT NULL,
+    swap_total INTEGER NOT NULL,
+    swap_used INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS processes (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+    pid INTEGER NOT NULL,
+    ppid INTEGER,
+    user TEXT,
+    name TEXT NOT NULL,
+    state TEXT NOT NULL,
+    cpu_percent REAL NOT NULL,
+    memory_percent REAL NOT NULL,
+    resident_bytes INTEGER NOT NULL,
+    virtual_bytes INTEGER NOT NULL,
+    run_time_seconds INTEGER NOT NULL,
+    command TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS snapshots_by_time ON snapshots (timestamp);
+CREATE INDEX IF NOT EXISTS processes_by_snapshot ON processes (snapshot_id);
+";
+
+/// Snapshots of the system and its processes written to an SQLite
+/// database, for `--record`
+///
+/// A database can be recorded to again; new snapshots are added to the
+/// ones already there.
+pub struct HistoryRecorder {
+    db: Connection,
+    last: Option<Instant>,
+}
+
+impl HistoryRecorder {
+    /// Open or create the database and its tables
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let db = Connection::open(path).map_err(io::Error::other)?;
+        db.execute_batch(SCHEMA).map_err(io::Error::other)?;
+        Ok(HistoryRecorder { db, last: None })
+    }
+
+    /// Record the latest refresh when a snapshot is due
+    ///
+    /// Threads are left out, and the `--user`/`--pid` filters apply.
+    pub fn record(
+        &mut self,
+        sys: &Snapshot,
+        users: &HashMap<u32, String>,
+        options: &Options,
+    ) -> io::Result<()> {
+        if self
+            .last
+            .is_some_and(|last| last.elapsed() < RECORD_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.last = Some(Instant::now());
+        self.write(sys, users, options).map_err(io::Error::other)
+    }
+
+    /// Write one snapshot and its processes in a single transaction
+    fn write(
+        &mut self,
+        sys: &Snapshot,
+        users: &HashMap<u32, String>,
+        options: &Options,
+    ) -> rusqlite::Result<()> {
+        let load = sys.load_average();
+        let total_memory = sys.total_memory().max(1) as f64;
+        let transaction = self.db.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO snapshots (timestamp, host, cpu_percent, load_one, load_five, \
+             load_fifteen, memory_total, memory_used, swap_total, swap_used) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                chrono::Utc::now().timestamp(),
+                sys.host_name(),
+                sys.global_cpu_info().cpu_usage(),
+                load.one,
+                load.five,
+                load.fifteen,
+                sys.total_memory() as i64,
+                sys.used_memory() as i64,
+                sys.total_swap() as i64,
+                sys.used_swap() as i64,
+            ],
+        )?;
+        let snapshot = transaction.last_insert_rowid();
+
+        {
+            let mut insert = transaction.prepare_cached(
+                "INSERT INTO processes (snapshot_id, pid, ppid, user, name, state, \
+                 cpu_percent, memory_percent, resident_bytes, virtual_bytes, \
+                 run_time_seconds, command) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            )?;
+            let processes = sys
+                .processes()
+                .values()
+                .filter(|process| process.thread_kind().is_none() && options.matches(sys, process));
+            for process in processes {
+                insert.execute(params![
+                    snapshot,
+                    process.pid().as_u32(),
+                    process.parent().map(|ppid| ppid.as_u32()),
+                    process.user_id().and_then(|uid| users.get(uid)),
+                    process.name(),
+                    state_name(process.status()),
+                    process.cpu_usage(),
+                    process.memory() as f64 / total_memory * 100.0,
+                    process.memory() as i64,
+                    process.virtual_memory() as i64,
+                    process.run_time() as i64,
+                    process.cmd().join(" "),
+                ])?;
+            }
+        }
+
+        transaction.commit()
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use sysinfo::{LoadAvg, Pid, Uid};
+
+use crate::agent::read_frame;
+use crate::capture::parse_state;
+use crate::export::SnapshotDocument;
+use sysly_core::collector::Collectors;
+use sysly_core::provider::{Demand, Readings, SlowReadings, SystemProvider};
+use sysly_core::snapshot::{CpuSnapshot, ProcessSnapshot, Snapshot};
+
+/// Time between attempts to reach an agent that is down
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// What the connection thread passes on
+enum Update {
+    Snapshot(SnapshotDocument),
+    /// The agent couldn't be reached or the stream broke; a retry follows
+    Lost(String),
+}
+
+/// Snapshots streamed by a `sysly agent`, for `--connect`
+///
+/// A thread keeps the connection open and reconnects when it drops. Each
+/// sample shows the newest snapshot to have arrived, and the last one
+/// again while the agent is out of reach.
+pub struct RemoteProvider {
+    address: String,
+    updates: Receiver<Update>,
+    latest: Snapshot,
+    /// User names by UID, as the agent's machine has them
+    users: HashMap<u32, String>,
+    /// Why the connection is down, while it is
+    lost: Option<String>,
+}
+
+impl RemoteProvider {
+    /// Start following the agent at `address`
+    pub fn connect(address: &str) -> Self {
+        let (sender, updates) = mpsc::channel();
+        let target = address.to_string();
+        thread::spawn(move || follow(&target, sender));
+
+        RemoteProvider {
+            address: address.to_string(),
+            updates,
+            latest: Snapshot::default(),
+            users: HashMap::new(),
+            lost: None,
+        }
+    }
+}
+
+impl SystemProvider for RemoteProvider {
+    fn snapshot(&mut self, _collectors: &mut Collectors) -> Snapshot {
+        for update in self.updates.try_iter() {
+            match update {
+                Update::Snapshot(document) => {
+                    self.users.extend(
+                        document
+                            .processes
+                            .iter()
+                            .filter_map(|process| Some((process.uid?, process.user.clone()?))),
+                    );
+                    self.latest = from_document(document);
+                    self.lost = None;
+                }
+                Update::Lost(reason) => self.lost = Some(reason),
+            }
+        }
+        self.latest.clone()
+    }
+
+    fn readings(
+        &mut self,
+        _system: &Snapshot,
+        _demand: &Demand,
+        _collectors: &mut Collectors,
+    ) -> Readings {
+        Readings {
+            users: Some(self.users.clone()),
+            ..Readings::default()
+        }
+    }
+
+    fn slow_readings(&mut self, _demand: &Demand, _collectors: &mut Collectors) -> SlowReadings {
+        SlowReadings::default()
+    }
+
+    fn source(&self) -> Option<String> {
+        Some(match &self.lost {
+            Some(reason) => format!(
+                "via {}: connection lost ({}), retrying",
+                self.address, reason
+            ),
+            None => format!("via {}", self.address),
+        })
+    }
+}
+
+/// Keep a connection to the agent open, passing on each snapshot and
+/// reconnecting whenever it drops
+fn follow(address: &str, updates: Sender<Update>) {
+    loop {
+        let reason = match TcpStream::connect(address) {
+            Ok(mut stream) => loop {
+                let document = read_frame(&mut stream)
+                    .and_then(|text| serde_json::from_str(&text).map_err(io::Error::from));
+                match document {
+                    Ok(document) => {
+                        if updates.send(Update::Snapshot(document)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(err) => break err.to_string(),
+                }
+            },
+            Err(err) => err.to_string(),
+        };
+        if updates.send(Update::Lost(reason)).is_err() {
+            return;
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// The system as an agent's document describes it
+///
+/// Usage the agent couldn't read, sent as null, reads as idle.
+fn from_document(document: SnapshotDocument) -> Snapshot {
+    let cpu = |name: String, usage: Option<f32>| CpuSnapshot {
+        name,
+        brand: document.cpu.brand.clone(),
+        usage: usage.unwrap_or(0.0),
+        frequency: 0,
+    };
+    let load = |average: Option<f64>| average.unwrap_or(0.0);
+    let processes = document
+        .processes
+        .iter()
+        .map(|row| {
+            let process = ProcessSnapshot {
+                pid: Pid::from_u32(row.pid),
+                parent: row.ppid.map(Pid::from_u32),
+                name: row.name.clone(),
+                cmd: Some(row.command.clone())
+                    .filter(|command| !command.is_empty())
+                    .into_iter()
+                    .collect(),
+                user_id: row.uid.and_then(|uid| Uid::try_from(uid as usize).ok()),
+                status: parse_state(&row.state),
+                start_time: row.started,
+                run_time: row.run_time,
+                cpu_usage: row.cpu.unwrap_or(0.0),
+                memory: row.memory,
+                virtual_memory: row.virtual_memory,
+                ..ProcessSnapshot::default()
+            };
+            (process.pid, process)
+        })
+        .collect();
+
+    Snapshot {
+        host_name: document.host.clone(),
+        cpus: document
+            .cpu
+            .cores
+            .iter()
+            .enumerate()
+            .map(|(index, usage)| cpu(format!("cpu{}", index), *usage))
+            .collect(),
+        global_cpu: cpu(String::new(), document.cpu.usage),
+        total_memory: document.memory.total,
+        used_memory: document.memory.used,
+        available_memory: document.memory.available,
+        total_swap: document.swap.total,
+        used_swap: document.swap.used,
+        load_average: LoadAvg {
+            one: load(document.load.one),
+            five: load(document.load.five),
+            fifteen: load(document.load.fifteen),
+        },
+        uptime: document.uptime,
+        boot_time: chrono::DateTime::parse_from_rfc3339(&document.timestamp).map_or(0, |taken| {
+            (taken.timestamp().max(0) as u64).saturating_sub(document.uptime)
+        }),
+        processes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::write_snapshot;
+    use sysinfo::ProcessStatus;
+
+    #[test]
+    fn agent_documents_read_back_into_snapshots() {
+        let sent = Snapshot {
+            host_name: Some("far-away".to_string()),
+            cpus: vec![CpuSnapshot::default(); 2],
+            total_memory: 8 << 30,
+            used_memory: 2 << 30,
+            uptime: 600,
+            ..Snapshot::default()
+        };
+        let process = ProcessSnapshot {
+            pid: Pid::from_u32(42),
+            parent: Some(Pid::from_u32(1)),
+            name: "worker".to_string(),
+            cmd: vec!["worker".to_string(), "--fast".to_string()],
+            status: ProcessStatus::Sleep,
+            cpu_usage: f32::NAN,
+            memory: 4096,
+            ..ProcessSnapshot::default()
+        };
+
+        let mut frame = Vec::new();
+        write_snapshot(&mut frame, &sent, &[&process]).unwrap();
+        let received = from_document(serde_json::from_slice(&frame).unwrap());
+
+        assert_eq!(received.host_name(), Some("far-away".to_string()));
+        assert_eq!(received.cpus().len(), 2);
+        assert_eq!(received.used_memory(), 2 << 30);
+        let worker = received.process(Pid::from_u32(42)).unwrap();
+        assert_eq!(worker.parent(), Some(Pid::from_u32(1)));
+        assert_eq!(worker.cmd().join(" "), "worker --fast");
+        assert_eq!(worker.status(), ProcessStatus::Sleep);
+        assert_eq!(worker.cpu_usage(), 0.0);
+        assert_eq!(worker.memory(), 4096);
+    }
+}
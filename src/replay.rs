@@ -0,0 +1,329 @@
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sysinfo::{LoadAvg, Pid, Uid};
+
+use crate::capture::parse_state;
+use sysly_core::collector::Collectors;
+use sysly_core::provider::{Demand, Playback, Readings, SlowReadings, SystemProvider};
+use sysly_core::snapshot::{CpuSnapshot, ProcessSnapshot, Snapshot};
+
+/// Playback speeds `+` and `-` step through, as multiples of real time
+const SPEEDS: [u32; 8] = [1, 2, 5, 10, 30, 60, 300, 600];
+
+/// Speed playback starts at: 10x shows the 10-second `--record` snapshots
+/// one per second
+const DEFAULT_SPEED: usize = 3;
+
+/// A `--record` database played back snapshot by snapshot, for
+/// `sysly replay`
+///
+/// Snapshots are read from the database as playback reaches them, so a
+/// night's recording opens at once. Recordings keep overall CPU usage
+/// only, which shows as a single core, and user names rather than UIDs,
+/// so each name is given a stand-in UID.
+pub struct RecordingProvider {
+    db: Connection,
+    name: String,
+    /// Id and Unix time of every snapshot, in time order
+    snapshots: Vec<(i64, i64)>,
+    position: usize,
+    playing: bool,
+    speed: usize,
+    /// When playback reached the current snapshot, to time the next one
+    shown_at: Instant,
+    /// The position moved and the snapshot at it isn't loaded yet
+    moved: bool,
+    current: Snapshot,
+    /// Stand-in UIDs by user name, in the order the names turned up
+    uids: HashMap<String, u32>,
+    /// Why the last snapshot couldn't be read
+    error: Option<String>,
+}
+
+impl RecordingProvider {
+    /// Open a recording, ready to play from its first snapshot
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let db = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(io::Error::other)?;
+        let snapshots = {
+            let mut query = db
+                .prepare("SELECT id, timestamp FROM snapshots ORDER BY timestamp, id")
+                .map_err(io::Error::other)?;
+            let rows = query
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(io::Error::other)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(io::Error::other)?
+        };
+        if snapshots.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the recording has no snapshots",
+            ));
+        }
+
+        Ok(RecordingProvider {
+            db,
+            name: path.display().to_string(),
+            snapshots,
+            position: 0,
+            playing: true,
+            speed: DEFAULT_SPEED,
+            shown_at: Instant::now(),
+            moved: true,
+            current: Snapshot::default(),
+            uids: HashMap::new(),
+            error: None,
+        })
+    }
+
+    /// Show the snapshot at `position` and pause there
+    fn seek(&mut self, position: usize) {
+        self.playing = false;
+        if position != self.position {
+            self.position = position;
+            self.moved = true;
+        }
+    }
+
+    /// Real time from the current snapshot to the next at the current speed
+    fn next_due(&self) -> Option<Duration> {
+        let (_, now) = self.snapshots[self.position];
+        let (_, next) = *self.snapshots.get(self.position + 1)?;
+        let gap = Duration::from_secs((next - now).max(0) as u64);
+        Some(gap / SPEEDS[self.speed])
+    }
+
+    /// Read one snapshot and its processes
+    fn load(&mut self, id: i64, timestamp: i64) -> rusqlite::Result<Snapshot> {
+        let mut snapshot = self.db.query_row(
+            "SELECT host, cpu_percent, load_one, load_five, load_fifteen, memory_total, \
+             memory_used, swap_total, swap_used FROM snapshots WHERE id = ?1",
+            [id],
+            |row| {
+                let total_memory = row.get::<_, i64>(5)? as u64;
+                let used_memory = row.get::<_, i64>(6)? as u64;
+                let cpu = CpuSnapshot {
+                    name: "cpu0".to_string(),
+                    usage: row.get(1)?,
+                    ..CpuSnapshot::default()
+                };
+                Ok(Snapshot {
+                    host_name: row.get(0)?,
+                    cpus: vec![cpu.clone()],
+                    global_cpu: cpu,
+                    total_memory,
+                    used_memory,
+                    available_memory: total_memory.saturating_sub(used_memory),
+                    total_swap: row.get::<_, i64>(7)? as u64,
+                    used_swap: row.get::<_, i64>(8)? as u64,
+                    load_average: LoadAvg {
+                        one: row.get(2)?,
+                        five: row.get(3)?,
+                        fifteen: row.get(4)?,
+                    },
+                    ..Snapshot::default()
+                })
+            },
+        )?;
+
+        let mut query = self.db.prepare_cached(
+            "SELECT pid, ppid, user, name, state, cpu_percent, resident_bytes, virtual_bytes, \
+             run_time_seconds, command FROM processes WHERE snapshot_id = ?1",
+        )?;
+        let rows = query.query_map([id], |row| {
+            let user: Option<String> = row.get(2)?;
+            let state: String = row.get(4)?;
+            let run_time = row.get::<_, i64>(8)?.max(0) as u64;
+            let command: String = row.get(9)?;
+            let process = ProcessSnapshot {
+                pid: Pid::from_u32(row.get(0)?),
+                parent: row.get::<_, Option<u32>>(1)?.map(Pid::from_u32),
+                name: row.get(3)?,
+                cmd: Some(command)
+                    .filter(|command| !command.is_empty())
+                    .into_iter()
+                    .collect(),
+                status: parse_state(&state),
+                start_time: (timestamp.max(0) as u64).saturating_sub(run_time),
+                run_time,
+                cpu_usage: row.get(5)?,
+                memory: row.get::<_, i64>(6)? as u64,
+                virtual_memory: row.get::<_, i64>(7)? as u64,
+                ..ProcessSnapshot::default()
+            };
+            Ok((process, user))
+        })?;
+        for row in rows {
+            let (mut process, user) = row?;
+            process.user_id = user.and_then(|user| {
+                let next = self.uids.len() as u32;
+                let uid = *self.uids.entry(user).or_insert(next);
+                Uid::try_from(uid as usize).ok()
+            });
+            snapshot.processes.insert(process.pid, process);
+        }
+        Ok(snapshot)
+    }
+}
+
+impl SystemProvider for RecordingProvider {
+    fn snapshot(&mut self, _collectors: &mut Collectors) -> Snapshot {
+        if self.playing {
+            // Every snapshot due since the last sample is passed over, so
+            // fast speeds keep to time
+            while let Some(due) = self
+                .next_due()
+                .filter(|due| self.shown_at.elapsed() >= *due)
+            {
+                self.shown_at += due;
+                self.position += 1;
+                self.moved = true;
+            }
+            if self.position + 1 == self.snapshots.len() {
+                self.playing = false;
+            }
+        }
+
+        if self.moved {
+            self.moved = false;
+            let (id, timestamp) = self.snapshots[self.position];
+            match self.load(id, timestamp) {
+                Ok(snapshot) => {
+                    self.current = snapshot;
+                    self.error = None;
+                }
+                Err(err) => self.error = Some(err.to_string()),
+            }
+        }
+        self.current.clone()
+    }
+
+    fn readings(
+        &mut self,
+        _system: &Snapshot,
+        _demand: &Demand,
+        _collectors: &mut Collectors,
+    ) -> Readings {
+        Readings {
+            users: Some(
+                self.uids
+                    .iter()
+                    .map(|(user, uid)| (*uid, user.clone()))
+                    .collect(),
+            ),
+            ..Readings::default()
+        }
+    }
+
+    fn slow_readings(&mut self, _demand: &Demand, _collectors: &mut Collectors) -> SlowReadings {
+        SlowReadings::default()
+    }
+
+    fn source(&self) -> Option<String> {
+        let (_, timestamp) = self.snapshots[self.position];
+        let time = chrono::DateTime::from_timestamp(timestamp, 0)
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let mut source = format!(
+            "replaying {}  {} {}x  {}  ({}/{})",
+            self.name,
+            if self.playing { "▶" } else { "⏸" },
+            SPEEDS[self.speed],
+            time,
+            self.position + 1,
+            self.snapshots.len()
+        );
+        if let Some(err) = &self.error {
+            source.push_str(&format!(": cannot read the snapshot ({})", err));
+        }
+        Some(source)
+    }
+
+    fn play(&mut self, playback: Playback) {
+        match playback {
+            Playback::Toggle => {
+                // Playing from the end starts over
+                if !self.playing && self.position + 1 == self.snapshots.len() {
+                    self.seek(0);
+                }
+                self.playing = !self.playing;
+                self.shown_at = Instant::now();
+            }
+            Playback::Forward => self.seek((self.position + 1).min(self.snapshots.len() - 1)),
+            Playback::Back => self.seek(self.position.saturating_sub(1)),
+            Playback::Faster => self.speed = (self.speed + 1).min(SPEEDS.len() - 1),
+            Playback::Slower => self.speed = self.speed.saturating_sub(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::HistoryRecorder;
+
+    /// A recording of two snapshots ten seconds apart, the second with a
+    /// process the first doesn't have
+    fn fixture(path: &Path) {
+        drop(HistoryRecorder::open(path).unwrap());
+        let db = Connection::open(path).unwrap();
+        db.execute_batch(
+            "INSERT INTO snapshots (id, timestamp, host, cpu_percent, load_one, load_five, \
+             load_fifteen, memory_total, memory_used, swap_total, swap_used) VALUES \
+             (1, 1000, 'recorded', 12.5, 1.0, 0.5, 0.25, 8192, 2048, 0, 0), \
+             (2, 1010, 'recorded', 50.0, 2.0, 1.0, 0.5, 8192, 4096, 0, 0); \
+             INSERT INTO processes (snapshot_id, pid, ppid, user, name, state, cpu_percent, \
+             memory_percent, resident_bytes, virtual_bytes, run_time_seconds, command) VALUES \
+             (1, 1, NULL, 'root', 'init', 'sleeping', 0.0, 0.1, 8, 16, 1000, '/sbin/init'), \
+             (2, 1, NULL, 'root', 'init', 'sleeping', 0.0, 0.1, 8, 16, 1010, '/sbin/init'), \
+             (2, 42, 1, 'alice', 'build', 'running', 97.0, 25.0, 2048, 4096, 3, 'make -j8');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn recordings_step_through_their_snapshots() {
+        let path = std::env::temp_dir().join(format!("sysly-replay-{}.db", std::process::id()));
+        fixture(&path);
+        let mut recording = RecordingProvider::open(&path).unwrap();
+        let mut collectors = Collectors::default();
+
+        let first = recording.snapshot(&mut collectors);
+        assert_eq!(first.host_name(), Some("recorded".to_string()));
+        assert_eq!(first.used_memory(), 2048);
+        assert_eq!(first.processes().len(), 1);
+
+        recording.play(Playback::Forward);
+        let second = recording.snapshot(&mut collectors);
+        assert_eq!(second.global_cpu_info().cpu_usage(), 50.0);
+        let build = second.process(Pid::from_u32(42)).unwrap();
+        assert_eq!(build.parent(), Some(Pid::from_u32(1)));
+        assert_eq!(build.cmd().join(" "), "make -j8");
+        assert_eq!(build.start_time(), 1007);
+        let users = recording
+            .readings(&second, &Demand::default(), &mut collectors)
+            .users
+            .unwrap();
+        let uid = build.user_id().unwrap();
+        assert_eq!(users.get(&(**uid)).map(String::as_str), Some("alice"));
+        assert!(recording.source().unwrap().contains("(2/2)"));
+
+        // Stepping past the end stays on the last snapshot
+        recording.play(Playback::Forward);
+        assert_eq!(recording.snapshot(&mut collectors).processes().len(), 2);
+
+        recording.play(Playback::Back);
+        assert_eq!(recording.snapshot(&mut collectors).processes().len(), 1);
+        assert!(recording.source().unwrap().contains("⏸"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::docker::{ContainerStats, DockerTracker};
+use sysly_core::collector::{CollectorHealth, Collectors};
+use sysly_core::provider::{Demand, Playback, Readings, SlowReadings, SystemProvider};
+use sysly_core::snapshot::Snapshot;
+
+/// Writes every snapshot somewhere, such as a CSV file or a recording,
+/// on the sampling thread
+///
+/// Returns the message for the status line when writing fails, after
+/// which the recorder is dropped.
+pub type Recorder = Box<dyn FnMut(&Snapshot) -> Result<(), String> + Send>;
+
+/// Readings taken on the sampling thread for one data tick
+pub struct Sample {
+    /// CPUs, memory and processes
+    pub system: Snapshot,
+    pub readings: Readings,
+    /// Readings from the slower collectors, on the samples they ran for
+    pub slow: Option<SlowReadings>,
+    /// Health of the collectors run for this sample, for the diagnostics view
+    pub health: Vec<CollectorHealth>,
+    /// Why recorders stopped while writing this sample
+    pub stopped: Vec<String>,
+    /// Running containers, or why Docker couldn't be read, when asked for
+    pub containers: Option<Result<Vec<ContainerStats>, String>>,
+    /// Container names by short ID, when they were listed
+    pub container_names: Option<HashMap<String, String>>,
+    /// Where the data came from, when it isn't this machine
+    pub source: Option<String>,
+}
+
+impl Sample {
+    /// Take over what an older sample the dashboard never picked up read
+    /// only once, so skipping it loses nothing but its figures
+    fn carry_over(&mut self, older: Sample) {
+        if self.slow.is_none() {
+            self.slow = older.slow;
+        }
+        let mut stopped = older.stopped;
+        stopped.append(&mut self.stopped);
+        self.stopped = stopped;
+        if self.container_names.is_none() {
+            self.container_names = older.container_names;
+        }
+        let readings = &mut self.readings;
+        if readings.volumes.is_none() {
+            readings.volumes = older.readings.volumes;
+        }
+        if readings.energy_impact.is_none() {
+            readings.energy_impact = older.readings.energy_impact;
+        }
+        if readings.energy_error.is_none() {
+            readings.energy_error = older.readings.energy_error;
+        }
+    }
+}
+
+/// Runs a provider on a worker thread, so a slow refresh never holds up
+/// keys or redraws
+///
+/// Only the newest sample waits, so a dashboard that stops taking them
+/// (while help is open) doesn't fall behind, and the thread keeps sampling
+/// and recording meanwhile.
+pub struct Sampler {
+    latest: Arc<Mutex<Option<Sample>>>,
+    demand: Arc<Mutex<Demand>>,
+    playback: Sender<Playback>,
+}
+
+impl Sampler {
+    /// Start the thread, which sends the provider's first reading straight
+    /// away, then a sample every `interval`, with slow readings in the
+    /// first and then about every `slow_interval`
+    ///
+    /// Every snapshot goes to `recorders`, whether or not the dashboard
+    /// takes it.
+    pub fn spawn(
+        mut provider: impl SystemProvider + 'static,
+        interval: Duration,
+        slow_interval: Duration,
+        mut recorders: Vec<Recorder>,
+    ) -> Self {
+        let latest: Arc<Mutex<Option<Sample>>> = Arc::default();
+        let (playback, steps) = mpsc::channel();
+        let demand = Arc::new(Mutex::new(Demand::default()));
+
+        let wanted = Arc::clone(&demand);
+        let slot = Arc::clone(&latest);
+        thread::spawn(move || {
+            let mut collectors = Collectors::default();
+            let mut slow_cadence = Cadence::new(interval, slow_interval);
+            let mut docker = DockerTracker::default();
+            let mut last_demand = Demand::default();
+            loop {
+                let started = Instant::now();
+                let demand = wanted
+                    .lock()
+                    .map_or_else(|_| Demand::default(), |demand| demand.clone());
+                // Traffic and container names show up as soon as they are
+                // asked for, rather than at the next slow reading
+                if (demand.network && !last_demand.network)
+                    || (demand.container_names && !last_demand.container_names)
+                {
+                    slow_cadence.restart();
+                }
+                last_demand = demand.clone();
+                let system = provider.snapshot(&mut collectors);
+                let readings = provider.readings(&system, &demand, &mut collectors);
+                let slow = slow_cadence
+                    .due()
+                    .then(|| provider.slow_readings(&demand, &mut collectors));
+                // Docker is local, so it is read here rather than by the
+                // provider, and not at all for data from elsewhere; the
+                // containers view reads the names along with the stats
+                let source = provider.source();
+                let local = source.is_none();
+                let containers = if demand.containers && local {
+                    collectors
+                        .run("docker", || docker.sample())
+                        .map(|result| result.map_err(|err| err.to_string()))
+                } else {
+                    if demand.container_names && local && slow.is_some() {
+                        collectors.run("docker names", || docker.refresh_names());
+                    }
+                    None
+                };
+                let container_names = ((demand.containers || demand.container_names) && local)
+                    .then(|| docker.names().clone());
+                let mut stopped = Vec::new();
+                recorders.retain_mut(|record| match record(&system) {
+                    Ok(()) => true,
+                    Err(message) => {
+                        stopped.push(message);
+                        false
+                    }
+                });
+                let mut sample = Sample {
+                    system,
+                    readings,
+                    slow,
+                    health: collectors.health().to_vec(),
+                    stopped,
+                    containers,
+                    container_names,
+                    source,
+                };
+                if let Ok(mut waiting) = slot.lock() {
+                    if let Some(older) = waiting.take() {
+                        sample.carry_over(older);
+                    }
+                    *waiting = Some(sample);
+                }
+                // A step through a recording shows at once rather than at
+                // the next sample; the thread ends with the sampler
+                match steps.recv_timeout(interval.saturating_sub(started.elapsed())) {
+                    Ok(step) => {
+                        provider.play(step);
+                        steps.try_iter().for_each(|step| provider.play(step));
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+
+        Sampler {
+            latest,
+            demand,
+            playback,
+        }
+    }
+
+    /// Tell the provider what the dashboard shows, from the next sample on
+    pub fn set_demand(&self, demand: Demand) {
+        if let Ok(mut current) = self.demand.lock() {
+            *current = demand;
+        }
+    }
+
+    /// Pass a playback key on to the provider, which shows its effect in
+    /// the next sample
+    pub fn play(&self, playback: Playback) {
+        let _ = self.playback.send(playback);
+    }
+
+    /// The newest sample, when one is ready
+    pub fn try_take(&self) -> Option<Sample> {
+        self.latest.lock().ok()?.take()
+    }
+}
+
+/// Counts samples to run something on every nth one
+///
+/// Counting keeps the spacing steady where comparing timestamps would skip
+/// a run whenever a sample lands a few milliseconds early.
+struct Cadence {
+    every: u32,
+    count: u32,
+}
+
+impl Cadence {
+    /// Due about once per `period` when samples come every `interval`, and
+    /// on the first sample
+    fn new(interval: Duration, period: Duration) -> Self {
+        let every = (period.as_secs_f64() / interval.as_secs_f64().max(f64::EPSILON))
+            .round()
+            .clamp(1.0, u32::MAX as f64) as u32;
+        Cadence { every, count: 0 }
+    }
+
+    /// Be due on the next sample, and count from there
+    fn restart(&mut self) {
+        self.count = 0;
+    }
+
+    fn due(&mut self) -> bool {
+        let due = self.count == 0;
+        self.count = (self.count + 1) % self.every;
+        due
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use sysinfo::Pid;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Exponential moving average of each process's CPU usage, so bursty
+/// processes don't flicker between refreshes
+#[derive(Debug)]
+pub struct CpuSmoother {
+    /// Weight of the newest reading, in (0, 1]; 1 turns smoothing off
+    alpha: f32,
+    averages: HashMap<Pid, f32>,
+}
+
+/// Smoothing off
+impl Default for CpuSmoother {
+    fn default() -> Self {
+        CpuSmoother::new(1.0)
+    }
+}
+
+impl CpuSmoother {
+    pub fn new(alpha: f32) -> Self {
+        CpuSmoother {
+            alpha,
+            averages: HashMap::new(),
+        }
+    }
+
+    /// Fold the latest readings into the averages and forget exited
+    /// processes; a new process starts from its first reading
+    pub fn update(&mut self, sys: &Snapshot) {
+        if self.alpha >= 1.0 {
+            return;
+        }
+        self.averages.retain(|pid, _| sys.process(*pid).is_some());
+        for (pid, process) in sys.processes() {
+            let usage = process.cpu_usage();
+            self.averages
+                .entry(*pid)
+                .and_modify(|average| *average += self.alpha * (usage - *average))
+                .or_insert(usage);
+        }
+    }
+
+    /// Smoothed averages by PID, or None when smoothing is off
+    pub fn averages(&self) -> Option<&HashMap<Pid, f32>> {
+        (self.alpha < 1.0).then_some(&self.averages)
+    }
+
+    /// CPU usage to display for a process
+    pub fn usage(&self, process: &ProcessSnapshot) -> f32 {
+        self.averages
+            .get(&process.pid())
+            .copied()
+            .unwrap_or_else(|| process.cpu_usage())
+    }
+}
@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use sysinfo::Pid;
+
+use crate::columns::Column;
+use sysly_core::sched::{fetch_sched_info, SchedInfo};
+use sysly_core::snapshot::ProcessSnapshot;
+
+/// How a column's values compare when sorting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortSemantics {
+    /// Compared numerically
+    Numeric,
+    /// Compared as text, alphabetically
+    Text,
+    /// Compared as elapsed seconds
+    Duration,
+}
+
+/// A column value extracted for sorting
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortValue {
+    Number(f64),
+    Text(String),
+    Seconds(u64),
+}
+
+impl SortSemantics {
+    /// Order two values of a column with these semantics, ascending
+    ///
+    /// Values of mismatched kinds compare equal, so a column that yields an
+    /// unexpected kind keeps its rows in their existing order.
+    pub fn compare(self, a: &SortValue, b: &SortValue) -> Ordering {
+        match (self, a, b) {
+            (SortSemantics::Numeric, SortValue::Number(a), SortValue::Number(b)) => {
+                a.partial_cmp(b).unwrap_or(Ordering::Equal)
+            }
+            (SortSemantics::Text, SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortSemantics::Duration, SortValue::Seconds(a), SortValue::Seconds(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// Columns the process list can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Cpu,
+    Memory,
+    Virtual,
+    Pid,
+    Time,
+    Started,
+    Command,
+    Qos,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 8] = [
+        SortKey::Cpu,
+        SortKey::Memory,
+        SortKey::Virtual,
+        SortKey::Pid,
+        SortKey::Time,
+        SortKey::Started,
+        SortKey::Command,
+        SortKey::Qos,
+    ];
+
+    /// Column title, as shown in the table header and sort menu
+    pub fn title(self) -> &'static str {
+        match self {
+            SortKey::Cpu => "CPU%",
+            SortKey::Memory => "MEM%",
+            SortKey::Virtual => "VIRT",
+            SortKey::Pid => "PID",
+            SortKey::Time => "TIME+",
+            SortKey::Started => "STARTED",
+            SortKey::Command => "Command",
+            SortKey::Qos => "QOS",
+        }
+    }
+
+    /// Parse a column name as accepted by htop's `--sort-key`
+    pub fn from_htop_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "PERCENT_CPU" | "CPU" => Some(SortKey::Cpu),
+            "PERCENT_MEM" | "M_RESIDENT" | "MEM" => Some(SortKey::Memory),
+            "M_VIRT" | "M_SIZE" => Some(SortKey::Virtual),
+            "PID" => Some(SortKey::Pid),
+            "TIME" => Some(SortKey::Time),
+            "STARTTIME" | "STARTED" | "START" => Some(SortKey::Started),
+            "COMM" | "COMMAND" => Some(SortKey::Command),
+            "QOS" | "SCHED" => Some(SortKey::Qos),
+            _ => None,
+        }
+    }
+
+    /// The table column sorted by, which says how its values compare
+    pub fn column(self) -> Column {
+        match self {
+            SortKey::Cpu => Column::Cpu,
+            SortKey::Memory => Column::Memory,
+            SortKey::Virtual => Column::Virtual,
+            SortKey::Pid => Column::Pid,
+            SortKey::Time => Column::Time,
+            SortKey::Started => Column::Started,
+            SortKey::Command => Column::Command,
+            SortKey::Qos => Column::Qos,
+        }
+    }
+
+    /// Extract this column's value from a process
+    pub fn value(self, process: &ProcessSnapshot, readings: SortReadings) -> SortValue {
+        match self {
+            SortKey::Cpu => SortValue::Number(process.cpu_usage() as f64),
+            SortKey::Memory => SortValue::Number(process.memory() as f64),
+            SortKey::Virtual => SortValue::Number(process.virtual_memory() as f64),
+            SortKey::Pid => SortValue::Number(process.pid().as_u32() as f64),
+            SortKey::Time => SortValue::Seconds(process.run_time()),
+            SortKey::Started => SortValue::Number(process.start_time() as f64),
+            SortKey::Command => SortValue::Text(process.name().to_string()),
+            // Most urgent class first; unreadable processes sort last
+            SortKey::Qos => {
+                let class = readings
+                    .sched
+                    .and_then(|sched| sched.get(&process.pid().as_u32()))
+                    .and_then(|info| info.class);
+                SortValue::Number(class.map_or(-1.0, |class| class.rank() as f64))
+            }
+        }
+    }
+
+    /// Order two of this column's values for display
+    pub fn compare(self, a: &SortValue, b: &SortValue) -> Ordering {
+        let column = self.column();
+        let ordering = column
+            .sort_semantics()
+            .map_or(Ordering::Equal, |semantics| semantics.compare(a, b));
+        if column.sorts_descending() {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// A sort column, and optionally a second one that orders rows the first
+/// leaves tied, e.g. CPU then memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortOrder {
+    pub primary: SortKey,
+    pub secondary: Option<SortKey>,
+}
+
+impl SortOrder {
+    /// Sort by the primary column alone
+    pub fn by(primary: SortKey) -> Self {
+        SortOrder {
+            primary,
+            secondary: None,
+        }
+    }
+
+    /// Sort by a new primary column, keeping the secondary one unless it is
+    /// the same column
+    pub fn set_primary(&mut self, key: SortKey) {
+        self.primary = key;
+        if self.secondary == Some(key) {
+            self.secondary = None;
+        }
+    }
+
+    /// Whether either column is `key`
+    pub fn uses(self, key: SortKey) -> bool {
+        self.primary == key || self.secondary == Some(key)
+    }
+
+    /// Parse `COLUMN[,COLUMN]` with htop's column names, as `--sort` takes
+    pub fn parse(value: &str) -> Option<Self> {
+        let (primary, secondary) = match value.split_once(',') {
+            Some((primary, secondary)) => (primary, Some(secondary)),
+            None => (value, None),
+        };
+        let primary = SortKey::from_htop_name(primary.trim())?;
+        let secondary = match secondary {
+            Some(name) => Some(SortKey::from_htop_name(name.trim())?),
+            None => None,
+        };
+        Some(SortOrder {
+            primary,
+            secondary: secondary.filter(|key| *key != primary),
+        })
+    }
+}
+
+/// Readings taken with the refresh that sorting uses in place of its own
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortReadings<'a> {
+    /// Replaces sysinfo's latest CPU readings, e.g. with smoothed averages
+    pub cpu: Option<&'a HashMap<Pid, f32>>,
+    /// Scheduling classes by PID; processes without one sort last by QOS
+    pub sched: Option<&'a HashMap<u32, SchedInfo>>,
+}
+
+/// Read the scheduling class of each process when `order` sorts by QOS,
+/// for listings that take no readings with their refresh
+pub fn read_sched(processes: &[&ProcessSnapshot], order: SortOrder) -> HashMap<u32, SchedInfo> {
+    if !order.uses(SortKey::Qos) {
+        return HashMap::new();
+    }
+    processes
+        .iter()
+        .map(|process| {
+            let pid = process.pid().as_u32();
+            (pid, fetch_sched_info(pid))
+        })
+        .collect()
+}
+
+/// Sort processes in place by the given columns
+///
+/// Each value is read once up front rather than on every comparison.
+pub fn sort_processes(
+    processes: &mut [&ProcessSnapshot],
+    order: SortOrder,
+    readings: SortReadings,
+) {
+    let value = |key: SortKey, process: &ProcessSnapshot| match readings
+        .cpu
+        .and_then(|cpu| cpu.get(&process.pid()))
+    {
+        Some(usage) if key == SortKey::Cpu => SortValue::Number(*usage as f64),
+        _ => key.value(process, readings),
+    };
+    let mut keyed: Vec<(SortValue, Option<SortValue>, &ProcessSnapshot)> = processes
+        .iter()
+        .map(|process| {
+            let secondary = order.secondary.map(|key| value(key, process));
+            (value(order.primary, process), secondary, *process)
+        })
+        .collect();
+    keyed.sort_by(|(a, a2, _), (b, b2, _)| {
+        order
+            .primary
+            .compare(a, b)
+            .then_with(|| match (order.secondary, a2, b2) {
+                (Some(key), Some(a2), Some(b2)) => key.compare(a2, b2),
+                _ => Ordering::Equal,
+            })
+    });
+
+    for (slot, (_, _, process)) in processes.iter_mut().zip(keyed) {
+        *slot = process;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysly_core::sched::SchedClass;
+
+    #[test]
+    fn every_sort_key_has_semantics() {
+        for key in SortKey::ALL {
+            assert!(key.column().sort_semantics().is_some(), "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn qos_sorts_by_the_classes_read_with_the_refresh() {
+        let process = |pid| ProcessSnapshot {
+            pid: Pid::from_u32(pid),
+            ..ProcessSnapshot::default()
+        };
+        let (idle, unread, realtime) = (process(1), process(2), process(3));
+        let sched = HashMap::from([
+            (
+                1,
+                SchedInfo {
+                    class: Some(SchedClass::Idle),
+                    boosted: false,
+                },
+            ),
+            (
+                3,
+                SchedInfo {
+                    class: Some(SchedClass::Fifo),
+                    boosted: false,
+                },
+            ),
+        ]);
+        let mut processes = vec![&idle, &unread, &realtime];
+        let readings = SortReadings {
+            sched: Some(&sched),
+            ..SortReadings::default()
+        };
+        sort_processes(&mut processes, SortOrder::by(SortKey::Qos), readings);
+
+        let pids: Vec<u32> = processes.iter().map(|p| p.pid().as_u32()).collect();
+        assert_eq!(pids, [3, 1, 2]);
+    }
+}
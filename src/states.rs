@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessStatus};
+use sysly_core::snapshot::Snapshot;
+
+/// Uninterruptible sleep longer than this usually means stuck I/O, e.g. a
+/// hung network mount
+const STUCK_UNINTERRUPTIBLE: Duration = Duration::from_secs(10);
+
+/// Zombies that linger this long have a parent that isn't reaping them
+const STUCK_ZOMBIE: Duration = Duration::from_secs(60);
+
+/// How long a process has been in its current state
+#[derive(Debug, Clone, Copy)]
+pub struct StateSpan {
+    pub status: ProcessStatus,
+    pub duration: Duration,
+    /// The process was already in this state when sysly started, so it
+    /// has been in it for at least `duration`
+    pub lower_bound: bool,
+}
+
+impl StateSpan {
+    /// Whether the process has been in this state for suspiciously long
+    pub fn is_pathological(&self) -> bool {
+        match self.status {
+            ProcessStatus::UninterruptibleDiskSleep => self.duration >= STUCK_UNINTERRUPTIBLE,
+            ProcessStatus::Zombie => self.duration >= STUCK_ZOMBIE,
+            _ => false,
+        }
+    }
+
+    /// The state in words, e.g. "uninterruptible"
+    pub fn description(&self) -> &'static str {
+        match self.status {
+            ProcessStatus::Run => "running",
+            ProcessStatus::Sleep => "sleeping",
+            ProcessStatus::Idle => "idle",
+            ProcessStatus::Stop => "stopped",
+            ProcessStatus::Zombie => "zombie",
+            ProcessStatus::UninterruptibleDiskSleep => "uninterruptible",
+            ProcessStatus::Tracing => "traced",
+            ProcessStatus::Dead => "dead",
+            _ => "other",
+        }
+    }
+}
+
+/// When each process entered the state it is in, noticed on each refresh
+///
+/// States are only seen at refreshes, so a change is dated to the refresh
+/// that first shows it, and brief trips into another state in between are
+/// missed.
+#[derive(Debug, Default)]
+pub struct StateTracker {
+    entered: HashMap<Pid, (ProcessStatus, Instant, bool)>,
+    sampled: bool,
+}
+
+impl StateTracker {
+    /// Note state changes since the previous refresh and forget processes
+    /// that exited
+    pub fn update(&mut self, sys: &Snapshot) {
+        let now = Instant::now();
+        let first_sample = !self.sampled;
+        self.entered.retain(|pid, _| sys.process(*pid).is_some());
+
+        for (pid, process) in sys.processes() {
+            let status = process.status();
+            match self.entered.get(pid) {
+                Some((current, _, _)) if *current == status => {}
+                _ => {
+                    self.entered.insert(*pid, (status, now, first_sample));
+                }
+            }
+        }
+        self.sampled = true;
+    }
+
+    /// How long a process has been in its current state
+    pub fn span(&self, pid: Pid) -> Option<StateSpan> {
+        self.entered
+            .get(&pid)
+            .map(|(status, since, lower_bound)| StateSpan {
+                status: *status,
+                duration: since.elapsed(),
+                lower_bound: *lower_bound,
+            })
+    }
+}
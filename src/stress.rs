@@ -0,0 +1,259 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+use sysly_core::helpers::format_bytes;
+use sysly_core::sensors::{SensorHistory, Sensors};
+
+/// How long the idle baseline and the recovery after the load are measured
+pub const SETTLE_TIME: Duration = Duration::from_secs(3);
+
+/// Interval between readings while measuring
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Memory is touched one page at a time to make it resident
+const PAGE_SIZE: usize = 4096;
+
+/// Load generated by `sysly stress`
+#[derive(Debug, Clone)]
+pub struct StressOptions {
+    /// Busy-looping worker threads
+    pub cpus: usize,
+    /// Bytes allocated and kept resident
+    pub memory: u64,
+    pub duration: Duration,
+}
+
+/// Readings taken over one phase of a stress run
+#[derive(Debug, Clone, Default)]
+pub struct Phase {
+    /// System-wide CPU usage in percent
+    cpu: Vec<f32>,
+    /// Used memory in bytes
+    memory: Vec<u64>,
+    /// Hottest sensor in °C
+    temperature: Vec<f32>,
+    /// Thermal-throttle episodes that started during the phase
+    throttle_episodes: usize,
+}
+
+impl Phase {
+    fn average_cpu(&self) -> Option<f32> {
+        (!self.cpu.is_empty()).then(|| self.cpu.iter().sum::<f32>() / self.cpu.len() as f32)
+    }
+
+    fn peak_cpu(&self) -> Option<f32> {
+        self.cpu.iter().copied().reduce(f32::max)
+    }
+
+    fn average_memory(&self) -> Option<u64> {
+        (!self.memory.is_empty())
+            .then(|| self.memory.iter().sum::<u64>() / self.memory.len() as u64)
+    }
+
+    fn peak_memory(&self) -> Option<u64> {
+        self.memory.iter().copied().max()
+    }
+
+    fn average_temperature(&self) -> Option<f32> {
+        (!self.temperature.is_empty())
+            .then(|| self.temperature.iter().sum::<f32>() / self.temperature.len() as f32)
+    }
+
+    fn peak_temperature(&self) -> Option<f32> {
+        self.temperature.iter().copied().reduce(f32::max)
+    }
+}
+
+/// Take one reading per second until `done` returns true
+fn record(mut done: impl FnMut() -> bool) -> Phase {
+    let mut system = System::new();
+    let mut sensors = Sensors::new();
+    let mut phase = Phase::default();
+    system.refresh_cpu();
+
+    while !done() {
+        thread::sleep(SAMPLE_INTERVAL);
+        system.refresh_cpu();
+        system.refresh_memory();
+        sensors.sample();
+
+        phase.cpu.push(system.global_cpu_info().cpu_usage());
+        phase.memory.push(system.used_memory());
+        if let Some(hottest) = sensors
+            .log
+            .history
+            .iter()
+            .filter_map(SensorHistory::current)
+            .reduce(f32::max)
+        {
+            phase.temperature.push(hottest);
+        }
+    }
+    phase.throttle_episodes = sensors.log.events.len();
+
+    phase
+}
+
+/// Measure the machine for a fixed time without adding load
+pub fn measure(duration: Duration) -> Phase {
+    let started = Instant::now();
+    record(|| started.elapsed() >= duration)
+}
+
+/// A stress run in progress: load workers plus a thread recording the
+/// machine while they run
+pub struct StressRun {
+    options: StressOptions,
+    baseline: Phase,
+    started: Instant,
+    stop: Arc<AtomicBool>,
+    workers: Vec<JoinHandle<()>>,
+    monitor: JoinHandle<Phase>,
+}
+
+impl StressRun {
+    /// Start the CPU workers and the memory hog
+    pub fn start(options: StressOptions, baseline: Phase) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let mut workers: Vec<JoinHandle<()>> = (0..options.cpus)
+            .map(|_| {
+                let stop = Arc::clone(&stop);
+                thread::spawn(move || spin(&stop))
+            })
+            .collect();
+        if options.memory > 0 {
+            let stop = Arc::clone(&stop);
+            let bytes = options.memory as usize;
+            workers.push(thread::spawn(move || hold_memory(bytes, &stop)));
+        }
+
+        let monitor = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || record(|| stop.load(Ordering::Relaxed)))
+        };
+
+        StressRun {
+            options,
+            baseline,
+            started: Instant::now(),
+            stop,
+            workers,
+            monitor,
+        }
+    }
+
+    /// Stop the load, measure how the machine recovers, and report
+    pub fn finish(self) -> StressReport {
+        let elapsed = self.started.elapsed();
+        self.stop.store(true, Ordering::Relaxed);
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+        let load = self.monitor.join().unwrap_or_default();
+
+        StressReport {
+            options: self.options,
+            elapsed,
+            before: self.baseline,
+            during: load,
+            after: measure(SETTLE_TIME),
+        }
+    }
+}
+
+/// Keep one CPU busy until told to stop
+fn spin(stop: &AtomicBool) {
+    let mut value: u64 = 1;
+    while !stop.load(Ordering::Relaxed) {
+        for _ in 0..100_000 {
+            value = std::hint::black_box(
+                value
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1),
+            );
+        }
+    }
+}
+
+/// Allocate memory and keep writing to it so it stays resident rather than
+/// being compressed or swapped out
+fn hold_memory(bytes: usize, stop: &AtomicBool) {
+    let mut block = vec![0u8; bytes];
+    let mut round: u8 = 0;
+    while !stop.load(Ordering::Relaxed) {
+        round = round.wrapping_add(1);
+        for page in block.iter_mut().step_by(PAGE_SIZE) {
+            *page = round;
+        }
+        thread::sleep(SAMPLE_INTERVAL);
+    }
+    std::hint::black_box(&block);
+}
+
+/// Before/after summary printed when a stress run ends
+pub struct StressReport {
+    options: StressOptions,
+    elapsed: Duration,
+    before: Phase,
+    during: Phase,
+    after: Phase,
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let percent = |value: Option<f32>| value.map_or("-".to_string(), |v| format!("{:.1}%", v));
+        let bytes = |value: Option<u64>| value.map_or("-".to_string(), format_bytes);
+        let celsius = |value: Option<f32>| value.map_or("-".to_string(), |v| format!("{:.0}°C", v));
+
+        writeln!(
+            f,
+            "sysly stress: {} CPU workers, {} held, ran {}s of {}s",
+            self.options.cpus,
+            format_bytes(self.options.memory),
+            self.elapsed.as_secs(),
+            self.options.duration.as_secs()
+        )?;
+        writeln!(
+            f,
+            "{:<12} {:>10} {:>10} {:>10} {:>10}",
+            "", "before", "load avg", "load peak", "after"
+        )?;
+        writeln!(
+            f,
+            "{:<12} {:>10} {:>10} {:>10} {:>10}",
+            "CPU",
+            percent(self.before.average_cpu()),
+            percent(self.during.average_cpu()),
+            percent(self.during.peak_cpu()),
+            percent(self.after.average_cpu())
+        )?;
+        writeln!(
+            f,
+            "{:<12} {:>10} {:>10} {:>10} {:>10}",
+            "Memory",
+            bytes(self.before.average_memory()),
+            bytes(self.during.average_memory()),
+            bytes(self.during.peak_memory()),
+            bytes(self.after.average_memory())
+        )?;
+        writeln!(
+            f,
+            "{:<12} {:>10} {:>10} {:>10} {:>10}",
+            "Hottest",
+            celsius(self.before.average_temperature()),
+            celsius(self.during.average_temperature()),
+            celsius(self.during.peak_temperature()),
+            celsius(self.after.average_temperature())
+        )?;
+        writeln!(
+            f,
+            "Throttle episodes during the load: {}",
+            self.during.throttle_episodes
+        )
+    }
+}
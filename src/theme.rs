@@ -0,0 +1,193 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use ratatui::style::Color;
+
+/// Colors the UI draws with, by what they mean rather than how they look
+#[derive(Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Fill behind panes and overlays
+    pub background: Color,
+    /// Regular values
+    pub text: Color,
+    /// Secondary text, hints and empty states
+    pub dim: Color,
+    /// Barely-there text: unavailable values, idle items
+    pub faint: Color,
+    /// Labels, headings and commands
+    pub accent: Color,
+    /// Healthy or low readings
+    pub good: Color,
+    /// Elevated readings
+    pub warning: Color,
+    /// High readings and failures
+    pub critical: Color,
+    /// Between warning and critical, e.g. heavy thermal pressure
+    pub severe: Color,
+    /// A second series color: compressed memory, sockets, stopped processes
+    pub special: Color,
+    /// A third series color: wired memory, directories, napping processes
+    pub info: Color,
+    /// Text drawn on a highlight background
+    pub on_highlight: Color,
+    /// Selected row or menu entry
+    pub selection: Color,
+    /// Table header rows
+    pub header: Color,
+    /// Tagged rows
+    pub tagged: Color,
+}
+
+/// The original look: terminal palette colors on black
+pub const DEFAULT: Theme = Theme {
+    name: "default",
+    background: Color::Black,
+    text: Color::White,
+    dim: Color::Gray,
+    faint: Color::DarkGray,
+    accent: Color::Cyan,
+    good: Color::Green,
+    warning: Color::Yellow,
+    critical: Color::Red,
+    severe: Color::LightRed,
+    special: Color::Magenta,
+    info: Color::Blue,
+    on_highlight: Color::Black,
+    selection: Color::Rgb(180, 220, 240),
+    header: Color::Rgb(200, 220, 180),
+    tagged: Color::Rgb(90, 80, 30),
+};
+
+/// Solarized dark
+pub const SOLARIZED: Theme = Theme {
+    name: "solarized",
+    background: Color::Rgb(0x00, 0x2b, 0x36),
+    text: Color::Rgb(0x93, 0xa1, 0xa1),
+    dim: Color::Rgb(0x83, 0x94, 0x96),
+    faint: Color::Rgb(0x58, 0x6e, 0x75),
+    accent: Color::Rgb(0x2a, 0xa1, 0x98),
+    good: Color::Rgb(0x85, 0x99, 0x00),
+    warning: Color::Rgb(0xb5, 0x89, 0x00),
+    critical: Color::Rgb(0xdc, 0x32, 0x2f),
+    severe: Color::Rgb(0xcb, 0x4b, 0x16),
+    special: Color::Rgb(0xd3, 0x36, 0x82),
+    info: Color::Rgb(0x26, 0x8b, 0xd2),
+    on_highlight: Color::Rgb(0x00, 0x2b, 0x36),
+    selection: Color::Rgb(0x93, 0xa1, 0xa1),
+    header: Color::Rgb(0xee, 0xe8, 0xd5),
+    tagged: Color::Rgb(0x07, 0x36, 0x42),
+};
+
+/// Dracula
+pub const DRACULA: Theme = Theme {
+    name: "dracula",
+    background: Color::Rgb(0x28, 0x2a, 0x36),
+    text: Color::Rgb(0xf8, 0xf8, 0xf2),
+    dim: Color::Rgb(0xbf, 0xbf, 0xbf),
+    faint: Color::Rgb(0x62, 0x72, 0xa4),
+    accent: Color::Rgb(0x8b, 0xe9, 0xfd),
+    good: Color::Rgb(0x50, 0xfa, 0x7b),
+    warning: Color::Rgb(0xf1, 0xfa, 0x8c),
+    critical: Color::Rgb(0xff, 0x55, 0x55),
+    severe: Color::Rgb(0xff, 0xb8, 0x6c),
+    special: Color::Rgb(0xff, 0x79, 0xc6),
+    info: Color::Rgb(0xbd, 0x93, 0xf9),
+    on_highlight: Color::Rgb(0x28, 0x2a, 0x36),
+    selection: Color::Rgb(0xbd, 0x93, 0xf9),
+    header: Color::Rgb(0x50, 0xfa, 0x7b),
+    tagged: Color::Rgb(0x44, 0x47, 0x5a),
+};
+
+/// Dark text on a light background, for light terminals
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    background: Color::Rgb(0xfa, 0xfa, 0xfa),
+    text: Color::Rgb(0x20, 0x20, 0x20),
+    dim: Color::Rgb(0x60, 0x60, 0x60),
+    faint: Color::Rgb(0xa0, 0xa0, 0xa0),
+    accent: Color::Rgb(0x00, 0x5f, 0x87),
+    good: Color::Rgb(0x00, 0x87, 0x00),
+    warning: Color::Rgb(0xaf, 0x5f, 0x00),
+    critical: Color::Rgb(0xd7, 0x00, 0x00),
+    severe: Color::Rgb(0xd7, 0x5f, 0x00),
+    special: Color::Rgb(0x87, 0x00, 0x87),
+    info: Color::Rgb(0x00, 0x00, 0xd7),
+    on_highlight: Color::Rgb(0x00, 0x00, 0x00),
+    selection: Color::Rgb(0xb4, 0xd5, 0xfe),
+    header: Color::Rgb(0xc8, 0xdc, 0xb4),
+    tagged: Color::Rgb(0xf5, 0xe6, 0xa0),
+};
+
+/// Built-in themes, in the order the theme key cycles through them
+pub const PRESETS: [&Theme; 4] = [&DEFAULT, &SOLARIZED, &DRACULA, &LIGHT];
+
+/// Index into `PRESETS` of the theme being drawn with
+///
+/// Global rather than passed around, since nearly every drawing helper
+/// picks colors and most of them don't see the app state.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// The theme being drawn with
+pub fn theme() -> &'static Theme {
+    PRESETS[ACTIVE.load(Ordering::Relaxed)]
+}
+
+/// Switch to a preset by name; unknown names are ignored
+pub fn set_theme(name: &str) {
+    if let Some(index) = find(name) {
+        ACTIVE.store(index, Ordering::Relaxed);
+    }
+}
+
+/// What meter bars are filled with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarStyle {
+    /// htop's `|` per cell
+    #[default]
+    Pipes,
+    /// `█` per cell, ending in an eighth-cell block such as `▍`, for eight
+    /// times the resolution in the same width
+    Blocks,
+}
+
+impl BarStyle {
+    /// Parse a config value such as `pipes` or `blocks`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pipes" | "htop" => Some(BarStyle::Pipes),
+            "blocks" | "unicode" => Some(BarStyle::Blocks),
+            _ => None,
+        }
+    }
+}
+
+/// Whether bars are drawn with blocks, global for the same reason as the
+/// theme
+static BLOCK_BARS: AtomicBool = AtomicBool::new(false);
+
+/// The bar style being drawn with
+pub fn bar_style() -> BarStyle {
+    if BLOCK_BARS.load(Ordering::Relaxed) {
+        BarStyle::Blocks
+    } else {
+        BarStyle::Pipes
+    }
+}
+
+pub fn set_bar_style(style: BarStyle) {
+    BLOCK_BARS.store(style == BarStyle::Blocks, Ordering::Relaxed);
+}
+
+/// Switch to the next preset
+pub fn cycle_theme() -> &'static Theme {
+    let next = (ACTIVE.load(Ordering::Relaxed) + 1) % PRESETS.len();
+    ACTIVE.store(next, Ordering::Relaxed);
+    PRESETS[next]
+}
+
+/// Index of the preset with this name, ignoring case
+pub fn find(name: &str) -> Option<usize> {
+    PRESETS
+        .iter()
+        .position(|theme| theme.name.eq_ignore_ascii_case(name.trim()))
+}
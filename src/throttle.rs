@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+/// Average draw time above which the terminal counts as slow, e.g. a
+/// congested ssh link where writes block until the far end catches up
+const SLOW_DRAW: Duration = Duration::from_millis(40);
+
+/// Average draw time below which a slow terminal counts as fast again; lower
+/// than `SLOW_DRAW` so a link near the limit doesn't flip back and forth
+const FAST_DRAW: Duration = Duration::from_millis(15);
+
+/// Weight of the newest draw time in the average
+const ALPHA: f64 = 0.3;
+
+/// Time between redraws on a slow terminal when no key was pressed
+const THROTTLED_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Decides when to redraw, from how long recent draws took
+///
+/// On a fast terminal every pass of the event loop redraws. Once draws get
+/// slow, redraws follow input right away but otherwise wait for
+/// `THROTTLED_INTERVAL`, so keys don't queue up behind frames that only
+/// carry new readings.
+#[derive(Debug, Default)]
+pub struct RenderThrottle {
+    /// Moving average of draw time, in seconds
+    average: f64,
+    slow: bool,
+    last_draw: Option<Instant>,
+}
+
+impl RenderThrottle {
+    /// Whether to draw now, given whether input arrived since the last draw
+    pub fn should_draw(&self, input: bool) -> bool {
+        match self.last_draw {
+            Some(last_draw) if self.slow && !input => last_draw.elapsed() >= THROTTLED_INTERVAL,
+            _ => true,
+        }
+    }
+
+    /// Fold in how long a draw took
+    ///
+    /// # Returns
+    /// The new state when the terminal turned slow or fast with this draw
+    pub fn record(&mut self, took: Duration) -> Option<bool> {
+        self.average += ALPHA * (took.as_secs_f64() - self.average);
+        self.last_draw = Some(Instant::now());
+        let slow = if self.slow {
+            self.average >= FAST_DRAW.as_secs_f64()
+        } else {
+            self.average > SLOW_DRAW.as_secs_f64()
+        };
+        (slow != self.slow).then(|| {
+            self.slow = slow;
+            slow
+        })
+    }
+}
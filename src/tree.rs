@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+use sysinfo::Pid;
+
+use crate::sort::{sort_processes, SortOrder, SortReadings};
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// A process positioned in the tree view render order
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub pid: Pid,
+    pub prefix: String,
+    /// Number of descendants hidden because this node is collapsed
+    pub hidden_children: usize,
+    /// CPU usage summed over the hidden descendants
+    pub hidden_cpu: f32,
+    /// Resident memory in bytes summed over the hidden descendants
+    pub hidden_memory: u64,
+}
+
+impl TreeNode {
+    /// Create a node with nothing hidden beneath it
+    pub fn new(pid: Pid, prefix: String) -> Self {
+        TreeNode {
+            pid,
+            prefix,
+            hidden_children: 0,
+            hidden_cpu: 0.0,
+            hidden_memory: 0,
+        }
+    }
+}
+
+/// Build the depth-first render order of the process hierarchy
+///
+/// Processes are grouped under their parent PID; processes without a parent,
+/// or whose parent is no longer present, become roots. Siblings are ordered
+/// by the same sort key as the flat view.
+///
+/// # Arguments
+/// * `sys` - System snapshot holding the process list
+/// * `collapsed` - PIDs whose descendants should be hidden
+/// * `order` - Ordering applied among siblings
+/// * `readings` - Readings to sort by instead of reading them again
+/// * `cpu` - CPU usage as the rows show it, e.g. smoothed, for the totals
+///   of collapsed subtrees; the latest readings when None
+///
+/// # Returns
+/// Vector of tree nodes in the order they should be rendered
+pub fn build_tree(
+    sys: &Snapshot,
+    collapsed: &HashSet<Pid>,
+    order: SortOrder,
+    readings: SortReadings,
+    cpu: Option<&HashMap<Pid, f32>>,
+) -> Vec<TreeNode> {
+    let processes = sys.processes();
+    let mut children: HashMap<Pid, Vec<&ProcessSnapshot>> = HashMap::new();
+    let mut roots: Vec<&ProcessSnapshot> = Vec::new();
+
+    for process in processes.values() {
+        match process.parent() {
+            Some(parent) if parent != process.pid() && processes.contains_key(&parent) => {
+                children.entry(parent).or_default().push(process);
+            }
+            _ => roots.push(process),
+        }
+    }
+
+    sort_processes(&mut roots, order, readings);
+    for siblings in children.values_mut() {
+        sort_processes(siblings, order, readings);
+    }
+
+    let mut nodes = Vec::with_capacity(processes.len());
+    let mut visited = HashSet::new();
+
+    let walker = Walker {
+        children: &children,
+        collapsed,
+        cpu,
+    };
+
+    for root in roots {
+        walker.walk(root.pid(), String::new(), "", &mut visited, &mut nodes);
+    }
+
+    nodes
+}
+
+/// Shared lookups for the depth-first traversal
+struct Walker<'a> {
+    children: &'a HashMap<Pid, Vec<&'a ProcessSnapshot>>,
+    collapsed: &'a HashSet<Pid>,
+    /// CPU usage to roll up in place of the latest readings
+    cpu: Option<&'a HashMap<Pid, f32>>,
+}
+
+impl Walker<'_> {
+    /// Recursively append a process and its descendants in depth-first order
+    fn walk(
+        &self,
+        pid: Pid,
+        indent: String,
+        branch: &str,
+        visited: &mut HashSet<Pid>,
+        nodes: &mut Vec<TreeNode>,
+    ) {
+        // Guard against PID reuse producing a parent cycle
+        if !visited.insert(pid) {
+            return;
+        }
+
+        let mut node = TreeNode::new(pid, format!("{}{}", indent, branch));
+
+        if self.collapsed.contains(&pid) {
+            self.roll_up_descendants(&mut node, visited);
+            nodes.push(node);
+            return;
+        }
+
+        nodes.push(node);
+
+        if let Some(kids) = self.children.get(&pid) {
+            let child_indent = match branch {
+                "" => indent,
+                "└─ " => format!("{}   ", indent),
+                _ => format!("{}│  ", indent),
+            };
+
+            for (i, child) in kids.iter().enumerate() {
+                let child_branch = if i + 1 == kids.len() {
+                    "└─ "
+                } else {
+                    "├─ "
+                };
+                self.walk(
+                    child.pid(),
+                    child_indent.clone(),
+                    child_branch,
+                    visited,
+                    nodes,
+                );
+            }
+        }
+    }
+
+    /// Accumulate the count, CPU, and memory of every descendant into `node`,
+    /// marking them as visited
+    fn roll_up_descendants(&self, node: &mut TreeNode, visited: &mut HashSet<Pid>) {
+        let mut stack = vec![node.pid];
+
+        while let Some(current) = stack.pop() {
+            if let Some(kids) = self.children.get(&current) {
+                for child in kids {
+                    if visited.insert(child.pid()) {
+                        node.hidden_children += 1;
+                        node.hidden_cpu += self
+                            .cpu
+                            .and_then(|cpu| cpu.get(&child.pid()))
+                            .copied()
+                            .unwrap_or_else(|| child.cpu_usage());
+                        node.hidden_memory += child.memory();
+                        stack.push(child.pid());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a process is one of `pids` or descends from one of them
+pub fn descends_from(sys: &Snapshot, process: &ProcessSnapshot, pids: &HashSet<Pid>) -> bool {
+    let mut current = Some(process);
+    // Bounded by the process count in case PID reuse made a parent cycle
+    for _ in 0..=sys.processes().len() {
+        let Some(process) = current else {
+            return false;
+        };
+        if pids.contains(&process.pid()) {
+            return true;
+        }
+        current = process
+            .parent()
+            .filter(|parent| *parent != process.pid())
+            .and_then(|parent| sys.process(parent));
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapsed_rows_total_the_cpu_the_rows_show() {
+        let process = |pid, parent: Option<u32>, cpu_usage| ProcessSnapshot {
+            pid: Pid::from_u32(pid),
+            parent: parent.map(Pid::from_u32),
+            cpu_usage,
+            ..ProcessSnapshot::default()
+        };
+        let sys = Snapshot {
+            processes: [
+                process(1, None, 1.0),
+                process(2, Some(1), 80.0),
+                process(3, Some(2), 40.0),
+            ]
+            .into_iter()
+            .map(|process| (process.pid, process))
+            .collect(),
+            ..Snapshot::default()
+        };
+        let collapsed = HashSet::from([Pid::from_u32(1)]);
+        let smoothed = HashMap::from([(Pid::from_u32(2), 20.0), (Pid::from_u32(3), 10.0)]);
+
+        let nodes = build_tree(
+            &sys,
+            &collapsed,
+            SortOrder::default(),
+            SortReadings::default(),
+            Some(&smoothed),
+        );
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].hidden_children, 2);
+        assert_eq!(nodes[0].hidden_cpu, 30.0);
+
+        let raw = build_tree(
+            &sys,
+            &collapsed,
+            SortOrder::default(),
+            SortReadings::default(),
+            None,
+        );
+        assert_eq!(raw[0].hidden_cpu, 120.0);
+    }
+}
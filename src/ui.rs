@@ -1,19 +1,66 @@
 use chrono::{self, Datelike};
-use once_cell::sync::Lazy;
 use ratatui::{
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table, TableState, Tabs, Wrap,
+    },
     Frame,
 };
-use std::collections::HashMap;
-use sysinfo::System;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::Child;
+use std::rc::Rc;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessStatus};
 
-use crate::helpers::{centered_rect, format_bytes, format_runtime, format_uptime};
-use crate::process::{
-    fetch_memory_map, fetch_priority_map, get_process_memory, get_process_priority,
+use crate::actions::{ProcessAction, SIGNALS};
+use crate::columns::{listing, Column, Meter, MeterMode};
+use crate::config::Config;
+use crate::docker::ContainerStats;
+use crate::energy::EnergyLedger;
+use crate::grouping::{
+    container_label, group_name, group_rows, user_totals, DisplayRow, GroupHeader, Grouping,
+    UserOrder,
 };
+use crate::inspector::{Inspector, InspectorTab, ProcessDetails};
+use crate::lifecycle::{EventKind, Lifecycle, LifecycleEvent};
+use crate::pidlog::PidLog;
+use crate::smoothing::CpuSmoother;
+use crate::sort::{sort_processes, SortKey, SortOrder, SortReadings};
+use crate::states::{StateSpan, StateTracker};
+use crate::theme::{bar_style, theme, BarStyle};
+use crate::tree::{build_tree, descends_from, TreeNode};
+use crate::watch::{DiffLine, RespawnDiff, WatchedProcess};
+use sysly_core::battery::{minutes_gained, Battery, BatteryState, BatteryTrend};
+use sysly_core::brew::BrewService;
+use sysly_core::cgroup::CgroupUsage;
+use sysly_core::collector::Collectors;
+use sysly_core::disk::{DiskMeter, Volume};
+use sysly_core::extensions::Extension;
+use sysly_core::fans::Fan;
+use sysly_core::gpu::{GpuMemoryKind, GpuUsage};
+use sysly_core::helpers::{
+    format_bytes, format_count, format_runtime, format_timestamp, format_uptime, group_digits,
+    TimeFormat,
+};
+use sysly_core::host::HostInfo;
+use sysly_core::memstat::{MemoryStats, SwapMeter, SwapRates};
+use sysly_core::network::NetworkUsage;
+use sysly_core::origin::{CodeSignature, Origin, OriginCache};
+use sysly_core::process::{
+    get_process_memory, get_process_priority, CompressorStats, DiskIoRates, ProcessMaps,
+};
+use sysly_core::rosetta::RosettaCache;
+use sysly_core::sched::{NapState, SchedClass, SchedInfo};
+use sysly_core::sensors::{SensorLog, ThermalPressure};
+use sysly_core::snapshot::{CpuSnapshot, ProcessSnapshot, Snapshot};
+use sysly_core::startup::StartupItem;
+use sysly_core::threads::ThreadTotals;
+use sysly_core::topology::CoreCluster;
 
 // Constants for UI layout and styling
 const CPU_COLUMNS: usize = 4;
@@ -30,14 +77,462 @@ const CPU_MEDIUM_THRESHOLD: f32 = 50.0;
 const MEMORY_HIGH_THRESHOLD: f64 = 0.8;
 const MEMORY_MEDIUM_THRESHOLD: f64 = 0.5;
 
+// Color thresholds for disk throughput, in bytes per second
+const DISK_HIGH_THRESHOLD: f64 = 100.0 * 1024.0 * 1024.0;
+const DISK_MEDIUM_THRESHOLD: f64 = 10.0 * 1024.0 * 1024.0;
+
+// Color thresholds for battery charge, in percent
+const BATTERY_LOW_THRESHOLD: f32 = 20.0;
+const BATTERY_MEDIUM_THRESHOLD: f32 = 50.0;
+
+// Swap traffic (bytes per second) above which the machine counts as thrashing
+const SWAP_HIGH_THRESHOLD: f64 = 1024.0 * 1024.0;
+
+// Color thresholds for idle wake-ups per second
+const WAKEUP_HIGH_THRESHOLD: f64 = 100.0;
+const WAKEUP_MEDIUM_THRESHOLD: f64 = 20.0;
+
+// Color thresholds for powermetrics energy impact
+const ENERGY_HIGH_THRESHOLD: f64 = 50.0;
+const ENERGY_MEDIUM_THRESHOLD: f64 = 10.0;
+
 // Color thresholds for process CPU/MEM usage
 const PROCESS_HIGH_THRESHOLD: f32 = 50.0;
 const PROCESS_MEDIUM_THRESHOLD: f32 = 20.0;
 
+/// Dashboard panes that can take focus and be paused independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Pane {
+    Meters,
+    #[default]
+    Processes,
+}
+
+impl Pane {
+    pub const ALL: [Pane; 2] = [Pane::Meters, Pane::Processes];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pane::Meters => "Meters",
+            Pane::Processes => "Processes",
+        }
+    }
+
+    /// The pane that receives focus after this one
+    pub fn next(self) -> Pane {
+        match self {
+            Pane::Meters => Pane::Processes,
+            Pane::Processes => Pane::Meters,
+        }
+    }
+}
+
+/// Views of the process pane, shown as tabs above the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum View {
+    #[default]
+    Processes,
+    /// Processes ranked by disk throughput, like iotop
+    Io,
+    /// Processes ranked by network traffic since sysly started
+    Network,
+    /// CPU, memory and process count summed by user
+    Users,
+    /// Energy impact and wake-ups by app over the session
+    Energy,
+    /// Temperature history and thermal-throttle log
+    Sensors,
+    /// Loaded kernel extensions and system extensions
+    Extensions,
+    /// Login items and boot-time agents and daemons
+    Startup,
+    /// Homebrew services with the process each is running as
+    Services,
+    /// Usage and limits of cgroup v2 slices and services
+    Cgroups,
+    /// Docker containers with their CPU, memory and network use
+    Containers,
+    /// Processes that started and exited, newest first
+    Events,
+}
+
+impl View {
+    pub const ALL: [View; 12] = [
+        View::Processes,
+        View::Io,
+        View::Network,
+        View::Users,
+        View::Energy,
+        View::Sensors,
+        View::Extensions,
+        View::Startup,
+        View::Services,
+        View::Cgroups,
+        View::Containers,
+        View::Events,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            View::Processes => "Processes",
+            View::Io => "I/O",
+            View::Network => "Network",
+            View::Users => "Users",
+            View::Energy => "Energy",
+            View::Sensors => "Sensors",
+            View::Extensions => "Extensions",
+            View::Startup => "Startup",
+            View::Services => "Services",
+            View::Cgroups => "Cgroups",
+            View::Containers => "Containers",
+            View::Events => "Events",
+        }
+    }
+
+    /// Whether the view lists processes, so selection and actions apply
+    pub fn lists_processes(self) -> bool {
+        matches!(self, View::Processes | View::Io | View::Network)
+    }
+
+    /// Whether the view shows this machine's own setup rather than the
+    /// snapshot, so it is left out when the data comes from elsewhere
+    pub fn is_local(self) -> bool {
+        matches!(
+            self,
+            View::Extensions | View::Startup | View::Services | View::Containers
+        )
+    }
+
+    /// The view after this one, wrapping around
+    pub fn next(self) -> View {
+        View::ALL[(self as usize + 1) % View::ALL.len()]
+    }
+
+    /// The view before this one, wrapping around
+    pub fn previous(self) -> View {
+        View::ALL[(self as usize + View::ALL.len() - 1) % View::ALL.len()]
+    }
+}
+
+/// Readings kept per core for the CPU history graphs, one per refresh;
+/// braille graphs show two per cell
+const CPU_HISTORY_LEN: usize = 240;
+
+/// Recent usage of every core and of memory, kept so the meter graphs have
+/// history to show as soon as they are turned on
+#[derive(Debug, Default)]
+pub struct CpuHistory {
+    /// Usage percentages per core, oldest first
+    cores: Vec<VecDeque<f32>>,
+    /// Percent of memory used, oldest first
+    memory: VecDeque<f32>,
+}
+
+impl CpuHistory {
+    pub fn record(&mut self, cpus: &[CpuSnapshot]) {
+        self.cores
+            .resize_with(cpus.len(), || VecDeque::with_capacity(CPU_HISTORY_LEN));
+        for (readings, cpu) in self.cores.iter_mut().zip(cpus) {
+            if readings.len() == CPU_HISTORY_LEN {
+                readings.pop_front();
+            }
+            readings.push_back(cpu.cpu_usage());
+        }
+    }
+
+    pub fn record_memory(&mut self, used: u64, total: u64) {
+        if self.memory.len() == CPU_HISTORY_LEN {
+            self.memory.pop_front();
+        }
+        let percent = if total > 0 {
+            used as f32 / total as f32 * 100.0
+        } else {
+            0.0
+        };
+        self.memory.push_back(percent);
+    }
+
+    pub fn core(&self, index: usize) -> Option<&VecDeque<f32>> {
+        self.cores.get(index)
+    }
+
+    pub fn memory(&self) -> &VecDeque<f32> {
+        &self.memory
+    }
+}
+
+/// What the network view ranks processes by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetworkOrder {
+    /// Bytes moved since sysly started, to find steady consumers
+    #[default]
+    Total,
+    /// Current bytes per second
+    Rate,
+}
+
+impl NetworkOrder {
+    pub fn toggle(self) -> Self {
+        match self {
+            NetworkOrder::Total => NetworkOrder::Rate,
+            NetworkOrder::Rate => NetworkOrder::Total,
+        }
+    }
+}
+
+/// A process action waiting for the user's confirmation
+pub struct PendingAction {
+    pub action: ProcessAction,
+    pub targets: Vec<Pid>,
+}
+
+/// A Yes/No prompt guarding an action
+pub struct Confirmation {
+    pub title: String,
+    pub message: String,
+    /// Context listed under the message, e.g. the affected processes
+    pub details: Vec<String>,
+    /// Destructive prompts are drawn in red and focus No by default
+    pub destructive: bool,
+    pub yes_focused: bool,
+    /// Action run when the user answers Yes
+    pub on_confirm: PendingAction,
+}
+
+impl Confirmation {
+    pub fn new(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        details: Vec<String>,
+        destructive: bool,
+        on_confirm: PendingAction,
+    ) -> Self {
+        Confirmation {
+            title: title.into(),
+            message: message.into(),
+            details,
+            destructive,
+            yes_focused: !destructive,
+            on_confirm,
+        }
+    }
+}
+
+/// The list the setup screen is editing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupSection {
+    Columns,
+    Meters,
+}
+
+/// The F2 setup screen, with the highlighted entry of the section in focus
+pub struct SetupMenu {
+    pub section: SetupSection,
+    pub selected: usize,
+}
+
+/// The overlay currently capturing keyboard input
+///
+/// At most one modal is open at a time; it receives every key until it
+/// closes or hands over to another modal.
+pub enum Modal {
+    Help,
+    Diagnostics,
+    Respawn(RespawnDiff),
+    /// Signal picker with the highlighted entry; `group` sends to the
+    /// targets' whole process groups
+    SignalMenu {
+        selected: usize,
+        group: bool,
+    },
+    /// Sort column picker with the highlighted entry
+    SortMenu(usize),
+    Setup(SetupMenu),
+    Confirm(Confirmation),
+    Inspector(Box<Inspector>),
+}
+
+impl Modal {
+    /// Whether the modal is taking text input, so keys like `q` are typed
+    /// rather than acted on
+    pub fn captures_text(&self) -> bool {
+        matches!(self, Modal::Inspector(inspector) if inspector.searching)
+    }
+}
+
 /// Application state for UI rendering
+#[derive(Default)]
 pub struct AppState {
-    pub show_help: bool,
+    pub modal: Option<Modal>,
     pub selected_row_index: usize, // Thêm trường này
+    pub tree_view: bool,
+    /// Section the process view under a header per user, app or container
+    pub grouping: Grouping,
+    /// Names of the groups showing only their header
+    pub collapsed_groups: HashSet<String>,
+    pub sort: SortOrder,
+    /// Only show processes owned by this UID
+    pub user_filter: Option<u32>,
+    /// Only show these PIDs and their descendants
+    pub pid_filter: Option<HashSet<Pid>>,
+    /// Show at most this many rows, from the top of the sort order
+    pub row_limit: Option<usize>,
+    /// Only show processes whose name or command line contains this,
+    /// ignoring case
+    pub name_filter: Option<String>,
+    pub collapsed: HashSet<Pid>,
+    pub table_offset: usize,
+    pub watched: Vec<WatchedProcess>,
+    /// Respawn diffs still to be shown, oldest first
+    pub respawns: VecDeque<RespawnDiff>,
+    pub compressor: Option<CompressorStats>,
+    /// Memory split into app, wired, compressed, and cached
+    pub memory_stats: Option<MemoryStats>,
+    pub swap: SwapMeter,
+    pub disk: DiskMeter,
+    pub gpu: Option<GpuUsage>,
+    /// None on machines without a battery
+    pub battery: Option<Battery>,
+    /// Recent charge while on battery, for the time at the current load
+    pub battery_trend: BatteryTrend,
+    /// Bars, or graphs of recent usage in the CPU (and with braille, Mem)
+    /// meters
+    pub meter_mode: MeterMode,
+    pub cpu_history: CpuHistory,
+    /// The terminal is slow to draw, so graphs are left out
+    pub reduced_rendering: bool,
+    /// Usage and limits of the cgroup sysly runs in, shown as meters, when
+    /// cgroup v2 is in use and it isn't the root group
+    pub cgroup: Option<CgroupUsage>,
+    /// Slices and their groups, read while the cgroups view is open
+    pub cgroups: Vec<CgroupUsage>,
+    /// Running containers, read while the containers view is open
+    pub containers: Vec<ContainerStats>,
+    /// Why Docker couldn't be read, shown in place of the containers
+    pub docker_error: Option<String>,
+    /// Container names by short ID, read while the container column or
+    /// grouping is on
+    pub container_names: HashMap<String, String>,
+    /// Performance and efficiency clusters, empty unless the CPU is hybrid
+    pub core_clusters: Vec<CoreCluster>,
+    /// Host name, OS and chip for the header
+    pub host: HostInfo,
+    /// Show the filesystem panel above the process pane
+    pub show_filesystems: bool,
+    pub volumes: Vec<Volume>,
+    pub sensors: SensorLog,
+    /// Empty on fanless machines
+    pub fans: Vec<Fan>,
+    /// User names by UID
+    pub users: HashMap<u32, String>,
+    /// Loaded extensions, read when the extensions view opens
+    pub extensions: Vec<Extension>,
+    /// Startup items, read when the startup view opens
+    pub startup_items: Vec<StartupItem>,
+    /// Homebrew services, read while the services view is open; None when
+    /// Homebrew isn't installed
+    pub brew_services: Option<Vec<BrewService>>,
+    /// A `brew services restart` still running, and the service it restarts
+    pub brew_restart: Option<(String, Child)>,
+    pub tagged: HashSet<Pid>,
+    /// Processes started and exited since sysly started
+    pub lifecycle: Lifecycle,
+    pub status_message: Option<String>,
+    pub config: Config,
+    pub focus: Pane,
+    pub paused: HashSet<Pane>,
+    pub row_cache: RowCache,
+    /// Process table rows in display order, kept until the next process
+    /// refresh or a change to how they are listed
+    pub display_cache: DisplayCache,
+    /// PRI/NI and VIRT/RES data from the last process refresh, so frames
+    /// between refreshes don't read them again
+    pub process_maps: ProcessMaps,
+    /// Process table columns, in order
+    pub columns: Vec<Column>,
+    /// Meters beside the CPU grid, in order
+    pub meters: Vec<Meter>,
+    /// Idle wake-ups per second, by process
+    pub wakeups: HashMap<Pid, f64>,
+    pub origins: OriginCache,
+    /// Processes running translated under Rosetta 2, marked in the command
+    pub rosetta: RosettaCache,
+    /// powermetrics is running, while the energy column is shown
+    pub energy: bool,
+    /// Energy impact by PID from the latest powermetrics sample
+    pub energy_impact: HashMap<u32, f64>,
+    /// Energy and wake-ups by app over the session, for the Energy view
+    pub energy_ledger: EnergyLedger,
+    /// Disk throughput by PID, read while a disk column is shown
+    pub disk_io: HashMap<u32, DiskIoRates>,
+    /// Scheduling class by PID, read while the QOS column is shown or
+    /// sorted by
+    pub sched: HashMap<u32, SchedInfo>,
+    /// App Nap state by PID of the processes held back, read while the NAP
+    /// column is shown
+    pub nap: HashMap<u32, NapState>,
+    /// Moving average of each process's CPU usage, when smoothing is on
+    pub cpu_smoother: CpuSmoother,
+    /// When each process entered its current state, for the inspector
+    pub process_states: StateTracker,
+    /// Processes being recorded to CSV, started with L
+    pub pid_logs: Vec<PidLog>,
+    pub view: View,
+    /// Per-process network traffic, sampled while the network view or the
+    /// inspector is open
+    pub network: HashMap<Pid, NetworkUsage>,
+    pub network_order: NetworkOrder,
+    pub user_order: UserOrder,
+    /// Time covered by the latest per-process deltas (disk I/O), used to
+    /// turn them into rates
+    pub process_interval: Duration,
+    /// Thread totals for the system info panel, recounted with the processes
+    pub thread_totals: ThreadTotals,
+    /// None where the platform doesn't report it
+    pub thermal_pressure: Option<ThermalPressure>,
+    /// Where the data comes from when it isn't this machine, such as an
+    /// agent's address; its processes can't be acted on or inspected
+    pub source: Option<String>,
+    pub collectors: Collectors,
+}
+
+impl AppState {
+    /// Whether the process table shows this column
+    pub fn shows(&self, column: Column) -> bool {
+        self.columns.contains(&column)
+    }
+}
+
+/// Calculate a centered rectangle within the given area
+///
+/// # Arguments
+/// * `percent_x` - Width percentage of the centered rectangle
+/// * `percent_y` - Height percentage of the centered rectangle  
+/// * `area` - The parent rectangle to center within
+///
+/// # Returns
+/// A centered rectangle with the specified dimensions
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    let vertical = popup_layout[1];
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical);
+
+    horizontal[1]
 }
 
 /// Draw the help window overlay
@@ -52,7 +547,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
             Span::styled(
                 "Sysly - macOS System Monitor Experiment",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme().warning)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(padding),
@@ -64,7 +559,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
                     "Version {} - Conceived Jul 1, 2019",
                     crate::build_info::VERSION
                 ),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme().accent),
             ),
             Span::raw(padding),
         ]),
@@ -72,7 +567,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
             Span::raw(padding),
             Span::styled(
                 format!("(C) 2019-{} Thinh Nguyen", chrono::Utc::now().year()),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme().warning),
             ),
             Span::raw(padding),
         ]),
@@ -80,7 +575,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
             Span::raw(padding),
             Span::styled(
                 crate::build_info::PROJECT_INSPIRED,
-                Style::default().fg(Color::Magenta),
+                Style::default().fg(theme().special),
             ),
             Span::raw(padding),
         ]),
@@ -89,7 +584,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
             Span::raw(padding),
             Span::styled(
                 "Released under the Apache License 2.0.",
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme().warning),
             ),
             Span::raw(padding),
         ]),
@@ -99,7 +594,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
             Span::styled(
                 "Press any key to return.",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme().good)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(padding),
@@ -110,7 +605,7 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
     let help_block = Block::default()
         .title("Help")
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
+        .style(Style::default().bg(theme().background));
 
     let help_paragraph = Paragraph::new(help_lines)
         .block(help_block)
@@ -119,379 +614,3793 @@ pub fn draw_help_window(f: &mut Frame, area: Rect) {
     f.render_widget(help_paragraph, help_area);
 }
 
-/// Draw the main dashboard layout
-pub fn draw_dashboard(f: &mut Frame, sys: &System, area: Rect, selected_row_index: usize) {
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(7), // Info bar
-            Constraint::Min(10),   // Process table
-        ])
-        .split(area);
-
-    draw_info_bar(sys, f, layout[0]);
-    draw_process_table(sys, f, layout[1], selected_row_index);
-}
-
-/// Draw the information bar with CPU, memory, and system info
-pub fn draw_info_bar(sys: &System, f: &mut Frame, area: Rect) {
-    let cpus = sys.cpus();
-    let cpu_count = cpus.len();
-    let cpu_rows = (cpu_count + CPU_COLUMNS - 1) / CPU_COLUMNS;
-
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(cpu_rows as u16), // CPU bars
-            Constraint::Min(3),                  // Memory bars + info
-        ])
-        .split(area);
+/// Draw the respawn diff overlay for a watched process
+pub fn draw_respawn_window(f: &mut Frame, area: Rect, diff: &RespawnDiff) {
+    let window_area = centered_rect(80, 60, area);
+    let padding = "  ";
 
-    draw_cpu_bars(cpus, f, layout[0]);
-    draw_memory_and_info(sys, f, layout[1]);
-}
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw(padding),
+            Span::styled(
+                format!(
+                    "{} respawned: PID {} -> {}",
+                    diff.name, diff.old_pid, diff.new_pid
+                ),
+                Style::default()
+                    .fg(theme().warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ];
 
-/// Draw CPU usage bars in a grid layout
-fn draw_cpu_bars(cpus: &[sysinfo::Cpu], f: &mut Frame, area: Rect) {
-    let cpu_count = cpus.len();
-    let cpu_rows = (cpu_count + CPU_COLUMNS - 1) / CPU_COLUMNS;
-    let total_padding = (CPU_COLUMNS - 1) * 3;
-    let label_length = 4;
-    let percent_length = 6;
-    let bracket_length = 2;
+    let sections = [
+        ("Command line", &diff.cmd_changes),
+        ("Environment", &diff.env_changes),
+    ];
 
-    let bar_length = ((area.width as usize - total_padding) / CPU_COLUMNS)
-        .saturating_sub(label_length + percent_length + bracket_length)
-        .max(MIN_BAR_LENGTH);
+    for (title, changes) in sections {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::raw(padding),
+            Span::styled(title, Style::default().fg(theme().accent)),
+        ]));
 
-    let mut cpu_lines = Vec::new();
+        if changes.is_empty() {
+            lines.push(Line::from(vec![
+                Span::raw(padding),
+                Span::styled("  (unchanged)", Style::default().fg(theme().dim)),
+            ]));
+        }
 
-    for row in 0..cpu_rows {
-        let mut spans = Vec::new();
+        for change in changes.iter() {
+            let (marker, text, color) = match change {
+                DiffLine::Added(text) => ("+ ", text, theme().good),
+                DiffLine::Removed(text) => ("- ", text, theme().critical),
+            };
+            lines.push(Line::from(vec![
+                Span::raw(padding),
+                Span::styled(format!("{}{}", marker, text), Style::default().fg(color)),
+            ]));
+        }
+    }
 
-        for col in 0..CPU_COLUMNS {
-            let cpu_index = row + col * cpu_rows;
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw(padding),
+        Span::styled(
+            "Press any key to return.",
+            Style::default()
+                .fg(theme().good)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
 
-            if cpu_index < cpus.len() {
-                let cpu = &cpus[cpu_index];
-                let usage = cpu.cpu_usage();
-                let used_bars = ((usage / 100.0) * bar_length as f32).round() as usize;
+    let block = Block::default()
+        .title("Respawn")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
 
-                let bar = create_progress_bar(used_bars, bar_length);
-                let color = get_cpu_color(usage);
-                let label = format!("{:>2}   ", cpu_index);
+    f.render_widget(Clear, window_area);
+    f.render_widget(Paragraph::new(lines).block(block), window_area);
+}
 
-                spans.extend_from_slice(&[
-                    Span::styled(label, Style::default().fg(Color::Cyan)),
-                    Span::raw("["),
-                    Span::styled(bar, Style::default().fg(color)),
-                    Span::raw("] "),
-                    Span::styled(format!("{:>5.1}%", usage), Style::default().fg(Color::Gray)),
-                ]);
-            } else {
-                let empty_space =
-                    " ".repeat(LABEL_WIDTH + 1 + bar_length + bracket_length + percent_length);
-                spans.push(Span::raw(empty_space));
-            }
+/// Draw the signal menu overlay for the kill action
+///
+/// * `selected` - Index into `SIGNALS` of the highlighted entry
+/// * `target_count` - Number of processes the signal will be sent to
+pub fn draw_signal_menu(
+    f: &mut Frame,
+    area: Rect,
+    selected: usize,
+    target_count: usize,
+    group: bool,
+) {
+    let menu_height = SIGNALS.len() as u16 + 4;
+    let menu_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height.saturating_sub(menu_height) / 2,
+        width: 24.min(area.width),
+        height: menu_height.min(area.height),
+    };
 
-            if col < CPU_COLUMNS - 1 {
-                spans.push(Span::raw("   "));
-            }
-        }
+    let unit = if group { "group" } else { "proc" };
+    let mut lines = vec![Line::from(Span::styled(
+        format!(" Send to {} {}", target_count, unit),
+        Style::default().fg(theme().warning),
+    ))];
 
-        cpu_lines.push(Line::from(spans));
+    for (index, (name, number)) in SIGNALS.iter().enumerate() {
+        let text = format!(" {:>2} {:<18}", number, format!("SIG{}", name));
+        let style = if index == selected {
+            Style::default()
+                .bg(theme().selection)
+                .fg(theme().on_highlight)
+        } else {
+            Style::default().fg(theme().text)
+        };
+        lines.push(Line::from(Span::styled(text, style)));
     }
 
-    let cpu_paragraph = Paragraph::new(cpu_lines).alignment(Alignment::Left);
-    f.render_widget(cpu_paragraph, area);
+    let block = Block::default()
+        .title(if group {
+            "Signal process group"
+        } else {
+            "Send signal"
+        })
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
+
+    f.render_widget(Clear, menu_area);
+    f.render_widget(Paragraph::new(lines).block(block), menu_area);
 }
 
-/// Draw memory bars and system information
-fn draw_memory_and_info(sys: &System, f: &mut Frame, area: Rect) {
-    let layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(50), // Memory bars
-            Constraint::Percentage(50), // System info
-        ])
-        .split(area);
+/// Draw the sort column menu on the left edge of the screen
+pub fn draw_sort_menu(f: &mut Frame, area: Rect, selected: usize, current: SortOrder) {
+    let menu_height = SortKey::ALL.len() as u16 + 4;
+    let menu_area = Rect {
+        x: area.x + 1,
+        y: area.y + area.height.saturating_sub(menu_height) / 2,
+        width: 24.min(area.width),
+        height: menu_height.min(area.height),
+    };
 
-    draw_memory_bars(sys, f, layout[0]);
-    draw_system_info(sys, f, layout[1]);
-}
+    let mut lines: Vec<Line> = SortKey::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, key)| {
+            let marker = if *key == current.primary {
+                '*'
+            } else if Some(*key) == current.secondary {
+                '2'
+            } else {
+                ' '
+            };
+            let text = format!(" {} {:<19}", marker, key.title());
+            let style = if index == selected {
+                Style::default()
+                    .bg(theme().selection)
+                    .fg(theme().on_highlight)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            Line::from(Span::styled(text, style))
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Space: then by (2)",
+        Style::default().fg(theme().dim),
+    )));
 
-/// Draw memory and swap usage bars
-fn draw_memory_bars(sys: &System, f: &mut Frame, area: Rect) {
-    let total_memory = sys.total_memory();
-    let used_memory = sys.used_memory();
-    let total_swap = sys.total_swap();
-    let used_swap = sys.used_swap();
+    let block = Block::default()
+        .title("Sort by")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
 
-    let bar_length = area.width.saturating_sub(LABEL_WIDTH as u16 + 3) as usize;
-    let bar_length = bar_length.max(MIN_MEMORY_BAR_LENGTH);
+    f.render_widget(Clear, menu_area);
+    f.render_widget(Paragraph::new(lines).block(block), menu_area);
+}
 
-    let memory_line = create_memory_bar("Mem", used_memory, total_memory, bar_length, LABEL_WIDTH);
+/// Draw the F2 setup screen: every column and meter, shown ones first in
+/// their order and checked, with the keys that edit them
+pub fn draw_setup_window(f: &mut Frame, area: Rect, setup: &SetupMenu, app_state: &AppState) {
+    let window_area = centered_rect(90, 80, area);
 
-    let swap_line = create_memory_bar("Swp", used_swap, total_swap, bar_length, LABEL_WIDTH);
+    let columns = listing(&app_state.columns, &Column::ALL)
+        .into_iter()
+        .map(|column| {
+            let shown = app_state.shows(column);
+            (column.title().trim_end(), column.description(), shown)
+        })
+        .collect::<Vec<_>>();
+    let meters = listing(&app_state.meters, &Meter::ALL)
+        .into_iter()
+        .map(|meter| {
+            let shown = app_state.meters.contains(&meter);
+            (meter.title(), meter.description(), shown)
+        })
+        .collect::<Vec<_>>();
 
-    let memory_paragraph = Paragraph::new(vec![memory_line, swap_line]);
-    f.render_widget(memory_paragraph, area);
-}
+    let block = Block::default()
+        .title("Setup")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
+    let inner = block.inner(window_area);
+    f.render_widget(Clear, window_area);
+    f.render_widget(block, window_area);
 
-/// Draw system information panel
-fn draw_system_info(sys: &System, f: &mut Frame, area: Rect) {
-    let processes = sys.processes();
-    let task_count = processes.len();
-    let running_count = processes
-        .values()
-        .filter(|p| p.status().to_string() == "Running")
-        .count();
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let sections = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(layout[0]);
 
-    let tasks_info = format!(
-        "Tasks: {}, N/A thr, 0 kthr; {} running",
-        task_count, running_count
-    );
+    for (section, entries, section_area) in [
+        (SetupSection::Columns, columns, sections[0]),
+        (SetupSection::Meters, meters, sections[1]),
+    ] {
+        let focused = setup.section == section;
+        let mut lines = vec![Line::from(Span::styled(
+            match section {
+                SetupSection::Columns => " Columns",
+                SetupSection::Meters => " Meters",
+            },
+            Style::default()
+                .fg(if focused { theme().accent } else { theme().dim })
+                .add_modifier(Modifier::BOLD),
+        ))];
+        // Keep the highlighted entry in view on short terminals
+        let visible = (section_area.height as usize).saturating_sub(1);
+        let skip = if focused {
+            (setup.selected + 1).saturating_sub(visible)
+        } else {
+            0
+        };
+        lines.extend(entries.iter().enumerate().skip(skip).map(
+            |(index, (title, description, shown))| {
+                let text = format!(
+                    " [{}] {:<9} {}",
+                    if *shown { 'x' } else { ' ' },
+                    title,
+                    description
+                );
+                let style = if focused && index == setup.selected {
+                    Style::default()
+                        .bg(theme().selection)
+                        .fg(theme().on_highlight)
+                } else if *shown {
+                    Style::default().fg(theme().text)
+                } else {
+                    Style::default().fg(theme().faint)
+                };
+                Line::from(Span::styled(text, style))
+            },
+        ));
+        f.render_widget(Paragraph::new(lines), section_area);
+    }
 
-    let load_avg = sysinfo::System::load_average();
-    let load_info = format!(
-        "Load average: {:.2} {:.2} {:.2}",
-        load_avg.one, load_avg.five, load_avg.fifteen
+    let hint = " Space toggle  F7/F8 move  Tab switch list  s save to config  Esc close";
+    f.render_widget(
+        Paragraph::new(Span::styled(hint, Style::default().fg(theme().dim))),
+        layout[1],
     );
+}
 
-    let uptime = sysinfo::System::uptime();
-    let uptime_info = format!("Uptime: {}", format_uptime(uptime));
+/// Draw the diagnostics overlay listing collector health and timings
+pub fn draw_diagnostics_window(f: &mut Frame, area: Rect, collectors: &Collectors) {
+    let window_area = centered_rect(80, 60, area);
+    let padding = "  ";
 
-    let info_lines = vec![
-        Line::from(vec![
-            Span::raw(INFO_PADDING),
-            Span::styled(tasks_info, Style::default().fg(Color::Cyan)),
-        ]),
-        Line::from(vec![
-            Span::raw(INFO_PADDING),
-            Span::styled(load_info, Style::default().fg(Color::Cyan)),
-        ]),
+    let mut lines = vec![
+        Line::from(""),
         Line::from(vec![
-            Span::raw(INFO_PADDING),
+            Span::raw(padding),
             Span::styled(
-                uptime_info,
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
+                format!(
+                    "{:<16}{:>8}{:>10}{:>10}{:>8}  {}",
+                    "Collector", "Runs", "Failures", "Last run", "Age", "Status"
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
             ),
         ]),
     ];
 
-    let info_paragraph = Paragraph::new(info_lines).alignment(Alignment::Left);
-    f.render_widget(info_paragraph, area);
-}
-
-/// Draw the process table
-pub fn draw_process_table(sys: &System, f: &mut Frame, area: Rect, selected_row_index: usize) {
-    let mut processes: Vec<_> = sys.processes().values().collect();
-    processes.sort_by(|a, b| {
-        b.cpu_usage()
-            .partial_cmp(&a.cpu_usage())
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    if collectors.health().is_empty() {
+        lines.push(Line::from(vec![
+            Span::raw(padding),
+            Span::styled(
+                "No collectors have run yet.",
+                Style::default().fg(theme().dim),
+            ),
+        ]));
+    }
 
-    let header = create_table_header();
-    let total_memory = sys.total_memory() as f64;
+    for health in collectors.health() {
+        let (status, color) = match &health.last_error {
+            Some(error) => (format!("FAILING: {}", error), theme().critical),
+            None if !health.available => ("UNAVAILABLE".to_string(), theme().dim),
+            None => ("OK".to_string(), theme().good),
+        };
+        let duration = format!("{:.1}ms", health.last_duration.as_secs_f64() * 1000.0);
+        let age = health
+            .data_age()
+            .map(|age| format!("{}s", age.as_secs()))
+            .unwrap_or_else(|| "-".to_string());
 
-    static UID_TO_USER: Lazy<HashMap<u32, String>> = Lazy::new(|| unsafe {
-        users::all_users()
-            .map(|u| (u.uid(), u.name().to_string_lossy().to_string()))
-            .collect()
-    });
+        lines.push(Line::from(vec![
+            Span::raw(padding),
+            Span::styled(
+                format!(
+                    "{:<16}{:>8}{:>10}{:>10}{:>8}  ",
+                    health.name, health.runs, health.failures, duration, age
+                ),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(status, Style::default().fg(color)),
+        ]));
+    }
 
-    let priority_map = fetch_priority_map();
-    let memory_map = fetch_memory_map();
-
-    let rows = processes.iter().enumerate().map(|(index, process)| {
-        create_process_row(
-            index,
-            process,
-            &UID_TO_USER,
-            &priority_map,
-            &memory_map,
-            total_memory,
-            selected_row_index,
-        )
-    });
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw(padding),
+        Span::styled(
+            "Press any key to return.",
+            Style::default()
+                .fg(theme().good)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
 
-    let table = Table::new(rows, get_table_constraints())
-        .header(header)
-        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM))
-        .column_spacing(1);
+    let block = Block::default()
+        .title("Diagnostics")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
 
-    f.render_widget(table, area);
+    f.render_widget(Clear, window_area);
+    f.render_widget(Paragraph::new(lines).block(block), window_area);
 }
 
-// Helper functions
+/// Build the confirmation prompt for a process action, listing every target
+pub fn action_confirmation(sys: &Snapshot, pending: PendingAction) -> Confirmation {
+    let describe = |pid: Pid| {
+        let command = sys
+            .process(pid)
+            .map(|p| p.cmd().join(" "))
+            .filter(|cmd| !cmd.is_empty())
+            .unwrap_or_else(|| "?".to_string());
+        format!("{:>7} {}", pid, command)
+    };
 
-fn create_progress_bar(used: usize, total: usize) -> String {
-    (0..total)
-        .map(|i| if i < used { '|' } else { ' ' })
-        .collect()
-}
+    // Group targets are group IDs, so list every member of each group
+    let details = if let ProcessAction::SignalGroup { .. } = pending.action {
+        pending
+            .targets
+            .iter()
+            .flat_map(|pgid| {
+                let mut members: Vec<Pid> = sys
+                    .processes()
+                    .values()
+                    .filter(|p| p.thread_kind().is_none() && p.process_group() == Some(*pgid))
+                    .map(|p| p.pid())
+                    .collect();
+                members.sort();
 
-fn get_cpu_color(usage: f32) -> Color {
-    match usage {
-        u if u > CPU_HIGH_THRESHOLD => Color::Red,
-        u if u > CPU_MEDIUM_THRESHOLD => Color::Yellow,
-        _ => Color::Green,
-    }
+                std::iter::once(format!("Group {}:", pgid))
+                    .chain(members.into_iter().map(describe))
+                    .collect::<Vec<String>>()
+            })
+            .collect()
+    } else {
+        pending.targets.iter().copied().map(describe).collect()
+    };
+
+    Confirmation::new(
+        "Confirm",
+        format!(
+            "{} {} {}?",
+            capitalize(&pending.action.describe()),
+            pending.targets.len(),
+            pending.action.target_noun()
+        ),
+        details,
+        pending.action.is_destructive(),
+        pending,
+    )
 }
 
-fn get_memory_color(used: u64, total: u64) -> Color {
-    if total == 0 {
-        return Color::Green;
-    }
+/// Draw a Yes/No confirmation dialog
+pub fn draw_confirm_window(f: &mut Frame, area: Rect, confirmation: &Confirmation) {
+    let window_area = centered_rect(70, 60, area);
+    let padding = "  ";
+    let accent = if confirmation.destructive {
+        theme().critical
+    } else {
+        theme().warning
+    };
 
-    let ratio = used as f64 / total as f64;
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw(padding),
+            Span::styled(
+                confirmation.message.clone(),
+                Style::default().fg(accent).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for detail in &confirmation.details {
+        lines.push(Line::from(vec![
+            Span::raw(padding),
+            Span::styled(detail.clone(), Style::default().fg(theme().accent)),
+        ]));
+    }
+
+    let button = |label: &'static str, focused: bool| {
+        if focused {
+            Span::styled(
+                label,
+                Style::default()
+                    .bg(theme().selection)
+                    .fg(theme().on_highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(label, Style::default().fg(theme().text))
+        }
+    };
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw(padding),
+        button("[ Yes ]", confirmation.yes_focused),
+        Span::raw("  "),
+        button("[ No ]", !confirmation.yes_focused),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::raw(padding),
+        Span::styled(
+            "y/n to answer, Left/Right to choose, Enter to select, Esc to cancel",
+            Style::default().fg(theme().dim),
+        ),
+    ]));
+
+    let block = Block::default()
+        .title(confirmation.title.clone())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(accent))
+        .style(Style::default().bg(theme().background));
+
+    f.render_widget(Clear, window_area);
+    f.render_widget(Paragraph::new(lines).block(block), window_area);
+}
+
+/// Draw the process inspector with details and CPU history
+pub fn draw_inspector_window(
+    f: &mut Frame,
+    area: Rect,
+    inspector: &Inspector,
+    network: Option<&NetworkUsage>,
+    state: Option<StateSpan>,
+    time_format: TimeFormat,
+) {
+    let window_area = centered_rect(90, 90, area);
+    let title = match &inspector.details {
+        Some(details) if inspector.exited => {
+            format!("Process {} - {} (exited)", inspector.pid, details.name)
+        }
+        Some(details) => format!("Process {} - {}", inspector.pid, details.name),
+        None => format!("Process {} (exited)", inspector.pid),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
+    let inner = block.inner(window_area);
+
+    f.render_widget(Clear, window_area);
+    f.render_widget(block, window_area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Tabs
+            Constraint::Min(4),    // Tab content
+            Constraint::Length(1), // Key hints
+        ])
+        .split(inner);
+
+    let tabs = Tabs::new(InspectorTab::ALL.iter().map(|tab| tab.title()))
+        .select(inspector.tab as usize)
+        .style(Style::default().fg(theme().dim))
+        .highlight_style(
+            Style::default()
+                .fg(theme().warning)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(tabs, layout[0]);
+
+    let hints = match inspector.tab {
+        InspectorTab::Overview | InspectorTab::Identity => {
+            "  Tab/Left/Right: switch tab   Esc: close"
+        }
+        _ if inspector.searching => "  Type to search   Enter: done   Esc: clear search",
+        InspectorTab::Environment => {
+            "  Tab/Left/Right: switch tab   /: search   m: mask secrets   Up/Down: scroll   Esc: close"
+        }
+        InspectorTab::Files => {
+            "  Tab/Left/Right: switch tab   /: search   r: reload   Up/Down: scroll   Esc: close"
+        }
+        InspectorTab::Threads => {
+            "  Tab/Left/Right: switch tab   /: search   Up/Down: scroll   Esc: close"
+        }
+    };
+    f.render_widget(
+        Paragraph::new(Span::styled(hints, Style::default().fg(theme().good))),
+        layout[2],
+    );
+
+    match inspector.tab {
+        InspectorTab::Overview => {
+            draw_inspector_overview(f, layout[1], inspector, network, state, time_format)
+        }
+        InspectorTab::Identity => draw_inspector_identity(f, layout[1], inspector, time_format),
+        InspectorTab::Environment => draw_inspector_environ(f, layout[1], inspector),
+        InspectorTab::Files => draw_inspector_files(f, layout[1], inspector),
+        InspectorTab::Threads => draw_inspector_threads(f, layout[1], inspector, time_format),
+    }
+}
+
+/// Draw the inspector's overview tab: details and CPU history
+fn draw_inspector_overview(
+    f: &mut Frame,
+    area: Rect,
+    inspector: &Inspector,
+    network: Option<&NetworkUsage>,
+    state: Option<StateSpan>,
+    time_format: TimeFormat,
+) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(4), Constraint::Length(7)])
+        .split(area);
+
+    let lines = match &inspector.details {
+        Some(details) => create_detail_lines(details, network, state, time_format),
+        None => vec![Line::from(Span::styled(
+            "  Process is no longer running.",
+            Style::default().fg(theme().dim),
+        ))],
+    };
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), layout[0]);
+
+    // Newest samples on the right, as many as fit
+    let width = layout[1].width.saturating_sub(2) as usize;
+    let samples: Vec<u64> = inspector
+        .cpu_history
+        .iter()
+        .skip(inspector.cpu_history.len().saturating_sub(width))
+        .map(|usage| usage.round() as u64)
+        .collect();
+    let peak = inspector.cpu_history.iter().cloned().fold(0.0, f32::max);
+
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .title(format!(
+                    "CPU history ({} samples, peak {:.1}%)",
+                    samples.len(),
+                    peak
+                ))
+                .borders(Borders::ALL),
+        )
+        .data(&samples)
+        .max(samples.iter().copied().max().unwrap_or(0).max(100))
+        .style(Style::default().fg(get_cpu_color(peak)));
+    f.render_widget(sparkline, layout[1]);
+}
+
+/// Draw the inspector's environment tab: search line and variable list
+fn draw_inspector_environ(f: &mut Frame, area: Rect, inspector: &Inspector) {
+    let entries = inspector.visible_environ();
+    let total = inspector.environ.as_ref().map_or(0, Vec::len);
+
+    let summary = format!(
+        "{} of {} variables, secrets {}",
+        entries.len(),
+        total,
+        if inspector.mask_secrets {
+            "masked"
+        } else {
+            "shown"
+        }
+    );
+    let mut lines = vec![create_search_line(inspector, summary), Line::from("")];
+
+    if total == 0 {
+        lines.push(Line::from(Span::styled(
+            "  Environment is not readable (the process may belong to another user).",
+            Style::default().fg(theme().dim),
+        )));
+    }
+
+    let rows = (area.height as usize).saturating_sub(lines.len());
+    for (key, value) in entries.into_iter().skip(inspector.scroll).take(rows) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}", key), Style::default().fg(theme().accent)),
+            Span::styled("=", Style::default().fg(theme().dim)),
+            Span::styled(value, Style::default().fg(theme().text)),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Draw the inspector's open files tab: search line and descriptor list
+fn draw_inspector_files(f: &mut Frame, area: Rect, inspector: &Inspector) {
+    let files = inspector.visible_files();
+    let total = inspector.open_files.as_ref().map_or(0, Vec::len);
+
+    let summary = format!("{} of {} open files", files.len(), total);
+    let mut lines = vec![
+        create_search_line(inspector, summary),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {:>6}  {:<8} {}", "FD", "TYPE", "NAME"),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    if total == 0 {
+        lines.push(Line::from(Span::styled(
+            "  No open files readable (the process may belong to another user, or lsof is missing).",
+            Style::default().fg(theme().dim),
+        )));
+    }
+
+    let rows = (area.height as usize).saturating_sub(lines.len());
+    for file in files.into_iter().skip(inspector.scroll).take(rows) {
+        // Sockets stand out, since they are often what's being looked for
+        let kind_color = match file.kind.as_str() {
+            "IPv4" | "IPv6" | "unix" | "socket" => theme().special,
+            "DIR" => theme().info,
+            _ => theme().accent,
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:>6}  ", file.fd),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(
+                format!("{:<8} ", file.kind),
+                Style::default().fg(kind_color),
+            ),
+            Span::styled(file.name.clone(), Style::default().fg(theme().text)),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Draw the inspector's threads tab: search line and per-thread usage
+fn draw_inspector_threads(
+    f: &mut Frame,
+    area: Rect,
+    inspector: &Inspector,
+    time_format: TimeFormat,
+) {
+    let threads = inspector.visible_threads();
+    let total = inspector.threads.as_ref().map_or(0, Vec::len);
+
+    let summary = format!("{} of {} threads", threads.len(), total);
+    let mut lines = vec![
+        create_search_line(inspector, summary),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {:>10}  {}  {:>6}  {:>9}  {}",
+                "TID", "S", "CPU%", "TIME+", "NAME"
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    if total == 0 {
+        lines.push(Line::from(Span::styled(
+            "  Threads are not readable (the process may belong to another user).",
+            Style::default().fg(theme().dim),
+        )));
+    }
+
+    let rows = (area.height as usize).saturating_sub(lines.len());
+    for thread in threads.into_iter().skip(inspector.scroll).take(rows) {
+        let cpu_time = thread
+            .cpu_time
+            .map(|secs| format_runtime(secs, time_format))
+            .unwrap_or_else(|| "-".to_string());
+        let state = thread.state.to_string();
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:>10}  ", thread.tid),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(format!("{}  ", state), get_status_color(&state)),
+            Span::styled(
+                format!("{:>6.1}  ", thread.cpu_usage),
+                get_usage_color(thread.cpu_usage),
+            ),
+            Span::styled(
+                format!("{:>9}  ", cpu_time),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(thread.name.clone(), Style::default().fg(theme().accent)),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Build the search line shown above an inspector list
+fn create_search_line(inspector: &Inspector, summary: String) -> Line<'static> {
+    let cursor = if inspector.searching { "_" } else { "" };
+
+    Line::from(vec![
+        Span::styled("  Search: ", Style::default().fg(theme().accent)),
+        Span::styled(
+            format!("{}{}", inspector.search, cursor),
+            Style::default().fg(theme().text),
+        ),
+        Span::styled(format!("   {}", summary), Style::default().fg(theme().dim)),
+    ])
+}
+
+/// Build the labelled detail lines of the inspector
+fn create_detail_lines(
+    details: &ProcessDetails,
+    network: Option<&NetworkUsage>,
+    state: Option<StateSpan>,
+    time_format: TimeFormat,
+) -> Vec<Line<'static>> {
+    let now = chrono::Local::now().timestamp() as u64;
+    let path_or_unknown = |path: &Option<std::path::PathBuf>| {
+        path.as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "?".to_string())
+    };
+
+    let mut fields = vec![
+        ("Command", details.cmd.join(" ")),
+        ("Exe", path_or_unknown(&details.exe)),
+        ("Cwd", path_or_unknown(&details.cwd)),
+        (
+            "Parent",
+            details
+                .parent
+                .as_ref()
+                .map(|(pid, name)| format!("{} ({})", pid, name))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "Started",
+            format!(
+                "{}, running {}",
+                format_timestamp(details.start_time, now, time_format),
+                format_runtime(details.run_time, time_format)
+            ),
+        ),
+        ("User", details.user.clone()),
+        ("Group", details.group.clone()),
+        (
+            "Memory",
+            format!(
+                "{} resident, {} virtual",
+                format_bytes(details.memory.resident),
+                format_bytes(details.memory.virtual_size)
+            ),
+        ),
+    ];
+
+    if let Some(split) = details.memory.split {
+        fields.push((
+            "Resident",
+            format!(
+                "{} anonymous (heap, stacks), {} file-backed (reclaimable)",
+                format_bytes(split.anonymous),
+                format_bytes(split.file_backed)
+            ),
+        ));
+    }
+
+    if let Some(task) = details.memory.task {
+        fields.push((
+            "Faults",
+            format!(
+                "{} faults, {} page-ins, {} copy-on-write, {} context switches",
+                task.faults, task.pageins, task.cow_faults, task.context_switches
+            ),
+        ));
+    }
+
+    if let Some(network) = network {
+        let mut value = format!(
+            "{} received, {} sent since sysly started",
+            format_bytes(network.received),
+            format_bytes(network.sent)
+        );
+        if let Some((received, sent)) = network.rates {
+            value.push_str(&format!(
+                " (now {}/s in, {}/s out)",
+                format_bytes(received as u64),
+                format_bytes(sent as u64)
+            ));
+        }
+        fields.push(("Network", value));
+    }
+
+    let mut lines: Vec<Line<'static>> = fields
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {:<9}", label),
+                    Style::default().fg(theme().accent),
+                ),
+                Span::styled(value, Style::default().fg(theme().text)),
+            ])
+        })
+        .collect();
+
+    // Below Started; processes stuck in D or Z for long are flagged
+    if let Some(state) = state {
+        let mut value = format!(
+            "{} ({}) for {}{}",
+            status_letter(state.status),
+            state.description(),
+            if state.lower_bound { "at least " } else { "" },
+            format_runtime(state.duration.as_secs(), time_format)
+        );
+        let style = if state.is_pathological() {
+            value.push_str(", stuck?");
+            Style::default().fg(theme().critical).bold()
+        } else {
+            Style::default().fg(theme().text)
+        };
+        lines.insert(
+            5,
+            Line::from(vec![
+                Span::styled(
+                    format!("  {:<9}", "State"),
+                    Style::default().fg(theme().accent),
+                ),
+                Span::styled(value, style),
+            ]),
+        );
+    }
+
+    // Unsigned and ad-hoc signed binaries are flagged, right below the Exe
+    if let Some(signature) = &details.signature {
+        lines.insert(2, create_signer_line(signature));
+    }
+
+    lines
+}
+
+/// Describe a code signature, colored by how much it vouches for the binary
+fn create_signer_line(signature: &CodeSignature) -> Line<'static> {
+    let (value, color) = match signature {
+        CodeSignature::Signed {
+            authority,
+            team,
+            identifier,
+        } => {
+            let mut value = authority.clone();
+            if let Some(team) = team.as_ref().filter(|team| !authority.contains(*team)) {
+                value.push_str(&format!(", team {}", team));
+            }
+            if let Some(identifier) = identifier {
+                value.push_str(&format!(" ({})", identifier));
+            }
+            (value, theme().good)
+        }
+        CodeSignature::AdHoc { identifier } => (
+            format!(
+                "ad-hoc signed, no identity{}",
+                identifier
+                    .as_ref()
+                    .map(|id| format!(" ({})", id))
+                    .unwrap_or_default()
+            ),
+            theme().warning,
+        ),
+        CodeSignature::Unsigned => ("unsigned".to_string(), theme().critical),
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("  {:<9}", "Signer"),
+            Style::default().fg(theme().accent),
+        ),
+        Span::styled(value, Style::default().fg(color).bold()),
+    ])
+}
+
+/// Draw the inspector's identity tab: what the binary is, who signed it,
+/// and how the process came to run
+fn draw_inspector_identity(
+    f: &mut Frame,
+    area: Rect,
+    inspector: &Inspector,
+    time_format: TimeFormat,
+) {
+    let Some(identity) = &inspector.identity else {
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                "  Process is no longer running.",
+                Style::default().fg(theme().dim),
+            )),
+            area,
+        );
+        return;
+    };
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("  {:<9}", label),
+                Style::default().fg(theme().accent),
+            ),
+            Span::styled(value, Style::default().fg(theme().text)),
+        ])
+    };
+    let unknown = || "?".to_string();
+
+    let mut lines = vec![
+        field(
+            "Exe",
+            identity
+                .exe
+                .as_ref()
+                .map_or_else(unknown, |exe| exe.display().to_string()),
+        ),
+        field("SHA-256", identity.sha256.clone().unwrap_or_else(unknown)),
+    ];
+    match inspector
+        .details
+        .as_ref()
+        .and_then(|details| details.signature.as_ref())
+    {
+        Some(signature) => {
+            lines.push(create_signer_line(signature));
+            let team = match signature {
+                CodeSignature::Signed {
+                    team: Some(team), ..
+                } => team.clone(),
+                _ => "-".to_string(),
+            };
+            lines.push(field("Team", team));
+        }
+        None => lines.push(field("Signer", "-".to_string())),
+    }
+    lines.push(field(
+        "Launched",
+        identity.since_boot.map_or_else(unknown, |seconds| {
+            format!("{} after boot", format_uptime(seconds, time_format))
+        }),
+    ));
+    lines.push(field(
+        "Service",
+        identity
+            .service
+            .clone()
+            .unwrap_or_else(|| "none found".to_string()),
+    ));
+
+    // Closest parent first, down to PID 1
+    if identity.ancestors.is_empty() {
+        lines.push(field("Parents", "-".to_string()));
+    }
+    for (index, (pid, name)) in identity.ancestors.iter().enumerate() {
+        let label = if index == 0 { "Parents" } else { "" };
+        lines.push(field(label, format!("{:>7}  {}", pid.as_u32(), name)));
+    }
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+}
+
+/// Reduce a rendered frame to a monochrome scheme
+///
+/// Colors are dropped; cells drawn on a highlight background (selection,
+/// headers, buttons, warnings) are shown reversed instead so they stay
+/// visible. Tagged rows are only bold, to tell them from the selection,
+/// and text colored as a warning or worse is made bold.
+pub fn strip_colors(buffer: &mut Buffer) {
+    let alerts = [theme().warning, theme().critical, theme().severe];
+    for cell in buffer.content.iter_mut() {
+        if cell.bg == theme().tagged && cell.bg != theme().selection {
+            cell.modifier.insert(Modifier::BOLD);
+        } else if cell.bg != Color::Reset && cell.bg != theme().background {
+            cell.modifier.insert(Modifier::REVERSED);
+        }
+        if alerts.contains(&cell.fg) && !cell.modifier.contains(Modifier::REVERSED) {
+            cell.modifier.insert(Modifier::BOLD);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+/// Let the terminal's own background show through a rendered frame
+///
+/// Panes and overlays fill with the theme's background; those cells are reset to the
+/// terminal default instead, which keeps transparent and themed terminals
+/// intact. Highlight backgrounds are left as they are.
+pub fn inherit_background(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if cell.bg == theme().background {
+            cell.bg = Color::Reset;
+        }
+    }
+}
+
+/// The border around the whole screen, headed by the host, OS and chip on
+/// the left and a clock on the right, so screenshots say where they're from
+///
+/// Data from elsewhere adds where it comes from after the host.
+pub fn outer_block(app_state: &AppState) -> Block<'static> {
+    let clock = chrono::Local::now().format("%H:%M:%S");
+    let summary = app_state.host.summary();
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme().background));
+    // Data from elsewhere has no host until its first snapshot arrives
+    if !summary.is_empty() {
+        block = block.title(Span::styled(
+            format!(" {} ", summary),
+            Style::default().fg(theme().accent).bold(),
+        ));
+    }
+    if let Some(source) = &app_state.source {
+        block = block.title(Span::styled(
+            format!(" {} ", source),
+            Style::default().fg(theme().special),
+        ));
+    }
+    block.title(
+        Title::from(Span::styled(
+            format!(" {} ", clock),
+            Style::default().fg(theme().text),
+        ))
+        .alignment(Alignment::Right),
+    )
+}
+
+/// Draw the main dashboard layout
+pub fn draw_dashboard(f: &mut Frame, sys: &Snapshot, area: Rect, app_state: &mut AppState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(info_bar_height(sys.cpus().len(), app_state)), // Info bar
+            Constraint::Length(filesystem_panel_height(app_state)),           // Filesystems
+            Constraint::Length(1),                                            // View tabs
+            Constraint::Min(10),                                              // Process table
+        ])
+        .split(area);
+
+    draw_info_bar(sys, f, layout[0], app_state);
+    if app_state.show_filesystems {
+        draw_filesystem_panel(f, layout[1], &app_state.volumes);
+    }
+    draw_view_tabs(f, layout[2], app_state.view, app_state.source.is_some());
+    match app_state.view {
+        View::Processes | View::Io | View::Network => {
+            draw_process_table(sys, f, layout[3], app_state)
+        }
+        View::Users => draw_users_view(sys, f, layout[3], app_state),
+        View::Energy => draw_energy_view(f, layout[3], app_state),
+        View::Sensors => draw_sensors_view(f, layout[3], app_state),
+        View::Extensions => draw_extensions_view(
+            f,
+            layout[3],
+            &app_state.extensions,
+            app_state.selected_row_index,
+        ),
+        View::Startup => draw_startup_view(
+            sys,
+            f,
+            layout[3],
+            &app_state.startup_items,
+            app_state.selected_row_index,
+        ),
+        View::Services => draw_services_view(
+            sys,
+            f,
+            layout[3],
+            app_state.brew_services.as_deref(),
+            app_state.status_message.as_deref(),
+            app_state.selected_row_index,
+        ),
+        View::Cgroups => draw_cgroups_view(
+            f,
+            layout[3],
+            &app_state.cgroups,
+            app_state.selected_row_index,
+        ),
+        View::Containers => draw_containers_view(
+            f,
+            layout[3],
+            &app_state.containers,
+            app_state.docker_error.as_deref(),
+            app_state.selected_row_index,
+        ),
+        View::Events => draw_events_view(
+            f,
+            layout[3],
+            app_state.lifecycle.events(),
+            app_state.selected_row_index,
+        ),
+    }
+}
+
+/// Draw Docker containers with their usage, as `docker stats` lists them
+fn draw_containers_view(
+    f: &mut Frame,
+    area: Rect,
+    containers: &[ContainerStats],
+    error: Option<&str>,
+    selected: usize,
+) {
+    let block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+
+    if containers.is_empty() {
+        let message = match error {
+            Some(error) => format!("  Cannot read containers: {}.", error),
+            None => "  No containers are running.".to_string(),
+        };
+        let message = Paragraph::new(message)
+            .style(Style::default().fg(theme().dim))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(
+        [
+            "Name", "ID", "Image", "CPU%", "MEM", "MEM max", "NET RX", "NET TX", "PIDs",
+        ]
+        .map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let rows = containers.iter().map(|container| {
+        let cpu = container.cpu.unwrap_or(0.0) as f32;
+        Row::new(vec![
+            Cell::from(container.name.clone()).style(Style::default().fg(theme().accent)),
+            Cell::from(container.id.clone()).style(Style::default().fg(theme().dim)),
+            Cell::from(container.image.clone()).style(Style::default().fg(theme().text)),
+            Cell::from(
+                container
+                    .cpu
+                    .map_or_else(|| "-".to_string(), |cpu| format!("{:.1}", cpu)),
+            )
+            .style(get_usage_color(cpu)),
+            Cell::from(format_bytes(container.memory)).style(
+                Style::default().fg(get_memory_color(container.memory, container.memory_limit)),
+            ),
+            Cell::from(format_bytes(container.memory_limit))
+                .style(Style::default().fg(theme().dim)),
+            Cell::from(format_bytes(container.received)).style(Style::default().fg(theme().good)),
+            Cell::from(format_bytes(container.sent)).style(Style::default().fg(theme().good)),
+            Cell::from(
+                container
+                    .pids
+                    .map_or_else(|| "-".to_string(), |pids| pids.to_string()),
+            )
+            .style(Style::default().fg(theme().text)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),    // Name
+            Constraint::Length(12), // ID
+            Constraint::Min(20),    // Image
+            Constraint::Length(6),  // CPU%
+            Constraint::Length(9),  // MEM
+            Constraint::Length(9),  // MEM max
+            Constraint::Length(9),  // NET RX
+            Constraint::Length(9),  // NET TX
+            Constraint::Length(5),  // PIDs
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw process start and exit events, newest first
+fn draw_events_view(f: &mut Frame, area: Rect, events: &VecDeque<LifecycleEvent>, selected: usize) {
+    let block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+
+    if events.is_empty() {
+        let message = Paragraph::new("  No processes have started or exited yet.")
+            .style(Style::default().fg(theme().dim))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(
+        ["Time", "Event", "PID", "User", "Reason", "Command"].map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let rows = events.iter().rev().map(|event| {
+        let kind_style = match event.kind {
+            EventKind::Started => Style::default().fg(theme().good),
+            EventKind::Exited => Style::default().fg(theme().warning),
+        };
+        Row::new([
+            Cell::from(event.time.format("%H:%M:%S").to_string())
+                .style(Style::default().fg(theme().dim)),
+            Cell::from(event.kind.label()).style(kind_style),
+            Cell::from(event.pid.to_string()).style(Style::default().fg(theme().text)),
+            Cell::from(event.user.clone().unwrap_or_else(|| "-".to_string()))
+                .style(Style::default().fg(theme().accent)),
+            Cell::from(event.reason.clone().unwrap_or_else(|| "-".to_string()))
+                .style(Style::default().fg(theme().critical)),
+            Cell::from(event.command.clone()).style(Style::default().fg(theme().text)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),  // Time
+            Constraint::Length(5),  // Event
+            Constraint::Length(7),  // PID
+            Constraint::Length(10), // User
+            Constraint::Length(18), // Reason
+            Constraint::Min(20),    // Command
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw cgroup slices and their services with usage against their limits
+fn draw_cgroups_view(f: &mut Frame, area: Rect, groups: &[CgroupUsage], selected: usize) {
+    let block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+
+    if groups.is_empty() {
+        let message = Paragraph::new("  No cgroup v2 hierarchy found.")
+            .style(Style::default().fg(theme().dim))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(
+        ["Group", "CPU", "CPU max", "MEM", "MEM max", "Tasks"]
+            .map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let unlimited = || "max".to_string();
+    let rows = groups.iter().map(|group| {
+        let indent = "  ".repeat(group.depth().saturating_sub(1));
+        let cpu_style = match (group.cpu_cores, group.cpu_limit) {
+            (Some(cores), Some(limit)) if limit > 0.0 => {
+                Style::default().fg(get_cpu_color((cores / limit * 100.0) as f32))
+            }
+            _ => Style::default().fg(theme().text),
+        };
+        let memory_color = group
+            .memory_limit
+            .map_or(theme().text, |limit| get_memory_color(group.memory, limit));
+
+        Row::new(vec![
+            Cell::from(format!("{}{}", indent, group.name())).style(Style::default().fg(
+                if group.depth() == 1 {
+                    theme().warning
+                } else {
+                    theme().text
+                },
+            )),
+            Cell::from(
+                group
+                    .cpu_cores
+                    .map_or_else(|| "-".to_string(), |cores| format!("{:.2}", cores)),
+            )
+            .style(cpu_style),
+            Cell::from(
+                group
+                    .cpu_limit
+                    .map_or_else(unlimited, |limit| format!("{:.2}", limit)),
+            )
+            .style(Style::default().fg(theme().dim)),
+            Cell::from(format_bytes(group.memory)).style(Style::default().fg(memory_color)),
+            Cell::from(group.memory_limit.map_or_else(unlimited, format_bytes))
+                .style(Style::default().fg(theme().dim)),
+            Cell::from(
+                group
+                    .tasks
+                    .map_or_else(|| "-".to_string(), |tasks| tasks.to_string()),
+            )
+            .style(Style::default().fg(theme().text)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(30),   // Group
+            Constraint::Length(7), // CPU
+            Constraint::Length(8), // CPU max
+            Constraint::Length(9), // MEM
+            Constraint::Length(9), // MEM max
+            Constraint::Length(6), // Tasks
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw startup items next to the process each one is running as
+fn draw_startup_view(
+    sys: &Snapshot,
+    f: &mut Frame,
+    area: Rect,
+    items: &[StartupItem],
+    selected: usize,
+) {
+    let block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+
+    if items.is_empty() {
+        let message = Paragraph::new("  No startup items found.")
+            .style(Style::default().fg(theme().dim))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(
+        ["Kind", "Label", "Program", "PID", "CPU%", "RES"].map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let rows = items.iter().map(|item| {
+        let program = item
+            .program
+            .as_ref()
+            .map(|program| program.to_string_lossy().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let mut cells = vec![
+            Cell::from(item.kind.label()).style(Style::default().fg(theme().text)),
+            Cell::from(item.label.clone()).style(Style::default().fg(theme().accent)),
+            Cell::from(program).style(Style::default().fg(theme().dim)),
+        ];
+
+        match item.running_process(sys).and_then(|pid| sys.process(pid)) {
+            Some(process) => cells.extend([
+                Cell::from(process.pid().to_string()).style(Style::default().fg(theme().text)),
+                Cell::from(format!("{:.1}", process.cpu_usage()))
+                    .style(get_usage_color(process.cpu_usage())),
+                Cell::from(format_bytes(process.memory())).style(Style::default().fg(theme().good)),
+            ]),
+            // Not running
+            None => cells.extend(
+                ["-", "-", "-"]
+                    .map(|text| Cell::from(text).style(Style::default().fg(theme().faint))),
+            ),
+        }
+        Row::new(cells)
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(7), // Kind
+            Constraint::Min(24),   // Label
+            Constraint::Min(24),   // Program
+            Constraint::Length(7), // PID
+            Constraint::Length(6), // CPU%
+            Constraint::Length(8), // RES
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw the usage of each user's processes, summed
+fn draw_users_view(sys: &Snapshot, f: &mut Frame, area: Rect, app_state: &AppState) {
+    let order = app_state.user_order;
+    let block = Block::default()
+        .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+        .title_bottom(Span::styled(
+            format!(" Sorted by {}: s changes ", order.label()),
+            Style::default().fg(theme().dim),
+        ));
+
+    let titles = ["User", "Procs", "CPU%", "RES"];
+    let header = Row::new(titles.map(|title| {
+        let cell = Cell::from(title).bold();
+        if title == order.label() {
+            cell.underlined()
+        } else {
+            cell
+        }
+    }))
+    .style(Style::default().fg(theme().accent));
+
+    let totals = user_totals(
+        sys,
+        &app_state.users,
+        |process| app_state.cpu_smoother.usage(process),
+        order,
+    );
+    let rows = totals.iter().map(|user| {
+        Row::new(vec![
+            Cell::from(user.name.clone()).style(Style::default().fg(theme().text)),
+            Cell::from(user.processes.to_string()).style(Style::default().fg(theme().text)),
+            Cell::from(format!("{:.1}", user.cpu)).style(get_usage_color(user.cpu)),
+            Cell::from(format_bytes(user.memory)).style(Style::default().fg(theme().good)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),    // User
+            Constraint::Length(6),  // Procs
+            Constraint::Length(7),  // CPU%
+            Constraint::Length(10), // RES
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(app_state.selected_row_index));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw Homebrew services next to the process each one is running as
+fn draw_services_view(
+    sys: &Snapshot,
+    f: &mut Frame,
+    area: Rect,
+    services: Option<&[BrewService]>,
+    status_message: Option<&str>,
+    selected: usize,
+) {
+    let mut block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+    // Restarts report back here
+    if let Some(message) = status_message {
+        block = block.title_bottom(Span::styled(
+            format!(" {} ", message),
+            Style::default().fg(theme().warning),
+        ));
+    }
+
+    let services = match services {
+        Some(services) if !services.is_empty() => services,
+        services => {
+            let text = if services.is_none() {
+                "  Homebrew not found: `brew services list` could not run."
+            } else {
+                "  No Homebrew services installed."
+            };
+            let message = Paragraph::new(text)
+                .style(Style::default().fg(theme().dim))
+                .block(block);
+            f.render_widget(message, area);
+            return;
+        }
+    };
+
+    let header = Row::new(
+        ["Name", "Status", "User", "PID", "CPU%", "RES", "File"]
+            .map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let rows = services.iter().map(|service| {
+        let status = match service.exit_code {
+            Some(code) => format!("{} {}", service.status, code),
+            None => service.status.clone(),
+        };
+        let status_color = match service.status.as_str() {
+            "started" => theme().good,
+            "error" => theme().critical,
+            "scheduled" => theme().warning,
+            _ => theme().dim,
+        };
+        let mut cells = vec![
+            Cell::from(service.name.clone()).style(Style::default().fg(theme().accent)),
+            Cell::from(status).style(Style::default().fg(status_color)),
+            Cell::from(service.user.clone().unwrap_or_else(|| "-".to_string()))
+                .style(Style::default().fg(theme().text)),
+        ];
+
+        match service
+            .running_process(sys)
+            .and_then(|pid| sys.process(pid))
+        {
+            Some(process) => cells.extend([
+                Cell::from(process.pid().to_string()).style(Style::default().fg(theme().text)),
+                Cell::from(format!("{:.1}", process.cpu_usage()))
+                    .style(get_usage_color(process.cpu_usage())),
+                Cell::from(format_bytes(process.memory())).style(Style::default().fg(theme().good)),
+            ]),
+            // Not running
+            None => cells.extend(
+                ["-", "-", "-"]
+                    .map(|text| Cell::from(text).style(Style::default().fg(theme().faint))),
+            ),
+        }
+        let file = service
+            .file
+            .as_ref()
+            .map(|file| file.to_string_lossy().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        cells.push(Cell::from(file).style(Style::default().fg(theme().dim)));
+        Row::new(cells)
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),    // Name
+            Constraint::Length(10), // Status
+            Constraint::Length(10), // User
+            Constraint::Length(7),  // PID
+            Constraint::Length(6),  // CPU%
+            Constraint::Length(8),  // RES
+            Constraint::Min(24),    // File
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw loaded kernel and system extensions, third-party ones highlighted
+fn draw_extensions_view(f: &mut Frame, area: Rect, extensions: &[Extension], selected: usize) {
+    let block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+
+    if extensions.is_empty() {
+        let message = Paragraph::new("  No extensions found.")
+            .style(Style::default().fg(theme().dim))
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    let header =
+        Row::new(["Kind", "Bundle ID", "Version", "Status"].map(|title| Cell::from(title).bold()))
+            .style(Style::default().fg(theme().accent));
+
+    let rows = extensions.iter().map(|extension| {
+        let color = if extension.builtin {
+            theme().dim
+        } else {
+            theme().warning
+        };
+        Row::new(vec![
+            Cell::from(extension.kind.label()).style(Style::default().fg(theme().text)),
+            Cell::from(extension.bundle_id.clone()).style(Style::default().fg(color)),
+            Cell::from(extension.version.clone().unwrap_or_else(|| "-".to_string()))
+                .style(Style::default().fg(theme().text)),
+            Cell::from(extension.status.clone()).style(Style::default().fg(theme().good)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(7),  // Kind
+            Constraint::Min(30),    // Bundle ID
+            Constraint::Length(16), // Version
+            Constraint::Length(24), // Status
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(selected));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Draw energy impact and wake-ups per app, now and over the session
+fn draw_energy_view(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let ledger = &app_state.energy_ledger;
+    let mut block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+    if !app_state.energy {
+        block = block.title_bottom(Span::styled(
+            " Energy impact needs powermetrics: press E (runs as root) ",
+            Style::default().fg(theme().warning),
+        ));
+    }
+
+    let header = Row::new(
+        [
+            "App",
+            "Procs",
+            "CPU%",
+            "Energy",
+            "Avg energy",
+            "Wakeups/s",
+            "Wakeups",
+        ]
+        .map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let impact = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.1}", v));
+    let rows = ledger.apps().into_iter().map(|app| {
+        // Apps that have quit stay listed for their session totals
+        let name_color = if app.processes == 0 {
+            theme().faint
+        } else {
+            theme().text
+        };
+        Row::new(vec![
+            Cell::from(app.name.clone()).style(Style::default().fg(name_color)),
+            Cell::from(app.processes.to_string()).style(Style::default().fg(theme().text)),
+            Cell::from(format!("{:.1}", app.cpu)).style(get_usage_color(app.cpu)),
+            Cell::from(impact(app.impact)).style(get_energy_color(app.impact.unwrap_or(0.0))),
+            Cell::from(impact(ledger.average_impact(app)))
+                .style(get_energy_color(ledger.average_impact(app).unwrap_or(0.0))),
+            Cell::from(format!("{:.0}", app.wakeups)).style(get_wakeup_color(app.wakeups)),
+            Cell::from(format_count(app.total_wakeups as u64))
+                .style(Style::default().fg(theme().text)),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(24),    // App
+            Constraint::Length(6),  // Procs
+            Constraint::Length(7),  // CPU%
+            Constraint::Length(8),  // Energy
+            Constraint::Length(11), // Avg energy
+            Constraint::Length(10), // Wakeups/s
+            Constraint::Length(10), // Wakeups
+        ],
+    )
+    .header(header)
+    .block(block)
+    .highlight_style(Style::default().bg(theme().faint))
+    .column_spacing(1);
+
+    let mut state = TableState::default().with_selected(Some(app_state.selected_row_index));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// Most throttle episodes listed in the sensors view
+const MAX_THROTTLE_ROWS: usize = 8;
+
+/// Draw sensor readings with their history, and the thermal-throttle log
+fn draw_sensors_view(f: &mut Frame, area: Rect, app_state: &AppState) {
+    let sensors = &app_state.sensors;
+    let time_format = app_state.config.time_format;
+
+    let block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let event_rows = sensors.events.len().clamp(1, MAX_THROTTLE_ROWS);
+    let fan_rows = app_state.fans.len().max(1);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),                        // Sensors
+            Constraint::Length(fan_rows as u16 + 2),   // Fans
+            Constraint::Length(event_rows as u16 + 2), // Throttle log
+        ])
+        .split(inner);
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let history_width = (layout[0].width as usize).saturating_sub(60).max(10);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "  {:<28} {:>8} {:>8} {:>8}  History",
+            "Sensor", "Now", "Peak", "Critical"
+        ),
+        bold,
+    ))];
+    if sensors.history.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No temperature sensors found.",
+            Style::default().fg(theme().dim),
+        )));
+    }
+    for sensor in &sensors.history {
+        let current = sensor.current().unwrap_or(0.0);
+        let scale = sensor.critical.filter(|c| *c > 0.0).unwrap_or(100.0);
+        let color = get_cpu_color(current / scale * 100.0);
+        let critical = sensor
+            .critical
+            .map(|c| format!("{:.1}°C", c))
+            .unwrap_or_else(|| "-".to_string());
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<28} ", sensor.label),
+                Style::default().fg(theme().accent),
+            ),
+            Span::styled(format!("{:>6.1}°C ", current), Style::default().fg(color)),
+            Span::styled(
+                format!("{:>6.1}°C {:>8}  ", sensor.peak, critical),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(
+                create_history_bar(&sensor.readings, scale, history_width),
+                Style::default().fg(color),
+            ),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), layout[0]);
+
+    let mut lines = vec![
+        Line::from(Span::styled("  Fans", bold)),
+        Line::from(Span::styled(
+            format!(
+                "  {:<28} {:>8} {:>8} {:>8}  Speed",
+                "Fan", "RPM", "Min", "Max"
+            ),
+            bold,
+        )),
+    ];
+    if app_state.fans.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No fans found.",
+            Style::default().fg(theme().dim),
+        )));
+    }
+    for fan in &app_state.fans {
+        let rpm = |value: Option<f32>| {
+            value
+                .map(|rpm| format!("{:.0}", rpm))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let percent = fan.percent();
+        // A stopped fan is normal at idle on many Macs, so it is only
+        // dimmed; a fan pinned at its maximum is the one worth noticing
+        let color = match percent {
+            _ if fan.rpm <= 0.0 => theme().dim,
+            Some(percent) => get_cpu_color(percent),
+            None => theme().text,
+        };
+        let bar = percent
+            .map(|percent| {
+                let filled = (percent / 100.0 * history_width as f32).round() as usize;
+                format!(
+                    "{}{}",
+                    "█".repeat(filled),
+                    "░".repeat(history_width - filled)
+                )
+            })
+            .unwrap_or_default();
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<28} ", fan.label),
+                Style::default().fg(theme().accent),
+            ),
+            Span::styled(
+                format!("{:>8} ", rpm(Some(fan.rpm))),
+                Style::default().fg(color),
+            ),
+            Span::styled(
+                format!("{:>8} {:>8}  ", rpm(fan.min), rpm(fan.max)),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(bar, Style::default().fg(color)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), layout[1]);
+
+    let now = chrono::Local::now().timestamp().max(0) as u64;
+    let mut lines = vec![
+        Line::from(Span::styled("  Throttle events", bold)),
+        Line::from(Span::styled(
+            format!("  {:<24} {:>12} {:>10}", "Started", "Duration", "Peak"),
+            bold,
+        )),
+    ];
+    if sensors.events.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No throttling recorded since sysly started.",
+            Style::default().fg(theme().dim),
+        )));
+    }
+    for event in sensors.events.iter().take(MAX_THROTTLE_ROWS) {
+        let mut duration = format_runtime(event.elapsed(), time_format);
+        if event.duration.is_none() {
+            duration.push_str(" (now)");
+        }
+        let peak = event
+            .peak_temperature
+            .map(|t| format!("{:.1}°C", t))
+            .unwrap_or_else(|| "-".to_string());
+
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {:<24} {:>12} {:>10}",
+                format_timestamp(event.started, now, time_format),
+                duration,
+                peak
+            ),
+            Style::default().fg(if event.duration.is_none() {
+                theme().critical
+            } else {
+                theme().warning
+            }),
+        )));
+    }
+    f.render_widget(Paragraph::new(lines), layout[2]);
+}
+
+/// Render readings as a row of block characters, newest on the right
+fn create_history_bar(readings: &VecDeque<f32>, scale: f32, width: usize) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let skip = readings.len().saturating_sub(width);
+    let bar: String = readings
+        .iter()
+        .skip(skip)
+        .map(|reading| {
+            let level = (reading / scale * (LEVELS.len() - 1) as f32).round();
+            LEVELS[(level.max(0.0) as usize).min(LEVELS.len() - 1)]
+        })
+        .collect();
+
+    format!("{:>width$}", bar, width = width)
+}
+
+/// Braille dots for a reading in the left and right column of a cell, by
+/// height from one dot to four, filled from the bottom
+const BRAILLE_LEFT: [u32; 4] = [0x40, 0x44, 0x46, 0x47];
+const BRAILLE_RIGHT: [u32; 4] = [0x80, 0xa0, 0xb0, 0xb8];
+
+/// Draw recent readings as braille dots, two readings per cell and four
+/// heights each, newest on the right
+fn create_braille_graph(readings: &VecDeque<f32>, scale: f32, width: usize) -> String {
+    // Any use shows at least one dot, so a quiet graph still reads as live
+    let height = |reading: &f32| {
+        let level = (reading / scale * BRAILLE_LEFT.len() as f32).round() as usize;
+        let floor = usize::from(*reading > 0.0);
+        level.clamp(floor, BRAILLE_LEFT.len())
+    };
+    let dots = |table: &[u32; 4], level: usize| level.checked_sub(1).map_or(0, |i| table[i]);
+
+    let shown: Vec<usize> = readings
+        .iter()
+        .skip(readings.len().saturating_sub(width * 2))
+        .map(height)
+        .collect();
+    // An odd count leaves the oldest reading alone in a right column
+    let (first, pairs) = shown.split_at(shown.len() % 2);
+    let cells = first
+        .iter()
+        .map(|&right| dots(&BRAILLE_RIGHT, right))
+        .chain(
+            pairs
+                .chunks(2)
+                .map(|pair| dots(&BRAILLE_LEFT, pair[0]) | dots(&BRAILLE_RIGHT, pair[1])),
+        )
+        .map(|bits| char::from_u32(0x2800 + bits).unwrap_or(' '));
+    let graph: String = cells.collect();
+
+    format!("{:>width$}", graph, width = width)
+}
+
+/// The meter mode to draw with: bars while the terminal is slow
+fn meter_mode(app_state: &AppState) -> MeterMode {
+    if app_state.reduced_rendering {
+        MeterMode::Bars
+    } else {
+        app_state.meter_mode
+    }
+}
+
+/// Rows taken by the info bar: CPU bars above the meters and system info
+///
+/// The meters column grows with the memory legend, the swap activity line,
+/// the cgroup meters, and the optional GPU and battery meters.
+pub fn info_bar_height(cpu_count: usize, app_state: &AppState) -> u16 {
+    let cpu_rows = cpu_grid_rows(cpu_count, &app_state.core_clusters);
+    let meter_rows: usize = app_state
+        .meters
+        .iter()
+        .map(|&meter| meter_height(meter, app_state))
+        .sum();
+    // The system info beside the meters grows by the thermal pressure line
+    let info_rows = 3 + usize::from(app_state.thermal_pressure.is_some());
+
+    (cpu_rows + meter_rows.max(info_rows)).max(7) as u16
+}
+
+/// Lines a meter takes; meters without data take none
+fn meter_height(meter: Meter, app_state: &AppState) -> usize {
+    let breakdown = usize::from(app_state.memory_stats.is_some());
+    match meter {
+        // The breakdown adds a legend under Mem and swap activity under Swp
+        Meter::Memory | Meter::Swap => 1 + breakdown,
+        Meter::Cgroup => 2 * usize::from(app_state.cgroup.is_some()),
+        Meter::Gpu => app_state.gpu.map_or(0, |gpu| {
+            usize::from(gpu.utilization.is_some()) + usize::from(gpu.memory.is_some())
+        }),
+        Meter::Disk => 1,
+        Meter::Battery => usize::from(app_state.battery.is_some()),
+    }
+}
+
+/// Most volumes listed before the filesystem panel stops growing
+const MAX_FILESYSTEM_ROWS: usize = 8;
+
+/// Rows taken by the filesystem panel, zero while it is hidden
+pub fn filesystem_panel_height(app_state: &AppState) -> u16 {
+    if !app_state.show_filesystems {
+        return 0;
+    }
+
+    // Header and bottom border around the volume rows
+    (app_state.volumes.len().clamp(1, MAX_FILESYSTEM_ROWS) + 2) as u16
+}
+
+/// Draw mounted volumes with their size, usage, and a usage bar
+fn draw_filesystem_panel(f: &mut Frame, area: Rect, volumes: &[Volume]) {
+    const BAR_LENGTH: usize = 20;
+
+    let header = Row::new(
+        [
+            "Mount", "FS", "Size", "Used", "Avail", "Use%", "Inodes", "IUse%",
+        ]
+        .map(|title| Cell::from(title).bold()),
+    )
+    .style(Style::default().fg(theme().accent));
+
+    let rows = volumes.iter().map(|volume| {
+        let used = volume.used();
+        let ratio = if volume.total > 0 {
+            used as f64 / volume.total as f64
+        } else {
+            0.0
+        };
+        let bar = create_progress_bar(ratio, BAR_LENGTH);
+
+        let mut cells = vec![
+            Cell::from(volume.mount_point.clone()).style(Style::default().fg(theme().text)),
+            Cell::from(volume.file_system.clone()).style(Style::default().fg(theme().dim)),
+            Cell::from(format_bytes(volume.total)).style(Style::default().fg(theme().text)),
+            Cell::from(format_bytes(used)).style(Style::default().fg(theme().text)),
+            Cell::from(format_bytes(volume.available)).style(Style::default().fg(theme().text)),
+            Cell::from(Line::from(vec![
+                Span::raw("["),
+                Span::styled(
+                    bar,
+                    Style::default().fg(get_memory_color(used, volume.total)),
+                ),
+                Span::raw(format!("] {:>3.0}%", ratio * 100.0)),
+            ])),
+        ];
+
+        // Running out of inodes fills a disk as surely as running out of bytes
+        match volume.inodes {
+            Some(inodes) => cells.extend([
+                Cell::from(format!(
+                    "{}/{}",
+                    format_count(inodes.used()),
+                    format_count(inodes.total)
+                ))
+                .style(Style::default().fg(theme().text)),
+                Cell::from(format!(
+                    "{:>3.0}%",
+                    inodes.used() as f64 / inodes.total as f64 * 100.0
+                ))
+                .style(Style::default().fg(get_memory_color(inodes.used(), inodes.total))),
+            ]),
+            None => cells.extend(
+                ["-", "-"].map(|text| Cell::from(text).style(Style::default().fg(theme().dim))),
+            ),
+        }
+        Row::new(cells)
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),                       // Mount
+            Constraint::Length(8),                     // FS
+            Constraint::Length(8),                     // Size
+            Constraint::Length(8),                     // Used
+            Constraint::Length(8),                     // Avail
+            Constraint::Length(BAR_LENGTH as u16 + 7), // Use%
+            Constraint::Length(13),                    // Inodes
+            Constraint::Length(5),                     // IUse%
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().faint)),
+    )
+    .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+/// Draw the row of process pane views
+fn draw_view_tabs(f: &mut Frame, area: Rect, view: View, remote: bool) {
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(20)])
+        .split(area);
+
+    let shown: Vec<View> = View::ALL
+        .into_iter()
+        .filter(|view| !(remote && view.is_local()))
+        .collect();
+    let tabs = Tabs::new(shown.iter().map(|view| view.title()))
+        .select(shown.iter().position(|shown| *shown == view).unwrap_or(0))
+        .style(Style::default().fg(theme().dim))
+        .highlight_style(
+            Style::default()
+                .fg(theme().warning)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(tabs, layout[0]);
+
+    let hint = Paragraph::new("[/]: switch view ")
+        .style(Style::default().fg(theme().faint))
+        .alignment(Alignment::Right);
+    f.render_widget(hint, layout[1]);
+}
+
+/// Draw the information bar with CPU, memory, and system info
+pub fn draw_info_bar(sys: &Snapshot, f: &mut Frame, area: Rect, app_state: &AppState) {
+    let cpus = sys.cpus();
+    let cpu_rows = cpu_grid_rows(cpus.len(), &app_state.core_clusters);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(cpu_rows as u16), // CPU bars
+            Constraint::Min(3),                  // Memory bars + info
+        ])
+        .split(area);
+
+    draw_cpu_bars(
+        cpus,
+        f,
+        layout[0],
+        meter_mode(app_state),
+        &app_state.cpu_history,
+        &app_state.core_clusters,
+    );
+    draw_memory_and_info(sys, f, layout[1], app_state);
+}
+
+/// Rows taken by the CPU grid, including a heading per core cluster
+fn cpu_grid_rows(cpu_count: usize, clusters: &[CoreCluster]) -> usize {
+    if clusters.is_empty() {
+        return cpu_count.div_ceil(CPU_COLUMNS);
+    }
+    clusters
+        .iter()
+        .map(|cluster| 1 + cluster.cpus.len().div_ceil(CPU_COLUMNS))
+        .sum()
+}
+
+/// Draw CPU usage bars in a grid layout
+///
+/// On hybrid CPUs the performance and efficiency cores are drawn as separate
+/// grids, each headed by the cluster's average usage. In the graph modes,
+/// each core shows a scrolling graph of its recent usage in place of the bar.
+fn draw_cpu_bars(
+    cpus: &[CpuSnapshot],
+    f: &mut Frame,
+    area: Rect,
+    mode: MeterMode,
+    history: &CpuHistory,
+    clusters: &[CoreCluster],
+) {
+    let total_padding = (CPU_COLUMNS - 1) * 3;
+    let label_length = 4;
+    let percent_length = 6;
+    let bracket_length = 2;
+
+    let bar_length = ((area.width as usize - total_padding) / CPU_COLUMNS)
+        .saturating_sub(label_length + percent_length + bracket_length)
+        .max(MIN_BAR_LENGTH);
+
+    let mut cpu_lines = Vec::new();
+    if clusters.is_empty() {
+        let indexes: Vec<usize> = (0..cpus.len()).collect();
+        cpu_lines.extend(create_cpu_grid(cpus, &indexes, bar_length, mode, history));
+    }
+    for cluster in clusters {
+        let average = cluster
+            .cpus
+            .iter()
+            .map(|&index| cpus[index].cpu_usage())
+            .sum::<f32>()
+            / cluster.cpus.len().max(1) as f32;
+        cpu_lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} cores ({})", cluster.name, cluster.cpus.len()),
+                Style::default()
+                    .fg(theme().accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  avg ", Style::default().fg(theme().dim)),
+            Span::styled(
+                format!("{:.1}%", average),
+                Style::default().fg(get_cpu_color(average)),
+            ),
+        ]));
+        cpu_lines.extend(create_cpu_grid(
+            cpus,
+            &cluster.cpus,
+            bar_length,
+            mode,
+            history,
+        ));
+    }
+
+    let cpu_paragraph = Paragraph::new(cpu_lines).alignment(Alignment::Left);
+    f.render_widget(cpu_paragraph, area);
+}
+
+/// Lay out the given cores column by column in a grid of CPU_COLUMNS
+fn create_cpu_grid(
+    cpus: &[CpuSnapshot],
+    indexes: &[usize],
+    bar_length: usize,
+    mode: MeterMode,
+    history: &CpuHistory,
+) -> Vec<Line<'static>> {
+    let cpu_rows = indexes.len().div_ceil(CPU_COLUMNS);
+    let percent_length = 6;
+    let bracket_length = 2;
+    let mut cpu_lines = Vec::new();
+
+    for row in 0..cpu_rows {
+        let mut spans = Vec::new();
+
+        for col in 0..CPU_COLUMNS {
+            if let Some(&cpu_index) = indexes.get(row + col * cpu_rows) {
+                let cpu = &cpus[cpu_index];
+                let usage = cpu.cpu_usage();
+                let color = get_cpu_color(usage);
+                let label = format!("{:>2}   ", cpu_index);
+
+                spans.push(Span::styled(label, Style::default().fg(theme().accent)));
+                let graph = match (mode, history.core(cpu_index)) {
+                    (MeterMode::Graphs, Some(readings)) => Some(create_history_bar(
+                        readings,
+                        100.0,
+                        bar_length + bracket_length,
+                    )),
+                    (MeterMode::Braille, Some(readings)) => Some(create_braille_graph(
+                        readings,
+                        100.0,
+                        bar_length + bracket_length,
+                    )),
+                    _ => None,
+                };
+                match graph {
+                    // The graph takes the bar's brackets too
+                    Some(graph) => spans.extend_from_slice(&[
+                        Span::styled(graph, Style::default().fg(color)),
+                        Span::raw(" "),
+                    ]),
+                    None => {
+                        spans.extend_from_slice(&[
+                            Span::raw("["),
+                            Span::styled(
+                                create_progress_bar(usage as f64 / 100.0, bar_length),
+                                Style::default().fg(color),
+                            ),
+                            Span::raw("] "),
+                        ]);
+                    }
+                }
+                spans.push(Span::styled(
+                    format!("{:>5.1}%", usage),
+                    Style::default().fg(theme().dim),
+                ));
+            } else {
+                let empty_space =
+                    " ".repeat(LABEL_WIDTH + 1 + bar_length + bracket_length + percent_length);
+                spans.push(Span::raw(empty_space));
+            }
+
+            if col < CPU_COLUMNS - 1 {
+                spans.push(Span::raw("   "));
+            }
+        }
+
+        cpu_lines.push(Line::from(spans));
+    }
+
+    cpu_lines
+}
+
+/// Draw memory bars and system information
+fn draw_memory_and_info(sys: &Snapshot, f: &mut Frame, area: Rect, app_state: &AppState) {
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50), // Memory bars
+            Constraint::Percentage(50), // System info
+        ])
+        .split(area);
+
+    draw_memory_bars(sys, f, layout[0], app_state);
+    draw_system_info(
+        sys,
+        f,
+        layout[1],
+        app_state.thread_totals,
+        app_state.thermal_pressure,
+        app_state.config.time_format,
+    );
+}
+
+/// Draw the meters picked in the setup screen, in their order
+///
+/// When the memory breakdown is available, the Mem bar is split into app,
+/// wired, compressed, and cached segments with a legend below it. When
+/// compressor statistics are available, the Swp bar also shows the
+/// memory compressor occupancy as a separate segment. The GPU bar is only
+/// shown when GPU memory can be read; unified memory is scaled to system RAM.
+/// The battery meter is only shown on machines with a battery.
+fn draw_memory_bars(sys: &Snapshot, f: &mut Frame, area: Rect, app_state: &AppState) {
+    let bar_length = area.width.saturating_sub(LABEL_WIDTH as u16 + 3) as usize;
+    let bar_length = bar_length.max(MIN_MEMORY_BAR_LENGTH);
+
+    let lines: Vec<Line> = app_state
+        .meters
+        .iter()
+        .flat_map(|&meter| create_meter_lines(sys, meter, app_state, bar_length))
+        .collect();
+
+    let memory_paragraph = Paragraph::new(lines);
+    f.render_widget(memory_paragraph, area);
+}
+
+/// Lines of one meter, as many as `meter_height` counts
+fn create_meter_lines(
+    sys: &Snapshot,
+    meter: Meter,
+    app_state: &AppState,
+    bar_length: usize,
+) -> Vec<Line<'static>> {
+    let total_memory = sys.total_memory();
+
+    match meter {
+        Meter::Memory => {
+            let mut lines = match &app_state.memory_stats {
+                Some(stats) => {
+                    create_memory_breakdown(stats, total_memory, bar_length, LABEL_WIDTH)
+                }
+                None => vec![create_memory_bar(
+                    "Mem",
+                    sys.used_memory(),
+                    total_memory,
+                    bar_length,
+                    LABEL_WIDTH,
+                )],
+            };
+            // The graph replaces the bar; the breakdown legend stays
+            if meter_mode(app_state) == MeterMode::Braille {
+                lines[0] = create_memory_graph(
+                    app_state.cpu_history.memory(),
+                    sys.used_memory(),
+                    total_memory,
+                    bar_length,
+                    LABEL_WIDTH,
+                );
+            }
+            lines
+        }
+        Meter::Swap => {
+            let (used_swap, total_swap) = (sys.used_swap(), sys.total_swap());
+            let mut lines = vec![match &app_state.compressor {
+                Some(stats) => {
+                    create_swap_bar(used_swap, total_swap, stats, bar_length, LABEL_WIDTH)
+                }
+                None => create_memory_bar("Swp", used_swap, total_swap, bar_length, LABEL_WIDTH),
+            }];
+            if app_state.memory_stats.is_some() {
+                lines.push(create_swap_activity_line(
+                    app_state.swap.rates(),
+                    LABEL_WIDTH,
+                ));
+            }
+            lines
+        }
+        Meter::Cgroup => match &app_state.cgroup {
+            Some(cgroup) => create_cgroup_bars(cgroup, sys.cpus().len(), total_memory, bar_length),
+            None => Vec::new(),
+        },
+        Meter::Gpu => {
+            let mut lines = Vec::new();
+            if let Some(utilization) = app_state.gpu.and_then(|gpu| gpu.utilization) {
+                lines.push(create_segmented_bar(
+                    "GPU",
+                    &[(utilization.round() as u64, get_cpu_color(utilization))],
+                    100,
+                    bar_length,
+                    LABEL_WIDTH,
+                    &format!("{:.0}%", utilization),
+                ));
+            }
+            if let Some(memory) = app_state.gpu.and_then(|gpu| gpu.memory) {
+                let label = match memory.kind {
+                    GpuMemoryKind::Unified => "GMem",
+                    GpuMemoryKind::Dedicated => "VRAM",
+                };
+                let total = memory.total.unwrap_or(total_memory);
+                lines.push(create_memory_bar(
+                    label,
+                    memory.used,
+                    total,
+                    bar_length,
+                    LABEL_WIDTH,
+                ));
+            }
+            lines
+        }
+        Meter::Disk => vec![create_disk_bar(&app_state.disk, bar_length, LABEL_WIDTH)],
+        Meter::Battery => match &app_state.battery {
+            Some(battery) => {
+                let at_load = app_state.battery_trend.minutes_at_load(battery);
+                vec![create_battery_bar(
+                    battery,
+                    at_load,
+                    bar_length,
+                    LABEL_WIDTH,
+                )]
+            }
+            None => Vec::new(),
+        },
+    }
+}
+
+/// Draw system information panel
+fn draw_system_info(
+    sys: &Snapshot,
+    f: &mut Frame,
+    area: Rect,
+    thread_totals: ThreadTotals,
+    thermal_pressure: Option<ThermalPressure>,
+    time_format: TimeFormat,
+) {
+    // Thread entries are counted in the thr/kthr totals, not as tasks
+    let processes: Vec<&ProcessSnapshot> = sys
+        .processes()
+        .values()
+        .filter(|p| p.thread_kind().is_none())
+        .collect();
+    let task_count = processes.len();
+    let running_count = processes
+        .iter()
+        .filter(|p| p.status() == ProcessStatus::Run)
+        .count();
+
+    let threads = thread_totals
+        .threads
+        .map_or_else(|| "N/A".to_string(), |threads| threads.to_string());
+    let tasks_info = format!(
+        "Tasks: {}, {} thr, {} kthr; {} running",
+        task_count, threads, thread_totals.kernel_threads, running_count
+    );
+
+    let load_avg = sys.load_average();
+    let load_info = format!(
+        "Load average: {:.2} {:.2} {:.2}",
+        load_avg.one, load_avg.five, load_avg.fifteen
+    );
+
+    let uptime = sys.uptime();
+    let boot_time = sys.boot_time();
+    let uptime_info = format!(
+        "Uptime: {} (since {})",
+        format_uptime(uptime, time_format),
+        format_timestamp(boot_time, boot_time + uptime, time_format)
+    );
+
+    let mut info_lines = vec![
+        Line::from(vec![
+            Span::raw(INFO_PADDING),
+            Span::styled(tasks_info, Style::default().fg(theme().accent)),
+        ]),
+        Line::from(vec![
+            Span::raw(INFO_PADDING),
+            Span::styled(load_info, Style::default().fg(theme().accent)),
+        ]),
+        Line::from(vec![
+            Span::raw(INFO_PADDING),
+            Span::styled(
+                uptime_info,
+                Style::default()
+                    .fg(theme().accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+    ];
+
+    if let Some(pressure) = thermal_pressure {
+        info_lines.push(Line::from(vec![
+            Span::raw(INFO_PADDING),
+            Span::styled("Thermal pressure: ", Style::default().fg(theme().accent)),
+            Span::styled(
+                pressure.label(),
+                Style::default()
+                    .fg(get_thermal_color(pressure))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+
+    let info_paragraph = Paragraph::new(info_lines).alignment(Alignment::Left);
+    f.render_widget(info_paragraph, area);
+}
+
+/// Compute the process table rows in display order
+///
+/// In tree view, rows follow the parent/child hierarchy with collapsed
+/// subtrees hidden; otherwise rows are sorted by the active sort key. The
+/// I/O view always ranks by current disk throughput. Processes excluded by
+/// the user or PID filters are left out of all of them.
+pub fn display_order(sys: &Snapshot, app_state: &AppState) -> Vec<TreeNode> {
+    if !app_state.view.lists_processes() {
+        return Vec::new();
+    }
+    let filter = app_state.name_filter.as_deref().map(str::to_lowercase);
+    let shown = |process: &ProcessSnapshot| is_shown(sys, process, app_state, filter.as_deref());
+
+    if app_state.view == View::Io {
+        let mut processes: Vec<_> = sys
+            .processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none() && shown(process))
+            .collect();
+        processes.sort_by_key(|process| std::cmp::Reverse(io_bytes(process)));
+
+        return processes
+            .into_iter()
+            .map(|p| TreeNode::new(p.pid(), String::new()))
+            .collect();
+    }
+
+    if app_state.view == View::Network {
+        // Only processes with network activity are listed
+        let usage = &app_state.network;
+        let mut processes: Vec<(Pid, &NetworkUsage)> = usage
+            .iter()
+            .filter(|(pid, _)| sys.process(**pid).is_some_and(shown))
+            .map(|(pid, usage)| (*pid, usage))
+            .collect();
+        match app_state.network_order {
+            NetworkOrder::Total => {
+                processes.sort_by_key(|(pid, usage)| (std::cmp::Reverse(usage.total()), *pid))
+            }
+            NetworkOrder::Rate => processes.sort_by(|(a_pid, a), (b_pid, b)| {
+                b.total_rate()
+                    .total_cmp(&a.total_rate())
+                    .then(a_pid.cmp(b_pid))
+            }),
+        }
+
+        return processes
+            .into_iter()
+            .map(|(pid, _)| TreeNode::new(pid, String::new()))
+            .collect();
+    }
+
+    // Smoothed CPU usage orders the rows too, unless raw readings are asked for
+    let readings = SortReadings {
+        cpu: app_state
+            .cpu_smoother
+            .averages()
+            .filter(|_| !app_state.config.sort_raw_cpu),
+        sched: Some(&app_state.sched),
+    };
+
+    if app_state.tree_view {
+        let mut nodes = build_tree(
+            sys,
+            &app_state.collapsed,
+            app_state.sort,
+            readings,
+            app_state.cpu_smoother.averages(),
+        );
+        nodes.retain(|node| sys.process(node.pid).is_some_and(shown));
+        return nodes;
+    }
+
+    let mut processes: Vec<_> = sys
+        .processes()
+        .values()
+        .filter(|process| shown(process))
+        .collect();
+    sort_processes(&mut processes, app_state.sort, readings);
+
+    processes
+        .into_iter()
+        .map(|p| TreeNode::new(p.pid(), String::new()))
+        .collect()
+}
+
+/// The process table rows, with group headers when grouping is on
+///
+/// Grouping sections the sorted process view; the I/O and network views
+/// and the tree view are never grouped. The rows are worked out once per
+/// process refresh and again only when how they are listed changes.
+pub fn display_rows(sys: &Snapshot, app_state: &AppState) -> Rc<[DisplayRow]> {
+    let inputs = DisplayInputs::of(app_state);
+    let mut cached = app_state.display_cache.cached.borrow_mut();
+    match &*cached {
+        Some((listed, rows)) if *listed == inputs => rows.clone(),
+        _ => {
+            let rows: Rc<[DisplayRow]> = compute_display_rows(sys, app_state).into();
+            *cached = Some((inputs, rows.clone()));
+            rows
+        }
+    }
+}
+
+/// Process table rows in display order, as last worked out
+#[derive(Debug, Default)]
+pub struct DisplayCache {
+    cached: RefCell<Option<(DisplayInputs, Rc<[DisplayRow]>)>>,
+}
+
+impl DisplayCache {
+    /// Drop the rows, for when the processes have been read again
+    pub fn clear(&mut self) {
+        *self.cached.get_mut() = None;
+    }
+}
+
+/// Settings that choose and order the process table rows
+#[derive(Debug, PartialEq)]
+struct DisplayInputs {
+    view: View,
+    tree_view: bool,
+    grouping: Grouping,
+    collapsed_groups: HashSet<String>,
+    sort: SortOrder,
+    sort_raw_cpu: bool,
+    user_filter: Option<u32>,
+    pid_filter: Option<HashSet<Pid>>,
+    name_filter: Option<String>,
+    row_limit: Option<usize>,
+    collapsed: HashSet<Pid>,
+    network_order: NetworkOrder,
+}
+
+impl DisplayInputs {
+    fn of(app_state: &AppState) -> Self {
+        DisplayInputs {
+            view: app_state.view,
+            tree_view: app_state.tree_view,
+            grouping: app_state.grouping,
+            collapsed_groups: app_state.collapsed_groups.clone(),
+            sort: app_state.sort,
+            sort_raw_cpu: app_state.config.sort_raw_cpu,
+            user_filter: app_state.user_filter,
+            pid_filter: app_state.pid_filter.clone(),
+            name_filter: app_state.name_filter.clone(),
+            row_limit: app_state.row_limit,
+            collapsed: app_state.collapsed.clone(),
+            network_order: app_state.network_order,
+        }
+    }
+}
+
+fn compute_display_rows(sys: &Snapshot, app_state: &AppState) -> Vec<DisplayRow> {
+    let mut nodes = display_order(sys, app_state);
+    if let Some(limit) = app_state.row_limit {
+        nodes.truncate(limit);
+    }
+    if app_state.grouping == Grouping::Off
+        || app_state.view != View::Processes
+        || app_state.tree_view
+    {
+        return nodes.into_iter().map(DisplayRow::Process).collect();
+    }
+
+    group_rows(
+        sys,
+        nodes,
+        |process| {
+            group_name(
+                app_state.grouping,
+                sys,
+                process,
+                &app_state.users,
+                &app_state.container_names,
+            )
+        },
+        |process| app_state.cpu_smoother.usage(process),
+        &app_state.collapsed_groups,
+    )
+}
+
+/// Bytes read and written by a process since the previous refresh
+fn io_bytes(process: &ProcessSnapshot) -> u64 {
+    let usage = process.disk_usage();
+    usage.read_bytes + usage.written_bytes
+}
+
+/// Whether a process passes the user, PID, and name filters, the name
+/// filter given in lowercase
+fn is_shown(
+    sys: &Snapshot,
+    process: &ProcessSnapshot,
+    app_state: &AppState,
+    name_filter: Option<&str>,
+) -> bool {
+    let user_matches = app_state
+        .user_filter
+        .is_none_or(|uid| process.user_id().is_some_and(|id| **id == uid));
+    let pid_matches = app_state
+        .pid_filter
+        .as_ref()
+        .is_none_or(|pids| descends_from(sys, process, pids));
+    let name_matches = name_filter.is_none_or(|filter| process.search_text().contains(filter));
+
+    user_matches && pid_matches && name_matches
+}
+
+/// Resolve the PID of the currently selected row
+pub fn selected_pid(sys: &Snapshot, app_state: &AppState) -> Option<Pid> {
+    display_rows(sys, app_state)
+        .get(app_state.selected_row_index)
+        .and_then(DisplayRow::pid)
+}
+
+/// Resolve the group whose header is the selected row
+pub fn selected_group(sys: &Snapshot, app_state: &AppState) -> Option<GroupHeader> {
+    match display_rows(sys, app_state).get(app_state.selected_row_index) {
+        Some(DisplayRow::Group(header)) => Some(header.clone()),
+        _ => None,
+    }
+}
+
+/// Resolve the PIDs an action applies to: every tagged process, or the
+/// selected one when nothing is tagged, leaving out PID 0
+pub fn action_targets(sys: &Snapshot, app_state: &AppState) -> Vec<Pid> {
+    // PID 0 is kernel_task on macOS, and to kill() means sysly's own group
+    let actionable = |pid: &Pid| pid.as_u32() != 0;
+    if app_state.tagged.is_empty() {
+        return selected_pid(sys, app_state)
+            .into_iter()
+            .filter(actionable)
+            .collect();
+    }
+
+    let mut targets: Vec<Pid> = app_state
+        .tagged
+        .iter()
+        .copied()
+        .filter(actionable)
+        .collect();
+    targets.sort();
+    targets
+}
+
+/// Resolve the process groups of the action targets, each listed once and
+/// leaving out group 0, which `killpg` takes to mean sysly's own
+pub fn group_targets(sys: &Snapshot, app_state: &AppState) -> Vec<Pid> {
+    let mut groups: Vec<Pid> = action_targets(sys, app_state)
+        .into_iter()
+        .filter_map(|pid| sys.process(pid)?.process_group())
+        .filter(|pgid| pgid.as_u32() != 0)
+        .collect();
+    groups.sort();
+    groups.dedup();
+    groups
+}
+
+/// Draw the process table
+///
+/// In tree view, the Command column is prefixed with branch characters and
+/// collapsed parents show how many descendants are hidden, with their CPU%
+/// and MEM% rolled up into the parent row.
+pub fn draw_process_table(sys: &Snapshot, f: &mut Frame, area: Rect, app_state: &mut AppState) {
+    let display = display_rows(sys, app_state);
+
+    // Only rows that fit below the header and above the bottom border are
+    // built; the table is handed just that slice
+    let visible = area.height.saturating_sub(2) as usize;
+    let offset = scroll_offset(
+        app_state.table_offset,
+        app_state.selected_row_index,
+        visible,
+        display.len(),
+    );
+    let window = &display[offset..(offset + visible).min(display.len())];
+
+    // The I/O and network views are never grouped, so every row is a process
+    let ordered = window.iter().enumerate().filter_map(|(index, row)| {
+        let process = row.pid().and_then(|pid| sys.process(pid))?;
+        Some((offset + index, process))
+    });
+
+    let total_memory = sys.total_memory() as f64;
+
+    let maps = &app_state.process_maps;
+    // Taken out while the rows borrow from it, and put back after drawing
+    let mut row_cache = std::mem::take(&mut app_state.row_cache);
+
+    let context = RowContext {
+        uid_to_user: &app_state.users,
+        priority_map: &maps.priority,
+        memory_map: &maps.memory,
+        total_memory,
+        selected_row_index: app_state.selected_row_index,
+        watched: &app_state.watched,
+        tagged: &app_state.tagged,
+        time_format: app_state.config.time_format,
+        thousands_separator: app_state.config.thousands_separator.as_deref(),
+        columns: &app_state.columns,
+        wakeups: &app_state.wakeups,
+        origins: &app_state.origins,
+        energy: &app_state.energy_impact,
+        disk_io: &app_state.disk_io,
+        sched: &app_state.sched,
+        nap: &app_state.nap,
+        rosetta: &app_state.rosetta,
+        cpu_smoother: &app_state.cpu_smoother,
+        containers: &app_state.container_names,
+    };
+
+    let (header, constraints, rows) = match app_state.view {
+        View::Io => (
+            create_io_header(),
+            get_io_constraints(),
+            ordered
+                .map(|(index, process)| {
+                    create_io_row(index, process, &context, app_state.process_interval)
+                })
+                .collect::<Vec<Row>>(),
+        ),
+        View::Network => (
+            create_network_header(app_state.network_order),
+            get_network_constraints(),
+            ordered
+                .map(|(index, process)| {
+                    let usage = app_state.network.get(&process.pid());
+                    create_network_row(index, process, &context, usage)
+                })
+                .collect(),
+        ),
+        _ => {
+            for row in window {
+                if let DisplayRow::Process(node) = row {
+                    if let Some(process) = sys.process(node.pid) {
+                        row_cache.update(process, node, &context);
+                    }
+                }
+            }
+            (
+                create_table_header(app_state),
+                get_table_constraints(app_state),
+                window
+                    .iter()
+                    .enumerate()
+                    .map(|(index, row)| (offset + index, row))
+                    .filter_map(|(index, row)| match row {
+                        DisplayRow::Process(node) => sys
+                            .process(node.pid)
+                            .map(|_| row_cache.row(index, node.pid, &context)),
+                        DisplayRow::Group(header) => {
+                            Some(create_group_row(index, header, &context))
+                        }
+                    })
+                    .collect(),
+            )
+        }
+    };
+
+    let mut block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
+    if let Some(message) = &app_state.status_message {
+        block = block.title_bottom(Span::styled(
+            format!(" {} ", message),
+            Style::default().fg(theme().warning),
+        ));
+    }
+    block = block.title(
+        Title::from(create_pane_status(
+            sys,
+            app_state,
+            display
+                .get(app_state.selected_row_index)
+                .and_then(DisplayRow::pid),
+        ))
+        .position(Position::Bottom)
+        .alignment(Alignment::Right),
+    );
+
+    let table = Table::new(rows, constraints)
+        .header(header)
+        .block(block)
+        .column_spacing(1);
+
+    let mut table_state = TableState::default()
+        .with_selected(Some(app_state.selected_row_index.saturating_sub(offset)));
+    f.render_stateful_widget(table, area, &mut table_state);
+    app_state.row_cache = row_cache;
+    app_state.table_offset = offset;
+}
+
+/// First row to show so the selected row stays in view, scrolling no
+/// further than needed from the previous offset
+fn scroll_offset(previous: usize, selected: usize, visible: usize, len: usize) -> usize {
+    if visible == 0 || len == 0 {
+        return 0;
+    }
+    let selected = selected.min(len - 1);
+    let offset = previous.min(selected);
+    if selected >= offset + visible {
+        selected + 1 - visible
+    } else {
+        offset
+    }
+}
+
+// Helper functions
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Build the pane indicator showing which pane has focus and which are paused,
+/// preceded by sysly's own CPU and memory usage
+fn create_pane_status(
+    sys: &Snapshot,
+    app_state: &AppState,
+    selected: Option<Pid>,
+) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+
+    if let Some(own) = sysinfo::get_current_pid()
+        .ok()
+        .and_then(|pid| sys.process(pid))
+    {
+        let over_budget = own.cpu_usage() > app_state.config.self_cpu_budget
+            || own.memory() > app_state.config.self_memory_budget;
+        let (text, style) = if over_budget {
+            (
+                format!(
+                    " sysly {:.1}% {} over budget ",
+                    own.cpu_usage(),
+                    format_bytes(own.memory())
+                ),
+                Style::default().fg(theme().text).bg(theme().critical),
+            )
+        } else {
+            (
+                format!(
+                    " sysly {:.1}% {} ",
+                    own.cpu_usage(),
+                    format_bytes(own.memory())
+                ),
+                Style::default().fg(theme().dim),
+            )
+        };
+        spans.push(Span::styled(text, style));
+        spans.push(Span::raw(" "));
+    }
+
+    if let Some(filter) = &app_state.name_filter {
+        spans.push(Span::styled(
+            format!(" filter: {} ", filter),
+            Style::default()
+                .fg(theme().on_highlight)
+                .bg(theme().warning),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
+    if let Some((name, minutes)) = battery_gain(sys, app_state, selected) {
+        spans.push(Span::styled(
+            format!(" +{} on battery without {} ", format_minutes(minutes), name),
+            Style::default().fg(theme().good),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
+    let failing = app_state.collectors.failing_count();
+    if failing > 0 {
+        spans.push(Span::styled(
+            format!(" {} collector(s) failing, D for details ", failing),
+            Style::default().fg(theme().text).bg(theme().critical),
+        ));
+        spans.push(Span::raw(" "));
+    }
+
+    for pane in Pane::ALL {
+        let mut label = pane.name().to_string();
+        if app_state.paused.contains(&pane) {
+            label.push_str(" (paused)");
+        }
+
+        let style = if pane == app_state.focus {
+            Style::default()
+                .fg(theme().on_highlight)
+                .bg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().dim)
+        };
+        spans.push(Span::styled(format!(" {} ", label), style));
+        spans.push(Span::raw(" "));
+    }
+
+    Line::from(spans)
+}
+
+/// How much longer the battery would likely last without the selected
+/// process, from its share of the measured energy impact
+///
+/// Needs energy impact from powermetrics and a time-at-load estimate, so it
+/// is only shown on battery with the ENERGY column on.
+fn battery_gain(
+    sys: &Snapshot,
+    app_state: &AppState,
+    selected: Option<Pid>,
+) -> Option<(String, u64)> {
+    let minutes = app_state
+        .battery_trend
+        .minutes_at_load(app_state.battery.as_ref()?)?;
+    let process = sys.process(selected?)?;
+    let impact = *app_state.energy_impact.get(&process.pid().as_u32())?;
+    let total: f64 = app_state.energy_impact.values().sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let gained = minutes_gained(minutes, impact / total)? as u64;
+    (gained > 0).then(|| (process.name().to_string(), gained))
+}
+
+/// Eighth-cell blocks ending a block-style bar, from one eighth to seven
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Fill `width` cells to `fraction` in the configured bar style, padded
+/// with spaces
+fn create_progress_bar(fraction: f64, width: usize) -> String {
+    let mut bar: String = bar_cells(fraction, width).collect();
+    let filled = bar.chars().count();
+    bar.extend(std::iter::repeat_n(' ', width.saturating_sub(filled)));
+    bar
+}
+
+/// The filled cells of a bar `width` cells wide, `fraction` full: whole
+/// cells, then with block bars an eighth-cell block for the remainder
+fn bar_cells(fraction: f64, width: usize) -> impl Iterator<Item = char> {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let (cell, full, partial) = match bar_style() {
+        BarStyle::Pipes => ('|', (fraction * width as f64).round() as usize, None),
+        BarStyle::Blocks => {
+            let eighths = (fraction * width as f64 * 8.0).round() as usize;
+            let partial = (eighths % 8)
+                .checked_sub(1)
+                .map(|index| PARTIAL_BLOCKS[index]);
+            ('█', eighths / 8, partial)
+        }
+    };
+    std::iter::repeat_n(cell, full).chain(partial).take(width)
+}
+
+fn get_cpu_color(usage: f32) -> Color {
+    match usage {
+        u if u > CPU_HIGH_THRESHOLD => theme().critical,
+        u if u > CPU_MEDIUM_THRESHOLD => theme().warning,
+        _ => theme().good,
+    }
+}
+
+fn get_disk_color(bytes_per_second: f64) -> Color {
+    match bytes_per_second {
+        r if r > DISK_HIGH_THRESHOLD => theme().critical,
+        r if r > DISK_MEDIUM_THRESHOLD => theme().warning,
+        _ => theme().good,
+    }
+}
+
+fn get_memory_color(used: u64, total: u64) -> Color {
+    if total == 0 {
+        return theme().good;
+    }
+
+    let ratio = used as f64 / total as f64;
     match ratio {
-        r if r > MEMORY_HIGH_THRESHOLD => Color::Red,
-        r if r > MEMORY_MEDIUM_THRESHOLD => Color::Yellow,
-        _ => Color::Green,
+        r if r > MEMORY_HIGH_THRESHOLD => theme().critical,
+        r if r > MEMORY_MEDIUM_THRESHOLD => theme().warning,
+        _ => theme().good,
+    }
+}
+
+fn create_memory_bar(
+    label: &str,
+    used: u64,
+    total: u64,
+    bar_length: usize,
+    label_width: usize,
+) -> Line<'_> {
+    let label_text = format!("{}/{}", format_bytes(used), format_bytes(total));
+    let fraction = if total > 0 {
+        used as f64 / total as f64
+    } else {
+        0.0
+    };
+
+    // By character, since block bars are multi-byte
+    let mut bar: Vec<char> = create_progress_bar(fraction, bar_length).chars().collect();
+
+    // Overlay label inside the bar
+    let label_start = if bar_length > label_text.len() {
+        bar_length - label_text.len()
+    } else {
+        0
+    };
+
+    for (i, ch) in label_text.chars().enumerate() {
+        if let Some(cell) = bar.get_mut(label_start + i) {
+            *cell = ch;
+        }
+    }
+    let bar: String = bar.into_iter().collect();
+
+    let color = get_memory_color(used, total);
+
+    Line::from(vec![
+        Span::styled(
+            format!("{:<width$}", label, width = label_width),
+            Style::default().fg(theme().accent),
+        ),
+        Span::raw("["),
+        Span::styled(bar, Style::default().fg(color)),
+        Span::raw("]"),
+    ])
+}
+
+/// Build the Mem meter as a braille graph of recent memory use, with the
+/// current use as its label
+fn create_memory_graph(
+    readings: &VecDeque<f32>,
+    used: u64,
+    total: u64,
+    bar_length: usize,
+    label_width: usize,
+) -> Line<'static> {
+    let label_text = format!("{}/{}", format_bytes(used), format_bytes(total));
+    // The label keeps the right end of the bar, as on the bars
+    let graph_length = bar_length.saturating_sub(label_text.chars().count() + 1);
+
+    Line::from(vec![
+        Span::styled(
+            format!("{:<width$}", "Mem", width = label_width),
+            Style::default().fg(theme().accent),
+        ),
+        Span::raw("["),
+        Span::styled(
+            create_braille_graph(readings, 100.0, graph_length),
+            Style::default().fg(get_memory_color(used, total)),
+        ),
+        Span::raw(format!(
+            "{:>width$}",
+            label_text,
+            width = bar_length - graph_length
+        )),
+        Span::raw("]"),
+    ])
+}
+
+/// Build the cCPU and cMem meters: the cgroup's usage against its
+/// `cpu.max` and `memory.max`, or against the machine when unlimited
+fn create_cgroup_bars(
+    cgroup: &CgroupUsage,
+    cpu_count: usize,
+    total_memory: u64,
+    bar_length: usize,
+) -> Vec<Line<'static>> {
+    let cores = cgroup.cpu_cores.unwrap_or(0.0);
+    let cpu_total = cgroup.cpu_limit.unwrap_or(cpu_count as f64);
+    let cpu_text = match cgroup.cpu_limit {
+        Some(limit) => format!("{:.2}/{:.2} cores", cores, limit),
+        None => format!("{:.2} cores, no limit", cores),
+    };
+    let cpu_percent = if cpu_total > 0.0 {
+        (cores / cpu_total * 100.0) as f32
+    } else {
+        0.0
+    };
+
+    let memory_total = cgroup.memory_limit.unwrap_or(total_memory);
+    let memory_text = match cgroup.memory_limit {
+        Some(limit) => format!("{}/{}", format_bytes(cgroup.memory), format_bytes(limit)),
+        None => format!("{}, no limit", format_bytes(cgroup.memory)),
+    };
+
+    // Thousandths of a CPU, so fractional cores still fill the bar
+    vec![
+        create_segmented_bar(
+            "cCPU",
+            &[((cores * 1000.0) as u64, get_cpu_color(cpu_percent))],
+            (cpu_total * 1000.0) as u64,
+            bar_length,
+            LABEL_WIDTH,
+            &cpu_text,
+        ),
+        create_segmented_bar(
+            "cMem",
+            &[(cgroup.memory, get_memory_color(cgroup.memory, memory_total))],
+            memory_total,
+            bar_length,
+            LABEL_WIDTH,
+            &memory_text,
+        ),
+    ]
+}
+
+/// Build the Dsk meter: combined read/write rate, scaled to the busiest
+/// refresh seen so far
+fn create_disk_bar(disk: &DiskMeter, bar_length: usize, label_width: usize) -> Line<'static> {
+    let Some(rates) = disk.rates() else {
+        return create_segmented_bar("Dsk", &[], 0, bar_length, label_width, "N/A");
+    };
+
+    let label_text = format!(
+        "r {}/s w {}/s",
+        format_bytes(rates.read_per_second as u64),
+        format_bytes(rates.written_per_second as u64)
+    );
+
+    create_segmented_bar(
+        "Dsk",
+        &[(rates.total() as u64, get_disk_color(rates.total()))],
+        disk.peak() as u64,
+        bar_length,
+        label_width,
+        &label_text,
+    )
+}
+
+/// Build the Bat meter: charge, charging state, time remaining by the
+/// battery's own estimate and at the current load, and cycles
+fn create_battery_bar(
+    battery: &Battery,
+    at_load: Option<f64>,
+    bar_length: usize,
+    label_width: usize,
+) -> Line<'static> {
+    let mut label_text = format!("{:.0}%", battery.percent);
+    match battery.state {
+        BatteryState::Charging => label_text.push_str(" charging"),
+        BatteryState::NotCharging => label_text.push_str(" on AC"),
+        BatteryState::Discharging => {}
     }
+    if let Some(minutes) = battery.minutes_remaining {
+        let target = match battery.state {
+            BatteryState::Charging => "to full",
+            _ => "left",
+        };
+        label_text.push_str(&format!(" {} {}", format_minutes(minutes), target));
+    }
+    if let Some(minutes) = at_load {
+        label_text.push_str(&format!(
+            ", {} at this load",
+            format_minutes(minutes as u64)
+        ));
+    }
+    if let Some(cycles) = battery.cycle_count {
+        label_text.push_str(&format!(", {} cycles", cycles));
+    }
+
+    // Low charge is the warning here, so the colors run the other way
+    let color = match battery.percent {
+        p if p <= BATTERY_LOW_THRESHOLD => theme().critical,
+        p if p <= BATTERY_MEDIUM_THRESHOLD => theme().warning,
+        _ => theme().good,
+    };
+
+    create_segmented_bar(
+        "Bat",
+        &[(battery.percent.round() as u64, color)],
+        100,
+        bar_length,
+        label_width,
+        &label_text,
+    )
 }
 
-fn create_memory_bar(
-    label: &str,
-    used: u64,
+/// Format a battery time as `H:MM`
+fn format_minutes(minutes: u64) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Build the segmented Mem bar (app, wired, compressed, cached) and its
+/// legend line
+fn create_memory_breakdown(
+    stats: &MemoryStats,
     total: u64,
     bar_length: usize,
     label_width: usize,
-) -> Line {
-    let label_text = format!("{}/{}", format_bytes(used), format_bytes(total));
-    let used_bars = if total > 0 {
-        ((used as f64 / total as f64) * bar_length as f64).round() as usize
-    } else {
-        0
-    };
+) -> Vec<Line<'static>> {
+    let segments = [
+        ("app", stats.app, theme().good),
+        ("wired", stats.wired, theme().info),
+        ("cmp", stats.compressed, theme().special),
+        ("cache", stats.cached, theme().warning),
+    ];
+    let label_text = format!("{}/{}", format_bytes(stats.used()), format_bytes(total));
 
-    let mut bar = create_progress_bar(used_bars, bar_length);
+    let bar = create_segmented_bar(
+        "Mem",
+        &segments.map(|(_, bytes, color)| (bytes, color)),
+        total,
+        bar_length,
+        label_width,
+        &label_text,
+    );
 
-    // Overlay label inside the bar
-    let label_start = if bar_length > label_text.len() {
-        bar_length - label_text.len()
-    } else {
-        0
+    let mut legend = vec![Span::raw(" ".repeat(label_width + 1))];
+    for (name, bytes, color) in segments {
+        legend.push(Span::styled("■ ", Style::default().fg(color)));
+        legend.push(Span::styled(
+            format!("{} {}  ", name, format_bytes(bytes)),
+            Style::default().fg(theme().dim),
+        ));
+    }
+
+    vec![bar, Line::from(legend)]
+}
+
+/// Build the line under the Swp bar with swap-in and swap-out rates, which
+/// turn red while the machine is thrashing
+fn create_swap_activity_line(rates: Option<SwapRates>, label_width: usize) -> Line<'static> {
+    let rate = |bytes: Option<f64>| match bytes {
+        Some(bytes) => Span::styled(
+            format!("{}/s", format_bytes(bytes as u64)),
+            Style::default().fg(match bytes {
+                b if b > SWAP_HIGH_THRESHOLD => theme().critical,
+                b if b > 0.0 => theme().warning,
+                _ => theme().dim,
+            }),
+        ),
+        None => Span::styled("-", Style::default().fg(theme().dim)),
     };
 
-    for (i, ch) in label_text.chars().enumerate() {
-        if label_start + i < bar.len() {
-            bar.replace_range(label_start + i..label_start + i + 1, &ch.to_string());
+    Line::from(vec![
+        Span::raw(" ".repeat(label_width + 1)),
+        Span::styled("swap in ", Style::default().fg(theme().dim)),
+        rate(rates.map(|rates| rates.in_per_second)),
+        Span::styled("  out ", Style::default().fg(theme().dim)),
+        rate(rates.map(|rates| rates.out_per_second)),
+    ])
+}
+
+/// Build the Swp bar with swap and compressor occupancy as separate segments
+fn create_swap_bar(
+    used_swap: u64,
+    total_swap: u64,
+    compressor: &CompressorStats,
+    bar_length: usize,
+    label_width: usize,
+) -> Line<'static> {
+    let label_text = format!(
+        "{}/{} cmp {} ({} pg)",
+        format_bytes(used_swap),
+        format_bytes(total_swap),
+        format_bytes(compressor.bytes),
+        compressor.pages
+    );
+    let scale = total_swap.max(used_swap + compressor.bytes);
+
+    create_segmented_bar(
+        "Swp",
+        &[
+            (used_swap, get_memory_color(used_swap, total_swap)),
+            (compressor.bytes, theme().special),
+        ],
+        scale,
+        bar_length,
+        label_width,
+        &label_text,
+    )
+}
+
+/// Build a bar made of consecutive colored segments with a right-aligned label
+fn create_segmented_bar(
+    label: &str,
+    segments: &[(u64, Color)],
+    total: u64,
+    bar_length: usize,
+    label_width: usize,
+    label_text: &str,
+) -> Line<'static> {
+    let mut cells: Vec<(char, Color)> = Vec::with_capacity(bar_length);
+
+    if total > 0 {
+        let mut cumulative = 0u64;
+        for &(value, color) in segments {
+            cumulative += value;
+            // Each segment fills up to where the running total ends; with
+            // block bars its last cell may be a partial block
+            let filled = bar_cells(cumulative as f64 / total as f64, bar_length);
+            for ch in filled.skip(cells.len()) {
+                cells.push((ch, color));
+            }
         }
     }
+    cells.resize(bar_length, (' ', theme().dim));
 
-    let color = get_memory_color(used, total);
+    // Overlay label inside the bar
+    let label_start = bar_length.saturating_sub(label_text.chars().count());
+    for (i, ch) in label_text.chars().enumerate() {
+        if let Some(cell) = cells.get_mut(label_start + i) {
+            cell.0 = ch;
+        }
+    }
 
-    Line::from(vec![
+    let mut spans = vec![
         Span::styled(
             format!("{:<width$}", label, width = label_width),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme().accent),
         ),
         Span::raw("["),
-        Span::styled(bar, Style::default().fg(color)),
-        Span::raw("]"),
-    ])
+    ];
+
+    // Group runs of the same color into a single span
+    let mut run = String::new();
+    let mut run_color = cells.first().map(|c| c.1).unwrap_or(theme().dim);
+    for (ch, color) in cells {
+        if color != run_color && !run.is_empty() {
+            spans.push(Span::styled(
+                std::mem::take(&mut run),
+                Style::default().fg(run_color),
+            ));
+        }
+        run_color = color;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, Style::default().fg(run_color)));
+    }
+
+    spans.push(Span::raw("]"));
+    Line::from(spans)
 }
 
-fn create_table_header() -> Row<'static> {
-    Row::new([
-        Cell::from("PID").bold(),
-        Cell::from("USER").bold(),
-        Cell::from("PRI").bold(),
-        Cell::from("NI").bold(),
-        Cell::from("VIRT").bold(),
-        Cell::from("RES").bold(),
-        Cell::from("S").bold(),
-        Cell::from("CPU% ").bold(),
-        Cell::from("MEM% ").bold(),
-        Cell::from("TIME+").bold(),
-        Cell::from("Command").bold(),
-    ])
-    .style(
-        Style::default()
-            .bg(Color::Rgb(200, 220, 180))
-            .fg(Color::Black),
-    ) // Header background
+/// Header for the process table's shown columns
+fn create_table_header(app_state: &AppState) -> Row<'static> {
+    // Highlight the column the table is sorted by, and underline the one
+    // breaking its ties
+    let sorts_by = |key: Option<SortKey>, title: &str| key.is_some_and(|key| key.title() == title);
+    Row::new(app_state.columns.iter().map(|column| {
+        let title = column.title();
+        // Titles line up with their values
+        let cell = if column.is_numeric() {
+            Cell::from(Line::from(title.trim_end()).right_aligned()).bold()
+        } else {
+            Cell::from(title).bold()
+        };
+        if sorts_by(Some(app_state.sort.primary), title.trim_end()) {
+            cell.bg(theme().accent)
+        } else if sorts_by(app_state.sort.secondary, title.trim_end()) {
+            cell.underlined()
+        } else {
+            cell
+        }
+    }))
+    .style(Style::default().bg(theme().header).fg(theme().on_highlight)) // Header background
 }
 
-fn get_table_constraints() -> [Constraint; 11] {
-    [
-        Constraint::Length(7),  // PID
-        Constraint::Length(12), // USER
-        Constraint::Length(5),  // PRI
-        Constraint::Length(4),  // NI
-        Constraint::Length(8),  // VIRT
-        Constraint::Length(8),  // RES
-        Constraint::Length(2),  // S
-        Constraint::Length(6),  // CPU%
-        Constraint::Length(6),  // MEM%
-        Constraint::Length(8),  // TIME+
-        Constraint::Min(10),    // Command
-    ]
+fn get_table_constraints(app_state: &AppState) -> Vec<Constraint> {
+    app_state
+        .columns
+        .iter()
+        .map(|column| match column {
+            Column::Command => Constraint::Min(column.width()),
+            // Room for the separators in a 7-digit ID
+            Column::Pid | Column::Ppid | Column::Pgid | Column::Sid
+                if app_state.config.thousands_separator.is_some() =>
+            {
+                Constraint::Length(column.width() + 2)
+            }
+            _ => Constraint::Length(column.width()),
+        })
+        .collect()
 }
 
-fn create_process_row<'a>(
-    index: usize,
-    process: &'a sysinfo::Process,
+/// Per-table lookups shared by every process row
+struct RowContext<'a> {
     uid_to_user: &'a HashMap<u32, String>,
-    priority_map: &'a HashMap<u32, crate::process::ProcessPriority>,
-    memory_map: &'a HashMap<u32, crate::process::ProcessMemory>,
+    priority_map: &'a HashMap<u32, sysly_core::process::ProcessPriority>,
+    memory_map: &'a HashMap<u32, sysly_core::process::ProcessMemory>,
     total_memory: f64,
     selected_row_index: usize,
-) -> Row<'a> {
+    watched: &'a [WatchedProcess],
+    tagged: &'a HashSet<Pid>,
+    time_format: TimeFormat,
+    /// Separator grouping the digits of IDs and counts
+    thousands_separator: Option<&'a str>,
+    columns: &'a [Column],
+    /// Idle wake-ups per second
+    wakeups: &'a HashMap<Pid, f64>,
+    origins: &'a OriginCache,
+    /// Energy impact by PID
+    energy: &'a HashMap<u32, f64>,
+    /// Disk read/write rates by PID
+    disk_io: &'a HashMap<u32, DiskIoRates>,
+    /// Scheduling class by PID
+    sched: &'a HashMap<u32, SchedInfo>,
+    /// App Nap state by PID
+    nap: &'a HashMap<u32, NapState>,
+    rosetta: &'a RosettaCache,
+    cpu_smoother: &'a CpuSmoother,
+    /// Container names by short ID
+    containers: &'a HashMap<String, String>,
+}
+
+/// Formatted cells of the process rows on screen, kept until the next
+/// process refresh so the frames in between only restyle them
+#[derive(Debug, Default)]
+pub struct RowCache {
+    rows: HashMap<Pid, CachedRow>,
+    /// Settings the cached cells were formatted with
+    columns: Vec<Column>,
+    time_format: TimeFormat,
+    thousands_separator: Option<String>,
+    theme: &'static str,
+}
+
+#[derive(Debug)]
+struct CachedRow {
+    /// Tree position the cells were built for, which changes without a refresh
+    /// when a branch is folded or the view switches
+    node: TreeNode,
+    watched: bool,
+    cells: Vec<ProcessCell>,
+}
+
+/// One column of a process row, with its text owned by the cache
+#[derive(Debug)]
+struct ProcessCell {
+    line: Line<'static>,
+    style: Style,
+}
+
+impl RowCache {
+    /// Drop every row, for when the processes have been read again
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Format the row for a process unless an up-to-date one is cached
+    fn update(&mut self, process: &ProcessSnapshot, node: &TreeNode, context: &RowContext) {
+        if self.columns != context.columns
+            || self.time_format != context.time_format
+            || self.thousands_separator.as_deref() != context.thousands_separator
+            || self.theme != theme().name
+        {
+            self.rows.clear();
+            self.columns = context.columns.to_vec();
+            self.time_format = context.time_format;
+            self.thousands_separator = context.thousands_separator.map(String::from);
+            self.theme = theme().name;
+        }
+
+        let watched = context.watched.iter().any(|w| w.pid == process.pid());
+        let fresh = self
+            .rows
+            .get(&process.pid())
+            .is_some_and(|row| row.node == *node && row.watched == watched);
+        if !fresh {
+            let cells = context
+                .columns
+                .iter()
+                .map(|&column| create_process_cell(column, process, node, context))
+                .collect();
+            self.rows.insert(
+                process.pid(),
+                CachedRow {
+                    node: node.clone(),
+                    watched,
+                    cells,
+                },
+            );
+        }
+    }
+
+    /// A row borrowing the cached text for a process, which `update` must
+    /// have formatted
+    fn row(&self, index: usize, pid: Pid, context: &RowContext) -> Row<'_> {
+        let cells = self.rows.get(&pid).into_iter().flat_map(|row| {
+            row.cells.iter().map(|cell| {
+                let spans = cell
+                    .line
+                    .spans
+                    .iter()
+                    .map(|span| Span::styled(span.content.as_ref(), span.style));
+                let mut line = Line::from(spans.collect::<Vec<_>>());
+                line.alignment = cell.line.alignment;
+                Cell::from(line).style(cell.style)
+            })
+        });
+
+        highlight_row(Row::new(cells), index, pid, context)
+    }
+}
+
+/// The cell one column shows for a process
+fn create_process_cell(
+    column: Column,
+    process: &ProcessSnapshot,
+    node: &TreeNode,
+    context: &RowContext,
+) -> ProcessCell {
     let pid = process.pid().as_u32();
-    let user = process
-        .user_id()
-        .and_then(|uid| uid_to_user.get(&uid))
-        .cloned()
-        .unwrap_or_else(|| "?".to_string());
+    let cell = |line: Line<'static>, style: Style| ProcessCell { line, style };
+    // Numbers are right-aligned so their digits line up down the column
+    let number = |text: String| Line::from(text).right_aligned();
+    let text = |text: String| cell(number(text), Style::default().fg(theme().text));
+    let id = |id: u64| match context.thousands_separator {
+        Some(separator) => group_digits(id, separator),
+        None => id.to_string(),
+    };
+    let disk_rate = |rate: Option<f64>| match rate {
+        Some(rate) => cell(
+            number(format_bytes(rate as u64)),
+            Style::default().fg(get_disk_color(rate)),
+        ),
+        None => cell(number("-".to_string()), Style::default().fg(theme().faint)),
+    };
+    let memory_info = || {
+        get_process_memory(
+            pid,
+            context.memory_map,
+            process.virtual_memory() / 1024,
+            process.memory() / 1024,
+        )
+    };
 
-    let priority_info = get_process_priority(pid, priority_map);
-    let memory_info = get_process_memory(
-        pid,
-        memory_map,
-        process.virtual_memory() / 1024,
-        process.memory() / 1024,
-    );
+    match column {
+        Column::Pid => {
+            // Watched processes stand out by PID color
+            let pid_color = if context.watched.iter().any(|w| w.pid == process.pid()) {
+                theme().special
+            } else {
+                theme().text
+            };
+            cell(number(id(pid as u64)), Style::default().fg(pid_color))
+        }
+        Column::Ppid => text(
+            process
+                .parent()
+                .map(|ppid| id(ppid.as_u32() as u64))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        Column::Pgid => text(
+            process
+                .process_group()
+                .map(|pgid| id(pgid.as_u32() as u64))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        Column::Sid => text(
+            process
+                .session_id()
+                .map(|sid| id(sid.as_u32() as u64))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        Column::User => {
+            let user = process
+                .user_id()
+                .and_then(|uid| context.uid_to_user.get(uid))
+                .cloned()
+                .unwrap_or_else(|| "?".to_string());
+            cell(Line::from(user), Style::default().fg(theme().accent))
+        }
+        Column::Priority => text(get_process_priority(pid, context.priority_map).priority),
+        Column::Nice => text(get_process_priority(pid, context.priority_map).nice),
+        Column::Virtual => cell(
+            number(format_bytes(memory_info().virtual_memory)),
+            Style::default().fg(theme().good),
+        ),
+        Column::Resident => cell(
+            number(format_bytes(memory_info().resident_memory)),
+            Style::default().fg(theme().good),
+        ),
+        Column::State => {
+            let status = get_process_status(process);
+            let style = get_status_color(&status);
+            cell(Line::from(status), style)
+        }
+        Column::Cpu => {
+            let cpu_usage = context.cpu_smoother.usage(process) + node.hidden_cpu;
+            cell(
+                number(format!("{:.1}", cpu_usage)),
+                get_usage_color(cpu_usage),
+            )
+        }
+        Column::Memory => {
+            let memory_usage = if context.total_memory > 0.0 {
+                ((process.memory() + node.hidden_memory) as f64 / context.total_memory) * 100.0
+            } else {
+                0.0
+            };
+            cell(
+                number(format!("{:.1}", memory_usage)),
+                get_usage_color(memory_usage as f32),
+            )
+        }
+        Column::Time => text(format_runtime(process.run_time(), context.time_format)),
+        Column::Started => text(format_timestamp(
+            process.start_time(),
+            chrono::Local::now().timestamp().max(0) as u64,
+            context.time_format,
+        )),
+        Column::IdleWakeups => {
+            let rate = context.wakeups.get(&process.pid()).copied();
+            let text = rate
+                .map(|rate| id(rate.round() as u64))
+                .unwrap_or_else(|| "-".to_string());
+            cell(number(text), get_wakeup_color(rate.unwrap_or(0.0)))
+        }
+        Column::Origin => {
+            let origin = context
+                .origins
+                .get(process.pid())
+                .unwrap_or(Origin::Unknown);
+            cell(Line::from(origin.code()), get_origin_color(origin))
+        }
+        Column::Energy => {
+            let impact = context.energy.get(&pid).copied();
+            let text = impact
+                .map(|impact| format!("{:.1}", impact))
+                .unwrap_or_else(|| "-".to_string());
+            cell(number(text), get_energy_color(impact.unwrap_or(0.0)))
+        }
+        Column::Qos => {
+            let info = context.sched.get(&pid);
+            let class = info.and_then(|info| info.class);
+            let label = class.map_or("-", SchedClass::label);
+            // A `+` marks a daemon running on a client's donated importance
+            if info.is_some_and(|info| info.boosted) {
+                cell(
+                    Line::from(format!("{}+", label)),
+                    get_sched_color(class).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                cell(Line::from(label), get_sched_color(class))
+            }
+        }
+        Column::Nap => {
+            let state = context.nap.get(&pid).copied();
+            cell(
+                Line::from(state.map_or("-", NapState::label)),
+                get_nap_color(state),
+            )
+        }
+        Column::DiskRead => disk_rate(context.disk_io.get(&pid).map(|rates| rates.read_per_second)),
+        Column::DiskWrite => disk_rate(
+            context
+                .disk_io
+                .get(&pid)
+                .map(|rates| rates.written_per_second),
+        ),
+        Column::Container => match container_label(process.pid(), context.containers) {
+            Some(container) => cell(Line::from(container), Style::default().fg(theme().special)),
+            None => cell(Line::from("-"), Style::default().fg(theme().faint)),
+        },
+        Column::Command => {
+            let prefix = if node.hidden_children > 0 {
+                format!("{}[+{}] ", node.prefix, node.hidden_children)
+            } else {
+                node.prefix.clone()
+            };
+            // x86_64 binaries translated by Rosetta 2 are marked after the tree lines
+            let mut command = vec![Span::raw(prefix)];
+            if context.rosetta.is_translated(process.pid()) {
+                command.push(Span::styled(
+                    "x86 ",
+                    Style::default().fg(theme().warning).bold(),
+                ));
+            }
+            command.push(Span::raw(process.cmd().join(" ")));
+            cell(Line::from(command), Style::default().fg(theme().accent))
+        }
+    }
+}
 
-    let status = get_process_status(process);
-    let cpu_usage = process.cpu_usage();
-    let memory_usage = if total_memory > 0.0 {
-        (process.memory() as f64 / total_memory) * 100.0
+/// A group's header row: its name, process count and subtotals under the
+/// columns they add up
+fn create_group_row<'a>(index: usize, header: &GroupHeader, context: &RowContext) -> Row<'a> {
+    let marker = if header.collapsed { '▸' } else { '▾' };
+    let label = format!(
+        "{} {} ({} processes)",
+        marker, header.name, header.processes
+    );
+    // The label goes under the command, or the last column without one
+    let label_column = if context.columns.contains(&Column::Command) {
+        Column::Command
+    } else {
+        context.columns.last().copied().unwrap_or(Column::Command)
+    };
+    let memory_usage = if context.total_memory > 0.0 {
+        header.memory as f64 / context.total_memory * 100.0
     } else {
         0.0
     };
-    let runtime = format_runtime(process.run_time());
-    let command = process.cmd().join(" ");
 
-    let cells = vec![
-        Cell::from(pid.to_string()).style(Style::default().fg(Color::White)),
-        Cell::from(user).style(Style::default().fg(Color::Cyan)),
-        Cell::from(priority_info.priority).style(Style::default().fg(Color::White)),
-        Cell::from(priority_info.nice).style(Style::default().fg(Color::White)),
-        Cell::from(format_bytes(memory_info.virtual_memory))
-            .style(Style::default().fg(Color::Green)),
-        Cell::from(format_bytes(memory_info.resident_memory))
-            .style(Style::default().fg(Color::Green)),
-        Cell::from(status.clone()).style(get_status_color(&status)),
-        Cell::from(format!("{:.1}", cpu_usage)).style(get_usage_color(cpu_usage)),
-        Cell::from(format!("{:.1}", memory_usage)).style(get_usage_color(memory_usage as f32)),
-        Cell::from(runtime).style(Style::default().fg(Color::White)),
-        Cell::from(command).style(Style::default().fg(Color::Cyan)),
-    ];
+    let cells = context.columns.iter().map(|&column| match column {
+        _ if column == label_column => Cell::from(label.clone()),
+        Column::Cpu => Cell::from(Line::from(format!("{:.1}", header.cpu)).right_aligned()),
+        Column::Memory => Cell::from(Line::from(format!("{:.1}", memory_usage)).right_aligned()),
+        Column::Resident => {
+            Cell::from(Line::from(format_bytes(header.memory / 1024)).right_aligned())
+        }
+        _ => Cell::from(""),
+    });
+
+    let style = if index == context.selected_row_index {
+        Style::default()
+            .bg(theme().selection)
+            .fg(theme().on_highlight)
+    } else {
+        Style::default().fg(theme().warning)
+    };
+    Row::new(cells).style(style.add_modifier(Modifier::BOLD))
+}
 
-    let mut row = Row::new(cells);
+/// Style a row for selection and tagging
+fn highlight_row<'a>(mut row: Row<'a>, index: usize, pid: Pid, context: &RowContext) -> Row<'a> {
+    let tagged = context.tagged.contains(&pid);
 
-    // Highlight selected row
-    if index == selected_row_index {
+    // Highlight selected row, then tagged rows
+    if index == context.selected_row_index {
+        let mut style = Style::default()
+            .bg(theme().selection)
+            .fg(theme().on_highlight);
+        if tagged {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        row = row.style(style);
+    } else if tagged {
         row = row.style(
             Style::default()
-                .bg(Color::Rgb(180, 220, 240))
-                .fg(Color::Black),
+                .bg(theme().tagged)
+                .add_modifier(Modifier::BOLD),
         );
     } else {
         row = row.style(Style::default());
@@ -500,28 +4409,343 @@ fn create_process_row<'a>(
     row
 }
 
-fn get_process_status(process: &sysinfo::Process) -> String {
-    match process.status().to_string().as_str() {
-        "Running" => "R".to_string(),
-        "Sleeping" => "S".to_string(),
-        "Zombie" => "Z".to_string(),
-        status => status.chars().next().unwrap_or('?').to_string(),
+fn create_io_header() -> Row<'static> {
+    let titles = [
+        "PID", "USER", "READ/s", "WRITE/s", "READ", "WRITE", "Command",
+    ];
+
+    Row::new(titles.map(|title| Cell::from(title).bold()))
+        .style(Style::default().bg(theme().header).fg(theme().on_highlight))
+}
+
+fn get_io_constraints() -> Vec<Constraint> {
+    vec![
+        Constraint::Length(7),  // PID
+        Constraint::Length(12), // USER
+        Constraint::Length(10), // READ/s
+        Constraint::Length(10), // WRITE/s
+        Constraint::Length(9),  // READ
+        Constraint::Length(9),  // WRITE
+        Constraint::Min(10),    // Command
+    ]
+}
+
+/// Build an I/O view row: current rates and totals since the process started
+fn create_io_row<'a>(
+    index: usize,
+    process: &'a ProcessSnapshot,
+    context: &RowContext,
+    interval: Duration,
+) -> Row<'a> {
+    let usage = process.disk_usage();
+    let user = process
+        .user_id()
+        .and_then(|uid| context.uid_to_user.get(uid))
+        .cloned()
+        .unwrap_or_else(|| "?".to_string());
+    let rate = |bytes: u64| {
+        let seconds = interval.as_secs_f64();
+        if seconds > 0.0 {
+            format!("{}/s", format_bytes((bytes as f64 / seconds) as u64))
+        } else {
+            "-".to_string()
+        }
+    };
+    let rate_color = |bytes: u64| {
+        if bytes > 0 {
+            theme().warning
+        } else {
+            theme().dim
+        }
+    };
+
+    let cells = vec![
+        Cell::from(process.pid().to_string()).style(Style::default().fg(theme().text)),
+        Cell::from(user).style(Style::default().fg(theme().accent)),
+        Cell::from(rate(usage.read_bytes)).style(Style::default().fg(rate_color(usage.read_bytes))),
+        Cell::from(rate(usage.written_bytes))
+            .style(Style::default().fg(rate_color(usage.written_bytes))),
+        Cell::from(format_bytes(usage.total_read_bytes)).style(Style::default().fg(theme().good)),
+        Cell::from(format_bytes(usage.total_written_bytes))
+            .style(Style::default().fg(theme().good)),
+        Cell::from(process.cmd().join(" ")).style(Style::default().fg(theme().accent)),
+    ];
+
+    highlight_row(Row::new(cells), index, process.pid(), context)
+}
+
+fn create_network_header(order: NetworkOrder) -> Row<'static> {
+    let titles = ["PID", "USER", "RECV/s", "SENT/s", "RECV", "SENT", "Command"];
+    let sorted: [&str; 2] = match order {
+        NetworkOrder::Total => ["RECV", "SENT"],
+        NetworkOrder::Rate => ["RECV/s", "SENT/s"],
+    };
+
+    // Highlight the columns the view is ranked by
+    Row::new(titles.map(|title| {
+        let cell = Cell::from(title).bold();
+        if sorted.contains(&title) {
+            cell.bg(theme().accent)
+        } else {
+            cell
+        }
+    }))
+    .style(Style::default().bg(theme().header).fg(theme().on_highlight))
+}
+
+fn get_network_constraints() -> Vec<Constraint> {
+    vec![
+        Constraint::Length(7),  // PID
+        Constraint::Length(12), // USER
+        Constraint::Length(10), // RECV/s
+        Constraint::Length(10), // SENT/s
+        Constraint::Length(9),  // RECV
+        Constraint::Length(9),  // SENT
+        Constraint::Min(10),    // Command
+    ]
+}
+
+/// Build a network view row: current rates and totals since sysly started
+fn create_network_row<'a>(
+    index: usize,
+    process: &'a ProcessSnapshot,
+    context: &RowContext,
+    usage: Option<&NetworkUsage>,
+) -> Row<'a> {
+    let user = process
+        .user_id()
+        .and_then(|uid| context.uid_to_user.get(uid))
+        .cloned()
+        .unwrap_or_else(|| "?".to_string());
+    let rates = usage.and_then(|usage| usage.rates);
+    let rate_cell = |rate: Option<f64>| match rate {
+        Some(rate) => Cell::from(format!("{}/s", format_bytes(rate as u64))).style(
+            Style::default().fg(if rate > 0.0 {
+                theme().warning
+            } else {
+                theme().dim
+            }),
+        ),
+        None => Cell::from("-").style(Style::default().fg(theme().dim)),
+    };
+    let total_cell =
+        |bytes: u64| Cell::from(format_bytes(bytes)).style(Style::default().fg(theme().good));
+
+    let cells = vec![
+        Cell::from(process.pid().to_string()).style(Style::default().fg(theme().text)),
+        Cell::from(user).style(Style::default().fg(theme().accent)),
+        rate_cell(rates.map(|(received, _)| received)),
+        rate_cell(rates.map(|(_, sent)| sent)),
+        total_cell(usage.map_or(0, |usage| usage.received)),
+        total_cell(usage.map_or(0, |usage| usage.sent)),
+        Cell::from(process.cmd().join(" ")).style(Style::default().fg(theme().accent)),
+    ];
+
+    highlight_row(Row::new(cells), index, process.pid(), context)
+}
+
+fn get_process_status(process: &ProcessSnapshot) -> String {
+    status_letter(process.status())
+}
+
+/// The one-letter state shown in the S column
+fn status_letter(status: ProcessStatus) -> String {
+    match status {
+        ProcessStatus::Run => "R".to_string(),
+        ProcessStatus::Sleep => "S".to_string(),
+        ProcessStatus::Stop => "T".to_string(),
+        ProcessStatus::Zombie => "Z".to_string(),
+        ProcessStatus::Idle => "I".to_string(),
+        ProcessStatus::UninterruptibleDiskSleep => "D".to_string(),
+        status => status.to_string().chars().next().unwrap_or('?').to_string(),
     }
 }
 
 fn get_status_color(status: &str) -> Style {
     match status {
-        "R" => Style::default().fg(Color::Yellow),
-        "S" => Style::default().fg(Color::Green),
-        "Z" => Style::default().fg(Color::Red),
-        _ => Style::default().fg(Color::Gray),
+        "R" => Style::default().fg(theme().warning),
+        "S" => Style::default().fg(theme().good),
+        "Z" => Style::default().fg(theme().critical),
+        "T" | "D" => Style::default().fg(theme().special),
+        _ => Style::default().fg(theme().dim),
+    }
+}
+
+fn get_wakeup_color(rate: f64) -> Style {
+    match rate {
+        r if r > WAKEUP_HIGH_THRESHOLD => Style::default().fg(theme().critical),
+        r if r > WAKEUP_MEDIUM_THRESHOLD => Style::default().fg(theme().warning),
+        _ => Style::default().fg(theme().text),
+    }
+}
+
+fn get_thermal_color(pressure: ThermalPressure) -> Color {
+    match pressure {
+        ThermalPressure::Nominal => theme().good,
+        ThermalPressure::Moderate => theme().warning,
+        ThermalPressure::Heavy => theme().severe,
+        ThermalPressure::Critical => theme().critical,
+    }
+}
+
+fn get_energy_color(impact: f64) -> Style {
+    match impact {
+        i if i > ENERGY_HIGH_THRESHOLD => Style::default().fg(theme().critical),
+        i if i > ENERGY_MEDIUM_THRESHOLD => Style::default().fg(theme().warning),
+        _ => Style::default().fg(theme().text),
+    }
+}
+
+/// Real-time and interactive work stands out; background work fades
+fn get_sched_color(class: Option<SchedClass>) -> Style {
+    match class {
+        Some(SchedClass::Deadline | SchedClass::Fifo | SchedClass::RoundRobin) => {
+            Style::default().fg(theme().critical)
+        }
+        Some(SchedClass::UserInteractive | SchedClass::UserInitiated) => {
+            Style::default().fg(theme().warning)
+        }
+        Some(SchedClass::Default) => Style::default().fg(theme().text),
+        _ => Style::default().fg(theme().faint),
+    }
+}
+
+/// Napping processes are dimmed like background work; throttled ones are
+/// flagged since they run slower than their CPU% suggests they could
+fn get_nap_color(state: Option<NapState>) -> Style {
+    match state {
+        Some(NapState::Napping) => Style::default().fg(theme().info),
+        Some(NapState::Throttled) => Style::default().fg(theme().warning),
+        None => Style::default().fg(theme().faint),
+    }
+}
+
+/// Processes from user paths stand out; system ones fade into the background
+fn get_origin_color(origin: Origin) -> Style {
+    match origin {
+        Origin::System => Style::default().fg(theme().faint),
+        Origin::AppStore => Style::default().fg(theme().good),
+        Origin::Installed => Style::default().fg(theme().text),
+        Origin::User => Style::default().fg(theme().warning),
+        Origin::Unknown => Style::default().fg(theme().faint),
     }
 }
 
-fn get_usage_color(usage: f32) -> Style {
+pub fn get_usage_color(usage: f32) -> Style {
     match usage {
-        u if u > PROCESS_HIGH_THRESHOLD => Style::default().fg(Color::Red),
-        u if u > PROCESS_MEDIUM_THRESHOLD => Style::default().fg(Color::Yellow),
-        _ => Style::default().fg(Color::White),
+        u if u > PROCESS_HIGH_THRESHOLD => Style::default().fg(theme().critical),
+        u if u > PROCESS_MEDIUM_THRESHOLD => Style::default().fg(theme().warning),
+        _ => Style::default().fg(theme().text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use sysly_core::collector::Collectors;
+    use sysly_core::provider::{Readings, ScriptedProvider, SystemProvider};
+    use sysly_core::snapshot::CpuSnapshot;
+
+    fn scripted_system() -> Snapshot {
+        let cpu = |name: &str, usage| CpuSnapshot {
+            name: name.to_string(),
+            usage,
+            ..CpuSnapshot::default()
+        };
+        let process = |pid, name: &str, cpu_usage, memory| ProcessSnapshot {
+            pid: Pid::from_u32(pid),
+            name: name.to_string(),
+            cmd: vec![format!("/usr/bin/{}", name)],
+            status: ProcessStatus::Run,
+            cpu_usage,
+            memory,
+            ..ProcessSnapshot::default()
+        };
+        Snapshot {
+            cpus: vec![cpu("cpu0", 12.5), cpu("cpu1", 87.5)],
+            global_cpu: cpu("cpu", 50.0),
+            total_memory: 8 << 30,
+            used_memory: 2 << 30,
+            processes: [
+                process(101, "scripted-busy", 42.0, 512 << 20),
+                process(202, "scripted-idle", 0.5, 64 << 20),
+            ]
+            .into_iter()
+            .map(|process| (process.pid, process))
+            .collect(),
+            ..Snapshot::default()
+        }
+    }
+
+    fn render(system: &Snapshot, app_state: &mut AppState) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(140, 40)).unwrap();
+        terminal
+            .draw(|frame| draw_dashboard(frame, system, frame.size(), app_state))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dashboard_draws_the_scripted_processes_and_cpus() {
+        let mut provider = ScriptedProvider::new([(scripted_system(), Readings::default())]);
+        let system = provider.snapshot(&mut Collectors::default());
+        let mut app_state = AppState::default();
+        app_state.columns = app_state.config.columns.clone();
+
+        let screen = render(&system, &mut app_state);
+
+        let row = |name| {
+            screen
+                .iter()
+                .find(|line| line.contains(name))
+                .unwrap_or_else(|| panic!("no row for {}:\n{}", name, screen.join("\n")))
+        };
+        assert!(row("scripted-busy").contains("101"));
+        assert!(row("scripted-busy").contains("42.0"));
+        assert!(row("scripted-idle").contains("202"));
+        let busy = screen
+            .iter()
+            .position(|line| line.contains("scripted-busy"));
+        let idle = screen
+            .iter()
+            .position(|line| line.contains("scripted-idle"));
+        assert!(busy < idle, "sorted by CPU:\n{}", screen.join("\n"));
+        assert!(
+            screen.iter().any(|line| line.contains("87.5")),
+            "no CPU meter reading:\n{}",
+            screen.join("\n")
+        );
+    }
+
+    #[test]
+    fn cached_rows_follow_the_filters() {
+        let system = scripted_system();
+        let mut app_state = AppState::default();
+        let pids = |app_state: &AppState| -> Vec<u32> {
+            display_rows(&system, app_state)
+                .iter()
+                .filter_map(|row| row.pid().map(Pid::as_u32))
+                .collect()
+        };
+        assert_eq!(pids(&app_state), [101, 202]);
+
+        // Matched against the command line, whatever the case
+        app_state.name_filter = Some("BIN/SCRIPTED-IDLE".to_string());
+        assert_eq!(pids(&app_state), [202]);
+        app_state.name_filter = None;
+        app_state.row_limit = Some(1);
+        assert_eq!(pids(&app_state), [101]);
+        app_state.row_limit = None;
+        app_state.user_filter = Some(0);
+        assert!(pids(&app_state).is_empty());
     }
 }
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use sysinfo::Pid;
+use sysly_core::snapshot::{ProcessSnapshot, Snapshot};
+
+/// A watched process and the last incarnation seen of it
+#[derive(Debug, Clone)]
+pub struct WatchedProcess {
+    pub name: String,
+    pub pid: Pid,
+    pub parent: Option<Pid>,
+    pub exe: Option<PathBuf>,
+    pub start_time: u64,
+    pub cmd: Vec<String>,
+    pub environ: Vec<String>,
+}
+
+/// A single changed line in a respawn diff
+#[derive(Debug, Clone)]
+pub enum DiffLine {
+    Added(String),
+    Removed(String),
+}
+
+/// Differences between two incarnations of a watched process
+#[derive(Debug, Clone)]
+pub struct RespawnDiff {
+    pub name: String,
+    pub old_pid: Pid,
+    pub new_pid: Pid,
+    pub cmd_changes: Vec<DiffLine>,
+    pub env_changes: Vec<DiffLine>,
+}
+
+impl WatchedProcess {
+    /// Capture the identity, command line, and environment of a process
+    pub fn from_process(process: &ProcessSnapshot) -> Self {
+        WatchedProcess {
+            name: process.name().to_string(),
+            pid: process.pid(),
+            parent: process.parent(),
+            exe: process.exe().map(PathBuf::from),
+            start_time: process.start_time(),
+            cmd: process.cmd().to_vec(),
+            environ: process.environ().to_vec(),
+        }
+    }
+}
+
+/// Check watched processes for respawns after a refresh
+///
+/// A watched process has respawned when its PID is gone and a process with
+/// the same name started since it did. Of several, the one with the same
+/// executable and parent is taken over one that merely shares the name, and
+/// the newest over older ones. The watch then follows the new PID.
+///
+/// # Arguments
+/// * `watched` - Watched processes, updated in place to their latest incarnation
+/// * `sys` - Freshly refreshed system snapshot
+///
+/// # Returns
+/// A diff for every watched process that respawned since the last check
+pub fn check_respawns(watched: &mut [WatchedProcess], sys: &Snapshot) -> Vec<RespawnDiff> {
+    let mut diffs = Vec::new();
+
+    for entry in watched.iter_mut() {
+        if let Some(process) = sys.process(entry.pid) {
+            // Still alive: keep the snapshot current so the diff reflects the
+            // last known state before exit
+            if !process.environ().is_empty() {
+                entry.environ = process.environ().to_vec();
+            }
+            continue;
+        }
+
+        let successor = sys
+            .processes()
+            .values()
+            .filter(|p| {
+                p.name() == entry.name && p.pid() != entry.pid && p.start_time() >= entry.start_time
+            })
+            .max_by_key(|p| {
+                (
+                    entry.exe.is_some() && p.exe() == entry.exe.as_deref(),
+                    entry.parent.is_some() && p.parent() == entry.parent,
+                    p.start_time(),
+                )
+            });
+
+        if let Some(process) = successor {
+            let next = WatchedProcess::from_process(process);
+            diffs.push(RespawnDiff {
+                name: entry.name.clone(),
+                old_pid: entry.pid,
+                new_pid: next.pid,
+                cmd_changes: diff_cmd(&entry.cmd, &next.cmd),
+                env_changes: diff_environ(&entry.environ, &next.environ),
+            });
+            *entry = next;
+        }
+    }
+
+    diffs
+}
+
+/// Compare two command lines as a whole
+fn diff_cmd(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    if old == new {
+        return Vec::new();
+    }
+
+    vec![
+        DiffLine::Removed(old.join(" ")),
+        DiffLine::Added(new.join(" ")),
+    ]
+}
+
+/// Compare two environments key by key, reporting added, removed, and
+/// changed variables
+///
+/// Values of variables that look like credentials are left out, so a
+/// rotated token shows as changed without appearing on screen.
+fn diff_environ(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let old_vars = parse_environ(old);
+    let new_vars = parse_environ(new);
+    let mut changes = Vec::new();
+
+    for (key, old_value) in &old_vars {
+        match new_vars.get(key) {
+            Some(new_value) if new_value == old_value => {}
+            Some(new_value) => {
+                changes.push(DiffLine::Removed(variable(key, old_value)));
+                changes.push(DiffLine::Added(variable(key, new_value)));
+            }
+            None => changes.push(DiffLine::Removed(variable(key, old_value))),
+        }
+    }
+
+    for (key, new_value) in &new_vars {
+        if !old_vars.contains_key(key) {
+            changes.push(DiffLine::Added(variable(key, new_value)));
+        }
+    }
+
+    changes
+}
+
+/// Name fragments of variables whose values are kept off screen
+const SECRET_NAMES: [&str; 4] = ["TOKEN", "SECRET", "KEY", "PASSWORD"];
+
+/// A variable as the diff shows it, with secret values redacted
+fn variable(key: &str, value: &str) -> String {
+    let upper = key.to_ascii_uppercase();
+    if SECRET_NAMES.iter().any(|name| upper.contains(name)) {
+        format!("{}=<redacted>", key)
+    } else {
+        format!("{}={}", key, value)
+    }
+}
+
+fn parse_environ(environ: &[String]) -> BTreeMap<&str, &str> {
+    environ
+        .iter()
+        .filter_map(|entry| entry.split_once('='))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent: u32, exe: &str, start_time: u64) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid: Pid::from_u32(pid),
+            parent: Some(Pid::from_u32(parent)),
+            name: "worker".to_string(),
+            exe: Some(PathBuf::from(exe)),
+            start_time,
+            ..ProcessSnapshot::default()
+        }
+    }
+
+    fn system(processes: Vec<ProcessSnapshot>) -> Snapshot {
+        Snapshot {
+            processes: processes
+                .into_iter()
+                .map(|process| (process.pid, process))
+                .collect(),
+            ..Snapshot::default()
+        }
+    }
+
+    #[test]
+    fn respawns_follow_the_newest_process_from_the_same_exe_and_parent() {
+        let watched_process = process(10, 1, "/usr/bin/worker", 100);
+        let mut watched = vec![WatchedProcess::from_process(&watched_process)];
+
+        // Still running: an unrelated namesake isn't a respawn
+        let running = system(vec![watched_process, process(11, 5, "/opt/worker", 200)]);
+        assert!(check_respawns(&mut watched, &running).is_empty());
+
+        let respawned = system(vec![
+            process(9, 1, "/usr/bin/worker", 50),
+            process(11, 5, "/opt/worker", 200),
+            process(12, 1, "/usr/bin/worker", 150),
+            process(13, 1, "/usr/bin/worker", 160),
+        ]);
+        let diffs = check_respawns(&mut watched, &respawned);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].new_pid, Pid::from_u32(13));
+        assert_eq!(watched[0].pid, Pid::from_u32(13));
+    }
+
+    #[test]
+    fn secret_variables_are_redacted() {
+        let old = vec!["API_TOKEN=abc".to_string(), "MODE=slow".to_string()];
+        let new = vec!["API_TOKEN=def".to_string(), "MODE=fast".to_string()];
+        let lines: Vec<String> = diff_environ(&old, &new)
+            .into_iter()
+            .map(|line| match line {
+                DiffLine::Added(text) => format!("+{}", text),
+                DiffLine::Removed(text) => format!("-{}", text),
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "-API_TOKEN=<redacted>",
+                "+API_TOKEN=<redacted>",
+                "-MODE=slow",
+                "+MODE=fast"
+            ]
+        );
+    }
+}
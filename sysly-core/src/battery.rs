@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "macos")]
+use crate::helpers::ioreg_property;
+
+/// Whether the battery is charging, draining, or idle on external power
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    /// Fully charged, or held below full on external power
+    NotCharging,
+}
+
+/// Charge and health of the built-in battery
+#[derive(Debug, Clone, Copy)]
+pub struct Battery {
+    /// Charge, 0–100
+    pub percent: f32,
+    pub state: BatteryState,
+    /// Minutes to empty while discharging or to full while charging; None
+    /// while the system is still estimating
+    pub minutes_remaining: Option<u64>,
+    pub cycle_count: Option<u64>,
+    /// Energy left, in watt-hours
+    pub energy_wh: Option<f64>,
+    /// Power flowing out of or into the battery right now, in watts
+    pub power_watts: Option<f64>,
+}
+
+/// Read the battery from the `AppleSmartBattery` IOKit service
+///
+/// # Returns
+/// Battery status, or None on machines without a battery
+#[cfg(target_os = "macos")]
+pub fn fetch_battery() -> Option<Battery> {
+    let output = Command::new("ioreg")
+        .args(["-r", "-c", "AppleSmartBattery", "-w", "0"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let number = |key: &str| ioreg_property(&stdout, key)?.parse::<u64>().ok();
+    // Current is signed but may be printed as its unsigned 64-bit pattern
+    let signed = |key: &str| {
+        let value = ioreg_property(&stdout, key)?;
+        value
+            .parse::<i64>()
+            .ok()
+            .or_else(|| value.parse::<u64>().ok().map(|value| value as i64))
+    };
+    let flag = |key: &str| ioreg_property(&stdout, key) == Some("Yes");
+
+    if !flag("BatteryInstalled") {
+        return None;
+    }
+
+    // Apple Silicon reports CurrentCapacity as a percentage already, with
+    // MaxCapacity at 100; Intel Macs report both in mAh
+    let percent = number("CurrentCapacity")? as f32 * 100.0 / number("MaxCapacity")?.max(1) as f32;
+    let state = if flag("IsCharging") {
+        BatteryState::Charging
+    } else if flag("ExternalConnected") {
+        BatteryState::NotCharging
+    } else {
+        BatteryState::Discharging
+    };
+
+    // 65535 means the estimate isn't ready yet
+    let minutes_remaining = match state {
+        BatteryState::Charging => number("AvgTimeToFull"),
+        BatteryState::Discharging => number("AvgTimeToEmpty"),
+        BatteryState::NotCharging => None,
+    }
+    .filter(|minutes| *minutes < 65535);
+
+    // Capacity in mAh, voltage in mV and current in mA
+    let voltage = number("Voltage").map(|mv| mv as f64 / 1000.0);
+    let energy_wh = number("AppleRawCurrentCapacity")
+        .zip(voltage)
+        .map(|(mah, volts)| mah as f64 / 1000.0 * volts);
+    let power_watts = signed("InstantAmperage")
+        .or_else(|| signed("Amperage"))
+        .zip(voltage)
+        .map(|(ma, volts)| ma.unsigned_abs() as f64 / 1000.0 * volts);
+
+    Some(Battery {
+        percent: percent.min(100.0),
+        state,
+        minutes_remaining,
+        cycle_count: number("CycleCount"),
+        energy_wh,
+        power_watts,
+    })
+}
+
+/// Read the first battery under `/sys/class/power_supply`
+///
+/// The time remaining is estimated from the present energy (or charge)
+/// level and draw, as the kernel doesn't report it directly.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_battery() -> Option<Battery> {
+    let supply = std::fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            std::fs::read_to_string(path.join("type")).is_ok_and(|kind| kind.trim() == "Battery")
+        })?;
+    let read = |name: &str| std::fs::read_to_string(supply.join(name)).ok();
+    let number = |name: &str| read(name)?.trim().parse::<u64>().ok();
+
+    let state = match read("status")?.trim() {
+        "Charging" => BatteryState::Charging,
+        "Discharging" => BatteryState::Discharging,
+        _ => BatteryState::NotCharging,
+    };
+
+    // Energy is in µWh and power in µW; some batteries report µAh and µA,
+    // which take the voltage in µV to convert
+    let (now, full, rate, volts) = match (number("energy_now"), number("energy_full")) {
+        (Some(now), Some(full)) => (now, full, number("power_now"), Some(1.0)),
+        _ => (
+            number("charge_now")?,
+            number("charge_full")?,
+            number("current_now"),
+            number("voltage_now").map(|microvolts| microvolts as f64 / 1e6),
+        ),
+    };
+    let minutes_remaining = rate.filter(|rate| *rate > 0).and_then(|rate| match state {
+        BatteryState::Charging => Some(full.saturating_sub(now) * 60 / rate),
+        BatteryState::Discharging => Some(now * 60 / rate),
+        BatteryState::NotCharging => None,
+    });
+
+    Some(Battery {
+        percent: number("capacity")? as f32,
+        state,
+        minutes_remaining,
+        cycle_count: number("cycle_count").filter(|count| *count > 0),
+        energy_wh: volts.map(|volts| now as f64 / 1e6 * volts),
+        power_watts: rate
+            .zip(volts)
+            .map(|(rate, volts)| rate as f64 / 1e6 * volts),
+    })
+}
+
+/// How far back the discharge trend looks
+const TREND_WINDOW: Duration = Duration::from_secs(600);
+
+/// Shortest stretch of discharging that gives a usable trend
+const MIN_TREND_SPAN: Duration = Duration::from_secs(60);
+
+/// Recent charge readings while on battery, for a time-remaining estimate
+/// that follows the current load rather than the battery's long average
+#[derive(Debug, Default)]
+pub struct BatteryTrend {
+    /// Charge in percent, oldest first
+    samples: VecDeque<(Instant, f32)>,
+}
+
+impl BatteryTrend {
+    /// Add a reading; plugging in starts the trend over
+    pub fn record(&mut self, battery: Option<&Battery>) {
+        let Some(battery) = battery.filter(|b| b.state == BatteryState::Discharging) else {
+            self.samples.clear();
+            return;
+        };
+        let now = Instant::now();
+        self.samples.push_back((now, battery.percent));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > TREND_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Minutes until empty at the rate the charge fell over the window
+    fn trend_minutes(&self) -> Option<f64> {
+        let (&(first_at, first), &(last_at, last)) = (self.samples.front()?, self.samples.back()?);
+        let span = last_at.duration_since(first_at);
+        let drop = (first - last) as f64;
+        if span < MIN_TREND_SPAN || drop <= 0.0 {
+            return None;
+        }
+        let percent_per_minute = drop / span.as_secs_f64() * 60.0;
+        Some(last as f64 / percent_per_minute)
+    }
+
+    /// Minutes until empty at the current load
+    ///
+    /// Averages the recent discharge trend with the energy left divided by
+    /// the present draw: the draw reacts at once to a change in load, and
+    /// the trend evens out its spikes.
+    pub fn minutes_at_load(&self, battery: &Battery) -> Option<f64> {
+        if battery.state != BatteryState::Discharging {
+            return None;
+        }
+        let at_draw = battery
+            .energy_wh
+            .zip(battery.power_watts)
+            .filter(|(_, watts)| *watts > 0.0)
+            .map(|(wh, watts)| wh / watts * 60.0);
+
+        match (self.trend_minutes(), at_draw) {
+            (Some(trend), Some(at_draw)) => Some((trend + at_draw) / 2.0),
+            (trend, at_draw) => trend.or(at_draw),
+        }
+    }
+}
+
+/// Extra minutes on battery if work using `share` of the power stopped
+pub fn minutes_gained(minutes: f64, share: f64) -> Option<f64> {
+    (share > 0.0 && share < 1.0).then(|| minutes * share / (1.0 - share))
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use sysinfo::Pid;
+
+use crate::snapshot::Snapshot;
+use crate::startup::service_program;
+
+/// A service managed by `brew services`
+#[derive(Debug, Clone)]
+pub struct BrewService {
+    pub name: String,
+    /// As `brew services` reports it: `started`, `stopped`, `scheduled`,
+    /// `error`, `none` or `unknown`
+    pub status: String,
+    /// Exit code of a service in the `error` state
+    pub exit_code: Option<i32>,
+    /// User the service runs as, when it is loaded
+    pub user: Option<String>,
+    /// The launchd property list or systemd unit brew wrote for it
+    pub file: Option<PathBuf>,
+    /// The program the service file starts, with symlinks resolved
+    pub program: Option<PathBuf>,
+}
+
+impl BrewService {
+    /// Find the running process started from this service's program
+    ///
+    /// Homebrew's service files point into `opt/`, which links into the
+    /// Cellar, so the program is compared after resolving links.
+    pub fn running_process(&self, sys: &Snapshot) -> Option<Pid> {
+        let program = self.program.as_deref()?;
+        sys.processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none())
+            .find(|process| process.exe() == Some(program))
+            .map(|process| process.pid())
+    }
+}
+
+/// List the services `brew services` manages
+///
+/// # Returns
+/// The services, or None when Homebrew isn't installed
+pub fn fetch_brew_services() -> Option<Vec<BrewService>> {
+    let output = Command::new("brew")
+        .args(["services", "list"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // "Name          Status  User  File", then one line per service; User
+    // and File are empty for services that were never started
+    let services = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(parse_service)
+        .collect();
+    Some(services)
+}
+
+/// Parse a line of `brew services list`, such as
+/// `postgresql@16 started alice ~/Library/LaunchAgents/homebrew.mxcl.postgresql@16.plist`
+/// or `redis error  256 root /Library/LaunchDaemons/homebrew.mxcl.redis.plist`
+fn parse_service(line: &str) -> Option<BrewService> {
+    let mut fields = line.split_whitespace().peekable();
+    let name = fields.next()?.to_string();
+    let status = fields.next()?.to_string();
+    let exit_code = fields.next_if(|field| field.parse::<i32>().is_ok());
+    let mut rest: Vec<&str> = fields.collect();
+
+    let file = rest
+        .last()
+        .filter(|field| field.starts_with('/') || field.starts_with('~'))
+        .map(|field| expand_home(field));
+    if file.is_some() {
+        rest.pop();
+    }
+    let program = file
+        .as_deref()
+        .and_then(service_program)
+        .map(|program| fs::canonicalize(&program).unwrap_or(program));
+
+    Some(BrewService {
+        name,
+        status,
+        exit_code: exit_code.and_then(|code| code.parse().ok()),
+        user: rest.first().map(|user| user.to_string()),
+        file,
+        program,
+    })
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Start `brew services restart` for a service without waiting for it
+///
+/// # Returns
+/// The running `brew` command, to poll for when it finishes
+pub fn restart_service(name: &str) -> io::Result<Child> {
+    Command::new("brew")
+        .args(["services", "restart", name])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::disk::{Inodes, Volume};
+use crate::topology::CoreCluster;
+
+/// Bumped whenever the file layout changes; older files are ignored
+const FORMAT_VERSION: &str = "1";
+
+/// A temperature sensor known from a previous run
+#[derive(Debug, Clone)]
+pub struct KnownSensor {
+    pub label: String,
+    pub critical: Option<f32>,
+}
+
+/// Slowly-changing system data saved between runs
+///
+/// Reading the user table, enumerating sensors and listing volumes can take
+/// seconds on some machines. With the previous run's inventory the first
+/// frame draws complete immediately, and the live data replaces it after
+/// the first full refresh.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    pub users: HashMap<u32, String>,
+    pub volumes: Vec<Volume>,
+    pub sensors: Vec<KnownSensor>,
+    pub core_clusters: Vec<CoreCluster>,
+}
+
+impl Inventory {
+    /// Read the inventory saved by the last run, if there is a usable one
+    pub fn load() -> Option<Self> {
+        let contents = fs::read_to_string(inventory_path()?).ok()?;
+        parse_inventory(&contents)
+    }
+
+    /// Write the inventory for the next run to start from
+    pub fn save(&self) -> io::Result<()> {
+        let path = inventory_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cache directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write a sibling file and rename it, so a concurrent start never
+        // reads half a file
+        let partial = path.with_extension("partial");
+        fs::write(&partial, self.to_string())?;
+        fs::rename(partial, path)
+    }
+}
+
+impl std::fmt::Display for Inventory {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "version\t{}", FORMAT_VERSION)?;
+
+        let mut uids: Vec<&u32> = self.users.keys().collect();
+        uids.sort();
+        for uid in uids {
+            let name = &self.users[uid];
+            if is_field(name) {
+                writeln!(f, "user\t{}\t{}", uid, name)?;
+            }
+        }
+        for volume in self
+            .volumes
+            .iter()
+            .filter(|v| is_field(&v.mount_point) && is_field(&v.file_system))
+        {
+            let (inodes_total, inodes_free) = volume
+                .inodes
+                .map_or((String::new(), String::new()), |inodes| {
+                    (inodes.total.to_string(), inodes.free.to_string())
+                });
+            writeln!(
+                f,
+                "volume\t{}\t{}\t{}\t{}\t{}\t{}",
+                volume.mount_point,
+                volume.file_system,
+                volume.total,
+                volume.available,
+                inodes_total,
+                inodes_free
+            )?;
+        }
+        for sensor in self.sensors.iter().filter(|s| is_field(&s.label)) {
+            let critical = sensor.critical.map(|c| c.to_string()).unwrap_or_default();
+            writeln!(f, "sensor\t{}\t{}", sensor.label, critical)?;
+        }
+        for cluster in &self.core_clusters {
+            let cpus: Vec<String> = cluster.cpus.iter().map(usize::to_string).collect();
+            writeln!(f, "cluster\t{}\t{}", cluster.name, cpus.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the user table, which goes through Directory Services on macOS
+pub fn fetch_users() -> HashMap<u32, String> {
+    unsafe { users::all_users() }
+        .map(|user| (user.uid(), user.name().to_string_lossy().to_string()))
+        .collect()
+}
+
+/// Whether a value can be stored as one tab-separated field
+fn is_field(value: &str) -> bool {
+    !value.is_empty() && !value.contains(['\t', '\n', '\r'])
+}
+
+/// Parse a saved inventory, skipping records that don't parse
+///
+/// Returns None for files written by a different version of the format.
+fn parse_inventory(contents: &str) -> Option<Inventory> {
+    let mut lines = contents.lines();
+    if lines.next()? != format!("version\t{}", FORMAT_VERSION) {
+        return None;
+    }
+
+    let mut inventory = Inventory::default();
+    for line in lines {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields.as_slice() {
+            ["user", uid, name] => {
+                if let Ok(uid) = uid.parse() {
+                    inventory.users.insert(uid, name.to_string());
+                }
+            }
+            ["volume", mount_point, file_system, total, available, inodes_total, inodes_free] => {
+                let (Ok(total), Ok(available)) = (total.parse(), available.parse()) else {
+                    continue;
+                };
+                let inodes = match (inodes_total.parse(), inodes_free.parse()) {
+                    (Ok(total), Ok(free)) => Some(Inodes { total, free }),
+                    _ => None,
+                };
+                inventory.volumes.push(Volume {
+                    mount_point: mount_point.to_string(),
+                    file_system: file_system.to_string(),
+                    total,
+                    available,
+                    inodes,
+                });
+            }
+            ["sensor", label, critical] => inventory.sensors.push(KnownSensor {
+                label: label.to_string(),
+                critical: critical.parse().ok(),
+            }),
+            ["cluster", name, cpus] => {
+                // Cluster names are a fixed pair, so map back to the statics
+                let name = match *name {
+                    "Performance" => "Performance",
+                    "Efficiency" => "Efficiency",
+                    _ => continue,
+                };
+                let Ok(cpus) = cpus.split(',').map(str::parse).collect() else {
+                    continue;
+                };
+                inventory.core_clusters.push(CoreCluster { name, cpus });
+            }
+            _ => {}
+        }
+    }
+
+    Some(inventory)
+}
+
+/// Location of the inventory, honoring `XDG_CACHE_HOME`
+fn inventory_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    Some(base.join("sysly").join("inventory"))
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Where the unified (v2) cgroup hierarchy is mounted
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Deepest level listed in the cgroups view: slices and the services or
+/// scopes directly inside them
+const MAX_GROUP_DEPTH: usize = 2;
+
+/// Limits and usage of one cgroup v2 group
+#[derive(Debug, Clone)]
+pub struct CgroupUsage {
+    /// Path inside the hierarchy, e.g. `/system.slice/sshd.service`
+    pub path: String,
+    /// CPU time used per second, in CPUs; None until sampled twice
+    pub cpu_cores: Option<f64>,
+    /// `cpu.max` quota in CPUs, None when unlimited
+    pub cpu_limit: Option<f64>,
+    pub memory: u64,
+    /// `memory.max`, None when unlimited
+    pub memory_limit: Option<u64>,
+    /// Processes and threads in the group (`pids.current`)
+    pub tasks: Option<u64>,
+}
+
+impl CgroupUsage {
+    /// How deep the group sits below the root
+    pub fn depth(&self) -> usize {
+        self.path.matches('/').count()
+    }
+
+    /// Last path component, e.g. `sshd.service`
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Whether the unified hierarchy is mounted
+fn has_cgroup_v2() -> bool {
+    Path::new(CGROUP_ROOT).join("cgroup.controllers").exists()
+}
+
+/// The cgroup sysly runs in, from the `0::` entry of `/proc/self/cgroup`
+///
+/// # Returns
+/// The group's path, or None without cgroup v2 or when sysly sits in the
+/// root group, whose limits are the machine's own
+pub fn current_cgroup() -> Option<String> {
+    if !has_cgroup_v2() {
+        return None;
+    }
+    let contents = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let path = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?
+        .trim();
+    (path != "/").then(|| path.to_string())
+}
+
+/// List the slices and the groups directly inside them
+///
+/// # Returns
+/// Group paths in hierarchy order, empty without cgroup v2
+pub fn list_cgroups() -> Vec<String> {
+    if !has_cgroup_v2() {
+        return Vec::new();
+    }
+    let mut groups = Vec::new();
+    collect_groups(Path::new(CGROUP_ROOT), "", 1, &mut groups);
+    groups
+}
+
+fn collect_groups(directory: &Path, prefix: &str, depth: usize, groups: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    let mut children: Vec<(PathBuf, String)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            (entry.path(), format!("{}/{}", prefix, name))
+        })
+        .collect();
+    children.sort_by(|a, b| a.1.cmp(&b.1));
+
+    for (child, path) in children {
+        groups.push(path.clone());
+        if depth < MAX_GROUP_DEPTH {
+            collect_groups(&child, &path, depth + 1, groups);
+        }
+    }
+}
+
+/// Turns each group's cumulative CPU time into CPUs in use between samples
+#[derive(Debug, Default)]
+pub struct CgroupTracker {
+    /// `usage_usec` from `cpu.stat` at the previous sample
+    previous: HashMap<String, (u64, Instant)>,
+}
+
+impl CgroupTracker {
+    /// Read the limits and usage of the given groups
+    ///
+    /// Groups that can't be read, e.g. because they vanished, are left out,
+    /// and groups not sampled this time are forgotten.
+    pub fn sample(&mut self, paths: &[String]) -> Vec<CgroupUsage> {
+        let now = Instant::now();
+        let mut previous = std::mem::take(&mut self.previous);
+
+        paths
+            .iter()
+            .filter_map(|path| {
+                let directory = Path::new(CGROUP_ROOT).join(path.trim_start_matches('/'));
+                let read = |file: &str| fs::read_to_string(directory.join(file)).ok();
+
+                let memory = read("memory.current")?.trim().parse().ok()?;
+                let usage = read("cpu.stat").and_then(|stat| {
+                    stat.lines().find_map(|line| {
+                        line.strip_prefix("usage_usec ")?.trim().parse::<u64>().ok()
+                    })
+                });
+                let cpu_cores = usage.and_then(|usage| {
+                    self.previous.insert(path.clone(), (usage, now));
+                    let (before, at) = previous.remove(path)?;
+                    let seconds = now.duration_since(at).as_secs_f64();
+                    (seconds > 0.0)
+                        .then(|| usage.saturating_sub(before) as f64 / 1_000_000.0 / seconds)
+                });
+
+                Some(CgroupUsage {
+                    path: path.clone(),
+                    cpu_cores,
+                    cpu_limit: read("cpu.max").and_then(|max| parse_cpu_max(&max)),
+                    memory,
+                    memory_limit: read("memory.max").and_then(|max| max.trim().parse().ok()),
+                    tasks: read("pids.current").and_then(|tasks| tasks.trim().parse().ok()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse `cpu.max` ("QUOTA PERIOD", or "max PERIOD" when unlimited) into CPUs
+fn parse_cpu_max(contents: &str) -> Option<f64> {
+    let mut fields = contents.split_whitespace();
+    let quota: f64 = fields.next()?.parse().ok()?;
+    let period: f64 = fields.next()?.parse().ok()?;
+    (period > 0.0).then(|| quota / period)
+}
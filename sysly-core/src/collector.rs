@@ -0,0 +1,168 @@
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// Set while a collector runs on this thread, so its panics stay quiet
+    static COLLECTING: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// Wrap the panic hook, once, so it skips panics raised inside collectors
+///
+/// Swapping the hook around each run would race when collectors run on
+/// more than one thread, and could leave the default hook lost for good.
+fn install_quiet_hook() {
+    QUIET_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !COLLECTING.with(Cell::get) {
+                previous_hook(info);
+            }
+        }));
+    });
+}
+
+/// Health of a single data collector
+#[derive(Debug, Clone)]
+pub struct CollectorHealth {
+    pub name: &'static str,
+    pub runs: u64,
+    pub failures: u64,
+    /// Panic message of the most recent failed run, cleared on success
+    pub last_error: Option<String>,
+    /// Wall-clock time taken by the most recent run
+    pub last_duration: Duration,
+    /// When the collector last produced data
+    pub last_success: Option<Instant>,
+    /// False when an optional collector reported no data on its last run,
+    /// e.g. a macOS-only integration on another platform
+    pub available: bool,
+}
+
+impl CollectorHealth {
+    pub fn is_failing(&self) -> bool {
+        self.last_error.is_some()
+    }
+
+    /// Time since the collector last produced data
+    pub fn data_age(&self) -> Option<Duration> {
+        self.last_success.map(|at| at.elapsed())
+    }
+}
+
+/// Runs collectors behind panic-catching boundaries and tracks their health
+///
+/// Collectors that parse external command output (`ps`, `vm_stat`, ...) can
+/// hit malformed input; a panic there is recorded and the collector's result
+/// is dropped instead of taking down the whole TUI.
+#[derive(Debug, Default)]
+pub struct Collectors {
+    health: Vec<CollectorHealth>,
+}
+
+impl Collectors {
+    /// Run a collector, catching any panic
+    ///
+    /// # Arguments
+    /// * `name` - Collector name shown in diagnostics
+    /// * `collect` - The collection function
+    ///
+    /// # Returns
+    /// The collected value, or None if the collector panicked
+    pub fn run<T>(&mut self, name: &'static str, collect: impl FnOnce() -> T) -> Option<T> {
+        // Silence the panic message so it doesn't corrupt the screen
+        install_quiet_hook();
+        COLLECTING.with(|collecting| collecting.set(true));
+        let started = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(collect));
+        let duration = started.elapsed();
+        COLLECTING.with(|collecting| collecting.set(false));
+
+        let health = self.entry(name);
+        health.runs += 1;
+        health.last_duration = duration;
+
+        match result {
+            Ok(value) => {
+                health.last_error = None;
+                health.last_success = Some(Instant::now());
+                health.available = true;
+                Some(value)
+            }
+            Err(payload) => {
+                health.failures += 1;
+                health.last_error = Some(panic_message(payload.as_ref()));
+                None
+            }
+        }
+    }
+
+    /// Run an optional collector that returns None when its data source is
+    /// unavailable, recording that in the collector's health
+    pub fn run_optional<T>(
+        &mut self,
+        name: &'static str,
+        collect: impl FnOnce() -> Option<T>,
+    ) -> Option<T> {
+        let value = self.run(name, collect).flatten();
+
+        let health = self.entry(name);
+        if health.last_error.is_none() && value.is_none() {
+            health.available = false;
+            health.last_success = None;
+        }
+
+        value
+    }
+
+    /// Take over the health of collectors that ran elsewhere, such as on the
+    /// sampling thread, replacing what was recorded under the same names
+    pub fn absorb(&mut self, health: &[CollectorHealth]) {
+        for record in health {
+            *self.entry(record.name) = record.clone();
+        }
+    }
+
+    /// Health of every collector that has run, in first-run order
+    pub fn health(&self) -> &[CollectorHealth] {
+        &self.health
+    }
+
+    /// Number of collectors whose most recent run failed
+    pub fn failing_count(&self) -> usize {
+        self.health.iter().filter(|h| h.is_failing()).count()
+    }
+
+    fn entry(&mut self, name: &'static str) -> &mut CollectorHealth {
+        let index = match self.health.iter().position(|h| h.name == name) {
+            Some(index) => index,
+            None => {
+                self.health.push(CollectorHealth {
+                    name,
+                    runs: 0,
+                    failures: 0,
+                    last_error: None,
+                    last_duration: Duration::ZERO,
+                    last_success: None,
+                    available: true,
+                });
+                self.health.len() - 1
+            }
+        };
+
+        &mut self.health[index]
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "collector panicked".to_string()
+    }
+}
@@ -0,0 +1,252 @@
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+#[cfg(target_os = "macos")]
+use crate::helpers::ioreg_number;
+use std::time::Instant;
+use sysinfo::Disks;
+
+/// Cumulative bytes transferred by every disk since boot
+#[derive(Debug, Clone, Copy)]
+pub struct DiskCounters {
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+}
+
+/// System-wide disk throughput over the last refresh
+#[derive(Debug, Clone, Copy)]
+pub struct DiskRates {
+    pub read_per_second: f64,
+    pub written_per_second: f64,
+}
+
+impl DiskRates {
+    pub fn total(&self) -> f64 {
+        self.read_per_second + self.written_per_second
+    }
+}
+
+/// A mounted volume and its space usage
+#[derive(Debug, Clone)]
+pub struct Volume {
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub available: u64,
+    /// None for filesystems that allocate inodes dynamically and don't
+    /// report a limit
+    pub inodes: Option<Inodes>,
+}
+
+impl Volume {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+}
+
+/// Inode (file slot) capacity of a volume
+#[derive(Debug, Clone, Copy)]
+pub struct Inodes {
+    pub total: u64,
+    pub free: u64,
+}
+
+impl Inodes {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+}
+
+/// Read a volume's inode counts with `statvfs`
+#[allow(clippy::unnecessary_cast)] // Field widths differ between platforms
+fn fetch_inodes(mount_point: &Path) -> Option<Inodes> {
+    let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 || stats.f_files == 0 {
+        return None;
+    }
+
+    Some(Inodes {
+        total: stats.f_files as u64,
+        free: stats.f_ffree as u64,
+    })
+}
+
+/// List mounted volumes with their space and inode usage, leaving out the
+/// given filesystem types
+///
+/// # Arguments
+/// * `hidden` - Filesystem types to skip, e.g. `devfs` or `tmpfs`
+///
+/// # Returns
+/// Volumes ordered by mount point
+pub fn fetch_volumes(hidden: &[String]) -> Vec<Volume> {
+    let mut volumes: Vec<Volume> = Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|disk| {
+            !hidden
+                .iter()
+                .any(|fs| fs.eq_ignore_ascii_case(&disk.file_system().to_string_lossy()))
+        })
+        .map(|disk| Volume {
+            mount_point: disk.mount_point().to_string_lossy().to_string(),
+            file_system: disk.file_system().to_string_lossy().to_string(),
+            total: disk.total_space(),
+            available: disk.available_space(),
+            inodes: fetch_inodes(disk.mount_point()),
+        })
+        .collect();
+
+    volumes.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    volumes
+}
+
+/// Turns successive counter samples into rates
+#[derive(Debug, Default)]
+pub struct DiskMeter {
+    previous: Option<(DiskCounters, Instant)>,
+    rates: Option<DiskRates>,
+    /// Highest total rate seen, which the meter bar is scaled against
+    peak: f64,
+}
+
+impl DiskMeter {
+    /// Record a new sample; rates are available from the second sample on
+    ///
+    /// A missing sample clears the rates rather than leaving stale ones.
+    pub fn sample(&mut self, counters: Option<DiskCounters>) {
+        let Some(counters) = counters else {
+            self.previous = None;
+            self.rates = None;
+            return;
+        };
+
+        let now = Instant::now();
+        if let Some((previous, at)) = self.previous {
+            let seconds = now.duration_since(at).as_secs_f64();
+            if seconds > 0.0 {
+                // Counters can go backwards when a disk is ejected
+                self.rates = Some(DiskRates {
+                    read_per_second: counters.read_bytes.saturating_sub(previous.read_bytes) as f64
+                        / seconds,
+                    written_per_second: counters
+                        .written_bytes
+                        .saturating_sub(previous.written_bytes)
+                        as f64
+                        / seconds,
+                });
+            }
+        }
+        self.previous = Some((counters, now));
+        self.peak = self
+            .rates
+            .map_or(self.peak, |rates| self.peak.max(rates.total()));
+    }
+
+    pub fn rates(&self) -> Option<DiskRates> {
+        self.rates
+    }
+
+    pub fn peak(&self) -> f64 {
+        self.peak
+    }
+}
+
+/// Read the cumulative disk counters on macOS
+///
+/// Uses `ioreg` to read the `Statistics` dictionary of every
+/// `IOBlockStorageDriver`, which holds the bytes read and written since boot
+///
+/// # Returns
+/// Counters summed over all drivers, or None if ioreg is unavailable
+#[cfg(target_os = "macos")]
+pub fn fetch_disk_counters() -> Option<DiskCounters> {
+    let output = Command::new("ioreg")
+        .args(["-r", "-c", "IOBlockStorageDriver", "-w", "0"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // "Statistics" = {"Bytes (Read)"=123,"Bytes (Write)"=456,...}
+    let mut counters = DiskCounters {
+        read_bytes: 0,
+        written_bytes: 0,
+    };
+    let mut found = false;
+    for line in stdout
+        .lines()
+        .filter(|line| line.contains("\"Statistics\""))
+    {
+        counters.read_bytes += ioreg_number(line, "Bytes (Read)").unwrap_or(0);
+        counters.written_bytes += ioreg_number(line, "Bytes (Write)").unwrap_or(0);
+        found = true;
+    }
+
+    found.then_some(counters)
+}
+
+/// Read the cumulative disk counters from `/proc/diskstats`
+///
+/// Only whole disks are counted, so partitions aren't added twice; loop and
+/// RAM-backed devices are skipped. Sector counts are in 512-byte units.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_disk_counters() -> Option<DiskCounters> {
+    let contents = std::fs::read_to_string("/proc/diskstats").ok()?;
+    Some(parse_diskstats(&contents, |name| {
+        Path::new("/sys/block").join(name).exists()
+    }))
+}
+
+/// Sum the sectors read and written in `/proc/diskstats` over the devices
+/// `is_disk` says are whole disks
+#[cfg(not(target_os = "macos"))]
+fn parse_diskstats(contents: &str, is_disk: impl Fn(&str) -> bool) -> DiskCounters {
+    let mut counters = DiskCounters {
+        read_bytes: 0,
+        written_bytes: 0,
+    };
+    for fields in contents
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+    {
+        let (Some(name), Some(read), Some(written)) = (fields.get(2), fields.get(5), fields.get(9))
+        else {
+            continue;
+        };
+        if ["loop", "ram", "zram"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+            || !is_disk(name)
+        {
+            continue;
+        }
+
+        counters.read_bytes += read.parse::<u64>().unwrap_or(0) * 512;
+        counters.written_bytes += written.parse::<u64>().unwrap_or(0) * 512;
+    }
+
+    counters
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diskstats_count_whole_disks_only() {
+        let contents = [
+            "   8       0 sda 100 0 2000 0 50 0 4000 0 0 0 0",
+            "   8       1 sda1 90 0 1800 0 40 0 3000 0 0 0 0",
+            "   7       0 loop0 5 0 10 0 1 0 20 0 0 0 0",
+            " 252       0 zram0 5 0 10 0 1 0 20 0 0 0 0",
+        ]
+        .join("\n");
+        let counters = parse_diskstats(&contents, |name| !name.starts_with("sda1"));
+        assert_eq!(counters.read_bytes, 2000 * 512);
+        assert_eq!(counters.written_bytes, 4000 * 512);
+    }
+}
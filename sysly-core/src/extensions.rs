@@ -0,0 +1,154 @@
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+/// Whether an extension runs in the kernel or in user space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionKind {
+    /// A kernel extension (or a Linux kernel module)
+    Kernel,
+    /// A macOS system extension (network, endpoint security, driver)
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    System,
+}
+
+impl ExtensionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExtensionKind::Kernel => "kext",
+            ExtensionKind::System => "sysext",
+        }
+    }
+}
+
+/// A loaded kernel extension or installed system extension
+#[derive(Debug, Clone)]
+pub struct Extension {
+    pub kind: ExtensionKind,
+    pub bundle_id: String,
+    pub version: Option<String>,
+    /// Load state as reported by the system, e.g. `loaded` or
+    /// `activated enabled`
+    pub status: String,
+    /// Whether the extension ships with the operating system
+    pub builtin: bool,
+}
+
+/// List loaded kernel extensions and installed system extensions
+///
+/// Uses `kmutil showloaded` and `systemextensionsctl list`; third-party
+/// extensions are listed before Apple's own.
+///
+/// # Returns
+/// The extensions, or None if neither tool could be run
+#[cfg(target_os = "macos")]
+pub fn fetch_extensions() -> Option<Vec<Extension>> {
+    let kexts = Command::new("kmutil")
+        .arg("showloaded")
+        .output()
+        .ok()
+        .map(|output| parse_kmutil(&String::from_utf8_lossy(&output.stdout)));
+    let system = Command::new("systemextensionsctl")
+        .arg("list")
+        .output()
+        .ok()
+        .map(|output| parse_systemextensionsctl(&String::from_utf8_lossy(&output.stdout)));
+    if kexts.is_none() && system.is_none() {
+        return None;
+    }
+
+    let mut extensions: Vec<Extension> = system
+        .into_iter()
+        .flatten()
+        .chain(kexts.into_iter().flatten())
+        .collect();
+    extensions.sort_by_key(|extension| extension.builtin);
+    Some(extensions)
+}
+
+/// Parse `kmutil showloaded`
+///
+/// Each loaded kext is a row starting with its index, e.g.
+/// `1  159 0  0  0  UUID  com.apple.kpi.bsd (23.2.0) UUID <>`
+#[cfg(target_os = "macos")]
+fn parse_kmutil(output: &str) -> Vec<Extension> {
+    output
+        .lines()
+        .filter(|line| {
+            line.split_whitespace()
+                .next()
+                .is_some_and(|index| index.parse::<u32>().is_ok())
+        })
+        .filter_map(|line| {
+            let (before, after) = line.split_once(" (")?;
+            let bundle_id = before.split_whitespace().last()?;
+            let version = after.split(')').next()?;
+            Some(Extension {
+                kind: ExtensionKind::Kernel,
+                bundle_id: bundle_id.to_string(),
+                version: Some(version.to_string()),
+                status: "loaded".to_string(),
+                builtin: bundle_id.starts_with("com.apple."),
+            })
+        })
+        .collect()
+}
+
+/// Parse `systemextensionsctl list`
+///
+/// Extensions are tab-separated rows ending in their state, e.g.
+/// `*  *  TEAMID  com.example.filter (1.2/3)  Filter  [activated enabled]`
+#[cfg(target_os = "macos")]
+fn parse_systemextensionsctl(output: &str) -> Vec<Extension> {
+    output
+        .lines()
+        .filter(|line| line.trim_end().ends_with(']'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            let (bundle_id, version) = match fields.get(3)?.split_once(" (") {
+                Some((id, version)) => (id, Some(version.trim_end_matches(')').to_string())),
+                None => (*fields.get(3)?, None),
+            };
+            let status = fields.last()?.trim_start_matches('[').trim_end_matches(']');
+            Some(Extension {
+                kind: ExtensionKind::System,
+                bundle_id: bundle_id.to_string(),
+                version,
+                status: status.to_string(),
+                builtin: bundle_id.starts_with("com.apple."),
+            })
+        })
+        .collect()
+}
+
+/// List loaded kernel modules from `/proc/modules`
+///
+/// Linux has no system extensions; module versions come from sysfs when the
+/// module declares one, and modules tainting the kernel as out-of-tree (`O`)
+/// count as third-party.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_extensions() -> Option<Vec<Extension>> {
+    let contents = std::fs::read_to_string("/proc/modules").ok()?;
+
+    let mut extensions: Vec<Extension> = contents
+        .lines()
+        .filter_map(|line| {
+            // name size refcount dependencies state address
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let name = *fields.first()?;
+            let read = |file: &str| {
+                std::fs::read_to_string(format!("/sys/module/{}/{}", name, file))
+                    .ok()
+                    .map(|contents| contents.trim().to_string())
+            };
+            Some(Extension {
+                kind: ExtensionKind::Kernel,
+                bundle_id: name.to_string(),
+                version: read("version"),
+                status: fields.get(4).unwrap_or(&"-").to_lowercase(),
+                builtin: !read("taint").is_some_and(|taint| taint.contains('O')),
+            })
+        })
+        .collect();
+    extensions.sort_by(|a, b| (a.builtin, &a.bundle_id).cmp(&(b.builtin, &b.bundle_id)));
+    Some(extensions)
+}
@@ -0,0 +1,218 @@
+/// A cooling fan and its speed
+#[derive(Debug, Clone)]
+pub struct Fan {
+    pub label: String,
+    /// Current speed in revolutions per minute
+    pub rpm: f32,
+    /// Speed range the firmware drives the fan in, when it reports one
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl Fan {
+    /// Current speed as a percentage of the fan's range, when it has one
+    pub fn percent(&self) -> Option<f32> {
+        let (min, max) = (self.min.unwrap_or(0.0), self.max?);
+        (max > min).then(|| ((self.rpm - min) / (max - min) * 100.0).clamp(0.0, 100.0))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod smc {
+    use std::ffi::c_void;
+
+    /// Selector of the SMC user client's struct method
+    const KERNEL_INDEX_SMC: u32 = 2;
+    const SMC_CMD_READ_BYTES: u8 = 5;
+    const SMC_CMD_READ_KEYINFO: u8 = 9;
+
+    type IoObject = libc::mach_port_t;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOServiceMatching(name: *const libc::c_char) -> *mut c_void;
+        fn IOServiceGetMatchingService(
+            main_port: libc::mach_port_t,
+            matching: *mut c_void,
+        ) -> IoObject;
+        fn IOServiceOpen(
+            service: IoObject,
+            owning_task: libc::mach_port_t,
+            kind: u32,
+            connection: *mut IoObject,
+        ) -> libc::kern_return_t;
+        fn IOServiceClose(connection: IoObject) -> libc::kern_return_t;
+        fn IOObjectRelease(object: IoObject) -> libc::kern_return_t;
+        fn IOConnectCallStructMethod(
+            connection: IoObject,
+            selector: u32,
+            input: *const c_void,
+            input_size: usize,
+            output: *mut c_void,
+            output_size: *mut usize,
+        ) -> libc::kern_return_t;
+    }
+
+    /// `SMCKeyData_t` as the AppleSMC user client expects it
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)] // Every field is needed for the C layout
+    struct KeyData {
+        key: u32,
+        version: [u8; 6],
+        power_limits: [u32; 4],
+        data_size: u32,
+        data_type: u32,
+        data_attributes: u8,
+        /// `keyInfo` is a nested struct padded to 12 bytes in C
+        _padding: [u8; 3],
+        result: u8,
+        status: u8,
+        command: u8,
+        data32: u32,
+        bytes: [u8; 32],
+    }
+
+    /// Four-character SMC keys and types are packed big-endian into a u32
+    fn fourcc(code: &str) -> u32 {
+        code.bytes()
+            .fold(0, |packed, byte| (packed << 8) | byte as u32)
+    }
+
+    /// An open connection to the System Management Controller
+    pub struct Smc(IoObject);
+
+    impl Smc {
+        pub fn open() -> Option<Self> {
+            unsafe {
+                let service =
+                    IOServiceGetMatchingService(0, IOServiceMatching(c"AppleSMC".as_ptr()));
+                if service == 0 {
+                    return None;
+                }
+                let mut connection: IoObject = 0;
+                let result = IOServiceOpen(service, libc::mach_task_self(), 0, &mut connection);
+                IOObjectRelease(service);
+                (result == 0).then_some(Smc(connection))
+            }
+        }
+
+        fn call(&self, input: &KeyData) -> Option<KeyData> {
+            let mut output = KeyData::default();
+            let mut output_size = std::mem::size_of::<KeyData>();
+            let result = unsafe {
+                IOConnectCallStructMethod(
+                    self.0,
+                    KERNEL_INDEX_SMC,
+                    input as *const KeyData as *const c_void,
+                    std::mem::size_of::<KeyData>(),
+                    &mut output as *mut KeyData as *mut c_void,
+                    &mut output_size,
+                )
+            };
+            (result == 0 && output.result == 0).then_some(output)
+        }
+
+        /// Read a numeric key, decoding the SMC's fixed-point and float types
+        pub fn read(&self, key: &str) -> Option<f32> {
+            let info = self.call(&KeyData {
+                key: fourcc(key),
+                command: SMC_CMD_READ_KEYINFO,
+                ..KeyData::default()
+            })?;
+            let value = self.call(&KeyData {
+                key: fourcc(key),
+                data_size: info.data_size,
+                command: SMC_CMD_READ_BYTES,
+                ..KeyData::default()
+            })?;
+            let bytes = value.bytes;
+
+            match info.data_type {
+                // Apple Silicon: little-endian IEEE float
+                t if t == fourcc("flt ") => {
+                    Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                }
+                // Intel: unsigned fixed point with two fraction bits
+                t if t == fourcc("fpe2") => {
+                    Some(u16::from_be_bytes([bytes[0], bytes[1]]) as f32 / 4.0)
+                }
+                t if t == fourcc("ui8 ") => Some(bytes[0] as f32),
+                t if t == fourcc("ui16") => Some(u16::from_be_bytes([bytes[0], bytes[1]]) as f32),
+                _ => None,
+            }
+        }
+    }
+
+    impl Drop for Smc {
+        fn drop(&mut self) {
+            unsafe { IOServiceClose(self.0) };
+        }
+    }
+}
+
+/// Read fan speeds from the SMC: `FNum` fans, each with `F<n>Ac` (actual),
+/// `F<n>Mn` and `F<n>Mx` keys
+///
+/// # Returns
+/// One entry per fan, or None on fanless Macs or when the SMC can't be read
+#[cfg(target_os = "macos")]
+pub fn fetch_fans() -> Option<Vec<Fan>> {
+    let smc = smc::Smc::open()?;
+    let count = smc.read("FNum")? as usize;
+
+    let fans: Vec<Fan> = (0..count)
+        .filter_map(|index| {
+            Some(Fan {
+                label: format!("Fan {}", index + 1),
+                rpm: smc.read(&format!("F{}Ac", index))?,
+                min: smc.read(&format!("F{}Mn", index)),
+                max: smc.read(&format!("F{}Mx", index)),
+            })
+        })
+        .collect();
+    (!fans.is_empty()).then_some(fans)
+}
+
+/// Read fan speeds from hwmon's `fan<n>_input` files
+///
+/// # Returns
+/// One entry per fan, or None when no hwmon driver reports a fan
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_fans() -> Option<Vec<Fan>> {
+    let read = |path: &std::path::Path| -> Option<String> {
+        Some(std::fs::read_to_string(path).ok()?.trim().to_string())
+    };
+
+    let mut devices: Vec<_> = std::fs::read_dir("/sys/class/hwmon")
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    devices.sort();
+
+    let mut fans = Vec::new();
+    for device in devices {
+        let driver = read(&device.join("name")).unwrap_or_else(|| "hwmon".to_string());
+        for index in 1.. {
+            let Some(rpm) = read(&device.join(format!("fan{}_input", index)))
+                .and_then(|value| value.parse::<f32>().ok())
+            else {
+                break;
+            };
+            let number = |name: &str| {
+                read(&device.join(format!("fan{}_{}", index, name)))?
+                    .parse::<f32>()
+                    .ok()
+            };
+            fans.push(Fan {
+                label: read(&device.join(format!("fan{}_label", index)))
+                    .unwrap_or_else(|| format!("{} fan {}", driver, index)),
+                rpm,
+                min: number("min"),
+                max: number("max"),
+            });
+        }
+    }
+    (!fans.is_empty()).then_some(fans)
+}
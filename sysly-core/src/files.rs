@@ -0,0 +1,232 @@
+#[cfg(target_os = "macos")]
+use std::collections::HashMap;
+use std::process::Command;
+
+/// A file descriptor held open by a process
+#[derive(Debug, Clone)]
+pub struct OpenFile {
+    /// Descriptor number, or an `lsof` tag such as `cwd` or `txt`
+    pub fd: String,
+    /// Kind of descriptor, e.g. `REG`, `DIR`, `IPv4`, `unix`, `PIPE`
+    pub kind: String,
+    /// Path for files, endpoints for sockets
+    pub name: String,
+}
+
+/// List the open files of a process
+///
+/// Descriptors are enumerated with `proc_pidinfo` and file paths resolved
+/// with `proc_pidfdinfo`. Socket endpoints, and everything when the native
+/// calls are refused, come from `lsof`.
+///
+/// # Arguments
+/// * `pid` - Process ID
+///
+/// # Returns
+/// Open files in descriptor order, or an empty vector if none are readable
+#[cfg(target_os = "macos")]
+pub fn fetch_open_files(pid: u32) -> Vec<OpenFile> {
+    let Some(descriptors) = list_descriptors(pid) else {
+        return fetch_open_files_lsof(pid);
+    };
+
+    let mut files: Vec<OpenFile> = descriptors
+        .iter()
+        .map(|info| OpenFile {
+            fd: info.proc_fd.to_string(),
+            kind: descriptor_kind(info.proc_fdtype as libc::c_int).to_string(),
+            name: match info.proc_fdtype as libc::c_int {
+                libc::PROX_FDTYPE_VNODE => vnode_path(pid, info.proc_fd).unwrap_or_default(),
+                _ => String::new(),
+            },
+        })
+        .collect();
+
+    // Socket endpoints need the socket_fdinfo union; lsof already decodes it
+    if files.iter().any(|file| file.kind == "socket") {
+        let endpoints: HashMap<String, OpenFile> = fetch_open_files_lsof(pid)
+            .into_iter()
+            .map(|file| (file.fd.clone(), file))
+            .collect();
+        for file in files.iter_mut().filter(|file| file.kind == "socket") {
+            if let Some(endpoint) = endpoints.get(&file.fd) {
+                file.kind = endpoint.kind.clone();
+                file.name = endpoint.name.clone();
+            }
+        }
+    }
+
+    files
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_open_files(pid: u32) -> Vec<OpenFile> {
+    fetch_open_files_lsof(pid)
+}
+
+/// Enumerate a process's descriptors with `PROC_PIDLISTFDS`
+#[cfg(target_os = "macos")]
+fn list_descriptors(pid: u32) -> Option<Vec<libc::proc_fdinfo>> {
+    let entry_size = std::mem::size_of::<libc::proc_fdinfo>() as libc::c_int;
+
+    // A null buffer asks for the size needed
+    let needed = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            libc::PROC_PIDLISTFDS,
+            0,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if needed <= 0 {
+        return None;
+    }
+
+    // Leave headroom for descriptors opened between the two calls
+    let capacity = (needed / entry_size) as usize + 16;
+    let mut buffer: Vec<libc::proc_fdinfo> = Vec::with_capacity(capacity);
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            libc::PROC_PIDLISTFDS,
+            0,
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            capacity as libc::c_int * entry_size,
+        )
+    };
+    if written <= 0 {
+        return None;
+    }
+
+    unsafe { buffer.set_len((written / entry_size) as usize) };
+    Some(buffer)
+}
+
+/// `proc_fileinfo` from `<sys/proc_info.h>`, not exported by libc
+///
+/// Only needed for its size and layout; the fields are never read.
+#[cfg(target_os = "macos")]
+#[allow(dead_code)]
+#[repr(C)]
+struct ProcFileInfo {
+    fi_openflags: u32,
+    fi_status: u32,
+    fi_offset: libc::off_t,
+    fi_type: i32,
+    fi_guardflags: u32,
+}
+
+/// `vnode_fdinfowithpath` from `<sys/proc_info.h>`, not exported by libc
+#[cfg(target_os = "macos")]
+#[allow(dead_code)]
+#[repr(C)]
+struct VnodeFdInfoWithPath {
+    pfi: ProcFileInfo,
+    pvip: libc::vnode_info_path,
+}
+
+#[cfg(target_os = "macos")]
+const PROC_PIDFDVNODEPATHINFO: libc::c_int = 2;
+
+/// Resolve the path of a vnode descriptor with `proc_pidfdinfo`
+#[cfg(target_os = "macos")]
+fn vnode_path(pid: u32, fd: i32) -> Option<String> {
+    let mut info: VnodeFdInfoWithPath = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<VnodeFdInfoWithPath>() as libc::c_int;
+
+    let written = unsafe {
+        libc::proc_pidfdinfo(
+            pid as libc::c_int,
+            fd,
+            PROC_PIDFDVNODEPATHINFO,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+
+    let path =
+        unsafe { std::ffi::CStr::from_ptr(info.pvip.vip_path.as_ptr() as *const libc::c_char) };
+    Some(path.to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn descriptor_kind(fdtype: libc::c_int) -> &'static str {
+    match fdtype {
+        libc::PROX_FDTYPE_VNODE => "file",
+        libc::PROX_FDTYPE_SOCKET => "socket",
+        libc::PROX_FDTYPE_PSHM => "PSXSHM",
+        libc::PROX_FDTYPE_PSEM => "PSXSEM",
+        libc::PROX_FDTYPE_KQUEUE => "KQUEUE",
+        libc::PROX_FDTYPE_PIPE => "PIPE",
+        libc::PROX_FDTYPE_FSEVENTS => "FSEVENT",
+        libc::PROX_FDTYPE_NETPOLICY => "NPOLICY",
+        libc::PROX_FDTYPE_CHANNEL => "CHANNEL",
+        libc::PROX_FDTYPE_NEXUS => "NEXUS",
+        _ => "?",
+    }
+}
+
+/// List open files with `lsof -F`, whose field output is stable to parse
+///
+/// Each field is on its own line, prefixed with its identifier: `f` starts
+/// a new descriptor, `t` is its type and `n` its name.
+fn fetch_open_files_lsof(pid: u32) -> Vec<OpenFile> {
+    let output = match Command::new("lsof")
+        .args(["-nP", "-a", "-p", &pid.to_string(), "-F", "ftn"])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return Vec::new(),
+    };
+    parse_lsof_fields(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the `f`, `t` and `n` fields of `lsof -F ftn` into open files
+fn parse_lsof_fields(stdout: &str) -> Vec<OpenFile> {
+    let mut files: Vec<OpenFile> = Vec::new();
+    for line in stdout.lines() {
+        let Some(field) = line.chars().next() else {
+            continue;
+        };
+        let value = &line[field.len_utf8()..];
+
+        match (field, files.last_mut()) {
+            ('f', _) => files.push(OpenFile {
+                fd: value.to_string(),
+                kind: String::new(),
+                name: String::new(),
+            }),
+            ('t', Some(file)) => file.kind = value.to_string(),
+            ('n', Some(file)) => file.name = value.to_string(),
+            _ => {}
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lsof_fields_group_by_descriptor() {
+        let stdout = "p123\nfcwd\ntDIR\nn/home/me\nf3\ntREG\nn/tmp/log.txt\n\
+                      f4\ntIPv4\nn127.0.0.1:8080->127.0.0.1:5000\n";
+        let files = parse_lsof_fields(stdout);
+        assert_eq!(files.len(), 3);
+        assert_eq!(
+            (
+                files[0].fd.as_str(),
+                files[0].kind.as_str(),
+                files[0].name.as_str()
+            ),
+            ("cwd", "DIR", "/home/me")
+        );
+        assert_eq!(files[2].name, "127.0.0.1:8080->127.0.0.1:5000");
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+#[cfg(target_os = "macos")]
+use crate::helpers::ioreg_number;
+
+/// Where the GPU's memory lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuMemoryKind {
+    /// Shared with the CPU, as on Apple Silicon
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Unified,
+    /// Dedicated VRAM on a discrete GPU
+    Dedicated,
+}
+
+/// Memory allocated by the GPU
+#[derive(Debug, Clone, Copy)]
+pub struct GpuMemory {
+    pub kind: GpuMemoryKind,
+    pub used: u64,
+    /// VRAM size; None for unified memory, which is bounded by system RAM
+    pub total: Option<u64>,
+}
+
+/// How busy the GPUs are and how much memory they hold
+#[derive(Debug, Clone, Copy)]
+pub struct GpuUsage {
+    /// Percentage of time the busiest GPU was working
+    pub utilization: Option<f32>,
+    pub memory: Option<GpuMemory>,
+}
+
+/// Read GPU utilization and memory usage on macOS
+///
+/// Uses `ioreg` to read the `PerformanceStatistics` of every
+/// `IOAccelerator`, which the `IOGPU` drivers on Apple Silicon publish too.
+/// Every GPU reports its `Device Utilization %`; discrete GPUs report VRAM
+/// used and free, and Apple Silicon the unified memory the GPU has in use.
+///
+/// # Returns
+/// GpuUsage over all accelerators, or None if none report statistics
+#[cfg(target_os = "macos")]
+pub fn fetch_gpu_usage() -> Option<GpuUsage> {
+    let output = Command::new("ioreg")
+        .args(["-r", "-d", "1", "-c", "IOAccelerator", "-w", "0"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut utilization: Option<f32> = None;
+    let mut memory: Option<GpuMemory> = None;
+    for line in stdout
+        .lines()
+        .filter(|line| line.contains("\"PerformanceStatistics\""))
+    {
+        if let Some(busy) = ioreg_number(line, "Device Utilization %") {
+            utilization = Some(utilization.unwrap_or(0.0).max(busy as f32));
+        }
+
+        let (kind, used, total) = if let Some(used) = ioreg_number(line, "vramUsedBytes") {
+            let free = ioreg_number(line, "vramFreeBytes").unwrap_or(0);
+            (GpuMemoryKind::Dedicated, used, Some(used + free))
+        } else if let Some(used) = ioreg_number(line, "In use system memory") {
+            (GpuMemoryKind::Unified, used, None)
+        } else {
+            continue;
+        };
+
+        // Dedicated VRAM is the one to watch when a Mac has both kinds
+        match &mut memory {
+            Some(current) if current.kind == kind => {
+                current.used += used;
+                current.total = current.total.zip(total).map(|(a, b)| a + b);
+            }
+            Some(current) if current.kind == GpuMemoryKind::Dedicated => {}
+            _ => memory = Some(GpuMemory { kind, used, total }),
+        }
+    }
+
+    (utilization.is_some() || memory.is_some()).then_some(GpuUsage {
+        utilization,
+        memory,
+    })
+}
+
+/// Read GPU utilization and VRAM usage from the amdgpu sysfs counters
+///
+/// Other drivers don't expose them through sysfs, so their GPUs are
+/// reported as unavailable.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_gpu_usage() -> Option<GpuUsage> {
+    let read = |path: std::path::PathBuf| -> Option<u64> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+
+    let mut utilization: Option<f32> = None;
+    let mut memory: Option<GpuMemory> = None;
+    for card in std::fs::read_dir("/sys/class/drm").ok()?.flatten() {
+        // Skip connectors (card0-DP-1) and render nodes, which share a device
+        let name = card.file_name().to_string_lossy().to_string();
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+
+        let device = card.path().join("device");
+        if let Some(busy) = read(device.join("gpu_busy_percent")) {
+            utilization = Some(utilization.unwrap_or(0.0).max(busy as f32));
+        }
+        let (Some(used), Some(total)) = (
+            read(device.join("mem_info_vram_used")),
+            read(device.join("mem_info_vram_total")),
+        ) else {
+            continue;
+        };
+
+        let entry = memory.get_or_insert(GpuMemory {
+            kind: GpuMemoryKind::Dedicated,
+            used: 0,
+            total: Some(0),
+        });
+        entry.used += used;
+        entry.total = entry.total.map(|sum| sum + total);
+    }
+
+    (utilization.is_some() || memory.is_some()).then_some(GpuUsage {
+        utilization,
+        memory,
+    })
+}
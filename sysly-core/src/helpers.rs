@@ -0,0 +1,373 @@
+use chrono::{Local, TimeZone};
+
+const SECONDS_PER_DAY: u64 = 86400;
+const SECONDS_PER_HOUR: u64 = 3600;
+const SECONDS_PER_MINUTE: u64 = 60;
+
+/// How durations (TIME+, Uptime) and timestamps (boot and start times) are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// Clock-style durations and short local timestamps (the classic htop look)
+    #[default]
+    Clock,
+    /// ISO 8601 durations (`PT2H5M`) and RFC 3339 timestamps
+    Iso,
+    /// Compact durations (`2h 5m`) and timestamps relative to now (`5m ago`)
+    Relative,
+    /// Clock-style durations and the locale's preferred date and time
+    Locale,
+}
+
+impl TimeFormat {
+    /// Parse a config value such as `iso` or `relative`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "clock" => Some(TimeFormat::Clock),
+            "iso" => Some(TimeFormat::Iso),
+            "relative" => Some(TimeFormat::Relative),
+            "locale" => Some(TimeFormat::Locale),
+            _ => None,
+        }
+    }
+}
+
+/// Format bytes into human-readable string with appropriate units
+///
+/// # Arguments
+/// * `bytes` - Number of bytes to format
+///
+/// # Returns
+/// Formatted string with unit (KB, MB, GB, TB)
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    const TB: f64 = GB * 1024.0;
+
+    let bytes = bytes as f64;
+
+    if bytes >= TB {
+        format!("{:.1}TB", bytes / TB)
+    } else if bytes >= GB {
+        format!("{:.1}GB", bytes / GB)
+    } else if bytes >= MB {
+        format!("{:.1}MB", bytes / MB)
+    } else if bytes >= KB {
+        format!("{:.1}KB", bytes / KB)
+    } else {
+        format!("{:.0}B", bytes)
+    }
+}
+
+/// Parse a size such as `512M`, `2G`, or `1048576` into bytes
+///
+/// Suffixes K, M, G and T are binary multiples; a trailing `B` or `iB` is
+/// accepted as well.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_ascii_uppercase();
+    let value = value
+        .strip_suffix("IB")
+        .or_else(|| value.strip_suffix('B'))
+        .unwrap_or(&value);
+    let (number, multiplier) = match value.chars().last()? {
+        'K' => (&value[..value.len() - 1], 1u64 << 10),
+        'M' => (&value[..value.len() - 1], 1 << 20),
+        'G' => (&value[..value.len() - 1], 1 << 30),
+        'T' => (&value[..value.len() - 1], 1 << 40),
+        _ => (value, 1),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    (number >= 0.0).then_some((number * multiplier as f64) as u64)
+}
+
+/// Format a count with a metric suffix, e.g. `999`, `12.3K`, or `4.5M`
+pub fn format_count(count: u64) -> String {
+    let count = count as f64;
+
+    if count >= 1e9 {
+        format!("{:.1}G", count / 1e9)
+    } else if count >= 1e6 {
+        format!("{:.1}M", count / 1e6)
+    } else if count >= 1e3 {
+        format!("{:.1}K", count / 1e3)
+    } else {
+        format!("{:.0}", count)
+    }
+}
+
+/// Group a number's digits in threes, e.g. `1234567` as `1,234,567` with a
+/// `,` separator
+pub fn group_digits(number: u64, separator: &str) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::with_capacity(digits.len() * (1 + separator.len()) / 3 + 1);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Format uptime duration into human-readable string
+///
+/// # Arguments
+/// * `uptime_seconds` - Uptime in seconds
+/// * `format` - Configured time format
+///
+/// # Returns
+/// Formatted uptime string (e.g., "2 days, 03:45:12" or "03:45:12")
+pub fn format_uptime(uptime_seconds: u64, format: TimeFormat) -> String {
+    match format {
+        TimeFormat::Iso => return format_iso_duration(uptime_seconds),
+        TimeFormat::Relative => return format_relative_duration(uptime_seconds),
+        TimeFormat::Clock | TimeFormat::Locale => {}
+    }
+
+    let days = uptime_seconds / SECONDS_PER_DAY;
+    let hours = (uptime_seconds % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+    let minutes = (uptime_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+    let seconds = uptime_seconds % SECONDS_PER_MINUTE;
+
+    if days > 0 {
+        format!("{} days, {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
+
+/// Format process runtime into MM:SS.ss format
+///
+/// # Arguments
+/// * `runtime_seconds` - Process runtime in seconds
+/// * `format` - Configured time format
+///
+/// # Returns
+/// Formatted runtime string (e.g., "02:30.45")
+pub fn format_runtime(runtime_seconds: u64, format: TimeFormat) -> String {
+    match format {
+        TimeFormat::Iso => return format_iso_duration(runtime_seconds),
+        TimeFormat::Relative => return format_relative_duration(runtime_seconds),
+        TimeFormat::Clock | TimeFormat::Locale => {}
+    }
+
+    let hours = runtime_seconds / SECONDS_PER_HOUR;
+    let minutes = (runtime_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+    let seconds = runtime_seconds % SECONDS_PER_MINUTE;
+
+    format!("{:02}:{:02}.{:02}", hours, minutes, seconds)
+}
+
+/// Format a point in time, such as a process start or the boot time
+///
+/// # Arguments
+/// * `epoch_seconds` - Timestamp in seconds since the Unix epoch
+/// * `now_seconds` - Current time in seconds since the Unix epoch
+/// * `format` - Configured time format
+///
+/// # Returns
+/// Formatted timestamp (e.g., "14:05" today, "Jun03" earlier, "5m ago", or RFC 3339)
+pub fn format_timestamp(epoch_seconds: u64, now_seconds: u64, format: TimeFormat) -> String {
+    if format == TimeFormat::Relative {
+        let elapsed = now_seconds.saturating_sub(epoch_seconds);
+        return match elapsed {
+            e if e < SECONDS_PER_MINUTE => format!("{}s ago", e),
+            e if e < SECONDS_PER_HOUR => format!("{}m ago", e / SECONDS_PER_MINUTE),
+            e if e < SECONDS_PER_DAY => format!("{}h ago", e / SECONDS_PER_HOUR),
+            e => format!("{}d ago", e / SECONDS_PER_DAY),
+        };
+    }
+
+    let Some(time) = Local.timestamp_opt(epoch_seconds as i64, 0).single() else {
+        return "?".to_string();
+    };
+
+    match format {
+        TimeFormat::Iso => time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        TimeFormat::Locale => time.format("%c").to_string(),
+        _ if now_seconds.saturating_sub(epoch_seconds) < SECONDS_PER_DAY => {
+            time.format("%H:%M").to_string()
+        }
+        _ => time.format("%b%d").to_string(),
+    }
+}
+
+/// Format a duration as ISO 8601 (e.g., "P2DT3H45M12S")
+fn format_iso_duration(total_seconds: u64) -> String {
+    let days = total_seconds / SECONDS_PER_DAY;
+    let hours = (total_seconds % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+    let minutes = (total_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+    let seconds = total_seconds % SECONDS_PER_MINUTE;
+
+    let mut result = String::from("P");
+    if days > 0 {
+        result.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || days == 0 {
+        result.push('T');
+        if hours > 0 {
+            result.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            result.push_str(&format!("{}M", minutes));
+        }
+        if seconds > 0 || (hours == 0 && minutes == 0) {
+            result.push_str(&format!("{}S", seconds));
+        }
+    }
+
+    result
+}
+
+/// Format a duration with its two most significant units (e.g., "2d 3h", "45m 12s")
+fn format_relative_duration(total_seconds: u64) -> String {
+    let days = total_seconds / SECONDS_PER_DAY;
+    let hours = (total_seconds % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+    let minutes = (total_seconds % SECONDS_PER_HOUR) / SECONDS_PER_MINUTE;
+    let seconds = total_seconds % SECONDS_PER_MINUTE;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Find a top-level property in `ioreg -r` output
+///
+/// Top-level properties are printed one per line as `"Key" = value`.
+///
+/// # Arguments
+/// * `output` - Output of `ioreg -r`
+/// * `key` - Property name, without quotes
+///
+/// # Returns
+/// The property's value as printed, or None if it is absent
+#[cfg(target_os = "macos")]
+pub fn ioreg_property<'a>(output: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("\"{}\" = ", key);
+    output.lines().find_map(|line| {
+        line.split_once(&pattern)
+            .filter(|(prefix, _)| prefix.trim_start_matches([' ', '|']).is_empty())
+            .map(|(_, value)| value.trim())
+    })
+}
+
+/// Extract a number from an `ioreg` dictionary line
+///
+/// ioreg prints dictionaries inline as `{"Key"=123,"Other"=456}`.
+///
+/// # Arguments
+/// * `line` - A line of `ioreg` output
+/// * `key` - Dictionary key, without quotes
+///
+/// # Returns
+/// The number stored under `key`, or None if the key is absent
+#[cfg(target_os = "macos")]
+pub fn ioreg_number(line: &str, key: &str) -> Option<u64> {
+    let pattern = format!("\"{}\"=", key);
+    let value = &line[line.find(&pattern)? + pattern.len()..];
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_time_format_names() {
+        assert_eq!(TimeFormat::parse("ISO"), Some(TimeFormat::Iso));
+        assert_eq!(TimeFormat::parse(" relative "), Some(TimeFormat::Relative));
+        assert_eq!(TimeFormat::parse("locale"), Some(TimeFormat::Locale));
+        assert_eq!(TimeFormat::parse("clock"), Some(TimeFormat::Clock));
+        assert_eq!(TimeFormat::parse("unix"), None);
+    }
+
+    #[test]
+    fn groups_digits_in_threes() {
+        assert_eq!(group_digits(7, ","), "7");
+        assert_eq!(group_digits(999, ","), "999");
+        assert_eq!(group_digits(1_000, ","), "1,000");
+        assert_eq!(group_digits(4_194_304, "."), "4.194.304");
+        assert_eq!(group_digits(123_456, "\u{202f}"), "123\u{202f}456");
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("512M"), Some(512 << 20));
+        assert_eq!(parse_size("1.5g"), Some(3 << 29));
+        assert_eq!(parse_size("2GiB"), Some(2 << 30));
+        assert_eq!(parse_size("64KB"), Some(64 << 10));
+        assert_eq!(parse_size("lots"), None);
+        assert_eq!(parse_size("-1G"), None);
+    }
+
+    #[test]
+    fn clock_format_keeps_classic_layout() {
+        assert_eq!(format_uptime(3_723, TimeFormat::Clock), "01:02:03");
+        assert_eq!(
+            format_uptime(2 * 86_400 + 3_723, TimeFormat::Clock),
+            "2 days, 01:02:03"
+        );
+        assert_eq!(format_runtime(3_723, TimeFormat::Clock), "01:02.03");
+    }
+
+    #[test]
+    fn iso_durations() {
+        assert_eq!(format_runtime(0, TimeFormat::Iso), "PT0S");
+        assert_eq!(format_runtime(45, TimeFormat::Iso), "PT45S");
+        assert_eq!(format_runtime(3_600, TimeFormat::Iso), "PT1H");
+        assert_eq!(format_runtime(3_723, TimeFormat::Iso), "PT1H2M3S");
+        assert_eq!(format_uptime(86_400, TimeFormat::Iso), "P1D");
+        assert_eq!(format_uptime(90_000, TimeFormat::Iso), "P1DT1H");
+    }
+
+    #[test]
+    fn relative_durations() {
+        assert_eq!(format_runtime(12, TimeFormat::Relative), "12s");
+        assert_eq!(format_runtime(312, TimeFormat::Relative), "5m 12s");
+        assert_eq!(format_runtime(3_723, TimeFormat::Relative), "1h 2m");
+        assert_eq!(format_uptime(183_600, TimeFormat::Relative), "2d 3h");
+    }
+
+    #[test]
+    fn relative_timestamps() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            format_timestamp(now - 30, now, TimeFormat::Relative),
+            "30s ago"
+        );
+        assert_eq!(
+            format_timestamp(now - 330, now, TimeFormat::Relative),
+            "5m ago"
+        );
+        assert_eq!(
+            format_timestamp(now - 7_200, now, TimeFormat::Relative),
+            "2h ago"
+        );
+        assert_eq!(
+            format_timestamp(now - 200_000, now, TimeFormat::Relative),
+            "2d ago"
+        );
+        // Timestamps in the future clamp to zero elapsed time
+        assert_eq!(
+            format_timestamp(now + 10, now, TimeFormat::Relative),
+            "0s ago"
+        );
+    }
+
+    #[test]
+    fn iso_timestamps_round_trip() {
+        let stamp = format_timestamp(1_700_000_000, 1_700_000_000, TimeFormat::Iso);
+        let parsed = chrono::DateTime::parse_from_rfc3339(&stamp).unwrap();
+        assert_eq!(parsed.timestamp(), 1_700_000_000);
+    }
+}
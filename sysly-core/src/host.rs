@@ -0,0 +1,94 @@
+use sysinfo::System;
+
+use crate::snapshot::Snapshot;
+
+/// What the machine is, for the dashboard header
+#[derive(Debug, Clone, Default)]
+pub struct HostInfo {
+    pub name: String,
+    /// Product name and version, e.g. `macOS 14.5` or `Ubuntu 22.04`
+    pub os: String,
+    /// macOS build number, e.g. `23F79`, or the kernel release on Linux
+    pub build: Option<String>,
+    /// CPU brand string, e.g. `Apple M2 Pro`
+    pub chip: String,
+}
+
+impl HostInfo {
+    /// One line naming the host, OS and chip, with the parts that are known
+    pub fn summary(&self) -> String {
+        let os = match &self.build {
+            Some(build) => format!("{} ({})", self.os, build),
+            None => self.os.clone(),
+        };
+        [self.name.as_str(), os.as_str(), self.chip.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// Read the host name, OS version and chip model
+///
+/// These don't change while sysly runs, so they are read once. The chip
+/// comes from the CPUs `sys` has already read.
+pub fn fetch_host_info(sys: &Snapshot) -> HostInfo {
+    let chip = sys
+        .cpus()
+        .first()
+        .map(|cpu| cpu.brand().trim().to_string())
+        .unwrap_or_default();
+
+    HostInfo {
+        name: System::host_name().unwrap_or_default(),
+        os: os_name(),
+        build: os_build(),
+        chip,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn os_name() -> String {
+    System::os_version()
+        .map(|version| format!("macOS {}", version))
+        .unwrap_or_else(|| "macOS".to_string())
+}
+
+/// The distribution and its version, e.g. `Debian GNU/Linux 12`
+#[cfg(not(target_os = "macos"))]
+fn os_name() -> String {
+    match (System::name(), System::os_version()) {
+        (Some(name), Some(version)) => format!("{} {}", name, version),
+        (name, _) => name.unwrap_or_default(),
+    }
+}
+
+/// The build `sw_vers -buildVersion` prints, from the `kern.osversion` sysctl
+#[cfg(target_os = "macos")]
+fn os_build() -> Option<String> {
+    let name = std::ffi::CString::new("kern.osversion").ok()?;
+    let mut value = [0u8; 64];
+    let mut size = value.len();
+
+    let result = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            value.as_mut_ptr() as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+    // The length includes the terminating NUL
+    let build = String::from_utf8_lossy(&value[..size.saturating_sub(1)]);
+    Some(build.trim_end_matches('\0').to_string()).filter(|build| !build.is_empty())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn os_build() -> Option<String> {
+    System::kernel_version()
+}
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use sysinfo::Pid;
+
+use crate::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Where a process came from, gathered for the inspector's Identity tab
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub exe: Option<PathBuf>,
+    /// SHA-256 of the executable file, in hex
+    pub sha256: Option<String>,
+    /// Parent, grandparent and so on, up to the root of the tree (PID 1)
+    pub ancestors: Vec<(Pid, String)>,
+    /// Seconds between boot and the process's start
+    pub since_boot: Option<u64>,
+    /// The launchd job (macOS) or systemd unit (Linux) that owns the process
+    pub service: Option<String>,
+}
+
+/// Gather the identity of a process
+///
+/// Hashing reads the whole executable and the service lookup may run
+/// `launchctl`, so this runs once, when the tab is first shown.
+pub fn fetch_identity(process: &ProcessSnapshot, sys: &Snapshot) -> Identity {
+    let ancestors = ancestors(process, sys);
+    let boot_time = sys.boot_time();
+
+    Identity {
+        exe: process.exe().map(PathBuf::from),
+        sha256: process.exe().and_then(hash_file),
+        since_boot: (boot_time > 0 && process.start_time() >= boot_time)
+            .then(|| process.start_time() - boot_time),
+        service: fetch_service(process.pid(), &ancestors),
+        ancestors,
+    }
+}
+
+/// Walk the parent chain up to a process without a parent
+fn ancestors(process: &ProcessSnapshot, sys: &Snapshot) -> Vec<(Pid, String)> {
+    let mut chain: Vec<(Pid, String)> = Vec::new();
+    let mut current = process;
+    while let Some(ppid) = current.parent() {
+        // Guard against PID reuse producing a parent cycle
+        if ppid == current.pid() || chain.iter().any(|(pid, _)| *pid == ppid) {
+            break;
+        }
+        let Some(parent) = sys.process(ppid) else {
+            chain.push((ppid, "?".to_string()));
+            break;
+        };
+        chain.push((ppid, parent.name().to_string()));
+        current = parent;
+    }
+    chain
+}
+
+/// Hash a file with the system's SHA-256 tool
+#[cfg(target_os = "macos")]
+fn hash_file(path: &Path) -> Option<String> {
+    run_hash(Command::new("shasum").args(["-a", "256"]).arg(path))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn hash_file(path: &Path) -> Option<String> {
+    run_hash(Command::new("sha256sum").arg(path))
+}
+
+/// Take the hash from "<hex>  <path>" output
+fn run_hash(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+/// Find the launchd job running the process or its closest ancestor, from
+/// `launchctl list`
+///
+/// Without root only the user's own jobs are listed, so system daemons come
+/// up empty.
+#[cfg(target_os = "macos")]
+fn fetch_service(pid: Pid, ancestors: &[(Pid, String)]) -> Option<String> {
+    let output = Command::new("launchctl").arg("list").output().ok()?;
+    // "PID	Status	Label", with "-" for jobs that aren't running
+    let jobs: Vec<(u32, String)> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let pid = fields.next()?.parse().ok()?;
+            let label = fields.nth(1)?;
+            Some((pid, label.to_string()))
+        })
+        .collect();
+
+    std::iter::once(pid)
+        .chain(ancestors.iter().map(|(pid, _)| *pid))
+        .find_map(|pid| {
+            jobs.iter()
+                .find(|(job_pid, _)| *job_pid == pid.as_u32())
+                .map(|(_, label)| label.clone())
+        })
+}
+
+/// Find the systemd unit owning the process from its cgroup, e.g.
+/// `nginx.service` for `/system.slice/nginx.service`
+#[cfg(not(target_os = "macos"))]
+fn fetch_service(pid: Pid, _ancestors: &[(Pid, String)]) -> Option<String> {
+    service_from_cgroup(&std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?)
+}
+
+/// The systemd unit in the contents of `/proc/<pid>/cgroup`
+#[cfg(not(target_os = "macos"))]
+fn service_from_cgroup(contents: &str) -> Option<String> {
+    // "0::/system.slice/nginx.service" on cgroup v2, "1:name=systemd:/..." on v1
+    let path = contents.lines().find_map(|line| {
+        line.strip_prefix("0::")
+            .or_else(|| line.strip_prefix("1:name=systemd:"))
+    })?;
+    path.rsplit('/')
+        .find(|part| part.ends_with(".service") || part.ends_with(".scope"))
+        .map(str::to_string)
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn services_come_from_the_systemd_cgroup() {
+        assert_eq!(
+            service_from_cgroup("0::/system.slice/nginx.service\n").as_deref(),
+            Some("nginx.service")
+        );
+        let v1 =
+            "12:pids:/user.slice\n1:name=systemd:/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(service_from_cgroup(v1).as_deref(), Some("session-2.scope"));
+        assert_eq!(service_from_cgroup("0::/\n"), None);
+    }
+}
@@ -0,0 +1,74 @@
+//! Data collection and formatting behind the sysly dashboard
+//!
+//! Each module reads one kind of system or process data, on macOS and
+//! Linux, and hands it back as plain structs that know nothing about the
+//! terminal UI, so scripts and tests can use them directly:
+//!
+//! ```no_run
+//! use sysly_core::helpers::format_bytes;
+//! use sysly_core::memstat::fetch_memory_stats;
+//!
+//! if let Some(stats) = fetch_memory_stats() {
+//!     println!("wired: {}", format_bytes(stats.wired));
+//! }
+//! ```
+//!
+//! Collectors that shell out or read the kernel return `Option` or an empty
+//! list when the data isn't available on the platform or without root;
+//! [`collector::Collectors`] runs them with panic isolation and timings.
+
+/// Battery charge, state and time-remaining estimates
+pub mod battery;
+/// Homebrew services and the processes they run
+pub mod brew;
+/// Cached system inventory (users, sensors, volumes, core clusters) for a
+/// warm start
+pub mod cache;
+/// cgroup v2 usage and limits
+pub mod cgroup;
+/// Panic-isolated collector runs with per-collector health and timings
+pub mod collector;
+/// Disk counters and rates, and mounted volumes
+pub mod disk;
+/// Loaded kernel and system extensions (kernel modules on Linux)
+pub mod extensions;
+/// Fan speeds from the Apple SMC (hwmon on Linux)
+pub mod fans;
+/// Open file descriptors of a process
+pub mod files;
+/// GPU utilization and memory
+pub mod gpu;
+/// Formatting of sizes, durations, timestamps and counts
+pub mod helpers;
+/// Host name, OS version and chip model
+pub mod host;
+/// Executable hash, ancestry and owning service of a process
+pub mod identity;
+/// Memory breakdown and swap activity
+pub mod memstat;
+/// Per-process network totals and rates
+pub mod network;
+/// Process origin and code signing identities
+pub mod origin;
+/// Per-process priorities, memory, disk I/O and privileged collectors
+pub mod process;
+/// The `SystemProvider` trait over sysinfo and the other collectors, with
+/// live and scripted implementations
+pub mod provider;
+/// Rosetta 2 translation check per process
+pub mod rosetta;
+/// Per-process resource usage counters (idle wake-ups)
+pub mod rusage;
+/// QoS class, App Nap state and scheduling policy per process
+pub mod sched;
+/// Temperatures, throttle episodes and thermal pressure
+pub mod sensors;
+/// Owned snapshots of CPUs, memory and processes, as providers hand them
+/// to the dashboard
+pub mod snapshot;
+/// Login items, launchd jobs, XDG autostart entries and systemd units
+pub mod startup;
+/// Per-process thread lists and system-wide thread counts
+pub mod threads;
+/// Performance and efficiency core clusters
+pub mod topology;
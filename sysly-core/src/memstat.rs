@@ -0,0 +1,203 @@
+#[cfg(target_os = "macos")]
+use std::collections::HashMap;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use std::time::Instant;
+
+/// Where physical memory goes, in bytes, split the way Activity Monitor
+/// and htop show it
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryStats {
+    /// Memory applications have allocated and can't be dropped
+    pub app: u64,
+    /// Memory the kernel keeps resident and never pages out
+    pub wired: u64,
+    /// Memory held compressed by the memory compressor
+    pub compressed: u64,
+    /// File caches and purgeable memory, reclaimed when needed
+    pub cached: u64,
+    /// Bytes swapped in and out since boot
+    pub swapped_in: u64,
+    pub swapped_out: u64,
+}
+
+impl MemoryStats {
+    /// Memory in use, not counting caches
+    pub fn used(&self) -> u64 {
+        self.app + self.wired + self.compressed
+    }
+}
+
+/// Read the memory breakdown from `vm_stat`
+///
+/// App memory is anonymous memory minus purgeable pages; cached is
+/// file-backed plus purgeable pages, as in Activity Monitor.
+///
+/// # Returns
+/// MemoryStats, or None if vm_stat is unavailable
+#[cfg(target_os = "macos")]
+pub fn fetch_memory_stats() -> Option<MemoryStats> {
+    let output = Command::new("vm_stat").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Header line: "Mach Virtual Memory Statistics: (page size of 16384 bytes)"
+    let page_size = stdout
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+
+    // "Pages wired down:                       123456."
+    let pages: HashMap<&str, u64> = stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let value = value.trim().trim_end_matches('.').parse().ok()?;
+            Some((name.trim(), value))
+        })
+        .collect();
+    let bytes = |name: &str| pages.get(name).copied().unwrap_or(0) * page_size;
+
+    let purgeable = bytes("Pages purgeable");
+    Some(MemoryStats {
+        app: bytes("Anonymous pages").saturating_sub(purgeable),
+        wired: bytes("Pages wired down"),
+        compressed: bytes("Pages occupied by compressor"),
+        cached: bytes("File-backed pages") + purgeable,
+        swapped_in: bytes("Swapins"),
+        swapped_out: bytes("Swapouts"),
+    })
+}
+
+/// Read the memory breakdown from `/proc/meminfo` and swap counters from
+/// `/proc/vmstat`
+///
+/// Anonymous pages stand in for app memory, unevictable and kernel-internal
+/// memory for wired, zswap for compressed, and page cache plus reclaimable
+/// slab for cached.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_memory_stats() -> Option<MemoryStats> {
+    let contents = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let vmstat = std::fs::read_to_string("/proc/vmstat").unwrap_or_default();
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(0) as u64;
+    parse_meminfo(&contents, &vmstat, page_size)
+}
+
+/// Build the breakdown from the contents of `/proc/meminfo` and
+/// `/proc/vmstat`, whose swap counters are in pages of `page_size` bytes
+#[cfg(not(target_os = "macos"))]
+fn parse_meminfo(contents: &str, vmstat: &str, page_size: u64) -> Option<MemoryStats> {
+    // "pswpin 1234"
+    let pages = |name: &str| -> u64 {
+        vmstat
+            .lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                (key == name).then(|| value.trim().parse::<u64>().ok())?
+            })
+            .unwrap_or(0)
+    };
+
+    // "AnonPages:       1234567 kB"
+    let kilobytes = |name: &str| -> u64 {
+        contents
+            .lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key == name).then(|| value.split_whitespace().next()?.parse::<u64>().ok())?
+            })
+            .unwrap_or(0)
+            * 1024
+    };
+
+    let app = kilobytes("AnonPages");
+    if app == 0 {
+        return None;
+    }
+    Some(MemoryStats {
+        app,
+        wired: kilobytes("Unevictable")
+            + kilobytes("SUnreclaim")
+            + kilobytes("KernelStack")
+            + kilobytes("PageTables"),
+        compressed: kilobytes("Zswap"),
+        cached: kilobytes("Buffers") + kilobytes("Cached") + kilobytes("SReclaimable"),
+        swapped_in: pages("pswpin") * page_size,
+        swapped_out: pages("pswpout") * page_size,
+    })
+}
+
+/// Swap traffic over the last refresh, in bytes per second
+#[derive(Debug, Clone, Copy)]
+pub struct SwapRates {
+    pub in_per_second: f64,
+    pub out_per_second: f64,
+}
+
+/// Turns the cumulative swap counters into rates, which show whether the
+/// machine is actively thrashing rather than just holding swap
+#[derive(Debug, Default)]
+pub struct SwapMeter {
+    previous: Option<(u64, u64, Instant)>,
+    rates: Option<SwapRates>,
+}
+
+impl SwapMeter {
+    /// Record a new sample; rates are available from the second sample on
+    pub fn sample(&mut self, stats: Option<&MemoryStats>) {
+        let Some(stats) = stats else {
+            self.previous = None;
+            self.rates = None;
+            return;
+        };
+
+        let now = Instant::now();
+        if let Some((swapped_in, swapped_out, at)) = self.previous {
+            let seconds = now.duration_since(at).as_secs_f64();
+            if seconds > 0.0 {
+                self.rates = Some(SwapRates {
+                    in_per_second: stats.swapped_in.saturating_sub(swapped_in) as f64 / seconds,
+                    out_per_second: stats.swapped_out.saturating_sub(swapped_out) as f64 / seconds,
+                });
+            }
+        }
+        self.previous = Some((stats.swapped_in, stats.swapped_out, now));
+    }
+
+    pub fn rates(&self) -> Option<SwapRates> {
+        self.rates
+    }
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meminfo_splits_into_app_wired_compressed_and_cached() {
+        let meminfo = "MemTotal:        8000000 kB\n\
+                       AnonPages:          1000 kB\n\
+                       Unevictable:          10 kB\n\
+                       SUnreclaim:           20 kB\n\
+                       KernelStack:          30 kB\n\
+                       PageTables:           40 kB\n\
+                       Zswap:                 5 kB\n\
+                       Buffers:             100 kB\n\
+                       Cached:              200 kB\n\
+                       SReclaimable:         50 kB\n";
+        let stats = parse_meminfo(meminfo, "pswpin 3\npswpout 4\n", 4096).unwrap();
+        assert_eq!(stats.app, 1000 * 1024);
+        assert_eq!(stats.wired, 100 * 1024);
+        assert_eq!(stats.compressed, 5 * 1024);
+        assert_eq!(stats.cached, 350 * 1024);
+        assert_eq!(stats.swapped_in, 3 * 4096);
+        assert_eq!(stats.swapped_out, 4 * 4096);
+
+        assert!(parse_meminfo("MemTotal: 8000000 kB\n", "", 4096).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use std::time::Instant;
+use sysinfo::Pid;
+
+/// Cumulative bytes a process has moved over the network since it started
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetworkCounters {
+    pub received: u64,
+    pub sent: u64,
+}
+
+/// A process's network traffic since sysly started, and its current rates
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkUsage {
+    pub received: u64,
+    pub sent: u64,
+    /// Bytes per second received and sent since the previous sample; None
+    /// until a process has been sampled twice
+    pub rates: Option<(f64, f64)>,
+}
+
+impl NetworkUsage {
+    pub fn total(&self) -> u64 {
+        self.received + self.sent
+    }
+
+    pub fn total_rate(&self) -> f64 {
+        self.rates.map_or(0.0, |(received, sent)| received + sent)
+    }
+}
+
+/// Read per-process network counters with `nettop`
+///
+/// Runs a single logging sample (`-L 1`) of the bytes in and out of every
+/// process with network activity, as raw numbers.
+///
+/// # Returns
+/// Counters by PID, or None if nettop could not be run
+#[cfg(target_os = "macos")]
+pub fn fetch_network_counters() -> Option<HashMap<Pid, NetworkCounters>> {
+    let output = Command::new("nettop")
+        .args(["-P", "-L", "1", "-x", "-n", "-J", "bytes_in,bytes_out"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // time,,bytes_in,bytes_out,
+    // 12:00:00.000000,Safari.123,4567,890,
+    let mut counters = HashMap::new();
+    for line in stdout.lines().skip(1) {
+        let fields: Vec<&str> = line.split(',').collect();
+        let (Some(name), Some(received), Some(sent)) =
+            (fields.get(1), fields.get(2), fields.get(3))
+        else {
+            continue;
+        };
+        let Some(pid) = name
+            .rsplit_once('.')
+            .and_then(|(_, pid)| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        counters.insert(
+            Pid::from_u32(pid),
+            NetworkCounters {
+                received: received.trim().parse().unwrap_or(0),
+                sent: sent.trim().parse().unwrap_or(0),
+            },
+        );
+    }
+
+    Some(counters)
+}
+
+/// Linux only accounts network traffic per interface, not per process
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_network_counters() -> Option<HashMap<Pid, NetworkCounters>> {
+    None
+}
+
+/// Turns per-process network counters into totals since sysly started and
+/// rates between samples
+///
+/// The counters are cumulative, so samples can be taken only while the
+/// traffic is on screen; the totals stay exact as long as the first sample
+/// was taken at startup.
+#[derive(Debug, Default)]
+pub struct NetworkTracker {
+    /// Counters at the first sample; processes started later count from zero
+    baseline: HashMap<Pid, NetworkCounters>,
+    previous: Option<(HashMap<Pid, NetworkCounters>, Instant)>,
+    pub usage: HashMap<Pid, NetworkUsage>,
+}
+
+impl NetworkTracker {
+    pub fn sample(&mut self, counters: HashMap<Pid, NetworkCounters>) {
+        let now = Instant::now();
+        if self.previous.is_none() {
+            self.baseline = counters.clone();
+        }
+
+        let previous = self.previous.take();
+        self.usage = counters
+            .iter()
+            .map(|(pid, current)| {
+                let baseline = self.baseline.get(pid).copied().unwrap_or_default();
+                let rates = previous.as_ref().and_then(|(previous, at)| {
+                    let before = previous.get(pid)?;
+                    let seconds = now.duration_since(*at).as_secs_f64();
+                    (seconds > 0.0).then(|| {
+                        (
+                            current.received.saturating_sub(before.received) as f64 / seconds,
+                            current.sent.saturating_sub(before.sent) as f64 / seconds,
+                        )
+                    })
+                });
+
+                let usage = NetworkUsage {
+                    received: current.received.saturating_sub(baseline.received),
+                    sent: current.sent.saturating_sub(baseline.sent),
+                    rates,
+                };
+                (*pid, usage)
+            })
+            .collect();
+        self.previous = Some((counters, now));
+    }
+}
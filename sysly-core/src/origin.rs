@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::path::Path;
+use sysinfo::Pid;
+
+use crate::snapshot::{ProcessSnapshot, Snapshot};
+
+/// Where a process's executable came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// Part of the operating system: an Apple platform binary or a file
+    /// under a system path
+    System,
+    /// An app bundle installed from the App Store
+    AppStore,
+    /// An installed application or package outside the user's home
+    Installed,
+    /// Launched from the user's home directory or another user path
+    User,
+    /// The executable path isn't readable
+    Unknown,
+}
+
+impl Origin {
+    /// Short code shown in the ORIG column
+    pub fn code(self) -> &'static str {
+        match self {
+            Origin::System => "sys",
+            Origin::AppStore => "mas",
+            Origin::Installed => "app",
+            Origin::User => "usr",
+            Origin::Unknown => "?",
+        }
+    }
+}
+
+/// Directories whose executables belong to the operating system
+const SYSTEM_PREFIXES: &[&str] = &[
+    "/System/",
+    "/usr/bin/",
+    "/usr/sbin/",
+    "/usr/lib/",
+    "/usr/libexec/",
+    "/bin/",
+    "/sbin/",
+    "/Library/Apple/",
+];
+
+/// Directories holding installed applications and packages
+const INSTALLED_PREFIXES: &[&str] = &[
+    "/Applications/",
+    "/Library/",
+    "/opt/",
+    "/usr/local/",
+    "/snap/",
+];
+
+/// Classify a process by its executable path and code signature
+///
+/// Apple platform binaries count as system processes wherever they live;
+/// an app bundle carrying an App Store receipt counts as an App Store app.
+pub fn classify(process: &ProcessSnapshot) -> Origin {
+    let Some(exe) = process.exe().filter(|exe| exe.is_absolute()) else {
+        return Origin::Unknown;
+    };
+
+    if is_platform_binary(process.pid()) || starts_with_any(exe, SYSTEM_PREFIXES) {
+        return Origin::System;
+    }
+    if has_app_store_receipt(exe) {
+        return Origin::AppStore;
+    }
+    if starts_with_any(exe, INSTALLED_PREFIXES) {
+        return Origin::Installed;
+    }
+
+    Origin::User
+}
+
+fn starts_with_any(path: &Path, prefixes: &[&str]) -> bool {
+    let path = path.to_string_lossy();
+    prefixes.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// The app bundle an executable belongs to: the outermost `.app` above
+/// it, so helper apps nested inside an app count as part of it
+pub fn app_bundle(exe: &Path) -> Option<&Path> {
+    exe.ancestors()
+        .filter(|dir| dir.extension().is_some_and(|ext| ext == "app"))
+        .last()
+}
+
+/// Whether the executable sits in an app bundle with a `_MASReceipt`
+fn has_app_store_receipt(exe: &Path) -> bool {
+    app_bundle(exe).is_some_and(|bundle| bundle.join("Contents/_MASReceipt/receipt").exists())
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn csops(
+        pid: libc::pid_t,
+        ops: libc::c_uint,
+        useraddr: *mut libc::c_void,
+        usersize: libc::size_t,
+    ) -> libc::c_int;
+}
+
+/// `<sys/codesign.h>` values not exported by libc
+#[cfg(target_os = "macos")]
+const CS_OPS_STATUS: libc::c_uint = 0;
+#[cfg(target_os = "macos")]
+const CS_VALID: u32 = 0x0000_0001;
+#[cfg(target_os = "macos")]
+const CS_PLATFORM_BINARY: u32 = 0x0400_0000;
+
+/// Whether the kernel marks the running process as a valid Apple platform
+/// binary, read from its code signing status
+#[cfg(target_os = "macos")]
+fn is_platform_binary(pid: Pid) -> bool {
+    let mut flags: u32 = 0;
+    let result = unsafe {
+        csops(
+            pid.as_u32() as libc::pid_t,
+            CS_OPS_STATUS,
+            &mut flags as *mut _ as *mut libc::c_void,
+            std::mem::size_of::<u32>(),
+        )
+    };
+
+    result == 0 && flags & CS_VALID != 0 && flags & CS_PLATFORM_BINARY != 0
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_platform_binary(_pid: Pid) -> bool {
+    false
+}
+
+/// How an executable is code signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum CodeSignature {
+    /// Signed with a certificate
+    Signed {
+        /// Leaf certificate, e.g. `Developer ID Application: Name (TEAMID)`
+        authority: String,
+        team: Option<String>,
+        identifier: Option<String>,
+    },
+    /// Signed without a certificate, so nothing vouches for its author
+    AdHoc {
+        identifier: Option<String>,
+    },
+    Unsigned,
+}
+
+/// Read the signing identity of an executable with `codesign -dv`
+///
+/// # Returns
+/// The signature, or None when codesign can't examine the file
+#[cfg(target_os = "macos")]
+pub fn fetch_code_signature(exe: &Path) -> Option<CodeSignature> {
+    let output = std::process::Command::new("codesign")
+        .args(["-dv", "--verbose=2"])
+        .arg(exe)
+        .output()
+        .ok()?;
+    // codesign reports on stderr
+    let report = String::from_utf8_lossy(&output.stderr);
+    if report.contains("not signed at all") {
+        return Some(CodeSignature::Unsigned);
+    }
+    if !output.status.success() {
+        return None;
+    }
+
+    // "Authority=Developer ID Application: Name (TEAMID)", leaf first
+    let field = |name: &str| {
+        report.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key == name).then(|| value.trim().to_string())
+        })
+    };
+    let identifier = field("Identifier");
+    if field("Signature").as_deref() == Some("adhoc") {
+        return Some(CodeSignature::AdHoc { identifier });
+    }
+
+    Some(CodeSignature::Signed {
+        authority: field("Authority").unwrap_or_else(|| "?".to_string()),
+        team: field("TeamIdentifier").filter(|team| team != "not set"),
+        identifier,
+    })
+}
+
+/// Linux executables carry no code signature
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_code_signature(_exe: &Path) -> Option<CodeSignature> {
+    None
+}
+
+/// Origins of running processes, classified once per process
+#[derive(Debug, Default)]
+pub struct OriginCache {
+    origins: HashMap<Pid, Origin>,
+}
+
+impl OriginCache {
+    /// Classify processes seen for the first time and forget exited ones
+    pub fn update(&mut self, sys: &Snapshot) {
+        self.origins.retain(|pid, _| sys.process(*pid).is_some());
+        for (pid, process) in sys.processes() {
+            self.origins
+                .entry(*pid)
+                .or_insert_with(|| classify(process));
+        }
+    }
+
+    pub fn get(&self, pid: Pid) -> Option<Origin> {
+        self.origins.get(&pid).copied()
+    }
+}
@@ -0,0 +1,800 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::collector::Collectors;
+use std::process::Command;
+
+/// Process information containing priority and nice values
+#[derive(Debug, Clone)]
+pub struct ProcessPriority {
+    pub priority: String,
+    pub nice: String,
+}
+
+/// Process memory information containing virtual and resident memory
+#[derive(Debug, Clone)]
+pub struct ProcessMemory {
+    pub virtual_memory: u64,
+    pub resident_memory: u64,
+}
+
+/// PRI/NI and VIRT/RES lookups gathered through libproc, keyed by PID
+#[derive(Debug, Clone, Default)]
+pub struct ProcessMaps {
+    pub priority: HashMap<u32, ProcessPriority>,
+    pub memory: HashMap<u32, ProcessMemory>,
+}
+
+impl ProcessMaps {
+    /// Collect both maps for all processes, each behind a panic boundary
+    pub fn fetch(collectors: &mut Collectors) -> Self {
+        ProcessMaps {
+            priority: collectors
+                .run("libproc pri/ni", fetch_priority_map)
+                .unwrap_or_default(),
+            memory: collectors
+                .run("libproc vsz/rss", fetch_memory_map)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Output of a privileged collector shared with its reader thread
+#[derive(Debug)]
+struct PrivilegedOutput<T> {
+    /// Most recent sample not yet taken
+    sample: Option<T>,
+    /// Why the command stopped, once it has
+    failure: Option<String>,
+}
+
+/// A long-running command that needs root, such as `powermetrics`
+///
+/// The command runs through `sudo -n` unless sysly is already root, so a
+/// missing sudo ticket fails straight away instead of prompting under the
+/// TUI. Its output is read on a background thread and split into samples
+/// at a separator byte; the command is killed when the collector is dropped.
+#[derive(Debug)]
+pub struct PrivilegedCollector<T> {
+    output: Arc<Mutex<PrivilegedOutput<T>>>,
+    child: Child,
+}
+
+impl<T: Send + 'static> PrivilegedCollector<T> {
+    /// Start a privileged command
+    ///
+    /// # Arguments
+    /// * `program` - Command to run as root
+    /// * `args` - Its arguments
+    /// * `separator` - Byte ending each sample in the command's output
+    /// * `parse` - Turns one sample into data, or None to skip it
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub fn spawn(
+        program: &str,
+        args: &[&str],
+        separator: u8,
+        parse: fn(&str) -> Option<T>,
+    ) -> io::Result<Self> {
+        let mut command = if unsafe { libc::geteuid() } == 0 {
+            Command::new(program)
+        } else {
+            let mut sudo = Command::new("sudo");
+            sudo.arg("-n").arg(program);
+            sudo
+        };
+        let mut child = command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let output = Arc::new(Mutex::new(PrivilegedOutput {
+            sample: None,
+            failure: None,
+        }));
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("stdout not captured"))?;
+        let stderr = child.stderr.take();
+        let shared = Arc::clone(&output);
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut buffer = Vec::new();
+            while reader.read_until(separator, &mut buffer).unwrap_or(0) > 0 {
+                if let Some(sample) = parse(&String::from_utf8_lossy(&buffer)) {
+                    if let Ok(mut output) = shared.lock() {
+                        output.sample = Some(sample);
+                    }
+                }
+                buffer.clear();
+            }
+
+            // The command only stops on error; sudo explains why on stderr
+            let mut message = String::new();
+            if let Some(mut stderr) = stderr {
+                let _ = stderr.read_to_string(&mut message);
+            }
+            let message = message
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or("command exited")
+                .trim()
+                .to_string();
+            if let Ok(mut output) = shared.lock() {
+                output.failure = Some(message);
+            }
+        });
+
+        Ok(PrivilegedCollector { output, child })
+    }
+
+    /// Take the newest sample since the last call
+    ///
+    /// # Returns
+    /// The sample, None while waiting for the next one, or the reason the
+    /// command stopped
+    pub fn take_sample(&self) -> Result<Option<T>, String> {
+        let mut output = self
+            .output
+            .lock()
+            .map_err(|_| "collector thread panicked".to_string())?;
+        match output.sample.take() {
+            Some(sample) => Ok(Some(sample)),
+            None => output.failure.clone().map_or(Ok(None), Err),
+        }
+    }
+}
+
+impl<T> Drop for PrivilegedCollector<T> {
+    fn drop(&mut self) {
+        // SIGTERM rather than SIGKILL, so sudo passes it on to the command
+        unsafe {
+            libc::kill(self.child.id() as libc::pid_t, libc::SIGTERM);
+        }
+        let _ = self.child.wait();
+    }
+}
+
+/// Disk throughput of one process, in bytes per second
+#[derive(Debug, Clone, Copy)]
+pub struct DiskIoRates {
+    pub read_per_second: f64,
+    pub written_per_second: f64,
+}
+
+/// Turns per-process cumulative disk byte counters into rates between
+/// refreshes, for the DISK R/s and W/s columns
+#[derive(Debug, Default)]
+pub struct DiskIoTracker {
+    previous: HashMap<u32, (u64, u64)>,
+    pub rates: HashMap<u32, DiskIoRates>,
+}
+
+impl DiskIoTracker {
+    /// Read the counters of every process and compute rates over `interval`
+    ///
+    /// Processes seen for the first time have no rate until the next sample.
+    ///
+    /// # Returns
+    /// None when no process could be read
+    pub fn sample(&mut self, pids: impl Iterator<Item = u32>, interval: Duration) -> Option<()> {
+        let seconds = interval.as_secs_f64();
+        let mut current = HashMap::new();
+        self.rates.clear();
+
+        for pid in pids {
+            let Some((read, written)) = fetch_disk_io_counters(pid) else {
+                continue;
+            };
+            if let Some((previous_read, previous_written)) = self.previous.get(&pid) {
+                if seconds > 0.0 {
+                    self.rates.insert(
+                        pid,
+                        DiskIoRates {
+                            read_per_second: read.saturating_sub(*previous_read) as f64 / seconds,
+                            written_per_second: written.saturating_sub(*previous_written) as f64
+                                / seconds,
+                        },
+                    );
+                }
+            }
+            current.insert(pid, (read, written));
+        }
+
+        self.previous = current;
+        (!self.previous.is_empty()).then_some(())
+    }
+}
+
+/// Memory compressor occupancy containing page count and size in bytes
+#[derive(Debug, Clone)]
+pub struct CompressorStats {
+    pub pages: u64,
+    pub bytes: u64,
+}
+
+/// List every PID on the system with `proc_listallpids`
+#[cfg(target_os = "macos")]
+fn list_all_pids() -> Vec<u32> {
+    let count = unsafe { libc::proc_listallpids(std::ptr::null_mut(), 0) };
+    if count <= 0 {
+        return Vec::new();
+    }
+
+    // Leave headroom for processes started since the count was taken
+    let mut pids: Vec<libc::pid_t> = vec![0; count as usize + 64];
+    let size = (pids.len() * std::mem::size_of::<libc::pid_t>()) as libc::c_int;
+    let count = unsafe { libc::proc_listallpids(pids.as_mut_ptr() as *mut libc::c_void, size) };
+    pids.truncate(count.max(0) as usize);
+
+    pids.into_iter()
+        .filter(|pid| *pid > 0)
+        .map(|pid| pid as u32)
+        .collect()
+}
+
+/// `<sys/proc_info.h>` flavors not exported by libc
+#[cfg(target_os = "macos")]
+pub const PROC_PIDTHREADID64INFO: libc::c_int = 15;
+#[cfg(target_os = "macos")]
+pub const PROC_PIDLISTTHREADIDS: libc::c_int = 28;
+
+/// Read one `proc_pidinfo` flavor into its struct
+///
+/// Task info is only readable for your own processes unless running as
+/// root; BSD info is readable for every process.
+#[cfg(target_os = "macos")]
+pub fn proc_info<T>(pid: u32, flavor: libc::c_int) -> Option<T> {
+    let mut info: T = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<T>() as libc::c_int;
+
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            flavor,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+    (written == size).then_some(info)
+}
+
+/// Fetch priority and nice values for all processes on macOS
+///
+/// The base priority comes from each process's task info and the nice
+/// value from its BSD info. Processes whose task info isn't readable, which
+/// are other users' processes unless sysly runs as root, are filled in from
+/// `ps`.
+///
+/// # Returns
+/// HashMap mapping PID to (priority, nice) values
+#[cfg(target_os = "macos")]
+pub fn fetch_priority_map() -> HashMap<u32, ProcessPriority> {
+    let mut map = HashMap::new();
+    let mut missing = false;
+
+    for pid in list_all_pids() {
+        let task = proc_info::<libc::proc_taskinfo>(pid, libc::PROC_PIDTASKINFO);
+        let bsd = proc_info::<libc::proc_bsdinfo>(pid, libc::PROC_PIDTBSDINFO);
+        match (task, bsd) {
+            (Some(task), Some(bsd)) => {
+                map.insert(
+                    pid,
+                    ProcessPriority {
+                        priority: task.pti_priority.to_string(),
+                        nice: bsd.pbi_nice.to_string(),
+                    },
+                );
+            }
+            _ => missing = true,
+        }
+    }
+
+    if missing || map.is_empty() {
+        for (pid, priority) in ps_priority_map() {
+            map.entry(pid).or_insert(priority);
+        }
+    }
+
+    map
+}
+
+/// Read PRI/NI for all processes from `ps`
+#[cfg(target_os = "macos")]
+fn ps_priority_map() -> HashMap<u32, ProcessPriority> {
+    match Command::new("ps").args(["-axo", "pid,pri,ni"]).output() {
+        Ok(output) => parse_ps_priorities(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Parse the PID, PRI and NI columns of `ps -axo pid,pri,ni`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps_priorities(stdout: &str) -> HashMap<u32, ProcessPriority> {
+    let mut map = HashMap::new();
+
+    for line in stdout.lines().skip(1) {
+        // Skip header line
+        let parts: Vec<&str> = line.split_whitespace().collect();
+
+        if parts.len() >= 3 {
+            if let Ok(pid) = parts[0].parse::<u32>() {
+                let priority = ProcessPriority {
+                    priority: parts[1].to_string(),
+                    nice: parts[2].to_string(),
+                };
+                map.insert(pid, priority);
+            }
+        }
+    }
+
+    map
+}
+
+/// Fetch memory information for all processes on macOS
+///
+/// Virtual and resident sizes come from each process's task info; processes
+/// whose task info isn't readable are filled in from `ps`.
+///
+/// # Returns
+/// HashMap mapping PID to (virtual_memory, resident_memory) values in KB
+#[cfg(target_os = "macos")]
+pub fn fetch_memory_map() -> HashMap<u32, ProcessMemory> {
+    let mut map = HashMap::new();
+    let mut missing = false;
+
+    for pid in list_all_pids() {
+        match proc_info::<libc::proc_taskinfo>(pid, libc::PROC_PIDTASKINFO) {
+            Some(task) => {
+                map.insert(
+                    pid,
+                    ProcessMemory {
+                        virtual_memory: task.pti_virtual_size / 1024,
+                        resident_memory: task.pti_resident_size / 1024,
+                    },
+                );
+            }
+            None => missing = true,
+        }
+    }
+
+    if missing || map.is_empty() {
+        for (pid, memory) in ps_memory_map() {
+            map.entry(pid).or_insert(memory);
+        }
+    }
+
+    map
+}
+
+/// Read VSZ/RSS in KB for all processes from `ps`
+#[cfg(target_os = "macos")]
+fn ps_memory_map() -> HashMap<u32, ProcessMemory> {
+    match Command::new("ps").args(["-axo", "pid,vsz,rss"]).output() {
+        Ok(output) => parse_ps_memory(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Parse the PID, VSZ and RSS columns of `ps -axo pid,vsz,rss`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps_memory(stdout: &str) -> HashMap<u32, ProcessMemory> {
+    let mut map = HashMap::new();
+
+    for line in stdout.lines().skip(1) {
+        // Skip header line
+        let parts: Vec<&str> = line.split_whitespace().collect();
+
+        if parts.len() >= 3 {
+            if let (Ok(pid), Ok(vsz), Ok(rss)) = (
+                parts[0].parse::<u32>(),
+                parts[1].parse::<u64>(),
+                parts[2].parse::<u64>(),
+            ) {
+                let memory = ProcessMemory {
+                    virtual_memory: vsz,
+                    resident_memory: rss,
+                };
+                map.insert(pid, memory);
+            }
+        }
+    }
+
+    map
+}
+
+/// Fetch memory compressor occupancy on macOS
+///
+/// Uses the `vm_stat` command to read the pages held by the compressor, which
+/// Activity Monitor accounts for alongside swap
+///
+/// # Returns
+/// CompressorStats with page count and bytes, or None if unavailable
+#[cfg(target_os = "macos")]
+pub fn fetch_compressor_stats() -> Option<CompressorStats> {
+    let output = Command::new("vm_stat").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Header line: "Mach Virtual Memory Statistics: (page size of 16384 bytes)"
+    let page_size = stdout
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+
+    let pages = stdout
+        .lines()
+        .find(|line| line.starts_with("Pages occupied by compressor:"))?
+        .split(':')
+        .nth(1)?
+        .trim()
+        .trim_end_matches('.')
+        .parse::<u64>()
+        .ok()?;
+
+    Some(CompressorStats {
+        pages,
+        bytes: pages * page_size,
+    })
+}
+
+/// Fetch a process's environment from `ps eww`
+///
+/// Used when sysinfo cannot read the environment directly. `ps` prints the
+/// command line followed by the variables, space-separated, so the known
+/// command line is stripped first and words that don't start a new
+/// `KEY=value` pair are joined back onto the previous value.
+///
+/// # Arguments
+/// * `pid` - Process ID
+/// * `cmd` - The process's command line, as known to sysinfo
+///
+/// # Returns
+/// `KEY=value` entries, or an empty vector if `ps` gave nothing usable
+#[cfg(target_os = "macos")]
+pub fn fetch_environ(pid: u32, cmd: &[String]) -> Vec<String> {
+    let output = match Command::new("ps")
+        .args(["eww", "-o", "command=", "-p", &pid.to_string()])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return Vec::new(),
+    };
+    parse_ps_environ(&String::from_utf8_lossy(&output.stdout), cmd)
+}
+
+/// Split the output of `ps eww` into `KEY=value` entries, given the command
+/// line it starts with
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps_environ(stdout: &str, cmd: &[String]) -> Vec<String> {
+    let line = stdout.trim();
+    let rest = line.strip_prefix(cmd.join(" ").as_str()).unwrap_or(line);
+
+    let mut environ: Vec<String> = Vec::new();
+    for word in rest.split_whitespace() {
+        let starts_pair = word.split_once('=').is_some_and(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        match environ.last_mut() {
+            Some(last) if !starts_pair => {
+                last.push(' ');
+                last.push_str(word);
+            }
+            _ if starts_pair => environ.push(word.to_string()),
+            _ => {}
+        }
+    }
+
+    environ
+}
+
+/// Start reading per-process energy impact from `powermetrics`
+///
+/// powermetrics needs root, so this only works when sysly runs as root or
+/// sudo has a cached ticket (`sudo -v`); otherwise the collector fails with
+/// sudo's message.
+///
+/// # Arguments
+/// * `interval` - Time between samples
+///
+/// # Returns
+/// A collector of energy impact by PID
+#[cfg(target_os = "macos")]
+pub fn spawn_energy_collector(
+    interval: Duration,
+) -> io::Result<PrivilegedCollector<HashMap<u32, f64>>> {
+    let interval = interval.as_millis().max(100).to_string();
+    PrivilegedCollector::spawn(
+        "powermetrics",
+        &[
+            "--samplers",
+            "tasks",
+            "--show-process-energy",
+            "-f",
+            "plist",
+            "-i",
+            &interval,
+        ],
+        // Each sample is a property list terminated by a NUL byte
+        b'\0',
+        parse_energy_sample,
+    )
+}
+
+/// Parse energy impact by PID from one powermetrics property list
+///
+/// The `tasks` array holds a dict per process with `pid` and
+/// `energy_impact` entries, alongside nested dicts of other counters.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_energy_sample(plist: &str) -> Option<HashMap<u32, f64>> {
+    let tasks = plist.split_once("<key>tasks</key>")?.1;
+
+    // Walk the dict tags to find each task's own dict; the closing tag of
+    // the enclosing dict ends the array
+    let mut tags: Vec<(usize, bool)> = tasks
+        .match_indices("<dict>")
+        .map(|(at, _)| (at, true))
+        .chain(tasks.match_indices("</dict>").map(|(at, _)| (at, false)))
+        .collect();
+    tags.sort_unstable();
+
+    let mut energy = HashMap::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (at, opening) in tags {
+        if opening {
+            if depth == 0 {
+                start = at;
+            }
+            depth += 1;
+            continue;
+        }
+        if depth == 0 {
+            break;
+        }
+        depth -= 1;
+        if depth == 0 {
+            let task = &tasks[start..at];
+            if let (Some(pid), Some(impact)) = (
+                plist_value(task, "pid").and_then(|pid| pid.parse().ok()),
+                plist_value(task, "energy_impact").and_then(|impact| impact.parse().ok()),
+            ) {
+                energy.insert(pid, impact);
+            }
+        }
+    }
+
+    (!energy.is_empty()).then_some(energy)
+}
+
+/// The scalar value following `<key>key</key>` in a property list
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn plist_value<'a>(plist: &'a str, key: &str) -> Option<&'a str> {
+    let tag = format!("<key>{}</key>", key);
+    let rest = plist[plist.find(&tag)? + tag.len()..].trim_start();
+    let value = &rest[rest.find('>')? + 1..];
+    Some(value[..value.find("</")?].trim())
+}
+
+/// Get process priority information for a specific PID
+///
+/// # Arguments
+/// * `pid` - Process ID
+/// * `priority_map` - HashMap containing priority data
+///
+/// # Returns
+/// ProcessPriority with priority and nice values, or default values if not found
+#[cfg(target_os = "macos")]
+pub fn get_process_priority(
+    pid: u32,
+    priority_map: &HashMap<u32, ProcessPriority>,
+) -> ProcessPriority {
+    priority_map
+        .get(&pid)
+        .cloned()
+        .unwrap_or_else(|| ProcessPriority {
+            priority: "?".to_string(),
+            nice: "?".to_string(),
+        })
+}
+
+/// Get process memory information for a specific PID
+///
+/// # Arguments
+/// * `pid` - Process ID
+/// * `memory_map` - HashMap containing memory data
+/// * `fallback_virt` - Fallback virtual memory value from sysinfo
+/// * `fallback_res` - Fallback resident memory value from sysinfo
+///
+/// # Returns
+/// ProcessMemory with virtual and resident memory values
+#[cfg(target_os = "macos")]
+pub fn get_process_memory(
+    pid: u32,
+    memory_map: &HashMap<u32, ProcessMemory>,
+    fallback_virt: u64,
+    fallback_res: u64,
+) -> ProcessMemory {
+    memory_map
+        .get(&pid)
+        .cloned()
+        .unwrap_or_else(|| ProcessMemory {
+            virtual_memory: fallback_virt,
+            resident_memory: fallback_res,
+        })
+}
+
+/// Read the bytes a process has read from and written to disk, from the
+/// `ri_diskio_bytesread/written` counters of `proc_pid_rusage`
+#[cfg(target_os = "macos")]
+pub fn fetch_disk_io_counters(pid: u32) -> Option<(u64, u64)> {
+    let mut info: libc::rusage_info_v2 = unsafe { std::mem::zeroed() };
+
+    let result = unsafe {
+        libc::proc_pid_rusage(
+            pid as libc::c_int,
+            libc::RUSAGE_INFO_V2,
+            &mut info as *mut _ as *mut libc::rusage_info_t,
+        )
+    };
+    (result == 0).then_some((info.ri_diskio_bytesread, info.ri_diskio_byteswritten))
+}
+
+/// Stub implementations for non-macOS platforms
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_priority_map() -> HashMap<u32, ProcessPriority> {
+    HashMap::new()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_memory_map() -> HashMap<u32, ProcessMemory> {
+    HashMap::new()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_compressor_stats() -> Option<CompressorStats> {
+    None
+}
+
+/// Read the bytes a process has read from and written to storage, from
+/// `/proc/<pid>/io`; only readable for your own processes unless root
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_disk_io_counters(pid: u32) -> Option<(u64, u64)> {
+    parse_proc_io(&std::fs::read_to_string(format!("/proc/{}/io", pid)).ok()?)
+}
+
+/// Read `read_bytes` and `write_bytes` from the contents of `/proc/<pid>/io`
+#[cfg(not(target_os = "macos"))]
+fn parse_proc_io(contents: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| {
+        contents
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.trim().parse::<u64>().ok())
+    };
+    Some((field("read_bytes:")?, field("write_bytes:")?))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_environ(_pid: u32, _cmd: &[String]) -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn spawn_energy_collector(
+    _interval: Duration,
+) -> io::Result<PrivilegedCollector<HashMap<u32, f64>>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "powermetrics is only available on macOS",
+    ))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn get_process_priority(
+    _pid: u32,
+    _priority_map: &HashMap<u32, ProcessPriority>,
+) -> ProcessPriority {
+    ProcessPriority {
+        priority: "N/A".to_string(),
+        nice: "N/A".to_string(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn get_process_memory(
+    _pid: u32,
+    _memory_map: &HashMap<u32, ProcessMemory>,
+    fallback_virt: u64,
+    fallback_res: u64,
+) -> ProcessMemory {
+    ProcessMemory {
+        virtual_memory: fallback_virt,
+        resident_memory: fallback_res,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ps_priorities_skip_the_header_and_bad_lines() {
+        let stdout = "  PID PRI NI\n    1  31   0\n  412   4 -20\n  ???\n";
+        let map = parse_ps_priorities(stdout);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&1].priority, "31");
+        assert_eq!(map[&412].nice, "-20");
+    }
+
+    #[test]
+    fn ps_memory_reads_kilobytes() {
+        let stdout = "  PID      VSZ    RSS\n    1 408712592  13616\n   99        x      1\n";
+        let map = parse_ps_memory(stdout);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&1].virtual_memory, 408712592);
+        assert_eq!(map[&1].resident_memory, 13616);
+    }
+
+    #[test]
+    fn ps_environ_rejoins_values_with_spaces() {
+        let cmd = vec!["/usr/bin/app".to_string(), "--fast".to_string()];
+        let stdout = "/usr/bin/app --fast PATH=/usr/bin:/bin GREETING=hello world TERM=xterm\n";
+        assert_eq!(
+            parse_ps_environ(stdout, &cmd),
+            ["PATH=/usr/bin:/bin", "GREETING=hello world", "TERM=xterm"]
+        );
+    }
+
+    #[test]
+    fn powermetrics_energy_comes_from_each_task_dict() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+<key>tasks</key>
+<array>
+<dict>
+<key>pid</key><integer>42</integer>
+<key>name</key><string>Safari</string>
+<key>timer_wakeups</key>
+<array>
+<dict><key>interval_ns</key><integer>2000000</integer><key>wakeups</key><integer>3</integer></dict>
+</array>
+<key>energy_impact</key><real>12.5</real>
+</dict>
+<dict>
+<key>pid</key><integer>7</integer>
+<key>energy_impact</key><real>0.25</real>
+</dict>
+</array>
+<key>all_tasks</key>
+<dict><key>energy_impact</key><real>99.0</real></dict>
+</dict>
+</plist>"#;
+        let energy = parse_energy_sample(plist).unwrap();
+        assert_eq!(energy.len(), 2);
+        assert_eq!(energy[&42], 12.5);
+        assert_eq!(energy[&7], 0.25);
+
+        assert!(parse_energy_sample("<plist><dict></dict></plist>").is_none());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "macos"))]
+    fn proc_io_reads_storage_bytes() {
+        let contents = "rchar: 4292\nwchar: 323\nsyscr: 9\nsyscw: 5\n\
+                        read_bytes: 4096\nwrite_bytes: 8192\ncancelled_write_bytes: 0\n";
+        assert_eq!(parse_proc_io(contents), Some((4096, 8192)));
+        assert_eq!(parse_proc_io("rchar: 4292\n"), None);
+    }
+}
@@ -0,0 +1,522 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind};
+
+use crate::battery::{fetch_battery, Battery};
+use crate::cache::KnownSensor;
+use crate::cgroup::{current_cgroup, list_cgroups, CgroupTracker, CgroupUsage};
+use crate::collector::Collectors;
+use crate::disk::{fetch_disk_counters, fetch_volumes, DiskCounters, Volume};
+use crate::fans::{fetch_fans, Fan};
+use crate::gpu::{fetch_gpu_usage, GpuUsage};
+use crate::memstat::{fetch_memory_stats, MemoryStats};
+use crate::network::{fetch_network_counters, NetworkTracker, NetworkUsage};
+use crate::process::{
+    fetch_compressor_stats, spawn_energy_collector, CompressorStats, DiskIoRates, DiskIoTracker,
+    PrivilegedCollector, ProcessMaps,
+};
+use crate::rusage::WakeupTracker;
+use crate::sched::{fetch_nap_state, fetch_sched_info, NapState, SchedInfo};
+use crate::sensors::{fetch_thermal_pressure, SensorLog, Sensors, ThermalPressure};
+use crate::snapshot::Snapshot;
+use crate::threads::{count_threads, ThreadTotals};
+
+/// What the dashboard shows that needs a collector of its own, so a
+/// provider only reads what will be drawn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Demand {
+    /// The filesystem panel is open
+    pub filesystems: bool,
+    /// Mount points the filesystem panel leaves out
+    pub hidden_filesystems: Vec<String>,
+    /// Per-process traffic, for the network view and the inspector
+    pub network: bool,
+    /// Every cgroup, for the cgroups view
+    pub cgroups: bool,
+    /// Per-process disk throughput, for the DISK R/s and W/s columns
+    pub disk_io: bool,
+    /// Energy impact from powermetrics, sampled this often
+    pub energy: Option<Duration>,
+    /// Usage of every running container, for the containers view
+    pub containers: bool,
+    /// Names of the running containers, for the container column and
+    /// grouping
+    pub container_names: bool,
+    /// Every process's scheduling class, for the QOS column and sorting
+    /// by it
+    pub sched: bool,
+    /// Every process's App Nap state, for the NAP column
+    pub nap: bool,
+}
+
+/// Readings that go with one snapshot
+#[derive(Debug, Clone, Default)]
+pub struct Readings {
+    pub compressor: Option<CompressorStats>,
+    pub memory_stats: Option<MemoryStats>,
+    pub disk_counters: Option<DiskCounters>,
+    pub gpu: Option<GpuUsage>,
+    pub battery: Option<Battery>,
+    pub fans: Vec<Fan>,
+    pub thermal_pressure: Option<ThermalPressure>,
+    pub sensors: SensorLog,
+    /// Usage and limits of the cgroup sysly runs in
+    pub cgroup: Option<CgroupUsage>,
+    /// Every slice and its groups, when asked for
+    pub cgroups: Option<Vec<CgroupUsage>>,
+    /// Mounted volumes, when the filesystem panel is open and on the first
+    /// reading
+    pub volumes: Option<Vec<Volume>>,
+    /// Idle wake-ups per second, by process
+    pub wakeups: HashMap<Pid, f64>,
+    /// Disk throughput by PID, when asked for
+    pub disk_io: HashMap<u32, DiskIoRates>,
+    /// Scheduling class by PID, when asked for
+    pub sched: HashMap<u32, SchedInfo>,
+    /// App Nap state by PID of the processes held back, when asked for
+    pub nap: HashMap<u32, NapState>,
+    /// Energy impact by PID, when a new powermetrics sample came in
+    pub energy_impact: Option<HashMap<u32, f64>>,
+    /// Why energy impact can't be read, when powermetrics failed this time
+    pub energy_error: Option<String>,
+    /// User names by UID, from providers whose processes belong to another
+    /// machine's accounts
+    pub users: Option<HashMap<u32, String>>,
+}
+
+/// Readings from collectors too costly to run with every refresh, which go
+/// through every process or shell out per sample
+#[derive(Debug, Clone, Default)]
+pub struct SlowReadings {
+    pub thread_totals: Option<ThreadTotals>,
+    /// PRI/NI and VIRT/RES for the processes in the snapshot
+    pub process_maps: ProcessMaps,
+    /// Network traffic by process since the first reading, when asked for
+    pub network: Option<HashMap<Pid, NetworkUsage>>,
+}
+
+/// A source of system data: CPUs, memory and processes, plus the readings
+/// sysinfo doesn't cover
+///
+/// The live provider reads this machine; others can replay a recording,
+/// read a remote host, or follow a script so output is deterministic.
+pub trait SystemProvider: Send {
+    /// Take the CPUs, memory and processes as they are now
+    ///
+    /// Called once per sample; CPU usage covers the time since the call
+    /// before. A provider with nothing new may block until it has.
+    fn snapshot(&mut self, collectors: &mut Collectors) -> Snapshot;
+
+    /// Take the readings that go with `system`, the latest snapshot
+    fn readings(
+        &mut self,
+        system: &Snapshot,
+        demand: &Demand,
+        collectors: &mut Collectors,
+    ) -> Readings;
+
+    /// Take the slower readings, on the samples they're due
+    fn slow_readings(&mut self, demand: &Demand, collectors: &mut Collectors) -> SlowReadings;
+
+    /// Where the data comes from when it isn't this machine, such as an
+    /// agent's address or the recording being played, for the header
+    ///
+    /// Processes from elsewhere can't be signalled or inspected here.
+    fn source(&self) -> Option<String> {
+        None
+    }
+
+    /// Move through data that is played back; providers that read as they
+    /// go ignore it
+    fn play(&mut self, _playback: Playback) {}
+}
+
+impl<P: SystemProvider + ?Sized> SystemProvider for Box<P> {
+    fn snapshot(&mut self, collectors: &mut Collectors) -> Snapshot {
+        (**self).snapshot(collectors)
+    }
+
+    fn readings(
+        &mut self,
+        system: &Snapshot,
+        demand: &Demand,
+        collectors: &mut Collectors,
+    ) -> Readings {
+        (**self).readings(system, demand, collectors)
+    }
+
+    fn slow_readings(&mut self, demand: &Demand, collectors: &mut Collectors) -> SlowReadings {
+        (**self).slow_readings(demand, collectors)
+    }
+
+    fn source(&self) -> Option<String> {
+        (**self).source()
+    }
+
+    fn play(&mut self, playback: Playback) {
+        (**self).play(playback)
+    }
+}
+
+/// A step through played-back data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    /// Play from here, or pause
+    Toggle,
+    /// Go back one snapshot and pause
+    Back,
+    /// Go forward one snapshot and pause
+    Forward,
+    Faster,
+    Slower,
+}
+
+/// Refresh every process, reading the command line and owner of the ones
+/// that are new
+///
+/// `System::refresh_processes` leaves both empty for processes that started
+/// after the first listing.
+pub fn refresh_processes(system: &mut System) {
+    system.refresh_processes_specifics(
+        ProcessRefreshKind::new()
+            .with_memory()
+            .with_cpu()
+            .with_disk_usage()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_user(UpdateKind::OnlyIfNotSet),
+    );
+}
+
+/// Reads this machine through sysinfo and the platform's collectors
+///
+/// Everything is read on the first snapshot, so creating one is cheap.
+pub struct LiveProvider {
+    system: System,
+    /// Whether `system` has been read in full
+    started: bool,
+    sensors: Sensors,
+    /// The cgroup sysly runs in, when cgroup v2 is in use and it isn't the
+    /// root group
+    cgroup_path: Option<String>,
+    cgroup: CgroupTracker,
+    cgroups: CgroupTracker,
+    wakeups: WakeupTracker,
+    disk_io: DiskIoTracker,
+    network: NetworkTracker,
+    /// Whether nettop has been read, so traffic counts from the start
+    network_started: bool,
+    /// powermetrics reader, running while energy impact is asked for
+    energy: Option<PrivilegedCollector<HashMap<u32, f64>>>,
+    /// powermetrics failed; it isn't started again until energy impact
+    /// stops being asked for and is asked for anew
+    energy_failed: bool,
+    /// When the per-process counters were last read, to turn them into rates
+    last_readings: Option<Instant>,
+}
+
+impl LiveProvider {
+    pub fn new() -> Self {
+        LiveProvider {
+            system: System::new(),
+            started: false,
+            sensors: Sensors::with_known(&[]),
+            cgroup_path: current_cgroup(),
+            cgroup: CgroupTracker::default(),
+            cgroups: CgroupTracker::default(),
+            wakeups: WakeupTracker::default(),
+            disk_io: DiskIoTracker::default(),
+            network: NetworkTracker::default(),
+            network_started: false,
+            energy: None,
+            energy_failed: false,
+            last_readings: None,
+        }
+    }
+
+    /// Start from the sensors seen in a previous run, so the sensors view
+    /// lists them before the slow enumeration finishes
+    pub fn with_known_sensors(mut self, known: &[KnownSensor]) -> Self {
+        self.sensors = Sensors::with_known(known);
+        self
+    }
+
+    /// Pick up the latest powermetrics sample, starting or stopping
+    /// powermetrics as energy impact is asked for
+    ///
+    /// # Returns
+    /// The sample when a new one came in, and why powermetrics couldn't be
+    /// read when it failed
+    fn sample_energy(
+        &mut self,
+        interval: Option<Duration>,
+        collectors: &mut Collectors,
+    ) -> (Option<HashMap<u32, f64>>, Option<String>) {
+        let Some(interval) = interval else {
+            self.energy = None;
+            self.energy_failed = false;
+            return (None, None);
+        };
+        if self.energy_failed {
+            return (None, None);
+        }
+        let collector = match &mut self.energy {
+            Some(collector) => collector,
+            None => match spawn_energy_collector(interval) {
+                Ok(collector) => self.energy.insert(collector),
+                Err(err) => {
+                    self.energy_failed = true;
+                    return (None, Some(err.to_string()));
+                }
+            },
+        };
+        match collector.take_sample() {
+            Ok(sample) => (collectors.run_optional("powermetrics", || sample), None),
+            Err(message) => {
+                self.energy = None;
+                self.energy_failed = true;
+                (None, Some(message))
+            }
+        }
+    }
+}
+
+impl Default for LiveProvider {
+    fn default() -> Self {
+        LiveProvider::new()
+    }
+}
+
+impl SystemProvider for LiveProvider {
+    fn snapshot(&mut self, collectors: &mut Collectors) -> Snapshot {
+        let system = &mut self.system;
+        if self.started {
+            collectors.run("sysinfo cpu/mem", || {
+                system.refresh_cpu();
+                system.refresh_memory();
+            });
+            collectors.run("sysinfo procs", || refresh_processes(system));
+        } else {
+            collectors.run("sysinfo", || system.refresh_all());
+            self.started = true;
+        }
+        Snapshot::from_system(system)
+    }
+
+    fn readings(
+        &mut self,
+        system: &Snapshot,
+        demand: &Demand,
+        collectors: &mut Collectors,
+    ) -> Readings {
+        let now = Instant::now();
+        let first = self.last_readings.is_none();
+        let interval = self.last_readings.map_or(Duration::ZERO, |last| now - last);
+        self.last_readings = Some(now);
+
+        // Wake-ups are always read, for the Energy view's totals
+        let wakeups = &mut self.wakeups;
+        collectors.run_optional("proc_pid_rusage", || {
+            wakeups.sample(system.processes().keys().copied(), interval)
+        });
+        if demand.disk_io {
+            let disk_io = &mut self.disk_io;
+            collectors.run_optional("disk i/o", || {
+                disk_io.sample(system.processes().keys().map(|pid| pid.as_u32()), interval)
+            });
+        } else {
+            self.disk_io = DiskIoTracker::default();
+        }
+        let sensors = &mut self.sensors;
+        collectors.run("sensors", || sensors.sample());
+        let cgroup = match &self.cgroup_path {
+            Some(path) => {
+                let tracker = &mut self.cgroup;
+                collectors.run_optional("cgroup", || {
+                    tracker.sample(std::slice::from_ref(path)).pop()
+                })
+            }
+            None => None,
+        };
+        let cgroups = if demand.cgroups {
+            let tracker = &mut self.cgroups;
+            collectors.run("cgroups", || tracker.sample(&list_cgroups()))
+        } else {
+            None
+        };
+        // Read once up front too, for the inventory saved for the next start
+        let volumes = (demand.filesystems || first)
+            .then(|| {
+                collectors.run("sysinfo disks", || {
+                    fetch_volumes(&demand.hidden_filesystems)
+                })
+            })
+            .flatten();
+        let (energy_impact, energy_error) = self.sample_energy(demand.energy, collectors);
+        let sched = if demand.sched {
+            collectors
+                .run("sched", || {
+                    system
+                        .processes()
+                        .keys()
+                        .map(|pid| (pid.as_u32(), fetch_sched_info(pid.as_u32())))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+        let nap = if demand.nap {
+            collectors
+                .run("nap", || {
+                    system
+                        .processes()
+                        .keys()
+                        .filter_map(|pid| Some((pid.as_u32(), fetch_nap_state(pid.as_u32())?)))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        Readings {
+            compressor: collectors.run_optional("vm_stat", fetch_compressor_stats),
+            memory_stats: collectors.run_optional("memory breakdown", fetch_memory_stats),
+            disk_counters: collectors.run_optional("disk counters", fetch_disk_counters),
+            gpu: collectors.run_optional("gpu", fetch_gpu_usage),
+            battery: collectors.run_optional("battery", fetch_battery),
+            fans: collectors
+                .run_optional("fans", fetch_fans)
+                .unwrap_or_default(),
+            thermal_pressure: collectors.run_optional("thermal pressure", fetch_thermal_pressure),
+            sensors: self.sensors.log.clone(),
+            cgroup,
+            cgroups,
+            volumes,
+            wakeups: self.wakeups.rates.clone(),
+            disk_io: self.disk_io.rates.clone(),
+            sched,
+            nap,
+            energy_impact,
+            energy_error,
+            users: None,
+        }
+    }
+
+    fn slow_readings(&mut self, demand: &Demand, collectors: &mut Collectors) -> SlowReadings {
+        // The first reading is the baseline traffic totals count from
+        if demand.network || !self.network_started {
+            if let Some(counters) = collectors.run_optional("nettop", fetch_network_counters) {
+                self.network.sample(counters);
+            }
+            self.network_started = true;
+        }
+        let system = &self.system;
+        SlowReadings {
+            thread_totals: collectors.run("thread counts", || count_threads(system)),
+            process_maps: ProcessMaps::fetch(collectors),
+            network: demand.network.then(|| self.network.usage.clone()),
+        }
+    }
+}
+
+/// Plays back a fixed list of snapshots and readings, one pair per sample,
+/// repeating the last; its slow readings are always empty
+///
+/// Nothing is read from the machine, so what is drawn depends only on the
+/// script.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedProvider {
+    script: VecDeque<(Snapshot, Readings)>,
+    last: (Snapshot, Readings),
+}
+
+impl ScriptedProvider {
+    pub fn new(script: impl IntoIterator<Item = (Snapshot, Readings)>) -> Self {
+        ScriptedProvider {
+            script: script.into_iter().collect(),
+            last: Default::default(),
+        }
+    }
+}
+
+impl SystemProvider for ScriptedProvider {
+    fn snapshot(&mut self, _collectors: &mut Collectors) -> Snapshot {
+        if let Some(next) = self.script.pop_front() {
+            self.last = next;
+        }
+        self.last.0.clone()
+    }
+
+    fn readings(
+        &mut self,
+        _system: &Snapshot,
+        _demand: &Demand,
+        _collectors: &mut Collectors,
+    ) -> Readings {
+        self.last.1.clone()
+    }
+
+    fn slow_readings(&mut self, _demand: &Demand, _collectors: &mut Collectors) -> SlowReadings {
+        SlowReadings::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::ProcessSnapshot;
+    use sysinfo::Pid;
+
+    #[test]
+    fn scripted_provider_plays_snapshots_in_order_then_repeats() {
+        let fan = |rpm| Fan {
+            label: "Fan".to_string(),
+            rpm,
+            min: None,
+            max: None,
+        };
+        let with_process = |pid, cpu_usage| {
+            let process = ProcessSnapshot {
+                pid: Pid::from_u32(pid),
+                cpu_usage,
+                ..ProcessSnapshot::default()
+            };
+            Snapshot {
+                processes: [(process.pid, process)].into(),
+                ..Snapshot::default()
+            }
+        };
+        let mut provider = ScriptedProvider::new([
+            (
+                with_process(10, 5.0),
+                Readings {
+                    fans: vec![fan(1200.0)],
+                    ..Readings::default()
+                },
+            ),
+            (
+                with_process(20, 50.0),
+                Readings {
+                    fans: vec![fan(2400.0)],
+                    ..Readings::default()
+                },
+            ),
+        ]);
+        let mut collectors = Collectors::default();
+        let mut samples = Vec::new();
+        for _ in 0..3 {
+            let snapshot = provider.snapshot(&mut collectors);
+            let readings = provider.readings(&snapshot, &Demand::default(), &mut collectors);
+            let (pid, process) = snapshot.processes().iter().next().unwrap();
+            samples.push((pid.as_u32(), process.cpu_usage(), readings.fans[0].rpm));
+        }
+
+        assert_eq!(
+            samples,
+            [(10, 5.0, 1200.0), (20, 50.0, 2400.0), (20, 50.0, 2400.0)]
+        );
+        assert!(collectors.health().is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use sysinfo::Pid;
+
+use crate::snapshot::Snapshot;
+
+/// `P_TRANSLATED` from `<sys/proc.h>`: the process runs under Rosetta 2
+#[cfg(target_os = "macos")]
+const P_TRANSLATED: i32 = 0x0002_0000;
+
+/// Offset of `kp_proc.p_flag` in `struct kinfo_proc` on 64-bit macOS,
+/// which libc doesn't define: it follows a 16-byte union and two pointers
+#[cfg(target_os = "macos")]
+const P_FLAG_OFFSET: usize = 32;
+
+/// Size of `struct kinfo_proc` on 64-bit macOS
+#[cfg(target_os = "macos")]
+const KINFO_PROC_SIZE: usize = 648;
+
+/// Whether the process is an x86_64 binary translated by Rosetta 2
+///
+/// `sysctl.proc_translated` only describes the calling process, so the flag
+/// is read from the process's `kinfo_proc` instead.
+#[cfg(target_os = "macos")]
+fn is_translated(pid: Pid) -> bool {
+    let mut mib = [
+        libc::CTL_KERN,
+        libc::KERN_PROC,
+        libc::KERN_PROC_PID,
+        pid.as_u32() as libc::c_int,
+    ];
+    let mut info = [0u8; KINFO_PROC_SIZE];
+    let mut size = info.len();
+
+    let result = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as libc::c_uint,
+            info.as_mut_ptr() as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 || size < P_FLAG_OFFSET + 4 {
+        return false;
+    }
+
+    let mut flag = [0u8; 4];
+    flag.copy_from_slice(&info[P_FLAG_OFFSET..P_FLAG_OFFSET + 4]);
+    i32::from_ne_bytes(flag) & P_TRANSLATED != 0
+}
+
+/// Rosetta 2 only exists on macOS
+#[cfg(not(target_os = "macos"))]
+fn is_translated(_pid: Pid) -> bool {
+    false
+}
+
+/// Which running processes are translated, checked once per process since
+/// a process can't change architecture after launch
+#[derive(Debug, Default)]
+pub struct RosettaCache {
+    translated: HashMap<Pid, bool>,
+}
+
+impl RosettaCache {
+    /// Check processes seen for the first time and forget exited ones
+    pub fn update(&mut self, sys: &Snapshot) {
+        self.translated.retain(|pid, _| sys.process(*pid).is_some());
+        for pid in sys.processes().keys() {
+            self.translated
+                .entry(*pid)
+                .or_insert_with(|| is_translated(*pid));
+        }
+    }
+
+    pub fn is_translated(&self, pid: Pid) -> bool {
+        self.translated.get(&pid).copied().unwrap_or(false)
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use sysinfo::Pid;
+
+/// Per-process counters from the kernel's resource usage accounting
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceUsage {
+    /// Times the process woke the CPU package from idle
+    pub idle_wakeups: u64,
+}
+
+/// Read a process's resource usage with `proc_pid_rusage`
+///
+/// # Arguments
+/// * `pid` - Process ID
+///
+/// # Returns
+/// The counters, or None if the process is gone or not readable
+#[cfg(target_os = "macos")]
+pub fn fetch_resource_usage(pid: Pid) -> Option<ResourceUsage> {
+    let mut info: libc::rusage_info_v2 = unsafe { std::mem::zeroed() };
+
+    let result = unsafe {
+        libc::proc_pid_rusage(
+            pid.as_u32() as libc::c_int,
+            libc::RUSAGE_INFO_V2,
+            &mut info as *mut _ as *mut libc::rusage_info_t,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+
+    Some(ResourceUsage {
+        idle_wakeups: info.ri_pkg_idle_wkups,
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_resource_usage(_pid: Pid) -> Option<ResourceUsage> {
+    None
+}
+
+/// Turns per-process idle wake-up counts into rates between refreshes
+#[derive(Debug, Default)]
+pub struct WakeupTracker {
+    previous: HashMap<Pid, u64>,
+    /// Idle wake-ups per second, by process
+    pub rates: HashMap<Pid, f64>,
+}
+
+impl WakeupTracker {
+    /// Read the counters of every process and compute rates over `interval`
+    ///
+    /// Processes seen for the first time have no rate until the next sample.
+    ///
+    /// # Returns
+    /// None when no process could be read, e.g. on platforms without
+    /// wake-up accounting
+    pub fn sample(&mut self, pids: impl Iterator<Item = Pid>, interval: Duration) -> Option<()> {
+        let seconds = interval.as_secs_f64();
+        let mut current = HashMap::new();
+        self.rates.clear();
+
+        for pid in pids {
+            let Some(usage) = fetch_resource_usage(pid) else {
+                continue;
+            };
+            if let Some(previous) = self.previous.get(&pid) {
+                if seconds > 0.0 {
+                    let delta = usage.idle_wakeups.saturating_sub(*previous);
+                    self.rates.insert(pid, delta as f64 / seconds);
+                }
+            }
+            current.insert(pid, usage.idle_wakeups);
+        }
+
+        self.previous = current;
+        (!self.previous.is_empty()).then_some(())
+    }
+}
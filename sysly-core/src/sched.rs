@@ -0,0 +1,238 @@
+/// How the kernel schedules a process: its QoS class on macOS, its
+/// scheduling policy on Linux
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum SchedClass {
+    /// Real-time deadline scheduling (`SCHED_DEADLINE`)
+    Deadline,
+    /// Real-time, first in first out
+    Fifo,
+    /// Real-time, round robin
+    RoundRobin,
+    /// QoS user-interactive: drawing the UI and handling events
+    UserInteractive,
+    /// QoS user-initiated: work the user is waiting on
+    UserInitiated,
+    /// QoS default, or `SCHED_OTHER` on Linux
+    Default,
+    /// QoS utility: long-running work with visible progress
+    Utility,
+    /// `SCHED_BATCH`: CPU-bound work that tolerates latency
+    Batch,
+    /// QoS background or maintenance, throttled on CPU and I/O
+    Background,
+    /// `SCHED_IDLE`: runs only when nothing else wants the CPU
+    Idle,
+}
+
+impl SchedClass {
+    /// Short label shown in the QOS column
+    pub fn label(self) -> &'static str {
+        match self {
+            SchedClass::Deadline => "dl",
+            SchedClass::Fifo => "fifo",
+            SchedClass::RoundRobin => "rr",
+            SchedClass::UserInteractive => "inter",
+            SchedClass::UserInitiated => "init",
+            SchedClass::Default => "default",
+            SchedClass::Utility => "utility",
+            SchedClass::Batch => "batch",
+            SchedClass::Background => "bg",
+            SchedClass::Idle => "idle",
+        }
+    }
+
+    /// Urgency used for sorting, higher first
+    pub fn rank(self) -> u8 {
+        match self {
+            SchedClass::Deadline => 9,
+            SchedClass::Fifo => 8,
+            SchedClass::RoundRobin => 7,
+            SchedClass::UserInteractive => 6,
+            SchedClass::UserInitiated => 5,
+            SchedClass::Default => 4,
+            SchedClass::Utility => 3,
+            SchedClass::Batch => 2,
+            SchedClass::Background => 1,
+            SchedClass::Idle => 0,
+        }
+    }
+}
+
+/// How a process is scheduled, read once per refresh for the QOS column and
+/// sorting by it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchedInfo {
+    pub class: Option<SchedClass>,
+    /// Running on a client's donated importance, see `fetch_boosted`
+    pub boosted: bool,
+}
+
+/// Read a process's scheduling class and whether it is boosted
+pub fn fetch_sched_info(pid: u32) -> SchedInfo {
+    SchedInfo {
+        class: fetch_sched_class(pid),
+        boosted: fetch_boosted(pid),
+    }
+}
+
+/// `<sys/proc_info.h>` `pbi_flags` bits: the process is an adaptive daemon
+/// whose priority follows its clients, and currently holds a boost
+#[cfg(target_os = "macos")]
+const PROC_FLAG_ADAPTIVE: u32 = 0x0010_0000;
+#[cfg(target_os = "macos")]
+const PROC_FLAG_ADAPTIVE_IMPORTANT: u32 = 0x0020_0000;
+
+/// `pbi_flags` bits for background throttling: the process runs in the
+/// Darwin background band, set on the process itself or from outside, or
+/// App Nap has suppressed it
+#[cfg(target_os = "macos")]
+const PROC_FLAG_DARWINBG: u32 = 0x8000;
+#[cfg(target_os = "macos")]
+const PROC_FLAG_EXT_DARWINBG: u32 = 0x0001_0000;
+#[cfg(target_os = "macos")]
+const PROC_FLAG_SUPPRESSED: u32 = 0x0080_0000;
+
+/// `<mach/policy.h>` real-time policies
+#[cfg(target_os = "macos")]
+const POLICY_RR: i32 = 2;
+#[cfg(target_os = "macos")]
+const POLICY_FIFO: i32 = 4;
+
+/// Read a process's scheduling class from its main thread
+///
+/// QoS isn't exposed for other processes, but each class runs threads at a
+/// fixed base priority (46, 37, 31, 20 and 4), so the main thread's base
+/// priority from `proc_pidinfo` gives the class back.
+#[cfg(target_os = "macos")]
+pub fn fetch_sched_class(pid: u32) -> Option<SchedClass> {
+    use crate::process::{PROC_PIDLISTTHREADIDS, PROC_PIDTHREADID64INFO};
+
+    let pid = pid as libc::c_int;
+
+    // The first thread listed is the main thread
+    let mut tid: u64 = 0;
+    let id_size = std::mem::size_of::<u64>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            PROC_PIDLISTTHREADIDS,
+            0,
+            &mut tid as *mut _ as *mut libc::c_void,
+            id_size,
+        )
+    };
+    if written < id_size {
+        return None;
+    }
+
+    let mut info: libc::proc_threadinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_threadinfo>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            PROC_PIDTHREADID64INFO,
+            tid,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+
+    Some(match (info.pth_policy, info.pth_priority) {
+        (POLICY_FIFO, _) => SchedClass::Fifo,
+        (POLICY_RR, _) => SchedClass::RoundRobin,
+        (_, priority) if priority >= 46 => SchedClass::UserInteractive,
+        (_, priority) if priority >= 37 => SchedClass::UserInitiated,
+        (_, priority) if priority >= 31 => SchedClass::Default,
+        (_, priority) if priority >= 20 => SchedClass::Utility,
+        _ => SchedClass::Background,
+    })
+}
+
+/// Whether the process is running boosted by importance donation
+///
+/// launchd starts adaptive daemons (most XPC services) throttled in the
+/// background band. A client sending them a message donates its importance,
+/// and the daemon runs at the client's priority until the work is done,
+/// which `PRI`, `NI` and even the QoS class don't show.
+#[cfg(target_os = "macos")]
+pub fn fetch_boosted(pid: u32) -> bool {
+    crate::process::proc_info::<libc::proc_bsdinfo>(pid, libc::PROC_PIDTBSDINFO).is_some_and(
+        |info| {
+            info.pbi_flags & PROC_FLAG_ADAPTIVE != 0
+                && info.pbi_flags & PROC_FLAG_ADAPTIVE_IMPORTANT != 0
+        },
+    )
+}
+
+/// Importance donation is specific to macOS
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_boosted(_pid: u32) -> bool {
+    false
+}
+
+/// Why a process is being held back while it looks idle or slow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum NapState {
+    /// App Nap suppressed the app: its timers are coalesced and it gets
+    /// little CPU until it is visible or does user-facing work again
+    Napping,
+    /// Running in the Darwin background band with CPU, disk and network
+    /// throttled
+    Throttled,
+}
+
+impl NapState {
+    /// Short label shown in the NAP column
+    pub fn label(self) -> &'static str {
+        match self {
+            NapState::Napping => "nap",
+            NapState::Throttled => "bg",
+        }
+    }
+}
+
+/// Read whether App Nap or background throttling is holding a process back
+///
+/// # Returns
+/// The state, or None when the process runs unrestricted
+#[cfg(target_os = "macos")]
+pub fn fetch_nap_state(pid: u32) -> Option<NapState> {
+    let info = crate::process::proc_info::<libc::proc_bsdinfo>(pid, libc::PROC_PIDTBSDINFO)?;
+    if info.pbi_flags & PROC_FLAG_SUPPRESSED != 0 {
+        Some(NapState::Napping)
+    } else if info.pbi_flags & (PROC_FLAG_DARWINBG | PROC_FLAG_EXT_DARWINBG) != 0 {
+        Some(NapState::Throttled)
+    } else {
+        None
+    }
+}
+
+/// App Nap is specific to macOS
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_nap_state(_pid: u32) -> Option<NapState> {
+    None
+}
+
+/// Read a process's scheduling policy with `sched_getscheduler`
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_sched_class(pid: u32) -> Option<SchedClass> {
+    let policy = unsafe { libc::sched_getscheduler(pid as libc::pid_t) };
+    if policy < 0 {
+        return None;
+    }
+
+    match policy & !libc::SCHED_RESET_ON_FORK {
+        libc::SCHED_OTHER => Some(SchedClass::Default),
+        libc::SCHED_FIFO => Some(SchedClass::Fifo),
+        libc::SCHED_RR => Some(SchedClass::RoundRobin),
+        libc::SCHED_BATCH => Some(SchedClass::Batch),
+        libc::SCHED_IDLE => Some(SchedClass::Idle),
+        libc::SCHED_DEADLINE => Some(SchedClass::Deadline),
+        _ => None,
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::VecDeque;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::Components;
+
+use crate::cache::KnownSensor;
+
+/// Readings kept per sensor, one per refresh
+pub const SENSOR_HISTORY_LEN: usize = 120;
+
+/// Throttle episodes kept in the event log
+const MAX_THROTTLE_EVENTS: usize = 50;
+
+/// A temperature sensor and its recent readings
+#[derive(Debug, Clone)]
+pub struct SensorHistory {
+    pub label: String,
+    /// Readings in °C, oldest first
+    pub readings: VecDeque<f32>,
+    /// Highest reading since sysly started
+    pub peak: f32,
+    pub critical: Option<f32>,
+}
+
+impl SensorHistory {
+    pub fn current(&self) -> Option<f32> {
+        self.readings.back().copied()
+    }
+}
+
+/// A period during which the CPU was thermally throttled
+#[derive(Debug, Clone)]
+pub struct ThrottleEvent {
+    /// Start time, in seconds since the Unix epoch
+    pub started: u64,
+    started_at: Instant,
+    /// How long the episode lasted; None while it is ongoing
+    pub duration: Option<u64>,
+    /// Hottest sensor reading seen during the episode, in °C
+    pub peak_temperature: Option<f32>,
+}
+
+impl ThrottleEvent {
+    /// Seconds the episode has lasted so far, or in total once it ended
+    pub fn elapsed(&self) -> u64 {
+        self.duration
+            .unwrap_or_else(|| self.started_at.elapsed().as_secs())
+    }
+}
+
+/// Temperature history and thermal-throttle log
+///
+/// Cloned out of `Sensors` with each sample, so the dashboard can draw it
+/// while the next sample is taken elsewhere.
+#[derive(Debug, Clone, Default)]
+pub struct SensorLog {
+    pub history: Vec<SensorHistory>,
+    /// Throttle episodes, most recent first
+    pub events: VecDeque<ThrottleEvent>,
+}
+
+impl SensorLog {
+    /// Sensors seen in a previous run, with no readings yet
+    pub fn with_known(known: &[KnownSensor]) -> Self {
+        SensorLog {
+            history: known
+                .iter()
+                .map(|sensor| SensorHistory {
+                    label: sensor.label.clone(),
+                    readings: VecDeque::with_capacity(SENSOR_HISTORY_LEN),
+                    peak: 0.0,
+                    critical: sensor.critical,
+                })
+                .collect(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Sensors to remember for the next run's warm start
+    pub fn known(&self) -> Vec<KnownSensor> {
+        self.history
+            .iter()
+            .map(|sensor| KnownSensor {
+                label: sensor.label.clone(),
+                critical: sensor.critical,
+            })
+            .collect()
+    }
+}
+
+/// Reads the temperature sensors into a `SensorLog` on each refresh
+pub struct Sensors {
+    components: Components,
+    /// False until the sensor list has been enumerated
+    listed: bool,
+    probe: ThrottleProbe,
+    pub log: SensorLog,
+}
+
+impl Default for Sensors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sensors {
+    pub fn new() -> Self {
+        Sensors {
+            components: Components::new_with_refreshed_list(),
+            listed: true,
+            probe: ThrottleProbe::default(),
+            log: SensorLog::default(),
+        }
+    }
+
+    /// Start from sensors seen in a previous run, deferring the slow
+    /// enumeration to the first sample
+    pub fn with_known(known: &[KnownSensor]) -> Self {
+        Sensors {
+            components: Components::new(),
+            listed: false,
+            probe: ThrottleProbe::default(),
+            log: SensorLog::with_known(known),
+        }
+    }
+
+    /// Record the current readings and update the throttle log
+    ///
+    /// Throttling comes from the platform when it reports it; otherwise a
+    /// sensor at or above its critical temperature counts as throttling.
+    pub fn sample(&mut self) {
+        if !self.listed {
+            self.components.refresh_list();
+            self.listed = true;
+        }
+        self.components.refresh();
+
+        for component in self.components.list() {
+            let temperature = component.temperature();
+            if !temperature.is_finite() {
+                continue;
+            }
+
+            let index = match self
+                .log
+                .history
+                .iter()
+                .position(|sensor| sensor.label == component.label())
+            {
+                Some(index) => index,
+                None => {
+                    self.log.history.push(SensorHistory {
+                        label: component.label().to_string(),
+                        readings: VecDeque::with_capacity(SENSOR_HISTORY_LEN),
+                        peak: temperature,
+                        critical: component.critical(),
+                    });
+                    self.log.history.len() - 1
+                }
+            };
+
+            let sensor = &mut self.log.history[index];
+            if sensor.readings.len() == SENSOR_HISTORY_LEN {
+                sensor.readings.pop_front();
+            }
+            // Sensors carried over from the cache have no peak yet
+            sensor.peak = if sensor.readings.is_empty() {
+                temperature
+            } else {
+                sensor.peak.max(temperature)
+            };
+            sensor.readings.push_back(temperature);
+        }
+
+        let hottest = self
+            .log
+            .history
+            .iter()
+            .filter_map(SensorHistory::current)
+            .reduce(f32::max);
+        let throttled = self.probe.throttled().unwrap_or_else(|| {
+            self.log.history.iter().any(|sensor| {
+                sensor
+                    .current()
+                    .zip(sensor.critical)
+                    .is_some_and(|(current, critical)| critical > 0.0 && current >= critical)
+            })
+        });
+
+        self.record_throttle(throttled, hottest);
+    }
+
+    fn record_throttle(&mut self, throttled: bool, hottest: Option<f32>) {
+        match self.log.events.front_mut() {
+            Some(event) if event.duration.is_none() => {
+                event.peak_temperature = match (event.peak_temperature, hottest) {
+                    (Some(peak), Some(current)) => Some(peak.max(current)),
+                    (peak, current) => peak.or(current),
+                };
+                if !throttled {
+                    event.duration = Some(event.started_at.elapsed().as_secs());
+                }
+            }
+            _ if throttled => {
+                if self.log.events.len() == MAX_THROTTLE_EVENTS {
+                    self.log.events.pop_back();
+                }
+                self.log.events.push_front(ThrottleEvent {
+                    started: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|since| since.as_secs())
+                        .unwrap_or(0),
+                    started_at: Instant::now(),
+                    duration: None,
+                    peak_temperature: hottest,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// How hard the system is working to keep itself cool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum ThermalPressure {
+    Nominal,
+    Moderate,
+    Heavy,
+    /// The system is trapping or about to sleep to shed heat
+    Critical,
+}
+
+impl ThermalPressure {
+    pub fn label(self) -> &'static str {
+        match self {
+            ThermalPressure::Nominal => "nominal",
+            ThermalPressure::Moderate => "moderate",
+            ThermalPressure::Heavy => "heavy",
+            ThermalPressure::Critical => "critical",
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn notify_register_check(name: *const libc::c_char, token: *mut libc::c_int) -> u32;
+    fn notify_get_state(token: libc::c_int, state: *mut u64) -> u32;
+    fn notify_cancel(token: libc::c_int) -> u32;
+}
+
+/// Read the thermal pressure level the system publishes through notifyd
+///
+/// This is the level behind `ProcessInfo.thermalState`, posted under
+/// `com.apple.system.thermalpressurelevel` (`<libkern/OSThermalNotification.h>`).
+///
+/// # Returns
+/// The pressure level, or None if it could not be read
+#[cfg(target_os = "macos")]
+pub fn fetch_thermal_pressure() -> Option<ThermalPressure> {
+    let mut token: libc::c_int = 0;
+    let mut state: u64 = 0;
+    let status = unsafe {
+        if notify_register_check(
+            c"com.apple.system.thermalpressurelevel".as_ptr(),
+            &mut token,
+        ) != 0
+        {
+            return None;
+        }
+        let status = notify_get_state(token, &mut state);
+        notify_cancel(token);
+        status
+    };
+    if status != 0 {
+        return None;
+    }
+
+    Some(match state {
+        0 => ThermalPressure::Nominal,
+        1 => ThermalPressure::Moderate,
+        2 => ThermalPressure::Heavy,
+        _ => ThermalPressure::Critical,
+    })
+}
+
+/// Linux has no system-wide thermal pressure level
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_thermal_pressure() -> Option<ThermalPressure> {
+    None
+}
+
+/// Reads whether the CPU is being thermally throttled
+#[derive(Debug, Default)]
+struct ThrottleProbe {
+    /// Throttle count at the previous sample
+    #[cfg(not(target_os = "macos"))]
+    last_count: Option<u64>,
+}
+
+#[cfg(target_os = "macos")]
+impl ThrottleProbe {
+    /// Read `CPU_Speed_Limit` from `pmset -g therm`; below 100 the CPU is
+    /// held back for thermal reasons
+    ///
+    /// Apple Silicon Macs don't report a speed limit, so this returns None
+    /// there.
+    fn throttled(&mut self) -> Option<bool> {
+        let output = Command::new("pmset").args(["-g", "therm"]).output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let limit = stdout
+            .lines()
+            .find(|line| line.contains("CPU_Speed_Limit"))?
+            .split('=')
+            .nth(1)?
+            .trim()
+            .parse::<u32>()
+            .ok()?;
+
+        Some(limit < 100)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+impl ThrottleProbe {
+    /// Compare the kernel's per-CPU thermal throttle counters with the
+    /// previous sample; any increase means the CPU throttled since then
+    fn throttled(&mut self) -> Option<bool> {
+        let mut count: Option<u64> = None;
+        for cpu in std::fs::read_dir("/sys/devices/system/cpu").ok()?.flatten() {
+            let path = cpu.path().join("thermal_throttle/core_throttle_count");
+            if let Some(value) = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|contents| contents.trim().parse::<u64>().ok())
+            {
+                *count.get_or_insert(0) += value;
+            }
+        }
+
+        let count = count?;
+        let previous = self.last_count.replace(count);
+        Some(previous.is_some_and(|previous| count > previous))
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use sysinfo::{
+    Cpu, DiskUsage, Gid, LoadAvg, Pid, Process, ProcessRefreshKind, ProcessStatus, System,
+    ThreadKind, Uid,
+};
+
+/// One CPU as a refresh saw it
+#[derive(Debug, Clone, Default)]
+pub struct CpuSnapshot {
+    pub name: String,
+    pub brand: String,
+    /// Percent busy since the refresh before
+    pub usage: f32,
+    /// Clock speed in MHz
+    pub frequency: u64,
+}
+
+impl CpuSnapshot {
+    pub fn from_cpu(cpu: &Cpu) -> Self {
+        CpuSnapshot {
+            name: cpu.name().to_string(),
+            brand: cpu.brand().to_string(),
+            usage: cpu.cpu_usage(),
+            frequency: cpu.frequency(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn brand(&self) -> &str {
+        &self.brand
+    }
+
+    pub fn cpu_usage(&self) -> f32 {
+        self.usage
+    }
+
+    pub fn frequency(&self) -> u64 {
+        self.frequency
+    }
+}
+
+/// One process as a refresh saw it
+///
+/// The accessors follow `sysinfo::Process`, so code reads the same whether
+/// the data came from this machine, a recording or a script.
+#[derive(Debug, Clone)]
+pub struct ProcessSnapshot {
+    pub pid: Pid,
+    pub parent: Option<Pid>,
+    pub name: String,
+    pub cmd: Vec<String>,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    /// Only known for processes listed with their environment, which the
+    /// live provider does for the ones running at startup
+    pub environ: Vec<String>,
+    pub user_id: Option<Uid>,
+    pub group_id: Option<Gid>,
+    pub session_id: Option<Pid>,
+    /// Process group ID, read with the refresh
+    pub process_group: Option<Pid>,
+    pub status: ProcessStatus,
+    /// Seconds since the Unix epoch
+    pub start_time: u64,
+    /// Seconds
+    pub run_time: u64,
+    /// Percent of one core since the refresh before
+    pub cpu_usage: f32,
+    /// Resident bytes
+    pub memory: u64,
+    pub virtual_memory: u64,
+    pub disk_usage: DiskUsage,
+    /// Set for the entries sysinfo lists for threads on Linux
+    pub thread_kind: Option<ThreadKind>,
+    /// Thread IDs of the process, where they are listed as entries
+    pub tasks: Option<HashSet<Pid>>,
+    /// Name and command line in lowercase, worked out the first time a
+    /// filter asks for them; leave it empty when building a snapshot
+    pub search_text: OnceLock<String>,
+}
+
+impl Default for ProcessSnapshot {
+    fn default() -> Self {
+        ProcessSnapshot {
+            pid: Pid::from_u32(0),
+            parent: None,
+            name: String::new(),
+            cmd: Vec::new(),
+            exe: None,
+            cwd: None,
+            environ: Vec::new(),
+            user_id: None,
+            group_id: None,
+            session_id: None,
+            process_group: None,
+            status: ProcessStatus::Unknown(0),
+            start_time: 0,
+            run_time: 0,
+            cpu_usage: 0.0,
+            memory: 0,
+            virtual_memory: 0,
+            disk_usage: DiskUsage::default(),
+            thread_kind: None,
+            tasks: None,
+            search_text: OnceLock::new(),
+        }
+    }
+}
+
+impl ProcessSnapshot {
+    pub fn from_process(process: &Process) -> Self {
+        ProcessSnapshot {
+            pid: process.pid(),
+            parent: process.parent(),
+            name: process.name().to_string(),
+            cmd: process.cmd().to_vec(),
+            exe: process.exe().map(Path::to_path_buf),
+            cwd: process.cwd().map(Path::to_path_buf),
+            environ: process.environ().to_vec(),
+            user_id: process.user_id().cloned(),
+            group_id: process.group_id(),
+            session_id: process.session_id(),
+            process_group: read_process_group(process.pid()),
+            status: process.status(),
+            start_time: process.start_time(),
+            run_time: process.run_time(),
+            cpu_usage: process.cpu_usage(),
+            memory: process.memory(),
+            virtual_memory: process.virtual_memory(),
+            disk_usage: process.disk_usage(),
+            thread_kind: process.thread_kind(),
+            tasks: process.tasks().cloned(),
+            search_text: OnceLock::new(),
+        }
+    }
+
+    /// Read everything sysinfo knows about one running process, including
+    /// the environment and working directory the regular refresh skips
+    ///
+    /// CPU usage needs two readings, so it is zero here.
+    pub fn read(pid: Pid) -> Option<Self> {
+        let mut system = System::new();
+        system.refresh_process_specifics(pid, ProcessRefreshKind::everything());
+        system.process(pid).map(ProcessSnapshot::from_process)
+    }
+
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    pub fn parent(&self) -> Option<Pid> {
+        self.parent
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn cmd(&self) -> &[String] {
+        &self.cmd
+    }
+
+    /// Name and command line in lowercase, one per line, for matching
+    /// filters without regard to case
+    pub fn search_text(&self) -> &str {
+        self.search_text
+            .get_or_init(|| format!("{}\n{}", self.name, self.cmd.join(" ")).to_lowercase())
+    }
+
+    pub fn exe(&self) -> Option<&Path> {
+        self.exe.as_deref()
+    }
+
+    pub fn cwd(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
+    pub fn environ(&self) -> &[String] {
+        &self.environ
+    }
+
+    pub fn user_id(&self) -> Option<&Uid> {
+        self.user_id.as_ref()
+    }
+
+    pub fn group_id(&self) -> Option<Gid> {
+        self.group_id
+    }
+
+    pub fn session_id(&self) -> Option<Pid> {
+        self.session_id
+    }
+
+    pub fn process_group(&self) -> Option<Pid> {
+        self.process_group
+    }
+
+    pub fn status(&self) -> ProcessStatus {
+        self.status
+    }
+
+    pub fn start_time(&self) -> u64 {
+        self.start_time
+    }
+
+    pub fn run_time(&self) -> u64 {
+        self.run_time
+    }
+
+    pub fn cpu_usage(&self) -> f32 {
+        self.cpu_usage
+    }
+
+    pub fn memory(&self) -> u64 {
+        self.memory
+    }
+
+    pub fn virtual_memory(&self) -> u64 {
+        self.virtual_memory
+    }
+
+    pub fn disk_usage(&self) -> DiskUsage {
+        self.disk_usage
+    }
+
+    pub fn thread_kind(&self) -> Option<ThreadKind> {
+        self.thread_kind
+    }
+
+    pub fn tasks(&self) -> Option<&HashSet<Pid>> {
+        self.tasks.as_ref()
+    }
+}
+
+/// The process group of a running process, or None once it has exited
+///
+/// macOS lists kernel_task as PID 0, whose group is 0; `getpgid(0)` would
+/// give sysly's own group instead.
+fn read_process_group(pid: Pid) -> Option<Pid> {
+    if pid.as_u32() == 0 {
+        return Some(pid);
+    }
+    let pgid = unsafe { libc::getpgid(pid.as_u32() as libc::pid_t) };
+    (pgid >= 0).then(|| Pid::from_u32(pgid as u32))
+}
+
+/// CPUs, memory and processes as one refresh saw them
+///
+/// Owned, unlike a `System`, so a sample can move between threads and a
+/// provider can build one from a recording, a remote host or a script.
+/// The accessors follow `sysinfo::System`, with the machine-wide figures
+/// sysinfo reads statically (load, uptime, host name) taken at the refresh.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub host_name: Option<String>,
+    pub cpus: Vec<CpuSnapshot>,
+    /// All CPUs together
+    pub global_cpu: CpuSnapshot,
+    /// Bytes
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub available_memory: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    pub load_average: LoadAvg,
+    /// Seconds
+    pub uptime: u64,
+    /// Seconds since the Unix epoch
+    pub boot_time: u64,
+    pub processes: HashMap<Pid, ProcessSnapshot>,
+}
+
+impl Snapshot {
+    /// Copy what a refreshed `System` holds, with this machine's load,
+    /// uptime and host name
+    pub fn from_system(system: &System) -> Self {
+        Snapshot {
+            host_name: System::host_name(),
+            cpus: system.cpus().iter().map(CpuSnapshot::from_cpu).collect(),
+            global_cpu: CpuSnapshot::from_cpu(system.global_cpu_info()),
+            total_memory: system.total_memory(),
+            used_memory: system.used_memory(),
+            available_memory: system.available_memory(),
+            total_swap: system.total_swap(),
+            used_swap: system.used_swap(),
+            load_average: System::load_average(),
+            uptime: System::uptime(),
+            boot_time: System::boot_time(),
+            processes: system
+                .processes()
+                .iter()
+                .map(|(pid, process)| (*pid, ProcessSnapshot::from_process(process)))
+                .collect(),
+        }
+    }
+
+    /// Take the CPU, memory and load figures of a newer snapshot, keeping
+    /// these processes
+    pub fn update_meters(&mut self, newer: Snapshot) {
+        let processes = std::mem::take(&mut self.processes);
+        *self = Snapshot { processes, ..newer };
+    }
+
+    /// Re-read one listed process from this machine, keeping its CPU usage,
+    /// which needs two readings
+    ///
+    /// The entry is left as it was when the process has exited.
+    ///
+    /// # Returns
+    /// Whether the process is still running
+    pub fn refresh_process(&mut self, pid: Pid) -> bool {
+        let Some(process) = self.processes.get_mut(&pid) else {
+            return false;
+        };
+        match ProcessSnapshot::read(pid) {
+            Some(fresh) => {
+                *process = ProcessSnapshot {
+                    cpu_usage: process.cpu_usage,
+                    ..fresh
+                };
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn host_name(&self) -> Option<String> {
+        self.host_name.clone()
+    }
+
+    pub fn cpus(&self) -> &[CpuSnapshot] {
+        &self.cpus
+    }
+
+    pub fn global_cpu_info(&self) -> &CpuSnapshot {
+        &self.global_cpu
+    }
+
+    pub fn total_memory(&self) -> u64 {
+        self.total_memory
+    }
+
+    pub fn used_memory(&self) -> u64 {
+        self.used_memory
+    }
+
+    pub fn available_memory(&self) -> u64 {
+        self.available_memory
+    }
+
+    pub fn total_swap(&self) -> u64 {
+        self.total_swap
+    }
+
+    pub fn used_swap(&self) -> u64 {
+        self.used_swap
+    }
+
+    pub fn load_average(&self) -> LoadAvg {
+        self.load_average.clone()
+    }
+
+    pub fn uptime(&self) -> u64 {
+        self.uptime
+    }
+
+    pub fn boot_time(&self) -> u64 {
+        self.boot_time
+    }
+
+    pub fn processes(&self) -> &HashMap<Pid, ProcessSnapshot> {
+        &self.processes
+    }
+
+    pub fn process(&self, pid: Pid) -> Option<&ProcessSnapshot> {
+        self.processes.get(&pid)
+    }
+}
@@ -0,0 +1,270 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use sysinfo::Pid;
+
+use crate::snapshot::Snapshot;
+
+/// When a startup item is launched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupKind {
+    /// An app opened when the user logs in
+    LoginItem,
+    /// A per-user agent started at login
+    Agent,
+    /// A system-wide daemon started at boot
+    Daemon,
+}
+
+impl StartupKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            StartupKind::LoginItem => "login",
+            StartupKind::Agent => "agent",
+            StartupKind::Daemon => "daemon",
+        }
+    }
+}
+
+/// Something the system starts at boot or login
+#[derive(Debug, Clone)]
+pub struct StartupItem {
+    pub kind: StartupKind,
+    pub label: String,
+    /// Executable or app bundle that gets launched
+    pub program: Option<PathBuf>,
+}
+
+impl StartupItem {
+    /// Find a running process launched from this item's program
+    ///
+    /// A process matches when its executable is the program itself or lives
+    /// inside the program's app bundle.
+    pub fn running_process(&self, sys: &Snapshot) -> Option<Pid> {
+        let program = self.program.as_deref()?;
+        sys.processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none())
+            .find(|process| {
+                process
+                    .exe()
+                    .is_some_and(|exe| exe == program || exe.starts_with(program))
+            })
+            .map(|process| process.pid())
+    }
+}
+
+/// List login items and launchd agents and daemons
+///
+/// Reads the launchd property lists in the user and local domains (Apple's
+/// own under `/System` are left out) and asks System Events for login items.
+#[cfg(target_os = "macos")]
+pub fn fetch_startup_items() -> Vec<StartupItem> {
+    let mut items = fetch_login_items();
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let directories = [
+        home.map(|home| (home.join("Library/LaunchAgents"), StartupKind::Agent)),
+        Some((PathBuf::from("/Library/LaunchAgents"), StartupKind::Agent)),
+        Some((PathBuf::from("/Library/LaunchDaemons"), StartupKind::Daemon)),
+    ];
+    for (directory, kind) in directories.into_iter().flatten() {
+        items.extend(
+            sorted_entries(&directory)
+                .into_iter()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "plist"))
+                .filter_map(|path| read_launchd_plist(&path, kind)),
+        );
+    }
+
+    items
+}
+
+/// Read login items through System Events
+///
+/// The first call asks the user to let the terminal control System Events;
+/// until they allow it, no login items are listed.
+#[cfg(target_os = "macos")]
+fn fetch_login_items() -> Vec<StartupItem> {
+    let Ok(output) = Command::new("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to get the path of every login item",
+        ])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    // "/Applications/Foo.app, /Applications/Bar.app"
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .split(", ")
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+            let program = PathBuf::from(path.trim_end_matches('/'));
+            StartupItem {
+                kind: StartupKind::LoginItem,
+                label: file_stem(&program),
+                program: Some(program),
+            }
+        })
+        .collect()
+}
+
+/// Read the label and program of a launchd job
+///
+/// Binary property lists are converted to XML with `plutil` first.
+#[cfg(target_os = "macos")]
+fn read_launchd_plist(path: &Path, kind: StartupKind) -> Option<StartupItem> {
+    let mut contents = fs::read(path).ok()?;
+    if contents.starts_with(b"bplist") {
+        contents = Command::new("plutil")
+            .args(["-convert", "xml1", "-o", "-"])
+            .arg(path)
+            .output()
+            .ok()?
+            .stdout;
+    }
+    let xml = String::from_utf8_lossy(&contents);
+
+    let label = plist_string(&xml, "Label").unwrap_or_else(|| file_stem(path));
+    let program = plist_string(&xml, "Program")
+        .or_else(|| plist_string(&xml, "ProgramArguments"))
+        .map(PathBuf::from);
+
+    Some(StartupItem {
+        kind,
+        label,
+        program,
+    })
+}
+
+/// The first `<string>` after `<key>key</key>` in an XML property list,
+/// which for an array is its first element
+#[cfg(target_os = "macos")]
+fn plist_string(xml: &str, key: &str) -> Option<String> {
+    let tag = format!("<key>{}</key>", key);
+    let rest = &xml[xml.find(&tag)? + tag.len()..];
+
+    // Stop at the next key so a non-string value doesn't borrow another's
+    let value = &rest[..rest.find("<key>").unwrap_or(rest.len())];
+    let string = value.split_once("<string>")?.1.split_once("</string>")?.0;
+    Some(string.trim().to_string())
+}
+
+/// List XDG autostart entries and enabled systemd services
+///
+/// Autostart entries are this platform's login items; enabled user services
+/// play the part of launchd agents, and system services wanted by the
+/// default target start at boot like launchd daemons.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_startup_items() -> Vec<StartupItem> {
+    let mut items = Vec::new();
+
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    for entry in config
+        .iter()
+        .flat_map(|config| sorted_entries(&config.join("autostart")))
+    {
+        if entry.extension().is_some_and(|ext| ext == "desktop") {
+            let contents = fs::read_to_string(&entry).unwrap_or_default();
+            items.push(StartupItem {
+                kind: StartupKind::LoginItem,
+                label: ini_value(&contents, "Name").unwrap_or_else(|| file_stem(&entry)),
+                program: ini_value(&contents, "Exec").and_then(|exec| resolve_command(&exec)),
+            });
+        }
+    }
+
+    let services = [
+        config.map(|config| {
+            (
+                config.join("systemd/user/default.target.wants"),
+                StartupKind::Agent,
+            )
+        }),
+        Some((
+            PathBuf::from("/etc/systemd/system/multi-user.target.wants"),
+            StartupKind::Daemon,
+        )),
+    ];
+    for (directory, kind) in services.into_iter().flatten() {
+        for entry in sorted_entries(&directory) {
+            let Some(unit) = fs::canonicalize(&entry)
+                .ok()
+                .and_then(|unit| fs::read_to_string(unit).ok())
+            else {
+                continue;
+            };
+            items.push(StartupItem {
+                kind,
+                label: file_stem(&entry),
+                program: ini_value(&unit, "ExecStart").and_then(|exec| resolve_command(&exec)),
+            });
+        }
+    }
+
+    items
+}
+
+/// The program a launchd property list or systemd unit starts
+#[cfg(target_os = "macos")]
+pub fn service_program(path: &Path) -> Option<PathBuf> {
+    read_launchd_plist(path, StartupKind::Agent)?.program
+}
+
+/// The program a launchd property list or systemd unit starts
+#[cfg(not(target_os = "macos"))]
+pub fn service_program(path: &Path) -> Option<PathBuf> {
+    let unit = fs::read_to_string(path).ok()?;
+    ini_value(&unit, "ExecStart").and_then(|exec| resolve_command(&exec))
+}
+
+fn sorted_entries(directory: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(directory)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The first `key=value` setting with the given key
+#[cfg(not(target_os = "macos"))]
+fn ini_value(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        (name.trim() == key).then(|| value.trim().to_string())
+    })
+}
+
+/// The executable a command line runs, looked up on `PATH` when it isn't
+/// absolute
+///
+/// systemd prefixes such as `-` or `@` are dropped.
+#[cfg(not(target_os = "macos"))]
+fn resolve_command(command: &str) -> Option<PathBuf> {
+    let program = command
+        .split_whitespace()
+        .next()?
+        .trim_start_matches(['-', '@', ':', '+', '!']);
+    let program = Path::new(program);
+    if program.is_absolute() {
+        return Some(program.to_path_buf());
+    }
+
+    std::env::var_os("PATH").and_then(|path| {
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(program))
+            .find(|candidate| candidate.is_file())
+    })
+}
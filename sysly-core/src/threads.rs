@@ -0,0 +1,206 @@
+#[cfg(not(target_os = "macos"))]
+use sysinfo::ProcessStatus;
+use sysinfo::{Pid, Process, System, ThreadKind};
+
+use crate::snapshot::Snapshot;
+
+/// A thread of the inspected process
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: u64,
+    pub name: String,
+    /// One-letter run state, as in the process table's S column
+    pub state: char,
+    pub cpu_usage: f32,
+    /// User plus system CPU time in seconds, where the platform reports it
+    pub cpu_time: Option<u64>,
+}
+
+/// Thread counts across every process
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadTotals {
+    /// Userland threads, or None if the platform doesn't report them
+    pub threads: Option<usize>,
+    pub kernel_threads: usize,
+}
+
+/// Count the threads of every process
+///
+/// On Linux, sysinfo lists each thread as its own entry; those are skipped
+/// so every thread is counted once through its owning process.
+pub fn count_threads(sys: &System) -> ThreadTotals {
+    let mut totals = ThreadTotals::default();
+
+    for process in sys.processes().values() {
+        match process.thread_kind() {
+            Some(ThreadKind::Kernel) => totals.kernel_threads += 1,
+            Some(ThreadKind::Userland) => {}
+            None => {
+                if let Some(count) = thread_count(process) {
+                    *totals.threads.get_or_insert(0) += count;
+                }
+            }
+        }
+    }
+
+    totals
+}
+
+/// Number of threads in a process, read from its task info
+#[cfg(target_os = "macos")]
+pub fn thread_count(process: &Process) -> Option<usize> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+
+    let written = unsafe {
+        libc::proc_pidinfo(
+            process.pid().as_u32() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+
+    (written == size).then_some(info.pti_threadnum as usize)
+}
+
+/// Number of threads in a process: its tasks plus the main thread
+#[cfg(not(target_os = "macos"))]
+pub fn thread_count(process: &Process) -> Option<usize> {
+    process.tasks().map(|tasks| tasks.len() + 1)
+}
+
+/// Room left in the thread list for threads started since the count was
+/// read
+#[cfg(target_os = "macos")]
+const THREAD_HEADROOM: usize = 64;
+
+/// List the threads of a process, busiest first
+///
+/// Thread IDs come from `PROC_PIDLISTTHREADIDS` and each thread's state,
+/// usage, and name from `PROC_PIDTHREADID64INFO`. The list is sized from
+/// the task's thread count and grown while it comes back full, so a process
+/// with thousands of threads lists them all.
+#[cfg(target_os = "macos")]
+pub fn fetch_threads(pid: Pid, _sys: &Snapshot) -> Vec<ThreadInfo> {
+    use crate::process::{proc_info, PROC_PIDLISTTHREADIDS, PROC_PIDTHREADID64INFO};
+
+    let counted = proc_info::<libc::proc_taskinfo>(pid.as_u32(), libc::PROC_PIDTASKINFO)
+        .map_or(0, |info| info.pti_threadnum.max(0) as usize);
+    let pid = pid.as_u32() as libc::c_int;
+    let id_size = std::mem::size_of::<u64>() as libc::c_int;
+
+    let mut capacity = counted + THREAD_HEADROOM;
+    let ids = loop {
+        let mut ids: Vec<u64> = vec![0; capacity];
+        let written = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                PROC_PIDLISTTHREADIDS,
+                0,
+                ids.as_mut_ptr() as *mut libc::c_void,
+                ids.len() as libc::c_int * id_size,
+            )
+        };
+        if written <= 0 {
+            return Vec::new();
+        }
+        let listed = (written / id_size) as usize;
+        // A full list may have been cut short
+        if listed < capacity {
+            ids.truncate(listed);
+            break ids;
+        }
+        capacity *= 2;
+    };
+
+    let mut threads: Vec<ThreadInfo> = ids
+        .into_iter()
+        .filter_map(|tid| {
+            let mut info: libc::proc_threadinfo = unsafe { std::mem::zeroed() };
+            let size = std::mem::size_of::<libc::proc_threadinfo>() as libc::c_int;
+            let written = unsafe {
+                libc::proc_pidinfo(
+                    pid,
+                    PROC_PIDTHREADID64INFO,
+                    tid,
+                    &mut info as *mut _ as *mut libc::c_void,
+                    size,
+                )
+            };
+            if written != size {
+                return None;
+            }
+
+            let name = unsafe { std::ffi::CStr::from_ptr(info.pth_name.as_ptr()) };
+            let state = match info.pth_run_state {
+                libc::TH_STATE_RUNNING => 'R',
+                libc::TH_STATE_STOPPED => 'T',
+                libc::TH_STATE_WAITING => 'S',
+                libc::TH_STATE_UNINTERRUPTIBLE => 'D',
+                libc::TH_STATE_HALTED => 'H',
+                _ => '?',
+            };
+
+            Some(ThreadInfo {
+                tid,
+                name: name.to_string_lossy().to_string(),
+                state,
+                // Usage is scaled to TH_USAGE_SCALE (1000 = 100%)
+                cpu_usage: info.pth_cpu_usage as f32 / 10.0,
+                cpu_time: Some((info.pth_user_time + info.pth_system_time) / 1_000_000_000),
+            })
+        })
+        .collect();
+
+    sort_by_cpu(&mut threads);
+    threads
+}
+
+/// List the threads of a process, busiest first
+///
+/// sysinfo reports each task as its own entry, so threads are the process
+/// itself plus the entries for its task IDs.
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_threads(pid: Pid, sys: &Snapshot) -> Vec<ThreadInfo> {
+    let Some(process) = sys.process(pid) else {
+        return Vec::new();
+    };
+
+    let mut threads: Vec<ThreadInfo> = std::iter::once(pid)
+        .chain(process.tasks().into_iter().flatten().copied())
+        .filter_map(|tid| sys.process(tid))
+        .map(|thread| ThreadInfo {
+            tid: thread.pid().as_u32() as u64,
+            name: thread.name().to_string(),
+            state: status_char(thread.status()),
+            cpu_usage: thread.cpu_usage(),
+            cpu_time: None,
+        })
+        .collect();
+
+    sort_by_cpu(&mut threads);
+    threads
+}
+
+#[cfg(not(target_os = "macos"))]
+fn status_char(status: ProcessStatus) -> char {
+    match status {
+        ProcessStatus::Run => 'R',
+        ProcessStatus::Sleep => 'S',
+        ProcessStatus::Stop => 'T',
+        ProcessStatus::Zombie => 'Z',
+        ProcessStatus::Idle => 'I',
+        ProcessStatus::UninterruptibleDiskSleep => 'D',
+        _ => '?',
+    }
+}
+
+fn sort_by_cpu(threads: &mut [ThreadInfo]) {
+    threads.sort_by(|a, b| {
+        b.cpu_usage
+            .partial_cmp(&a.cpu_usage)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
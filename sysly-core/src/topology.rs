@@ -0,0 +1,108 @@
+/// A group of cores of the same kind on a hybrid CPU
+#[derive(Debug, Clone)]
+pub struct CoreCluster {
+    /// `Performance` or `Efficiency`
+    pub name: &'static str,
+    /// Indexes of the logical CPUs in the cluster, as sysinfo numbers them
+    pub cpus: Vec<usize>,
+}
+
+/// Read a numeric sysctl such as `hw.nperflevels`
+#[cfg(target_os = "macos")]
+fn sysctl_u32(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut value: u32 = 0;
+    let mut size = std::mem::size_of::<u32>();
+
+    let result = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            &mut value as *mut _ as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (result == 0).then_some(value)
+}
+
+/// Detect the performance and efficiency clusters of an Apple Silicon CPU
+///
+/// Uses the `hw.perflevel*` sysctls: level 0 is the performance cores and
+/// level 1 the efficiency cores, which the kernel numbers first.
+///
+/// # Returns
+/// Performance cluster first, or an empty list on CPUs with a single kind
+/// of core
+#[cfg(target_os = "macos")]
+pub fn fetch_core_clusters(cpu_count: usize) -> Vec<CoreCluster> {
+    if sysctl_u32("hw.nperflevels").unwrap_or(1) < 2 {
+        return Vec::new();
+    }
+    let (Some(performance), Some(efficiency)) = (
+        sysctl_u32("hw.perflevel0.logicalcpu"),
+        sysctl_u32("hw.perflevel1.logicalcpu"),
+    ) else {
+        return Vec::new();
+    };
+    let efficiency = efficiency as usize;
+    if efficiency + performance as usize != cpu_count {
+        return Vec::new();
+    }
+
+    vec![
+        CoreCluster {
+            name: "Performance",
+            cpus: (efficiency..cpu_count).collect(),
+        },
+        CoreCluster {
+            name: "Efficiency",
+            cpus: (0..efficiency).collect(),
+        },
+    ]
+}
+
+/// Detect the performance and efficiency cores of a hybrid Intel CPU from
+/// the `cpu_core` and `cpu_atom` PMU devices
+#[cfg(not(target_os = "macos"))]
+pub fn fetch_core_clusters(cpu_count: usize) -> Vec<CoreCluster> {
+    let read = |kind: &str| -> Option<Vec<usize>> {
+        let list = std::fs::read_to_string(format!("/sys/devices/{}/cpus", kind)).ok()?;
+        parse_cpu_list(list.trim())
+    };
+    let (Some(performance), Some(efficiency)) = (read("cpu_core"), read("cpu_atom")) else {
+        return Vec::new();
+    };
+    if performance.len() + efficiency.len() != cpu_count
+        || performance
+            .iter()
+            .chain(&efficiency)
+            .any(|&cpu| cpu >= cpu_count)
+    {
+        return Vec::new();
+    }
+
+    vec![
+        CoreCluster {
+            name: "Performance",
+            cpus: performance,
+        },
+        CoreCluster {
+            name: "Efficiency",
+            cpus: efficiency,
+        },
+    ]
+}
+
+/// Parse a kernel CPU list such as `0-7,16-23`
+#[cfg(not(target_os = "macos"))]
+fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for part in list.split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => cpus.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}